@@ -0,0 +1,99 @@
+//! Element animations (`getAnimations`)
+//!
+//! The DOM does not run CSS transitions or animations itself; the style or
+//! animation engine installs an [`AnimationProvider`] on the document with
+//! [`Document::set_animation_provider`](dom_core::Document::set_animation_provider)
+//! that reports what is currently running on its elements. Without a
+//! provider, elements have no animations.
+
+use dom_core::ElementRef;
+pub use dom_core::{AnimationHandle, AnimationPlayState, AnimationProvider, AnimationType};
+
+/// Animation methods for Element
+pub trait ElementAnimationExt {
+    /// Get the transitions and animations currently running on the element
+    ///
+    /// Returns an empty list when the element is not bound to an
+    /// [`AnimationProvider`].
+    fn get_animations(&self) -> Vec<AnimationHandle>;
+}
+
+impl ElementAnimationExt for ElementRef {
+    fn get_animations(&self) -> Vec<AnimationHandle> {
+        let provider = self.read().animation_provider().cloned();
+        provider
+            .map(|provider| provider.animations(self))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dom_core::{Document, Element, Node, NodeRef};
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct MockProvider;
+
+    impl AnimationProvider for MockProvider {
+        fn animations(&self, element: &ElementRef) -> Vec<AnimationHandle> {
+            if element.read().tag_name() != "DIV" {
+                return Vec::new();
+            }
+            vec![
+                AnimationHandle::transition("opacity"),
+                AnimationHandle::new("spin", AnimationType::Animation, AnimationPlayState::Paused),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_get_animations_from_provider() {
+        let mut doc = Document::new();
+        doc.set_animation_provider(Some(Arc::new(MockProvider)));
+
+        let animations = doc.create_element("div").unwrap().get_animations();
+        assert_eq!(animations.len(), 2);
+        assert_eq!(animations[0].name(), "opacity");
+        assert_eq!(animations[0].animation_type(), AnimationType::Transition);
+        assert_eq!(animations[0].play_state(), AnimationPlayState::Running);
+        assert_eq!(animations[1].name(), "spin");
+        assert_eq!(animations[1].animation_type(), AnimationType::Animation);
+        assert_eq!(animations[1].play_state(), AnimationPlayState::Paused);
+
+        let span = doc.create_element("span").unwrap();
+        assert!(span.get_animations().is_empty());
+    }
+
+    #[test]
+    fn test_provider_binds_existing_and_inserted_elements() {
+        let mut doc = Document::new();
+        let html = doc.create_element("html").unwrap();
+        doc.set_document_element(html.clone());
+        let existing = doc.create_element("div").unwrap();
+        html.write().append_child(existing.clone()).unwrap();
+
+        doc.set_animation_provider(Some(Arc::new(MockProvider)));
+        assert_eq!(existing.get_animations().len(), 2);
+
+        let inserted = Arc::new(RwLock::new(Element::new("div")));
+        let node: NodeRef = inserted.clone();
+        html.write().append_child(node).unwrap();
+        assert_eq!(inserted.get_animations().len(), 2);
+
+        // Elements of another document do not see the provider
+        let other = Document::new().create_element("div").unwrap();
+        assert!(other.get_animations().is_empty());
+
+        doc.set_animation_provider(None);
+        assert!(existing.get_animations().is_empty());
+    }
+
+    #[test]
+    fn test_get_animations_defaults_to_empty() {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        assert!(element.get_animations().is_empty());
+    }
+}
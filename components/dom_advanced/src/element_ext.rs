@@ -3,7 +3,9 @@
 //! This module provides extension methods for Element to add geometry
 //! measurement and scrolling capabilities.
 
-use crate::geometry::{DOMRect, DOMRectList, ScrollIntoViewOptions};
+use crate::geometry::{DOMRect, DOMRectList, ScrollBehavior, ScrollIntoViewOptions};
+use dom_core::ElementRef;
+use dom_types::LayoutMetrics;
 
 /// Geometry and scrolling methods for Element
 ///
@@ -14,7 +16,8 @@ pub trait ElementGeometryExt {
     ///
     /// Returns a DOMRect representing the size and position of the element
     /// relative to the viewport. This includes the element's padding and borders
-    /// but not margins.
+    /// but not margins. The box comes from the recorded layout metrics, so it
+    /// is empty at the origin until the element has been laid out.
     ///
     /// # Returns
     /// A DOMRect with the element's position and dimensions
@@ -35,7 +38,7 @@ pub trait ElementGeometryExt {
     ///
     /// For most elements, this returns a single DOMRect. For inline elements
     /// that span multiple lines, this returns multiple DOMRect objects, one
-    /// for each line box.
+    /// for each line box. Elements that have not been laid out have no boxes.
     ///
     /// # Returns
     /// A DOMRectList containing one or more DOMRect objects
@@ -75,11 +78,140 @@ pub trait ElementGeometryExt {
     /// element.scroll_into_view(options);
     /// ```
     fn scroll_into_view(&self, options: ScrollIntoViewOptions);
+
+    /// Scroll the element's content to the given offsets
+    ///
+    /// Offsets are clamped to `[0, scrollWidth - clientWidth]` horizontally
+    /// and `[0, scrollHeight - clientHeight]` vertically. With
+    /// `ScrollBehavior::Smooth` the current offsets are left in place and the
+    /// clamped target is recorded for an animator to move towards.
+    ///
+    /// # Arguments
+    /// * `x` - The horizontal offset to scroll to
+    /// * `y` - The vertical offset to scroll to
+    /// * `behavior` - Whether to jump or animate to the target
+    fn scroll_to(&self, x: f64, y: f64, behavior: ScrollBehavior);
+
+    /// Scroll the element's content by the given amounts
+    ///
+    /// The deltas are added to the current offsets and the result is clamped
+    /// as in [`scroll_to`](ElementGeometryExt::scroll_to).
+    ///
+    /// # Arguments
+    /// * `dx` - The horizontal distance to scroll
+    /// * `dy` - The vertical distance to scroll
+    /// * `behavior` - Whether to jump or animate to the target
+    fn scroll_by(&self, dx: f64, dy: f64, behavior: ScrollBehavior);
+
+    /// Width of the element's content including overflow (`scrollWidth`)
+    fn scroll_width(&self) -> f64;
+
+    /// Height of the element's content including overflow (`scrollHeight`)
+    fn scroll_height(&self) -> f64;
+
+    /// Inner width excluding borders and scrollbars (`clientWidth`)
+    fn client_width(&self) -> f64;
+
+    /// Inner height excluding borders and scrollbars (`clientHeight`)
+    fn client_height(&self) -> f64;
+
+    /// Border-box width (`offsetWidth`)
+    fn offset_width(&self) -> f64;
+
+    /// Border-box height (`offsetHeight`)
+    fn offset_height(&self) -> f64;
+
+    /// Record the metrics computed by the layout engine
+    ///
+    /// Until this is called, for example while the element is detached and
+    /// has never been laid out, every metric reads as 0.
+    fn set_layout_metrics(&self, metrics: LayoutMetrics);
+}
+
+impl ElementGeometryExt for ElementRef {
+    fn get_bounding_client_rect(&self) -> DOMRect {
+        border_box(self.read().layout_metrics())
+    }
+
+    fn get_client_rects(&self) -> DOMRectList {
+        let metrics = *self.read().layout_metrics();
+        if metrics == LayoutMetrics::default() {
+            return DOMRectList::new();
+        }
+        DOMRectList::from_vec(vec![border_box(&metrics)])
+    }
+
+    fn scroll_into_view(&self, options: ScrollIntoViewOptions) {
+        perform_scroll_into_view(&options);
+    }
+
+    fn scroll_to(&self, x: f64, y: f64, behavior: ScrollBehavior) {
+        let mut element = self.write();
+        let metrics = *element.layout_metrics();
+        let left = x.clamp(0.0, metrics.max_scroll_left());
+        let top = y.clamp(0.0, metrics.max_scroll_top());
+
+        let state = element.scroll_state_mut();
+        match behavior {
+            ScrollBehavior::Auto => {
+                state.left = left;
+                state.top = top;
+                state.smooth_target = None;
+            }
+            ScrollBehavior::Smooth => state.smooth_target = Some((left, top)),
+        }
+    }
+
+    fn scroll_by(&self, dx: f64, dy: f64, behavior: ScrollBehavior) {
+        let (left, top) = {
+            let element = self.read();
+            let state = element.scroll_state();
+            (state.left, state.top)
+        };
+        self.scroll_to(left + dx, top + dy, behavior);
+    }
+
+    fn scroll_width(&self) -> f64 {
+        self.read().layout_metrics().scroll_width
+    }
+
+    fn scroll_height(&self) -> f64 {
+        self.read().layout_metrics().scroll_height
+    }
+
+    fn client_width(&self) -> f64 {
+        self.read().layout_metrics().client_width
+    }
+
+    fn client_height(&self) -> f64 {
+        self.read().layout_metrics().client_height
+    }
+
+    fn offset_width(&self) -> f64 {
+        self.read().layout_metrics().offset_width
+    }
+
+    fn offset_height(&self) -> f64 {
+        self.read().layout_metrics().offset_height
+    }
+
+    fn set_layout_metrics(&self, metrics: LayoutMetrics) {
+        self.write().set_layout_metrics(metrics);
+    }
 }
 
-// Note: The actual implementation would be provided when both dom_core
-// and dom_advanced are available. For now, we provide helper functions
-// that can be used to implement these methods.
+/// The border box recorded in `metrics`, in viewport coordinates
+///
+/// Element and Range geometry both read it, so a range selecting an element
+/// reports the element's own box.
+pub(crate) fn border_box(metrics: &LayoutMetrics) -> DOMRect {
+    DOMRect::new(
+        metrics.x,
+        metrics.y,
+        metrics.offset_width,
+        metrics.offset_height,
+    )
+}
 
 /// Helper: Create a default bounding rect (stub implementation)
 pub fn get_default_bounding_rect() -> DOMRect {
@@ -103,7 +235,126 @@ pub fn perform_scroll_into_view(_options: &ScrollIntoViewOptions) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::geometry::{ScrollBehavior, ScrollLogicalPosition};
+    use crate::geometry::ScrollLogicalPosition;
+    use dom_core::Element;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    fn scroll_container() -> ElementRef {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        element.set_layout_metrics(LayoutMetrics {
+            scroll_width: 500.0,
+            scroll_height: 1000.0,
+            client_width: 200.0,
+            client_height: 400.0,
+            ..LayoutMetrics::default()
+        });
+        element
+    }
+
+    fn scroll_offsets(element: &ElementRef) -> (f64, f64) {
+        let state = *element.read().scroll_state();
+        (state.left, state.top)
+    }
+
+    #[test]
+    fn test_scroll_to_clamps_at_boundaries() {
+        let element = scroll_container();
+
+        element.scroll_to(120.0, 250.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (120.0, 250.0));
+
+        element.scroll_to(10_000.0, 10_000.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (300.0, 600.0));
+
+        element.scroll_to(-50.0, -1.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_scroll_without_overflow_stays_at_origin() {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        element.scroll_to(30.0, 40.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_scroll_by_accumulates() {
+        let element = scroll_container();
+
+        element.scroll_by(100.0, 150.0, ScrollBehavior::Auto);
+        element.scroll_by(100.0, 150.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (200.0, 300.0));
+
+        element.scroll_by(150.0, -400.0, ScrollBehavior::Auto);
+        assert_eq!(scroll_offsets(&element), (300.0, 0.0));
+    }
+
+    #[test]
+    fn test_layout_metrics_round_trip() {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        element.set_layout_metrics(LayoutMetrics {
+            scroll_width: 640.0,
+            scroll_height: 900.0,
+            client_width: 600.0,
+            client_height: 300.0,
+            offset_width: 620.0,
+            offset_height: 320.0,
+            ..LayoutMetrics::default()
+        });
+
+        assert_eq!(element.scroll_width(), 640.0);
+        assert_eq!(element.scroll_height(), 900.0);
+        assert_eq!(element.client_width(), 600.0);
+        assert_eq!(element.client_height(), 300.0);
+        assert_eq!(element.offset_width(), 620.0);
+        assert_eq!(element.offset_height(), 320.0);
+    }
+
+    #[test]
+    fn test_detached_element_metrics_are_zero() {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        assert_eq!(element.scroll_width(), 0.0);
+        assert_eq!(element.scroll_height(), 0.0);
+        assert_eq!(element.client_width(), 0.0);
+        assert_eq!(element.client_height(), 0.0);
+        assert_eq!(element.offset_width(), 0.0);
+        assert_eq!(element.offset_height(), 0.0);
+    }
+
+    #[test]
+    fn test_client_rects_read_layout_metrics() {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        assert_eq!(element.get_bounding_client_rect(), DOMRect::default());
+        assert_eq!(element.get_client_rects().length(), 0);
+
+        element.set_layout_metrics(LayoutMetrics {
+            x: 12.0,
+            y: 30.0,
+            offset_width: 620.0,
+            offset_height: 320.0,
+            ..LayoutMetrics::default()
+        });
+        let rect = DOMRect::new(12.0, 30.0, 620.0, 320.0);
+        assert_eq!(element.get_bounding_client_rect(), rect);
+        let rects = element.get_client_rects();
+        assert_eq!(rects.length(), 1);
+        assert_eq!(rects.item(0), Some(&rect));
+    }
+
+    #[test]
+    fn test_smooth_scroll_records_target() {
+        let element = scroll_container();
+        element.scroll_to(50.0, 50.0, ScrollBehavior::Auto);
+
+        element.scroll_by(1000.0, 100.0, ScrollBehavior::Smooth);
+        let state = *element.read().scroll_state();
+        assert_eq!((state.left, state.top), (50.0, 50.0));
+        assert_eq!(state.smooth_target, Some((300.0, 150.0)));
+
+        element.scroll_to(0.0, 0.0, ScrollBehavior::Auto);
+        assert_eq!(element.read().scroll_state().smooth_target, None);
+    }
 
     #[test]
     fn test_get_default_bounding_rect() {
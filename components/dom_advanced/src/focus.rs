@@ -0,0 +1,242 @@
+//! Focus management with shadow DOM focus delegation
+//!
+//! Focus is tracked on tree nodes rather than `ElementRef`s so that elements
+//! inside shadow trees keep their identity. When a focused host's shadow root
+//! was attached with `delegates_focus`, focus moves to the first focusable
+//! element of the shadow tree and the host matches `:focus` along with it.
+
+use dom_core::{Element, NodeRef};
+use std::sync::Arc;
+
+/// Elements that are focusable without a `tabindex`
+const FOCUSABLE_TAGS: &[&str] = &["BUTTON", "SELECT", "TEXTAREA", "IFRAME", "SUMMARY"];
+
+/// Form controls that lose focusability when `disabled`
+const DISABLEABLE_TAGS: &[&str] = &["BUTTON", "INPUT", "SELECT", "TEXTAREA"];
+
+/// Check whether `element` can receive focus
+///
+/// An element is focusable if it has a valid `tabindex`, is editable, or is an
+/// enabled form control, link or other interactive element.
+pub fn is_focusable(element: &Element) -> bool {
+    let tag = element.tag_name();
+    if DISABLEABLE_TAGS.contains(&tag) && element.has_attribute("disabled") {
+        return false;
+    }
+    if element
+        .get_attribute("tabindex")
+        .is_some_and(|index| index.trim().parse::<i32>().is_ok())
+    {
+        return true;
+    }
+    if element
+        .get_attribute("contenteditable")
+        .is_some_and(|value| !value.eq_ignore_ascii_case("false"))
+    {
+        return true;
+    }
+    match tag {
+        "A" | "AREA" => element.has_attribute("href"),
+        "INPUT" => !element
+            .get_attribute("type")
+            .is_some_and(|kind| kind.eq_ignore_ascii_case("hidden")),
+        _ => FOCUSABLE_TAGS.contains(&tag),
+    }
+}
+
+/// Tracks the focused element of a document
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    focused: Option<NodeRef>,
+    // Hosts that delegated focus to `focused`, outermost first
+    delegating_hosts: Vec<NodeRef>,
+}
+
+impl FocusManager {
+    /// Create a focus manager with nothing focused
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Focus `node`
+    ///
+    /// A host whose shadow root delegates focus passes it on to the first
+    /// focusable element of its shadow tree, recursively. Returns `false`,
+    /// leaving focus unchanged, if nothing focusable was found.
+    pub fn focus(&mut self, node: &NodeRef) -> bool {
+        let mut hosts = Vec::new();
+        let mut target = node.clone();
+        while delegates_focus(&target) {
+            let Some(delegate) = first_focusable_in_shadow(&target) else {
+                return false;
+            };
+            hosts.push(target);
+            target = delegate;
+        }
+
+        if !node_is_focusable(&target) {
+            return false;
+        }
+        self.focused = Some(target);
+        self.delegating_hosts = hosts;
+        true
+    }
+
+    /// Remove focus
+    pub fn blur(&mut self) {
+        self.focused = None;
+        self.delegating_hosts.clear();
+    }
+
+    /// The element that has focus, possibly inside a shadow tree
+    pub fn focused_element(&self) -> Option<NodeRef> {
+        self.focused.clone()
+    }
+
+    /// Check whether `node` matches `:focus`
+    ///
+    /// True for the focused element and for the hosts that delegated focus
+    /// to it.
+    pub fn has_focus(&self, node: &NodeRef) -> bool {
+        self.focused
+            .iter()
+            .chain(&self.delegating_hosts)
+            .any(|focused| Arc::ptr_eq(focused, node))
+    }
+}
+
+fn node_is_focusable(node: &NodeRef) -> bool {
+    let node = node.read();
+    node.as_any()
+        .downcast_ref::<Element>()
+        .is_some_and(is_focusable)
+}
+
+fn delegates_focus(node: &NodeRef) -> bool {
+    let node = node.read();
+    node.as_any()
+        .downcast_ref::<Element>()
+        .and_then(Element::shadow_root_content)
+        .is_some_and(|shadow| shadow.delegates_focus())
+}
+
+/// First focusable element of `host`'s shadow tree, in tree order
+fn first_focusable_in_shadow(host: &NodeRef) -> Option<NodeRef> {
+    let children = {
+        let host = host.read();
+        let shadow = host
+            .as_any()
+            .downcast_ref::<Element>()?
+            .shadow_root_content()?
+            .clone();
+        shadow.child_nodes()
+    };
+
+    let mut stack: Vec<NodeRef> = children.into_iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        if node_is_focusable(&node) || delegates_focus(&node) {
+            return Some(node);
+        }
+        let children = node.read().child_nodes();
+        stack.extend(children.into_iter().rev());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shadow::{ShadowRoot, ShadowRootInit, ShadowRootMode};
+    use dom_core::new_node_ref;
+    use parking_lot::RwLock;
+
+    fn element(tag: &str, attributes: &[(&str, &str)]) -> NodeRef {
+        let mut element = Element::new(tag);
+        for (name, value) in attributes {
+            element.set_attribute(*name, *value).unwrap();
+        }
+        new_node_ref(element)
+    }
+
+    /// Host node with a shadow root attached, returning both
+    fn host_with_shadow(delegates_focus: bool) -> (NodeRef, ShadowRoot) {
+        let host_ref = Arc::new(RwLock::new(Element::new("x-field")));
+        let shadow = ShadowRoot::attach(
+            host_ref.clone(),
+            ShadowRootInit {
+                delegates_focus,
+                ..ShadowRootInit::new(ShadowRootMode::Open)
+            },
+        );
+        // The tree node for the host shares the shadow root
+        let mut host = host_ref.read().clone();
+        host.set_shadow_root_content(host_ref.read().shadow_root_content().cloned());
+        (new_node_ref(host), shadow)
+    }
+
+    #[test]
+    fn test_is_focusable() {
+        let focusable = |tag: &str, attributes: &[(&str, &str)]| {
+            let mut element = Element::new(tag);
+            for (name, value) in attributes {
+                element.set_attribute(*name, *value).unwrap();
+            }
+            is_focusable(&element)
+        };
+        assert!(focusable("button", &[]));
+        assert!(focusable("input", &[]));
+        assert!(focusable("a", &[("href", "#")]));
+        assert!(focusable("div", &[("tabindex", "-1")]));
+        assert!(focusable("p", &[("contenteditable", "")]));
+        assert!(!focusable("a", &[]));
+        assert!(!focusable("div", &[]));
+        assert!(!focusable("div", &[("tabindex", "x")]));
+        assert!(!focusable("button", &[("disabled", "")]));
+        assert!(!focusable("input", &[("type", "hidden")]));
+    }
+
+    #[test]
+    fn test_delegates_focus_to_first_focusable_descendant() {
+        let (host, shadow) = host_with_shadow(true);
+        let label = element("label", &[]);
+        let wrapper = element("div", &[]);
+        let input = element("input", &[]);
+        let button = element("button", &[]);
+        wrapper.write().append_child(input.clone()).unwrap();
+        shadow.append_child(label).unwrap();
+        shadow.append_child(wrapper).unwrap();
+        shadow.append_child(button.clone()).unwrap();
+
+        let mut focus = FocusManager::new();
+        assert!(focus.focus(&host));
+
+        assert!(Arc::ptr_eq(&focus.focused_element().unwrap(), &input));
+        assert!(focus.has_focus(&input));
+        assert!(focus.has_focus(&host));
+        assert!(!focus.has_focus(&button));
+
+        // Focusing something else clears the host's focus state
+        let outside = element("button", &[]);
+        assert!(focus.focus(&outside));
+        assert!(!focus.has_focus(&host));
+
+        focus.blur();
+        assert!(focus.focused_element().is_none());
+    }
+
+    #[test]
+    fn test_host_without_delegation_or_focusable_content() {
+        let (host, shadow) = host_with_shadow(false);
+        shadow.append_child(element("input", &[])).unwrap();
+
+        let mut focus = FocusManager::new();
+        // The host itself is not focusable and doesn't delegate
+        assert!(!focus.focus(&host));
+        assert!(focus.focused_element().is_none());
+
+        let (empty_host, shadow) = host_with_shadow(true);
+        shadow.append_child(element("span", &[])).unwrap();
+        assert!(!focus.focus(&empty_host));
+        assert!(focus.focused_element().is_none());
+    }
+}
@@ -3,6 +3,7 @@
 //! Implements the CSSOM View Module geometry interfaces for
 //! measuring element positions and sizes.
 
+pub use dom_core::RangeGeometryProvider;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -170,6 +171,17 @@ impl From<DOMRect> for DOMRectReadOnly {
     }
 }
 
+impl From<dom_types::DOMRect> for DOMRect {
+    fn from(rect: dom_types::DOMRect) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
 /// DOMRectList represents a list of DOMRect objects
 pub type DOMRectListRef = Arc<RwLock<DOMRectList>>;
 
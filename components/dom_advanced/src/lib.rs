@@ -12,6 +12,7 @@
 //! - **Selection**: Handle user text selections
 //! - **Shadow DOM**: Encapsulated DOM subtrees
 //! - **Geometry**: Element bounding boxes and scroll methods
+//! - **Animations**: Running transitions and animations via a pluggable provider
 //!
 //! # MutationObserver
 //!
@@ -148,17 +149,25 @@ pub mod selection;
 pub mod shadow;
 pub mod geometry;
 pub mod element_ext;
+pub mod animation;
+pub mod focus;
 
 // Re-exports
 pub use mutation::{MutationObserver, MutationRecord};
-pub use range::Range;
+pub use range::{Range, RangeCompare};
 pub use selection::Selection;
-pub use shadow::{ShadowRoot, ShadowRootMode};
+pub use shadow::{ShadowRoot, ShadowRootInit, ShadowRootMode};
 pub use geometry::{
     DOMRect, DOMRectReadOnly, DOMRectList, DOMRectListRef,
     ScrollIntoViewOptions, ScrollBehavior, ScrollLogicalPosition,
+    RangeGeometryProvider,
 };
 pub use element_ext::{
     ElementGeometryExt,
     get_default_bounding_rect, get_default_client_rects, perform_scroll_into_view,
 };
+pub use dom_types::LayoutMetrics;
+pub use focus::{is_focusable, FocusManager};
+pub use animation::{
+    AnimationHandle, AnimationPlayState, AnimationProvider, AnimationType, ElementAnimationExt,
+};
@@ -5,5 +5,8 @@
 mod observer;
 mod record;
 
-pub use observer::MutationObserver;
+pub use observer::{
+    append_child_observed, insert_before_observed, queue_mutation_record, remove_child_observed,
+    MutationObserver,
+};
 pub use record::{MutationRecord, MutationType, MutationObserverInit};
@@ -1,17 +1,33 @@
 //! MutationObserver implementation
 
 use super::{MutationObserverInit, MutationRecord, MutationType};
-use dom_core::{NodeRef, WeakNodeRef};
-use dom_types::DomException;
-use parking_lot::Mutex;
-use std::sync::Arc;
+use dom_core::{
+    validate_pre_insertion, CharacterDataChange, CharacterDataObserver, NodeRef, TreeGenerations,
+    WeakNodeRef,
+};
+use dom_events::SchedulerRef;
+use dom_types::{DomException, NodeType};
+use parking_lot::{Mutex, MutexGuard, RwLock};
+use std::sync::{Arc, Weak};
 
 type ObserverCallback = Arc<dyn Fn(&[MutationRecord]) + Send + Sync>;
 
+/// Registered observers keyed by the node passed to `observe()`
+///
+/// Lets mutation generation find observers on the target's ancestors, so
+/// subtree observation covers descendants inserted after `observe()`.
+static REGISTERED_OBSERVERS: RwLock<Vec<(WeakNodeRef, Weak<Mutex<MutationObserverInner>>)>> =
+    parking_lot::const_rwlock(Vec::new());
+
 /// Observer for DOM mutations
 ///
 /// MutationObserver provides a way to watch for changes being made to the DOM tree.
-/// It will fire asynchronously via microtask queue when mutations occur.
+/// Given a scheduler, it fires asynchronously in a microtask when mutations
+/// occur; otherwise records wait for [`take_records`](Self::take_records).
+///
+/// Edits to text, comment and CDATA section data are seen in the documents
+/// of the observed nodes; nodes outside any document only report the
+/// records queued for them explicitly.
 #[derive(Clone)]
 pub struct MutationObserver {
     inner: Arc<Mutex<MutationObserverInner>>,
@@ -20,10 +36,24 @@ pub struct MutationObserver {
 struct MutationObserverInner {
     callback: ObserverCallback,
     observed_nodes: Vec<ObservedNode>,
-    record_queue: Vec<MutationRecord>,
+    record_queue: Vec<QueuedRecord>,
     is_active: bool,
+    // Runs the delivery of queued records, if any
+    scheduler: Option<SchedulerRef>,
+    delivery_scheduled: bool,
+    // Hooks registered with the documents of the observed nodes
+    character_data_hooks: Vec<(Weak<TreeGenerations>, CharacterDataObserver)>,
+}
+
+/// Entry of an observer's record queue
+enum QueuedRecord {
+    Ready(MutationRecord),
+    /// Character data change reported while its node was locked, turned into
+    /// a record, if a registration covers it, once the queue is taken
+    CharacterData(CharacterDataChange),
 }
 
+#[derive(Clone)]
 struct ObservedNode {
     node: WeakNodeRef,
     options: MutationObserverInit,
@@ -32,17 +62,36 @@ struct ObservedNode {
 impl MutationObserver {
     /// Create a new MutationObserver with the given callback
     ///
-    /// The callback will be invoked asynchronously when mutations are detected.
+    /// Without a scheduler nothing delivers the records: they are only
+    /// returned by [`take_records`](Self::take_records).
     pub fn new<F>(callback: F) -> Self
     where
         F: Fn(&[MutationRecord]) + Send + Sync + 'static,
     {
+        Self::build(Arc::new(callback), None)
+    }
+
+    /// Create a new MutationObserver that delivers through `scheduler`
+    ///
+    /// The first record queued after a delivery schedules the next one as a
+    /// microtask, which calls the callback with every record queued by then.
+    pub fn with_scheduler<F>(callback: F, scheduler: SchedulerRef) -> Self
+    where
+        F: Fn(&[MutationRecord]) + Send + Sync + 'static,
+    {
+        Self::build(Arc::new(callback), Some(scheduler))
+    }
+
+    fn build(callback: ObserverCallback, scheduler: Option<SchedulerRef>) -> Self {
         Self {
             inner: Arc::new(Mutex::new(MutationObserverInner {
-                callback: Arc::new(callback),
+                callback,
                 observed_nodes: Vec::new(),
                 record_queue: Vec::new(),
                 is_active: true,
+                scheduler,
+                delivery_scheduled: false,
+                character_data_hooks: Vec::new(),
             })),
         }
     }
@@ -67,7 +116,14 @@ impl MutationObserver {
             ));
         }
 
+        let generations = target.read().generations();
+
         let mut inner = self.inner.lock();
+        // Observing again after disconnect() reactivates the observer
+        inner.is_active = true;
+        if let Some(generations) = generations {
+            self.hook_character_data(&mut inner, &generations);
+        }
 
         // Check if already observing this node, update options if so
         if let Some(observed) = inner
@@ -85,29 +141,115 @@ impl MutationObserver {
             options,
         });
 
+        let mut registry = REGISTERED_OBSERVERS.write();
+        registry.retain(|(node, observer)| node.strong_count() > 0 && observer.strong_count() > 0);
+        registry.push((Arc::downgrade(&target), Arc::downgrade(&self.inner)));
+
         Ok(())
     }
 
     /// Stop observing all nodes
+    ///
+    /// Pending records are dropped along with the nodes they reference, and
+    /// no records are queued until [`observe`](Self::observe) is called again.
+    /// Observed targets are only weakly referenced, so disconnecting leaves
+    /// nothing keeping them alive.
     pub fn disconnect(&self) {
         let mut inner = self.inner.lock();
         inner.observed_nodes.clear();
         inner.record_queue.clear();
         inner.is_active = false;
+        inner.unhook_character_data();
+
+        let observer = Arc::downgrade(&self.inner);
+        REGISTERED_OBSERVERS
+            .write()
+            .retain(|(_, registered)| !Weak::ptr_eq(registered, &observer));
     }
 
     /// Take all pending mutation records without waiting for callback
     ///
-    /// This clears the record queue and returns all pending records.
+    /// This clears the record queue and returns all pending records, so the
+    /// next delivery does not call the callback with them. The queue lock is
+    /// released before the callback runs, so the callback may call this too;
+    /// it then gets only the records queued since its own delivery began.
     pub fn take_records(&self) -> Vec<MutationRecord> {
+        let (queue, observed) = {
+            let mut inner = self.inner.lock();
+            let queue = std::mem::take(&mut inner.record_queue);
+            (queue, inner.observed_nodes.clone())
+        };
+        resolve_records(queue, &observed)
+    }
+
+    /// Registers a hook routing the character data changes of the document
+    /// behind `generations` here, unless one is registered already
+    fn hook_character_data(
+        &self,
+        inner: &mut MutationObserverInner,
+        generations: &Arc<TreeGenerations>,
+    ) {
+        inner
+            .character_data_hooks
+            .retain(|(document, _)| document.strong_count() > 0);
+        let document = Arc::downgrade(generations);
+        if inner
+            .character_data_hooks
+            .iter()
+            .any(|(hooked, _)| Weak::ptr_eq(hooked, &document))
+        {
+            return;
+        }
+        let observer = Arc::downgrade(&self.inner);
+        let hook: CharacterDataObserver = Arc::new(move |change| {
+            if let Some(inner) = observer.upgrade() {
+                MutationObserver { inner }.queue_character_data(change);
+            }
+        });
+        generations.observe_character_data(hook.clone());
+        inner.character_data_hooks.push((document, hook));
+    }
+
+    /// Queues a character data change reported by `dom_core`
+    ///
+    /// The changed node, and possibly its ancestors, are write-locked while
+    /// this runs, so no node is touched: whether a registration covers the
+    /// change is decided when the queue is taken.
+    fn queue_character_data(&self, change: &CharacterDataChange) {
         let mut inner = self.inner.lock();
-        std::mem::take(&mut inner.record_queue)
+        let wanted = inner
+            .observed_nodes
+            .iter()
+            .any(|observed| observed.options.character_data);
+        if !inner.is_active || !wanted {
+            return;
+        }
+        inner
+            .record_queue
+            .push(QueuedRecord::CharacterData(change.clone()));
+        self.schedule_delivery(inner);
+    }
+
+    /// Schedules a delivery of the queue unless one is already pending
+    fn schedule_delivery(&self, mut inner: MutexGuard<'_, MutationObserverInner>) {
+        let Some(scheduler) = inner.scheduler.clone() else {
+            return;
+        };
+        if std::mem::replace(&mut inner.delivery_scheduled, true) {
+            return;
+        }
+        drop(inner);
+        let observer = self.clone();
+        scheduler.enqueue_microtask(Box::new(move || observer.deliver_mutations()));
     }
 
     /// Queue a mutation record (internal API)
     ///
-    /// This is called by the DOM implementation when mutations occur.
-    pub(crate) fn queue_record(&self, record: MutationRecord) {
+    /// This is called by the DOM implementation when mutations occur, with
+    /// the inclusive ancestors of the record's target, target first. The
+    /// target itself is never locked. The old value is dropped unless a
+    /// matching registration asked for it.
+    pub(crate) fn queue_record(&self, mut record: MutationRecord, ancestors: &[NodeRef]) {
         let mut inner = self.inner.lock();
 
         if !inner.is_active {
@@ -115,71 +257,86 @@ impl MutationObserver {
         }
 
         // Check if this observer is interested in this mutation
-        let is_interested = inner.observed_nodes.iter().any(|observed| {
-            if let Some(target_node) = observed.node.upgrade() {
-                // Check if this is the observed node or a descendant (if subtree)
-                let is_target = std::sync::Arc::ptr_eq(&target_node, &record.target);
-                let is_descendant = observed.options.subtree
-                    && Self::is_descendant_of(&record.target, &target_node);
-
-                if !is_target && !is_descendant {
+        let Some(wants_old_value) = interest(&inner.observed_nodes, &record, ancestors) else {
+            return;
+        };
+        if !wants_old_value {
+            record.old_value = None;
+        }
+        inner.record_queue.push(QueuedRecord::Ready(record));
+        self.schedule_delivery(inner);
+    }
+
+    /// Whether the registration `observed` covers `record`
+    fn registration_matches(
+        observed: &ObservedNode,
+        record: &MutationRecord,
+        ancestors: &[NodeRef],
+    ) -> bool {
+        let Some(target_node) = observed.node.upgrade() else {
+            return false;
+        };
+
+        // Check if this is the observed node or a descendant (if subtree)
+        let is_target = std::sync::Arc::ptr_eq(&target_node, &record.target);
+        let is_descendant = observed.options.subtree
+            && ancestors
+                .iter()
+                .skip(1)
+                .any(|ancestor| Arc::ptr_eq(ancestor, &target_node));
+
+        if !is_target && !is_descendant {
+            return false;
+        }
+
+        // Check if mutation type matches observed options
+        match record.record_type {
+            MutationType::ChildList => observed.options.child_list,
+            MutationType::Attributes => {
+                if !observed.options.attributes {
                     return false;
                 }
-
-                // Check if mutation type matches observed options
-                match record.record_type {
-                    MutationType::ChildList => observed.options.child_list,
-                    MutationType::Attributes => {
-                        if !observed.options.attributes {
-                            return false;
-                        }
-                        // Check attribute filter
-                        if let Some(ref filter) = observed.options.attribute_filter {
-                            if let Some(ref attr_name) = record.attribute_name {
-                                filter.contains(attr_name)
-                            } else {
-                                false
-                            }
-                        } else {
-                            true
-                        }
+                // Check attribute filter
+                if let Some(ref filter) = observed.options.attribute_filter {
+                    if let Some(ref attr_name) = record.attribute_name {
+                        filter.contains(attr_name)
+                    } else {
+                        false
                     }
-                    MutationType::CharacterData => observed.options.character_data,
+                } else {
+                    true
                 }
-            } else {
-                false
             }
-        });
-
-        if is_interested {
-            inner.record_queue.push(record);
-
-            // Schedule delivery if we have records
-            // In a real implementation, this would queue a microtask
-            // For now, we'll deliver synchronously in tests
+            MutationType::CharacterData => observed.options.character_data,
         }
     }
 
     /// Deliver pending mutations (internal API)
     ///
-    /// This should be called from the microtask queue in a real implementation.
+    /// Runs in the microtask scheduled by [`queue_record`](Self::queue_record).
     pub(crate) fn deliver_mutations(&self) {
-        let (callback, records) = {
+        let (callback, queue, observed) = {
             let mut inner = self.inner.lock();
+            inner.delivery_scheduled = false;
             if inner.record_queue.is_empty() || !inner.is_active {
                 return;
             }
 
             let callback = Arc::clone(&inner.callback);
-            let records = std::mem::take(&mut inner.record_queue);
-            (callback, records)
+            let queue = std::mem::take(&mut inner.record_queue);
+            (callback, queue, inner.observed_nodes.clone())
         };
 
-        // Call the callback outside the lock
-        callback(&records);
+        // Resolve the character data changes and call the callback outside
+        // the lock
+        let records = resolve_records(queue, &observed);
+        if !records.is_empty() {
+            callback(&records);
+        }
     }
 
     /// Check if the observer is interested in a node
+    #[cfg(test)]
     pub(crate) fn is_observing(&self, node: &NodeRef) -> bool {
         let inner = self.inner.lock();
         inner.observed_nodes.iter().any(|observed| {
@@ -190,20 +347,205 @@ impl MutationObserver {
                 .unwrap_or(false)
         })
     }
+}
+
+/// Queue `record` on every observer interested in its target
+///
+/// Walks the target's inclusive ancestors looking for registered observers,
+/// so a node inserted under an observed subtree is covered without
+/// re-observing it. Each observer receives the record at most once.
+pub fn queue_mutation_record(record: &MutationRecord) {
+    let parent = record.target.read().parent_node();
+    let ancestors = inclusive_ancestors(&record.target, parent);
+    let mut observers: Vec<MutationObserver> = Vec::new();
+
+    {
+        let registry = REGISTERED_OBSERVERS.read();
+        for node in &ancestors {
+            for (observed, inner) in registry.iter() {
+                if !std::ptr::addr_eq(observed.as_ptr(), Arc::as_ptr(node)) {
+                    continue;
+                }
+                if let Some(inner) = inner.upgrade() {
+                    if !observers.iter().any(|o| Arc::ptr_eq(&o.inner, &inner)) {
+                        observers.push(MutationObserver { inner });
+                    }
+                }
+            }
+        }
+    }
+
+    for observer in observers {
+        observer.queue_record(record.clone(), &ancestors);
+    }
+}
+
+/// `node` followed by `parent` and its ancestors, locking only the latter
+fn inclusive_ancestors(node: &NodeRef, parent: Option<NodeRef>) -> Vec<NodeRef> {
+    let mut ancestors = vec![Arc::clone(node)];
+    let mut current = parent;
+    while let Some(ancestor) = current {
+        current = ancestor.read().parent_node();
+        ancestors.push(ancestor);
+    }
+    ancestors
+}
+
+/// Whether a registration in `observed` covers `record`, and if so whether
+/// one of those asked for the old value
+fn interest(
+    observed: &[ObservedNode],
+    record: &MutationRecord,
+    ancestors: &[NodeRef],
+) -> Option<bool> {
+    let mut is_interested = false;
+    let mut wants_old_value = false;
+    for observed in observed {
+        if MutationObserver::registration_matches(observed, record, ancestors) {
+            is_interested = true;
+            wants_old_value |= match record.record_type {
+                MutationType::ChildList => false,
+                MutationType::Attributes => observed.options.attribute_old_value,
+                MutationType::CharacterData => observed.options.character_data_old_value,
+            };
+        }
+    }
+    is_interested.then_some(wants_old_value)
+}
+
+/// Turns a taken queue into records, in order
+///
+/// Character data changes become records only if a registration in
+/// `observed` covers their target, which needs the target's ancestors and so
+/// is done here, with no lock held.
+fn resolve_records(queue: Vec<QueuedRecord>, observed: &[ObservedNode]) -> Vec<MutationRecord> {
+    let mut records = Vec::with_capacity(queue.len());
+    for entry in queue {
+        match entry {
+            QueuedRecord::Ready(record) => records.push(record),
+            QueuedRecord::CharacterData(change) => {
+                let ancestors = inclusive_ancestors(&change.target, change.parent);
+                let mut record = MutationRecord::character_data(change.target, None);
+                if let Some(wants_old_value) = interest(observed, &record, &ancestors) {
+                    record.old_value = wants_old_value.then_some(change.old_value);
+                    records.push(record);
+                }
+            }
+        }
+    }
+    records
+}
 
-    // Helper method to check if a node is a descendant of another
-    fn is_descendant_of(node: &NodeRef, ancestor: &NodeRef) -> bool {
-        let mut current = node.read().parent_node();
-        while let Some(parent) = current {
-            if std::sync::Arc::ptr_eq(&parent, ancestor) {
-                return true;
+impl MutationObserverInner {
+    /// Unregisters the character data hooks from their documents
+    fn unhook_character_data(&mut self) {
+        for (document, hook) in self.character_data_hooks.drain(..) {
+            if let Some(generations) = document.upgrade() {
+                generations.unobserve_character_data(&hook);
             }
-            current = parent.read().parent_node();
         }
-        false
     }
 }
 
+impl Drop for MutationObserverInner {
+    fn drop(&mut self) {
+        self.unhook_character_data();
+    }
+}
+
+/// Appends `node` to `parent`, queueing the childList records it causes
+///
+/// See [`insert_before_observed`].
+pub fn append_child_observed(parent: &NodeRef, node: NodeRef) -> Result<NodeRef, DomException> {
+    insert_before_observed(parent, node, None)
+}
+
+/// Inserts `node` into `parent` before `ref_child`, queueing the childList
+/// records it causes
+///
+/// Tree methods on `dom_core` nodes know nothing of observers, so insertions
+/// that should be observable go through here. The record for `parent` holds
+/// the inserted nodes, the children of `node` if it is a fragment, along
+/// with the siblings around them at insertion time. A node moved out of
+/// another parent, or the emptied fragment, gets a removal record first.
+pub fn insert_before_observed(
+    parent: &NodeRef,
+    node: NodeRef,
+    ref_child: Option<NodeRef>,
+) -> Result<NodeRef, DomException> {
+    validate_pre_insertion(&*parent.read(), &node, ref_child.as_ref())?;
+
+    // Inserting a node before itself inserts it before its next sibling
+    let ref_child = match ref_child {
+        Some(ref_child) if Arc::ptr_eq(&ref_child, &node) => node.read().next_sibling(),
+        ref_child => ref_child,
+    };
+
+    let (node_type, old_parent) = {
+        let node = node.read();
+        (node.node_type(), node.parent_node())
+    };
+    let added = if node_type == NodeType::DocumentFragment {
+        let children = node.read().child_nodes();
+        if !children.is_empty() {
+            let removed = children.clone();
+            let record = MutationRecord::child_list(node.clone(), vec![], removed, None, None);
+            queue_mutation_record(&record);
+        }
+        children
+    } else {
+        if let Some(old_parent) = old_parent {
+            remove_child_observed(&old_parent, node.clone())?;
+        }
+        vec![node.clone()]
+    };
+
+    let children = parent.read().child_nodes();
+    let index = match &ref_child {
+        Some(ref_child) => children.iter().position(|c| Arc::ptr_eq(c, ref_child)),
+        None => None,
+    };
+    let previous_sibling = match index {
+        Some(index) => index.checked_sub(1).map(|i| children[i].clone()),
+        None => children.last().cloned(),
+    };
+
+    let inserted = parent.write().insert_before(node, ref_child.clone())?;
+    if !added.is_empty() {
+        let record =
+            MutationRecord::child_list(parent.clone(), added, vec![], previous_sibling, ref_child);
+        queue_mutation_record(&record);
+    }
+    Ok(inserted)
+}
+
+/// Removes `child` from `parent`, queueing a childList record with the
+/// siblings it had
+///
+/// # Errors
+///
+/// Returns `DomException::NotFoundError` if `child` is not a child of `parent`.
+pub fn remove_child_observed(parent: &NodeRef, child: NodeRef) -> Result<NodeRef, DomException> {
+    let children = parent.read().child_nodes();
+    let index = children
+        .iter()
+        .position(|c| Arc::ptr_eq(c, &child))
+        .ok_or(DomException::NotFoundError)?;
+    let previous_sibling = index.checked_sub(1).map(|i| children[i].clone());
+    let next_sibling = children.get(index + 1).cloned();
+
+    let removed = parent.write().remove_child(child)?;
+    let record = MutationRecord::child_list(
+        parent.clone(),
+        vec![],
+        vec![removed.clone()],
+        previous_sibling,
+        next_sibling,
+    );
+    queue_mutation_record(&record);
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,8 +634,8 @@ mod tests {
             .unwrap();
 
         // Queue a mutation
-        let record = MutationRecord::child_list(node, vec![], vec![], None, None);
-        observer.queue_record(record);
+        let record = MutationRecord::child_list(node.clone(), vec![], vec![], None, None);
+        observer.queue_record(record, &[node]);
 
         let records = observer.take_records();
         assert_eq!(records.len(), 1);
@@ -302,4 +644,457 @@ mod tests {
         let records = observer.take_records();
         assert_eq!(records.len(), 0);
     }
+
+    fn element_node(tag: &str) -> NodeRef {
+        use dom_core::{new_node_ref, Element};
+
+        new_node_ref(Element::new(tag))
+    }
+
+    #[test]
+    fn test_subtree_covers_descendants_added_after_observe() {
+        let root = element_node("div");
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    attributes: true,
+                    subtree: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let child = element_node("span");
+        let grandchild = element_node("b");
+        root.write().append_child(child.clone()).unwrap();
+        child.write().append_child(grandchild.clone()).unwrap();
+
+        queue_mutation_record(&MutationRecord::attributes(
+            grandchild.clone(),
+            "class".to_string(),
+            None,
+            None,
+        ));
+
+        let records = observer.take_records();
+        assert_eq!(records.len(), 1);
+        assert!(Arc::ptr_eq(&records[0].target, &grandchild));
+    }
+
+    #[test]
+    fn test_descendants_ignored_without_subtree() {
+        let root = element_node("div");
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    attributes: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let child = element_node("span");
+        root.write().append_child(child.clone()).unwrap();
+
+        queue_mutation_record(&MutationRecord::attributes(
+            child,
+            "class".to_string(),
+            None,
+            None,
+        ));
+        assert!(observer.take_records().is_empty());
+
+        queue_mutation_record(&MutationRecord::attributes(
+            root,
+            "class".to_string(),
+            None,
+            None,
+        ));
+        assert_eq!(observer.take_records().len(), 1);
+    }
+
+    #[test]
+    fn test_take_records_before_delivery_leaves_nothing_to_deliver() {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let delivered_clone = delivered.clone();
+        let observer = MutationObserver::new(move |records| {
+            delivered_clone.lock().push(records.len());
+        });
+        let root = element_node("div");
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    child_list: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let child = element_node("p");
+        append_child_observed(&root, child.clone()).unwrap();
+        append_child_observed(&root, element_node("p")).unwrap();
+        remove_child_observed(&root, child).unwrap();
+
+        assert_eq!(observer.take_records().len(), 3);
+        observer.deliver_mutations();
+        assert!(delivered.lock().is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_delivers_batched_records_in_a_microtask() {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let delivered_clone = delivered.clone();
+        let scheduler = dom_events::ManualScheduler::new_ref();
+        let observer = MutationObserver::with_scheduler(
+            move |records| delivered_clone.lock().push(records.len()),
+            scheduler.clone(),
+        );
+        let root = element_node("div");
+        let options = MutationObserverInit {
+            child_list: true,
+            ..Default::default()
+        };
+        observer.observe(root.clone(), options).unwrap();
+
+        append_child_observed(&root, element_node("p")).unwrap();
+        append_child_observed(&root, element_node("p")).unwrap();
+        assert!(delivered.lock().is_empty());
+        assert_eq!(scheduler.pending_microtasks(), 1);
+
+        assert_eq!(scheduler.run_microtasks(), 1);
+        assert_eq!(*delivered.lock(), [2]);
+
+        // The next record schedules the next delivery
+        append_child_observed(&root, element_node("p")).unwrap();
+        assert_eq!(scheduler.run_microtasks(), 1);
+        assert_eq!(*delivered.lock(), [2, 1]);
+    }
+
+    #[test]
+    fn test_take_records_from_callback() {
+        let handle: Arc<Mutex<Option<MutationObserver>>> = Arc::default();
+        let taken = Arc::new(Mutex::new(None));
+        let (handle_clone, taken_clone) = (handle.clone(), taken.clone());
+        let observer = MutationObserver::new(move |records| {
+            assert_eq!(records.len(), 1);
+            let observer = handle_clone.lock().clone().unwrap();
+            *taken_clone.lock() = Some(observer.take_records().len());
+        });
+        *handle.lock() = Some(observer.clone());
+
+        let root = element_node("div");
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    child_list: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        append_child_observed(&root, element_node("p")).unwrap();
+
+        observer.deliver_mutations();
+        assert_eq!(*taken.lock(), Some(0));
+        handle.lock().take();
+    }
+
+    #[test]
+    fn test_disconnect_then_observe_again() {
+        let options = MutationObserverInit {
+            child_list: true,
+            ..Default::default()
+        };
+        let root = element_node("div");
+        let observer = MutationObserver::new(|_| {});
+        observer.observe(root.clone(), options.clone()).unwrap();
+
+        append_child_observed(&root, element_node("p")).unwrap();
+        observer.disconnect();
+        assert!(observer.take_records().is_empty());
+
+        append_child_observed(&root, element_node("p")).unwrap();
+        assert!(observer.take_records().is_empty());
+
+        observer.observe(root.clone(), options).unwrap();
+        append_child_observed(&root, element_node("p")).unwrap();
+        assert_eq!(observer.take_records().len(), 1);
+    }
+
+    #[test]
+    fn test_disconnect_releases_targets() {
+        let root = element_node("div");
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    child_list: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        append_child_observed(&root, element_node("p")).unwrap();
+
+        observer.disconnect();
+        let weak = Arc::downgrade(&root);
+        drop(root);
+        assert!(weak.upgrade().is_none());
+    }
+
+    fn text_node(data: &str) -> NodeRef {
+        use dom_core::{new_node_ref, Text};
+
+        new_node_ref(Text::new(data))
+    }
+
+    /// Element that is the root of a new document, which reports the edits
+    /// to its text
+    fn element_in_document(tag: &str) -> (NodeRef, NodeRef) {
+        use dom_core::{new_node_ref, Document};
+
+        let document = new_node_ref(Document::new());
+        let root = element_node(tag);
+        document.write().append_child(root.clone()).unwrap();
+        (document, root)
+    }
+
+    #[test]
+    fn test_character_data_records_for_descendant_text() {
+        use dom_core::{CharacterData, Text};
+
+        let (_document, root) = element_in_document("div");
+        let paragraph = element_node("p");
+        let text = text_node("Hello");
+        root.write().append_child(paragraph.clone()).unwrap();
+        paragraph.write().append_child(text.clone()).unwrap();
+
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    character_data: true,
+                    character_data_old_value: true,
+                    subtree: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        text.write().set_node_value(Some("Hi".to_string()));
+        text.write()
+            .as_any_mut()
+            .downcast_mut::<Text>()
+            .unwrap()
+            .append_data(" there");
+
+        let records = observer.take_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].record_type, MutationType::CharacterData);
+        assert!(Arc::ptr_eq(&records[0].target, &text));
+        assert_eq!(records[0].old_value.as_deref(), Some("Hello"));
+        assert_eq!(records[1].old_value.as_deref(), Some("Hi"));
+        assert_eq!(text.read().node_value(), Some("Hi there"));
+
+        // Text outside the observed subtree is not reported
+        let detached = text_node("detached");
+        detached.write().set_text_content("x".to_string());
+        assert!(observer.take_records().is_empty());
+    }
+
+    #[test]
+    fn test_normalize_under_active_observer() {
+        let (_document, root) = element_in_document("div");
+        let paragraph = element_node("p");
+        root.write().append_child(paragraph.clone()).unwrap();
+        let first = text_node("Hello");
+        paragraph.write().append_child(first.clone()).unwrap();
+        let second = text_node(", world");
+        paragraph.write().append_child(second).unwrap();
+
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    character_data: true,
+                    character_data_old_value: true,
+                    subtree: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // Merging the text runs under the parent's write lock
+        paragraph.write().normalize();
+
+        assert_eq!(first.read().node_value(), Some("Hello, world"));
+        let records = observer.take_records();
+        assert_eq!(records.len(), 1);
+        assert!(Arc::ptr_eq(&records[0].target, &first));
+        assert_eq!(records[0].old_value.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_character_data_old_value_only_when_requested() {
+        let (_document, root) = element_in_document("div");
+        let text = text_node("before");
+        root.write().append_child(text.clone()).unwrap();
+
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    character_data: true,
+                    subtree: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        text.write().set_text_content("after".to_string());
+
+        let records = observer.take_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].old_value, None);
+    }
+
+    #[test]
+    fn test_scheduler_delivers_text_only_edits() {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let delivered_clone = delivered.clone();
+        let scheduler = dom_events::ManualScheduler::new_ref();
+        let observer = MutationObserver::with_scheduler(
+            move |records| delivered_clone.lock().push(records.len()),
+            scheduler.clone(),
+        );
+        let (_document, root) = element_in_document("p");
+        let text = text_node("Hello");
+        root.write().append_child(text.clone()).unwrap();
+        let options = MutationObserverInit {
+            character_data: true,
+            subtree: true,
+            ..Default::default()
+        };
+        observer.observe(root.clone(), options).unwrap();
+
+        text.write().set_text_content("Hi".to_string());
+        text.write().set_text_content("Hey".to_string());
+        assert_eq!(scheduler.pending_microtasks(), 1);
+        assert_eq!(scheduler.run_microtasks(), 1);
+        assert_eq!(*delivered.lock(), [2]);
+    }
+
+    #[test]
+    fn test_character_data_hooks_are_per_document() {
+        let (_document, root) = element_in_document("div");
+        let observer = MutationObserver::new(|_| {});
+        let options = MutationObserverInit {
+            character_data: true,
+            subtree: true,
+            ..Default::default()
+        };
+        observer.observe(root.clone(), options).unwrap();
+
+        // Edits in another document are not held on to
+        let (_other, other_root) = element_in_document("div");
+        let text = text_node("Hello");
+        other_root.write().append_child(text.clone()).unwrap();
+        let count = Arc::strong_count(&text);
+        text.write().set_text_content("Hi".to_string());
+        assert_eq!(Arc::strong_count(&text), count);
+
+        // Nor, once the observer is gone, edits in the observed one
+        let observed_text = text_node("Hello");
+        root.write().append_child(observed_text.clone()).unwrap();
+        drop(observer);
+        let count = Arc::strong_count(&observed_text);
+        observed_text.write().set_text_content("Hi".to_string());
+        assert_eq!(Arc::strong_count(&observed_text), count);
+    }
+
+    #[test]
+    fn test_child_list_records_capture_nodes_and_siblings() {
+        let root = element_node("ul");
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    child_list: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let first = element_node("li");
+        let second = element_node("li");
+        append_child_observed(&root, first.clone()).unwrap();
+        append_child_observed(&root, second.clone()).unwrap();
+        remove_child_observed(&root, first.clone()).unwrap();
+
+        let records = observer.take_records();
+        assert_eq!(records.len(), 3);
+        let types: Vec<MutationType> = records.iter().map(|r| r.record_type).collect();
+        assert_eq!(types, [MutationType::ChildList; 3]);
+
+        let added = records[0].added_nodes();
+        assert_eq!(added.length(), 1);
+        assert!(Arc::ptr_eq(&added.item(0).unwrap(), &first));
+        assert!(records[0].previous_sibling().is_none());
+        assert!(records[0].next_sibling().is_none());
+
+        let added = records[1].added_nodes();
+        assert!(Arc::ptr_eq(&added.item(0).unwrap(), &second));
+        assert!(Arc::ptr_eq(records[1].previous_sibling().unwrap(), &first));
+        assert!(records[1].next_sibling().is_none());
+
+        let removed = records[2].removed_nodes();
+        assert_eq!(removed.length(), 1);
+        assert!(Arc::ptr_eq(&removed.item(0).unwrap(), &first));
+        assert_eq!(records[2].added_nodes().length(), 0);
+        assert!(records[2].previous_sibling().is_none());
+        assert!(Arc::ptr_eq(records[2].next_sibling().unwrap(), &second));
+    }
+
+    #[test]
+    fn test_insert_before_records_reference_siblings() {
+        let root = element_node("div");
+        let a = element_node("a");
+        let c = element_node("i");
+        root.write().append_child(a.clone()).unwrap();
+        root.write().append_child(c.clone()).unwrap();
+        let observer = MutationObserver::new(|_| {});
+        observer
+            .observe(
+                root.clone(),
+                MutationObserverInit {
+                    child_list: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let b = element_node("b");
+        insert_before_observed(&root, b.clone(), Some(c.clone())).unwrap();
+
+        let records = observer.take_records();
+        assert_eq!(records.len(), 1);
+        assert!(Arc::ptr_eq(records[0].previous_sibling().unwrap(), &a));
+        assert!(Arc::ptr_eq(records[0].next_sibling().unwrap(), &c));
+        assert!(Arc::ptr_eq(&root.read().child_nodes()[1], &b));
+
+        assert!(matches!(
+            remove_child_observed(&root, element_node("p")),
+            Err(DomException::NotFoundError)
+        ));
+        assert!(observer.take_records().is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 //! MutationRecord and related types
 
+use dom_collections::NodeList;
 use dom_core::NodeRef;
 
 /// Type of mutation that occurred
@@ -111,4 +112,24 @@ impl MutationRecord {
             old_value,
         }
     }
+
+    /// Gets the nodes added by a childList mutation as a static list
+    pub fn added_nodes(&self) -> NodeList {
+        NodeList::new_static(self.added_nodes.clone())
+    }
+
+    /// Gets the nodes removed by a childList mutation as a static list
+    pub fn removed_nodes(&self) -> NodeList {
+        NodeList::new_static(self.removed_nodes.clone())
+    }
+
+    /// Gets the sibling before the added or removed nodes when they changed
+    pub fn previous_sibling(&self) -> Option<&NodeRef> {
+        self.previous_sibling.as_ref()
+    }
+
+    /// Gets the sibling after the added or removed nodes when they changed
+    pub fn next_sibling(&self) -> Option<&NodeRef> {
+        self.next_sibling.as_ref()
+    }
 }
@@ -2,11 +2,29 @@
 //!
 //! Represents a fragment of a document that can contain nodes and parts of text nodes.
 
-use dom_core::NodeRef;
-use dom_types::{DomException, NodeType};
+use crate::element_ext::border_box;
+use crate::geometry::{DOMRect, DOMRectList, RangeGeometryProvider};
+use dom_core::{compare_document_position, contains, DocumentPosition, Element, NodeRef};
+use dom_types::{DomException, LayoutMetrics, NodeType};
 use std::cmp::Ordering;
 use std::sync::Arc;
 
+/// Pair of boundary points compared by [`Range::compare_boundary_points`]
+///
+/// The first half of each name picks the point of the source range and the
+/// second the point of the range being asked, as in the DOM constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeCompare {
+    /// This range's start against the source's start (`START_TO_START`)
+    StartToStart,
+    /// This range's end against the source's start (`START_TO_END`)
+    StartToEnd,
+    /// This range's end against the source's end (`END_TO_END`)
+    EndToEnd,
+    /// This range's start against the source's end (`END_TO_START`)
+    EndToStart,
+}
+
 /// A Range represents a fragment of a document
 #[derive(Debug, Clone)]
 pub struct Range {
@@ -14,26 +32,27 @@ pub struct Range {
     start_offset: usize,
     end_container: NodeRef,
     end_offset: usize,
+    geometry: Option<Arc<dyn RangeGeometryProvider>>,
 }
 
 impl Range {
     /// Create a new collapsed Range
     ///
-    /// Note: In a real implementation, Range would be associated with a document.
-    /// For simplicity, we create a detached range that can be positioned later.
-    pub fn new(_document: &dom_core::Document) -> Self {
+    /// The range reports text boxes through the document's
+    /// [`RangeGeometryProvider`], if one is installed. It starts out detached
+    /// and is positioned with `set_start` and `set_end`.
+    pub fn new(document: &dom_core::Document) -> Self {
         // Create a dummy node reference for initialization
         // In practice, this should be set via set_start/set_end
         let dummy_text = dom_core::Text::new("");
-        let node_ref = std::sync::Arc::new(parking_lot::RwLock::new(
-            Box::new(dummy_text) as Box<dyn dom_core::Node>
-        ));
+        let node_ref = std::sync::Arc::new(parking_lot::RwLock::new(dummy_text));
 
         Self {
             start_container: node_ref.clone(),
             start_offset: 0,
             end_container: node_ref,
             end_offset: 0,
+            geometry: document.range_geometry_provider().cloned(),
         }
     }
 
@@ -68,8 +87,9 @@ impl Range {
             return self.start_container.clone();
         }
 
-        // Find common ancestor by traversing up from start
-        let mut ancestors = Vec::new();
+        // Collect the inclusive ancestors of start, since either container
+        // may itself contain the other
+        let mut ancestors = vec![self.start_container.clone()];
         let mut current = self.start_container.clone();
         loop {
             let parent = current.read().parent_node();
@@ -84,6 +104,9 @@ impl Range {
 
         // Traverse up from end until we find a common ancestor
         let mut current = self.end_container.clone();
+        if ancestors.iter().any(|a| Arc::ptr_eq(a, &current)) {
+            return current;
+        }
         loop {
             let parent = current.read().parent_node();
             match parent {
@@ -111,8 +134,8 @@ impl Range {
         self.start_container = node;
         self.start_offset = offset;
 
-        // If start is after end, collapse to start
-        if self.compare_boundary_points(&self.start_container, self.start_offset, &self.end_container, self.end_offset) == Ordering::Greater {
+        // If start is after end or in another tree, collapse to start
+        if !self.is_ordered() {
             self.end_container = self.start_container.clone();
             self.end_offset = self.start_offset;
         }
@@ -130,8 +153,8 @@ impl Range {
         self.end_container = node;
         self.end_offset = offset;
 
-        // If end is before start, collapse to end
-        if self.compare_boundary_points(&self.start_container, self.start_offset, &self.end_container, self.end_offset) == Ordering::Greater {
+        // If end is before start or in another tree, collapse to end
+        if !self.is_ordered() {
             self.start_container = self.end_container.clone();
             self.start_offset = self.end_offset;
         }
@@ -139,6 +162,30 @@ impl Range {
         Ok(())
     }
 
+    /// Compare a boundary point of this range with one of `source`
+    ///
+    /// Returns -1, 0 or 1 as this range's point is before, equal to or after
+    /// the source's point in tree order; `how` picks the two points.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WrongDocumentError` if the ranges are in different trees.
+    pub fn compare_boundary_points(
+        &self,
+        how: RangeCompare,
+        source: &Range,
+    ) -> Result<i8, DomException> {
+        let ((node, offset), (source_node, source_offset)) = match how {
+            RangeCompare::StartToStart => (self.start(), source.start()),
+            RangeCompare::StartToEnd => (self.end(), source.start()),
+            RangeCompare::EndToEnd => (self.end(), source.end()),
+            RangeCompare::EndToStart => (self.start(), source.end()),
+        };
+        compare_points(node, offset, source_node, source_offset)
+            .map(|order| order as i8)
+            .ok_or(DomException::WrongDocumentError)
+    }
+
     /// Set both boundary points to the same position
     pub fn collapse(&mut self, to_start: bool) {
         if to_start {
@@ -199,9 +246,7 @@ impl Range {
 
                     // Create a text node with extracted content
                     let text_node = dom_core::Text::new(&extracted);
-                    let text_ref = std::sync::Arc::new(parking_lot::RwLock::new(
-                        Box::new(text_node) as Box<dyn dom_core::Node>
-                    ));
+                    let text_ref = std::sync::Arc::new(parking_lot::RwLock::new(text_node));
                     fragment.append_child(text_ref)?;
 
                     // Remove extracted text from original node
@@ -270,9 +315,7 @@ impl Range {
                         .collect::<String>();
 
                     let text_node = dom_core::Text::new(&cloned);
-                    let text_ref = std::sync::Arc::new(parking_lot::RwLock::new(
-                        Box::new(text_node) as Box<dyn dom_core::Node>
-                    ));
+                    let text_ref = std::sync::Arc::new(parking_lot::RwLock::new(text_node));
                     fragment.append_child(text_ref)?;
                 }
             }
@@ -281,8 +324,117 @@ impl Range {
         Ok(fragment)
     }
 
+    /// Get the boxes of the content selected by the range
+    ///
+    /// Each element that lies wholly inside the range and has been laid out
+    /// contributes its border box, taken from its layout metrics, in place of
+    /// the boxes of its descendants. The selected parts of the remaining text
+    /// nodes contribute the boxes reported by the
+    /// [`RangeGeometryProvider`] of the document the range was created from,
+    /// and nothing when it has none.
+    ///
+    /// A collapsed range selects nothing: it yields the zero-width caret box
+    /// at its position when the provider reports one, and an empty list
+    /// otherwise.
+    pub fn get_client_rects(&self) -> DOMRectList {
+        let provider = self.geometry.as_ref();
+
+        if self.collapsed() {
+            let caret = provider
+                .and_then(|provider| provider.caret_rect(&self.start_container, self.start_offset))
+                .map(|caret| DOMRect::new(caret.x, caret.y, 0.0, caret.height));
+            return DOMRectList::from_vec(caret.into_iter().collect());
+        }
+
+        let mut selected = Vec::new();
+        let mut inside = false;
+        let root = self.common_ancestor_container();
+        self.collect_selected_boxes(&root, &mut inside, &mut selected);
+        DOMRectList::from_vec(
+            selected
+                .into_iter()
+                .flat_map(|piece| match piece {
+                    SelectedBox::Element(rect) => vec![rect],
+                    SelectedBox::Text(text, start, end) => provider
+                        .map(|provider| provider.text_rects(&text, start, end))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(DOMRect::from)
+                        .collect(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the smallest box enclosing all of [`get_client_rects`](Self::get_client_rects)
+    ///
+    /// A collapsed range yields its zero-width caret box. When the range has
+    /// no boxes at all the result is an empty rect at the origin.
+    pub fn get_bounding_client_rect(&self) -> DOMRect {
+        let rects = self.get_client_rects();
+        let mut rects = rects.iter();
+        let Some(first) = rects.next() else {
+            return DOMRect::default();
+        };
+        let edges = (first.left(), first.top(), first.right(), first.bottom());
+        let (left, top, right, bottom) = rects.fold(edges, |(left, top, right, bottom), rect| {
+            (
+                left.min(rect.left()),
+                top.min(rect.top()),
+                right.max(rect.right()),
+                bottom.max(rect.bottom()),
+            )
+        });
+        DOMRect::from_rect(left, top, right, bottom)
+    }
+
     // Helper methods
 
+    /// Collects what is selected below `node`, in tree order
+    ///
+    /// `inside` tracks whether the walk is past the start boundary. Returns
+    /// `false` once the end boundary has been reached.
+    fn collect_selected_boxes(
+        &self,
+        node: &NodeRef,
+        inside: &mut bool,
+        selected: &mut Vec<SelectedBox>,
+    ) -> bool {
+        let is_start = Arc::ptr_eq(node, &self.start_container);
+        let is_end = Arc::ptr_eq(node, &self.end_container);
+
+        if node.read().node_type() == NodeType::Text {
+            let length = self.get_node_length(node);
+            let start = if is_start { self.start_offset } else { 0 };
+            let end = if is_end { self.end_offset } else { length };
+            if (*inside || is_start) && start < end {
+                selected.push(SelectedBox::Text(node.clone(), start, end));
+            }
+            *inside |= is_start;
+            return !is_end;
+        }
+
+        let children = node.read().child_nodes();
+        for (index, child) in children.iter().enumerate() {
+            *inside |= is_start && index == self.start_offset;
+            if is_end && index == self.end_offset {
+                return false;
+            }
+            let (was_inside, first) = (*inside, selected.len());
+            if !self.collect_selected_boxes(child, inside, selected) {
+                return false;
+            }
+            // The child lies wholly inside the range, so its own box covers
+            // everything collected below it
+            if let Some(rect) = was_inside.then(|| laid_out_border_box(child)).flatten() {
+                selected.truncate(first);
+                selected.push(SelectedBox::Element(rect));
+            }
+        }
+        *inside |= is_start && children.len() == self.start_offset;
+        !is_end
+    }
+
     fn validate_boundary_point(&self, node: &NodeRef, offset: usize) -> Result<(), DomException> {
         match node.read().node_type() {
             NodeType::DocumentType => {
@@ -309,33 +461,90 @@ impl Range {
         }
     }
 
-    fn compare_boundary_points(
-        &self,
-        a_node: &NodeRef,
-        a_offset: usize,
-        b_node: &NodeRef,
-        b_offset: usize,
-    ) -> Ordering {
-        if Arc::ptr_eq(a_node, b_node) {
-            return a_offset.cmp(&b_offset);
+    fn start(&self) -> (&NodeRef, usize) {
+        (&self.start_container, self.start_offset)
+    }
+
+    fn end(&self) -> (&NodeRef, usize) {
+        (&self.end_container, self.end_offset)
+    }
+
+    /// Checks that the start is in the same tree as the end and not after it
+    fn is_ordered(&self) -> bool {
+        let ((start, start_offset), (end, end_offset)) = (self.start(), self.end());
+        compare_points(start, start_offset, end, end_offset).is_some_and(Ordering::is_le)
+    }
+}
+
+/// Orders the boundary point `(a, a_offset)` against `(b, b_offset)`
+///
+/// Returns `None` when the nodes are in different trees.
+fn compare_points(a: &NodeRef, a_offset: usize, b: &NodeRef, b_offset: usize) -> Option<Ordering> {
+    if Arc::ptr_eq(a, b) {
+        return Some(a_offset.cmp(&b_offset));
+    }
+
+    let position = compare_document_position(a, b);
+    if position & DocumentPosition::Disconnected as u16 != 0 {
+        return None;
+    }
+    let a_precedes_b =
+        contains(a, b) || (!contains(b, a) && position & DocumentPosition::Following as u16 != 0);
+    if !a_precedes_b {
+        return compare_points(b, b_offset, a, a_offset).map(Ordering::reverse);
+    }
+
+    // A point in `a` lies after `b` when it is past the child holding `b`
+    if contains(a, b) {
+        let mut child = b.clone();
+        loop {
+            let parent = child.read().parent_node();
+            match parent {
+                Some(parent) if Arc::ptr_eq(&parent, a) => break,
+                Some(parent) => child = parent,
+                None => return None,
+            }
+        }
+        let index = a
+            .read()
+            .child_nodes()
+            .iter()
+            .position(|sibling| Arc::ptr_eq(sibling, &child));
+        if index.is_some_and(|index| index < a_offset) {
+            return Some(Ordering::Greater);
         }
+    }
+    Some(Ordering::Less)
+}
+
+/// Part of a range's selection that has boxes of its own
+enum SelectedBox {
+    /// Border box of an element wholly inside the range
+    Element(DOMRect),
+    /// Selected characters `start..end` of a text node
+    Text(NodeRef, usize, usize),
+}
 
-        // Simplified: just compare by document order
-        // A full implementation would need proper tree position comparison
-        Ordering::Equal
+/// Gets the border box of `node` if it is an element that has been laid out
+fn laid_out_border_box(node: &NodeRef) -> Option<DOMRect> {
+    let node = node.read();
+    let metrics = node.as_any().downcast_ref::<Element>()?.layout_metrics();
+    if *metrics == LayoutMetrics::default() {
+        return None;
     }
+    Some(border_box(metrics))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::{Document, Node, Text};
+    use dom_core::{new_node_ref, Document, Text};
     use std::sync::Arc;
     use parking_lot::RwLock;
 
     fn create_text_node_ref(content: &str) -> NodeRef {
         let text = Text::new(content);
-        Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>))
+        Arc::new(RwLock::new(text))
     }
 
     #[test]
@@ -414,4 +623,266 @@ mod tests {
         // Original text should be unchanged
         assert_eq!(text_ref.read().text_content(), Some("Hello World".to_string()));
     }
+
+    /// Lays text out in 8px wide characters on one line, recording requests
+    #[derive(Debug, Default)]
+    struct MockGeometry {
+        with_caret: bool,
+        requests: std::sync::Mutex<Vec<(String, usize, usize)>>,
+    }
+
+    impl RangeGeometryProvider for MockGeometry {
+        fn text_rects(&self, text: &NodeRef, start: usize, end: usize) -> Vec<dom_types::DOMRect> {
+            let data = text.read().text_content().unwrap_or_default();
+            self.requests.lock().unwrap().push((data, start, end));
+            let (x, width) = (start as f64 * 8.0, (end - start) as f64 * 8.0);
+            vec![dom_types::DOMRect::new(x, 0.0, width, 16.0)]
+        }
+
+        fn caret_rect(&self, _node: &NodeRef, offset: usize) -> Option<dom_types::DOMRect> {
+            self.with_caret
+                .then(|| dom_types::DOMRect::new(offset as f64 * 8.0, 0.0, 1.0, 16.0))
+        }
+    }
+
+    /// Creates a document whose ranges lay text out with `geometry`
+    fn document_with(geometry: MockGeometry) -> (Document, Arc<MockGeometry>) {
+        let geometry = Arc::new(geometry);
+        let mut doc = Document::new();
+        doc.set_range_geometry_provider(Some(geometry.clone()));
+        (doc, geometry)
+    }
+
+    #[test]
+    fn test_collapsed_range_client_rects() {
+        let text_ref = create_text_node_ref("Hello");
+        let caret_at_3 = |doc: &Document| {
+            let mut range = Range::new(doc);
+            range.set_start(text_ref.clone(), 3).unwrap();
+            range.set_end(text_ref.clone(), 3).unwrap();
+            range
+        };
+
+        assert_eq!(caret_at_3(&Document::new()).get_client_rects().length(), 0);
+
+        let (doc, _) = document_with(MockGeometry::default());
+        assert_eq!(caret_at_3(&doc).get_client_rects().length(), 0);
+
+        let (doc, geometry) = document_with(MockGeometry {
+            with_caret: true,
+            ..Default::default()
+        });
+        let rects = caret_at_3(&doc).get_client_rects();
+        assert_eq!(rects.length(), 1);
+        assert_eq!(rects.item(0), Some(&DOMRect::new(24.0, 0.0, 0.0, 16.0)));
+        assert!(geometry.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_range_client_rects_cover_selected_text() {
+        // <p>Hello <b>big</b> world</p>
+        let paragraph = new_node_ref(dom_core::Element::new("p"));
+        let bold = new_node_ref(dom_core::Element::new("b"));
+        let hello = new_node_ref(Text::new("Hello "));
+        let big = new_node_ref(Text::new("big"));
+        let world = new_node_ref(Text::new(" world"));
+        bold.write().append_child(big).unwrap();
+        for child in [&hello, &bold, &world] {
+            paragraph.write().append_child(child.clone()).unwrap();
+        }
+        let (doc, geometry) = document_with(MockGeometry::default());
+
+        let mut range = Range::new(&doc);
+        range.set_start(hello.clone(), 1).unwrap();
+        range.set_end(hello.clone(), 4).unwrap();
+        let rects = range.get_client_rects();
+        assert_eq!(rects.length(), 1);
+        assert_eq!(rects.item(0), Some(&DOMRect::new(8.0, 0.0, 24.0, 16.0)));
+
+        geometry.requests.lock().unwrap().clear();
+        range.set_start(hello, 2).unwrap();
+        range.set_end(world, 3).unwrap();
+        assert_eq!(range.get_client_rects().length(), 3);
+        assert_eq!(
+            *geometry.requests.lock().unwrap(),
+            [
+                ("Hello ".to_string(), 2, 6),
+                ("big".to_string(), 0, 3),
+                (" world".to_string(), 0, 3),
+            ]
+        );
+    }
+
+    fn laid_out(tag: &str, x: f64, y: f64, width: f64, height: f64) -> NodeRef {
+        let mut element = dom_core::Element::new(tag);
+        element.set_layout_metrics(LayoutMetrics {
+            x,
+            y,
+            offset_width: width,
+            offset_height: height,
+            ..LayoutMetrics::default()
+        });
+        new_node_ref(element)
+    }
+
+    #[test]
+    fn test_range_bounding_rect_encloses_selected_elements() {
+        // <div><p>one</p><p>two</p></div>
+        let div = new_node_ref(dom_core::Element::new("div"));
+        let first = laid_out("p", 10.0, 20.0, 100.0, 30.0);
+        let second = laid_out("p", 10.0, 60.0, 150.0, 30.0);
+        let one = new_node_ref(Text::new("one"));
+        first.write().append_child(one.clone()).unwrap();
+        second
+            .write()
+            .append_child(new_node_ref(Text::new("two")))
+            .unwrap();
+        for child in [&first, &second] {
+            div.write().append_child(child.clone()).unwrap();
+        }
+        let doc = Document::new();
+
+        let mut range = Range::new(&doc);
+        range.set_start(div.clone(), 0).unwrap();
+        range.set_end(div.clone(), 2).unwrap();
+        let rects = range.get_client_rects();
+        assert_eq!(rects.length(), 2);
+        assert_eq!(rects.item(1), Some(&DOMRect::new(10.0, 60.0, 150.0, 30.0)));
+        assert_eq!(
+            range.get_bounding_client_rect(),
+            DOMRect::from_rect(10.0, 20.0, 160.0, 90.0)
+        );
+
+        // The partially selected paragraph contributes its text instead
+        let (doc, _) = document_with(MockGeometry::default());
+        let mut range = Range::new(&doc);
+        range.set_start(one, 1).unwrap();
+        range.set_end(div.clone(), 2).unwrap();
+        let rects = range.get_client_rects();
+        assert_eq!(rects.length(), 2);
+        assert_eq!(rects.item(0), Some(&DOMRect::new(8.0, 0.0, 16.0, 16.0)));
+        assert_eq!(
+            range.get_bounding_client_rect(),
+            DOMRect::from_rect(8.0, 0.0, 160.0, 90.0)
+        );
+    }
+
+    #[test]
+    fn test_range_over_element_matches_element_rect() {
+        use crate::ElementGeometryExt;
+
+        let div = new_node_ref(dom_core::Element::new("div"));
+        let element = Arc::new(RwLock::new(dom_core::Element::new("p")));
+        element.set_layout_metrics(LayoutMetrics {
+            x: 5.0,
+            y: 40.0,
+            offset_width: 200.0,
+            offset_height: 24.0,
+            ..LayoutMetrics::default()
+        });
+        let node: NodeRef = element.clone();
+        div.write().append_child(node).unwrap();
+
+        let doc = Document::new();
+        let mut range = Range::new(&doc);
+        range.set_start(div.clone(), 0).unwrap();
+        range.set_end(div, 1).unwrap();
+        assert_eq!(
+            range.get_bounding_client_rect(),
+            element.get_bounding_client_rect()
+        );
+    }
+
+    #[test]
+    fn test_collapsed_range_bounding_rect_has_zero_width() {
+        let text_ref = create_text_node_ref("Hello");
+        let caret_at_3 = |doc: &Document| {
+            let mut range = Range::new(doc);
+            range.set_start(text_ref.clone(), 3).unwrap();
+            range.set_end(text_ref.clone(), 3).unwrap();
+            range
+        };
+
+        assert_eq!(
+            caret_at_3(&Document::new()).get_bounding_client_rect(),
+            DOMRect::default()
+        );
+
+        let (doc, _) = document_with(MockGeometry {
+            with_caret: true,
+            ..Default::default()
+        });
+        let rect = caret_at_3(&doc).get_bounding_client_rect();
+        assert_eq!(rect, DOMRect::new(24.0, 0.0, 0.0, 16.0));
+        assert_eq!(rect.width, 0.0);
+    }
+
+    fn range(start: (&NodeRef, usize), end: (&NodeRef, usize)) -> Range {
+        let mut range = Range::new(&Document::new());
+        range.set_start(start.0.clone(), start.1).unwrap();
+        range.set_end(end.0.clone(), end.1).unwrap();
+        range
+    }
+
+    fn compare_all(range: &Range, source: &Range) -> [i8; 4] {
+        [
+            RangeCompare::StartToStart,
+            RangeCompare::StartToEnd,
+            RangeCompare::EndToEnd,
+            RangeCompare::EndToStart,
+        ]
+        .map(|how| range.compare_boundary_points(how, source).unwrap())
+    }
+
+    #[test]
+    fn test_compare_boundary_points_in_tree_order() {
+        // <div><p>one</p><p>two</p></div>
+        let div = new_node_ref(dom_core::Element::new("div"));
+        let one = new_node_ref(Text::new("one"));
+        let two = new_node_ref(Text::new("two"));
+        for text in [&one, &two] {
+            let paragraph = new_node_ref(dom_core::Element::new("p"));
+            paragraph.write().append_child(text.clone()).unwrap();
+            div.write().append_child(paragraph).unwrap();
+        }
+
+        let outer = range((&div, 0), (&div, 2));
+        let nested = range((&one, 1), (&two, 1));
+        let overlapping = range((&two, 0), (&div, 2));
+
+        assert_eq!(compare_all(&outer, &outer), [0, 1, 0, -1]);
+        assert_eq!(compare_all(&outer, &nested), [-1, 1, 1, -1]);
+        assert_eq!(compare_all(&nested, &outer), [1, 1, -1, -1]);
+        assert_eq!(compare_all(&nested, &overlapping), [-1, 1, -1, -1]);
+        assert_eq!(compare_all(&overlapping, &nested), [1, 1, 1, -1]);
+    }
+
+    #[test]
+    fn test_common_ancestor_container_includes_either_container() {
+        // <div><p>text</p></div>
+        let div = new_node_ref(dom_core::Element::new("div"));
+        let paragraph = new_node_ref(dom_core::Element::new("p"));
+        let text = new_node_ref(Text::new("text"));
+        paragraph.write().append_child(text.clone()).unwrap();
+        div.write().append_child(paragraph.clone()).unwrap();
+
+        let ancestor = range((&div, 0), (&text, 2)).common_ancestor_container();
+        assert!(Arc::ptr_eq(&ancestor, &div));
+
+        let ancestor = range((&text, 2), (&paragraph, 1)).common_ancestor_container();
+        assert!(Arc::ptr_eq(&ancestor, &paragraph));
+    }
+
+    #[test]
+    fn test_compare_boundary_points_across_trees() {
+        let here = create_text_node_ref("here");
+        let there = create_text_node_ref("there");
+        let here = range((&here, 0), (&here, 4));
+        let there = range((&there, 1), (&there, 3));
+
+        assert_eq!(
+            here.compare_boundary_points(RangeCompare::StartToStart, &there),
+            Err(DomException::WrongDocumentError)
+        );
+    }
 }
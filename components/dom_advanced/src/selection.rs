@@ -218,13 +218,13 @@ impl Default for Selection {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::{Document, Node, Text};
+    use dom_core::{Document, Text};
     use std::sync::Arc;
     use parking_lot::RwLock;
 
     fn create_text_node_ref(content: &str) -> NodeRef {
         let text = Text::new(content);
-        Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>))
+        Arc::new(RwLock::new(text))
     }
 
     #[test]
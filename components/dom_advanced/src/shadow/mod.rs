@@ -5,5 +5,5 @@
 mod shadow_root;
 mod slot;
 
-pub use shadow_root::{ShadowRoot, ShadowRootMode};
-pub use slot::{SlotAssignmentMode, SlotElement};
+pub use shadow_root::{ShadowRoot, ShadowRootInit, ShadowRootMode};
+pub use slot::{assigned_slot, SlotAssignmentMode, SlotElement};
@@ -1,8 +1,11 @@
 //! ShadowRoot implementation
 
-use super::slot::SlotAssignmentMode;
-use dom_core::{ElementRef, Node, NodeRef};
-use dom_types::DomException;
+use super::slot::{SlotAssignmentMode, SlotElement};
+use dom_core::{
+    new_node_ref, DocumentFragment, ElementRef, IdIndex, IdIndexRef, Node, NodeRef,
+    ShadowRootContent,
+};
+use dom_types::{DomException, NodeType};
 use std::sync::Weak;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -16,6 +19,31 @@ pub enum ShadowRootMode {
     Closed,
 }
 
+/// Options for attaching a shadow root (`ShadowRootInit`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShadowRootInit {
+    /// Encapsulation mode
+    pub mode: ShadowRootMode,
+    /// Move focus to the first focusable shadow descendant when the host is focused
+    pub delegates_focus: bool,
+    /// How slottables are assigned to slots
+    pub slot_assignment: SlotAssignmentMode,
+    /// Include the shadow root in opt-in serialization
+    pub serializable: bool,
+}
+
+impl ShadowRootInit {
+    /// Options with the given mode and all other fields at their defaults
+    pub fn new(mode: ShadowRootMode) -> Self {
+        Self {
+            mode,
+            delegates_focus: false,
+            slot_assignment: SlotAssignmentMode::Named,
+            serializable: false,
+        }
+    }
+}
+
 /// A shadow root for encapsulated DOM subtrees
 #[derive(Clone)]
 pub struct ShadowRoot {
@@ -27,8 +55,12 @@ struct ShadowRootInner {
     mode: ShadowRootMode,
     delegates_focus: bool,
     slot_assignment: SlotAssignmentMode,
-    // The shadow root acts as a document fragment
-    children: Vec<NodeRef>,
+    serializable: bool,
+    // Fragment holding the shadow tree's children, with the host recorded on it
+    fragment: NodeRef,
+    slots: Vec<SlotElement>,
+    // Ids are scoped to the shadow tree, separate from the host's document
+    id_index: IdIndexRef,
 }
 
 impl ShadowRoot {
@@ -39,15 +71,36 @@ impl ShadowRoot {
         delegates_focus: bool,
         slot_assignment: SlotAssignmentMode,
     ) -> Self {
-        Self {
+        let mut root = DocumentFragment::new();
+        root.set_host(Some(Arc::downgrade(&host)));
+        // Changes to the shadow tree count as changes to the host's document
+        root.set_tree_generations(host.read().generations().as_ref());
+        // The shadow tree counts its depth from its own root
+        root.set_depth_limit(host.read().depth_limit());
+        let fragment = new_node_ref(root);
+
+        let shadow = Self {
             inner: Arc::new(RwLock::new(ShadowRootInner {
                 host: Arc::downgrade(&host),
                 mode,
                 delegates_focus,
                 slot_assignment,
-                children: Vec::new(),
+                serializable: false,
+                fragment,
+                slots: Vec::new(),
+                id_index: IdIndex::new_ref(),
             })),
-        }
+        };
+        host.write()
+            .set_shadow_root_content(Some(Arc::new(shadow.clone())));
+        shadow
+    }
+
+    /// Attach a shadow root to `host` with the given options
+    pub fn attach(host: ElementRef, init: ShadowRootInit) -> Self {
+        let shadow = Self::new(host, init.mode, init.delegates_focus, init.slot_assignment);
+        shadow.set_serializable(init.serializable);
+        shadow
     }
 
     /// Get the host element
@@ -74,99 +127,181 @@ impl ShadowRoot {
         inner.slot_assignment
     }
 
+    /// Check if the shadow root is included by opt-in serialization
+    pub fn serializable(&self) -> bool {
+        let inner = self.inner.read();
+        inner.serializable
+    }
+
+    /// Mark the shadow root as serializable (or not)
+    pub fn set_serializable(&self, serializable: bool) {
+        let mut inner = self.inner.write();
+        inner.serializable = serializable;
+    }
+
     /// Get the shadow root as a node reference
     ///
-    /// In a real implementation, ShadowRoot would implement Node traits
+    /// This is the document fragment at the root of the shadow tree: the
+    /// parent of the root's children, with the host recorded on it.
     pub fn as_node(&self) -> NodeRef {
-        // Placeholder: create a pseudo-node for the shadow root
-        // In reality, this would be a proper Node implementation
-        let host = self.host().unwrap();
-        // Convert ElementRef to NodeRef by boxing the element
-        let element_clone = host.read().clone();
-        Arc::new(RwLock::new(Box::new(element_clone) as Box<dyn Node>))
+        let inner = self.inner.read();
+        inner.fragment.clone()
+    }
+
+    /// Get the shadow root of the tree containing `node`, if it is in one
+    pub fn containing(node: &NodeRef) -> Option<ShadowRoot> {
+        let mut root = node.clone();
+        loop {
+            let parent = root.read().parent_node();
+            match parent {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+
+        let host = {
+            let guard = root.read();
+            guard.as_any().downcast_ref::<DocumentFragment>()?.host()?
+        };
+        let shadow = Self::of_host(&host)?;
+        Arc::ptr_eq(&shadow.as_node(), &root).then_some(shadow)
+    }
+
+    /// Get the shadow root attached to `host`, if any
+    pub fn of_host(host: &ElementRef) -> Option<ShadowRoot> {
+        let content = host.read().shadow_root_content().cloned()?;
+        let shadow = content.as_any().downcast_ref::<ShadowRoot>()?.clone();
+        Some(shadow)
     }
 
     /// Append a child to the shadow root
+    ///
+    /// The child is removed from its old parent, if any, and its elements
+    /// are bound to this tree's id index. A document fragment is replaced by
+    /// its children. Fails with `HierarchyRequestError` if the child may not
+    /// go in a shadow tree, such as the host or one of its ancestors.
     pub fn append_child(&self, child: NodeRef) -> Result<(), DomException> {
-        let mut inner = self.inner.write();
+        let inner = self.inner.read();
 
-        // Check if child is already in the children list
-        if inner.children.iter().any(|c| Arc::ptr_eq(c, &child)) {
-            return Ok(());
-        }
+        // A document fragment is replaced by its children
+        let inserted = if child.read().node_type() == NodeType::DocumentFragment {
+            child.read().child_nodes()
+        } else {
+            vec![child.clone()]
+        };
 
-        inner.children.push(child);
+        inner.fragment.write().append_child(child)?;
+        for node in &inserted {
+            IdIndex::bind_subtree(node, Some(&inner.id_index));
+        }
         Ok(())
     }
 
     /// Get all children of the shadow root
     pub fn children(&self) -> Vec<NodeRef> {
         let inner = self.inner.read();
-        inner.children.clone()
+        let children = inner.fragment.read().child_nodes();
+        children
     }
 
     /// Get the first child
     pub fn first_child(&self) -> Option<NodeRef> {
-        let inner = self.inner.read();
-        inner.children.first().cloned()
+        self.children().first().cloned()
     }
 
     /// Get the last child
     pub fn last_child(&self) -> Option<NodeRef> {
-        let inner = self.inner.read();
-        inner.children.last().cloned()
+        self.children().last().cloned()
     }
 
-    /// Query for an element within the shadow root
-    pub fn query_selector(&self, _selector: &str) -> Option<ElementRef> {
-        // Simplified: would need full CSS selector implementation
-        None
+    /// Add a slot belonging to this shadow tree
+    ///
+    /// Records this root's host on the slot so the flattened tree can be
+    /// walked from slotted content up to the host.
+    pub fn add_slot(&self, slot: &SlotElement) {
+        let mut inner = self.inner.write();
+        slot.set_host(inner.host.clone(), inner.slot_assignment);
+        if !inner
+            .slots
+            .iter()
+            .any(|s| Arc::ptr_eq(&s.element(), &slot.element()))
+        {
+            inner.slots.push(slot.clone());
+        }
     }
 
-    /// Query for all elements within the shadow root
-    pub fn query_selector_all(&self, _selector: &str) -> Vec<ElementRef> {
-        // Simplified: would need full CSS selector implementation
-        Vec::new()
+    /// Get the slots added to this shadow tree
+    pub fn slots(&self) -> Vec<SlotElement> {
+        let inner = self.inner.read();
+        inner.slots.clone()
+    }
+
+    /// Get the id index of the shadow tree
+    ///
+    /// Elements appended to the shadow root are bound to this index and
+    /// found by `get_element_by_id`.
+    pub fn id_index(&self) -> IdIndexRef {
+        let inner = self.inner.read();
+        inner.id_index.clone()
     }
 
     /// Get element by ID within the shadow root
     pub fn get_element_by_id(&self, id: &str) -> Option<ElementRef> {
         let inner = self.inner.read();
-        for child in &inner.children {
-            let node_guard = child.read();
-            if let Some(element) = node_guard.as_any().downcast_ref::<dom_core::Element>() {
-                if let Some(elem_id) = element.get_attribute("id") {
-                    if elem_id == id {
-                        // Create an ElementRef from the child
-                        // Note: This is a simplified conversion
-                        drop(node_guard);
-                        // We can't directly convert NodeRef to ElementRef here
-                        // Return None for now - a proper implementation would need better type handling
-                        return None;
-                    }
-                }
-            }
-        }
-        None
+        let element = inner.id_index.read().get(id);
+        element
     }
 }
 
 impl std::fmt::Debug for ShadowRoot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let inner = self.inner.read();
+        let children_count = inner.fragment.read().child_nodes().len();
         f.debug_struct("ShadowRoot")
             .field("mode", &inner.mode)
             .field("delegates_focus", &inner.delegates_focus)
             .field("slot_assignment", &inner.slot_assignment)
-            .field("children_count", &inner.children.len())
+            .field("serializable", &inner.serializable)
+            .field("children_count", &children_count)
             .finish()
     }
 }
 
+impl ShadowRootContent for ShadowRoot {
+    fn mode(&self) -> &'static str {
+        match ShadowRoot::mode(self) {
+            ShadowRootMode::Open => "open",
+            ShadowRootMode::Closed => "closed",
+        }
+    }
+
+    fn is_serializable(&self) -> bool {
+        self.serializable()
+    }
+
+    fn child_nodes(&self) -> Vec<NodeRef> {
+        self.children()
+    }
+
+    fn delegates_focus(&self) -> bool {
+        ShadowRoot::delegates_focus(self)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::Document;
+    use dom_core::{Document, Element};
+
+    fn element_node(tag: &str) -> NodeRef {
+        let element = Arc::new(RwLock::new(Element::new(tag)));
+        element.write().set_self_ref(Arc::downgrade(&element));
+        element
+    }
 
     #[test]
     fn test_shadow_root_creation() {
@@ -207,22 +342,129 @@ mod tests {
         let host = doc.create_element("div").unwrap();
         let child = doc.create_element("span").unwrap();
 
+        let grandchild = doc.create_element("b").unwrap();
+        child.write().append_child(grandchild.clone()).unwrap();
+
         let shadow = ShadowRoot::new(
-            host,
+            host.clone(),
             ShadowRootMode::Open,
             false,
             SlotAssignmentMode::Named,
         );
+        let child_node: NodeRef = child.clone();
+        assert!(ShadowRoot::containing(&child_node).is_none());
 
-        // Convert ElementRef to NodeRef
-        let child_node = {
-            let element_clone = child.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
-        };
-        shadow.append_child(child_node).unwrap();
+        shadow.append_child(child_node.clone()).unwrap();
+        shadow.append_child(child_node.clone()).unwrap();
 
         let children = shadow.children();
         assert_eq!(children.len(), 1);
+        let parent = child.read().parent_node().unwrap();
+        assert!(Arc::ptr_eq(&parent, &shadow.as_node()));
+
+        // Nodes anywhere in the shadow tree lead back to the shadow root
+        let grandchild: NodeRef = grandchild;
+        let found = ShadowRoot::containing(&grandchild).unwrap();
+        assert!(Arc::ptr_eq(&found.as_node(), &shadow.as_node()));
+    }
+
+    #[test]
+    fn test_shadow_root_append_child_validates_insertion() {
+        let mut doc = Document::new();
+        let outer = doc.create_element("section").unwrap();
+        let host = doc.create_element("div").unwrap();
+        outer.write().append_child(host.clone()).unwrap();
+        let shadow = ShadowRoot::attach(host.clone(), ShadowRootInit::new(ShadowRootMode::Open));
+
+        // The host and its ancestors would make a cycle
+        for ancestor in [host as NodeRef, outer] {
+            let result = shadow.append_child(ancestor);
+            assert_eq!(result, Err(DomException::HierarchyRequestError));
+        }
+        let document: NodeRef = Arc::new(RwLock::new(Document::new()));
+        let result = shadow.append_child(document);
+        assert_eq!(result, Err(DomException::HierarchyRequestError));
+
+        // Fragments are spliced, and their elements join the tree's ids
+        let fragment = doc.create_document_fragment();
+        let first = doc.create_element("p").unwrap();
+        first.write().set_attribute("id", "first").unwrap();
+        fragment.write().append_child(first.clone()).unwrap();
+        fragment.write().append_child(element_node("p")).unwrap();
+        shadow.append_child(fragment.clone()).unwrap();
+
+        assert_eq!(shadow.children().len(), 2);
+        assert!(fragment.read().child_nodes().is_empty());
+        let found = shadow.get_element_by_id("first").unwrap();
+        assert!(Arc::ptr_eq(&found, &first));
+    }
+
+    #[test]
+    fn test_shadow_tree_mutations_count_for_the_host_document() {
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        let shadow = ShadowRoot::new(
+            host.clone(),
+            ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        );
+        let child = element_node("span");
+
+        let start = doc.structure_generation();
+        shadow.append_child(child.clone()).unwrap();
+        assert_eq!(doc.structure_generation(), start + 1);
+
+        // The inserted element reports its own changes to the document
+        child.write().append_child(element_node("b")).unwrap();
+        assert_eq!(doc.structure_generation(), start + 2);
+
+        shadow.as_node().write().remove_child(child).unwrap();
+        assert_eq!(doc.structure_generation(), start + 3);
+    }
+
+    #[test]
+    fn test_shadow_root_add_slot() {
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        let slot = SlotElement::new(doc.create_element("slot").unwrap());
+
+        let shadow = ShadowRoot::new(
+            host.clone(),
+            ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        );
+        assert!(slot.host().is_none());
+
+        shadow.add_slot(&slot);
+        shadow.add_slot(&slot);
+
+        assert_eq!(shadow.slots().len(), 1);
+        assert!(Arc::ptr_eq(&slot.host().unwrap(), &host));
+    }
+
+    #[test]
+    fn test_get_element_by_id_is_scoped_per_tree() {
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        let shadow = ShadowRoot::new(host, ShadowRootMode::Open, false, SlotAssignmentMode::Named);
+
+        let light = doc.create_element("span").unwrap();
+        light.write().set_attribute("id", "x").unwrap();
+        doc.set_document_element(light.clone());
+
+        let shadowed = doc.create_element("span").unwrap();
+        shadowed.write().set_attribute("id", "x").unwrap();
+        shadow.append_child(shadowed.clone()).unwrap();
+
+        let from_doc = doc.get_element_by_id("x").unwrap();
+        let from_shadow = shadow.get_element_by_id("x").unwrap();
+        assert!(Arc::ptr_eq(&from_doc, &light));
+        assert!(Arc::ptr_eq(&from_shadow, &shadowed));
+        assert!(!Arc::ptr_eq(&from_doc, &from_shadow));
+
+        assert!(shadow.get_element_by_id("missing").is_none());
     }
 
     #[test]
@@ -246,4 +488,33 @@ mod tests {
         );
         assert_eq!(closed_shadow.mode(), ShadowRootMode::Closed);
     }
+
+    #[test]
+    fn test_shadow_content_serialization() {
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        let shadow = ShadowRoot::new(
+            host.clone(),
+            ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        );
+        let inner: NodeRef = Arc::new(RwLock::new(dom_core::Element::new("span")));
+        shadow.append_child(inner).unwrap();
+
+        let options = dom_core::SerializeOptions {
+            serializable_shadow_roots: true,
+            ..Default::default()
+        };
+        assert_eq!(host.read().outer_html(), "<div></div>");
+        assert_eq!(host.read().outer_html_with(&options), "<div></div>");
+
+        shadow.set_serializable(true);
+        assert_eq!(host.read().outer_html(), "<div></div>");
+        assert_eq!(
+            host.read().outer_html_with(&options),
+            "<div><template shadowrootmode=\"open\" shadowrootserializable=\"\">\
+             <span></span></template></div>"
+        );
+    }
 }
@@ -1,8 +1,9 @@
 //! Slot element and distribution implementation
 
-use dom_core::{ElementRef, NodeRef};
+use super::ShadowRoot;
+use dom_core::{downcast_element, Element, ElementRef, Node, NodeRef};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// Slot assignment mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,11 +22,45 @@ pub struct SlotElement {
 
 struct SlotElementInner {
     element: ElementRef,
+    // The slot element as a tree node, to recognize it among assigned nodes
+    node: NodeRef,
+    host: Option<Weak<RwLock<Element>>>,
+    // Assignment mode of the shadow root, once the slot has been added to one
+    assignment_mode: Option<SlotAssignmentMode>,
     name: Option<String>,
     assigned_nodes: Vec<NodeRef>,
+    // Explicit list from assign(), used in manual assignment mode
+    manual_nodes: Vec<NodeRef>,
     fallback_nodes: Vec<NodeRef>,
 }
 
+impl SlotElementInner {
+    /// Nodes currently assigned to the slot
+    ///
+    /// In manual mode this is the explicit list, limited to nodes that are
+    /// still children of the host.
+    fn assigned(&self) -> Vec<NodeRef> {
+        if self.assignment_mode != Some(SlotAssignmentMode::Manual) {
+            return self.assigned_nodes.clone();
+        }
+        let children = self.host_children();
+        self.manual_nodes
+            .iter()
+            .filter(|node| children.iter().any(|child| Arc::ptr_eq(child, node)))
+            .cloned()
+            .collect()
+    }
+
+    /// Light-DOM children of the host, empty if the host is unknown or gone
+    fn host_children(&self) -> Vec<NodeRef> {
+        self.host
+            .as_ref()
+            .and_then(|host| host.upgrade())
+            .map(|host| host.read().child_nodes())
+            .unwrap_or_default()
+    }
+}
+
 impl SlotElement {
     /// Create a new slot element
     pub fn new(element: ElementRef) -> Self {
@@ -33,9 +68,13 @@ impl SlotElement {
 
         Self {
             inner: Arc::new(RwLock::new(SlotElementInner {
+                node: element.clone(),
                 element,
+                host: None,
+                assignment_mode: None,
                 name,
                 assigned_nodes: Vec::new(),
+                manual_nodes: Vec::new(),
                 fallback_nodes: Vec::new(),
             })),
         }
@@ -61,22 +100,121 @@ impl SlotElement {
     }
 
     /// Get assigned nodes (slottables)
-    pub fn assigned_nodes(&self) -> Vec<NodeRef> {
-        let inner = self.inner.read();
-        inner.assigned_nodes.clone()
+    ///
+    /// With `flatten`, assigned nodes that are themselves slots are replaced
+    /// by their own flattened assigned nodes, and a slot with nothing
+    /// assigned contributes its fallback content instead.
+    pub fn assigned_nodes(&self, flatten: bool) -> Vec<NodeRef> {
+        if !flatten {
+            let inner = self.inner.read();
+            return inner.assigned();
+        }
+
+        let mut flattened = Vec::new();
+        self.flatten_into(&mut Vec::new(), &mut flattened);
+        flattened
+    }
+
+    /// Append this slot's flattened assigned nodes to `out`
+    ///
+    /// `visited` holds the slots being flattened, so a slot reached again
+    /// through its own assigned nodes contributes nothing.
+    fn flatten_into(&self, visited: &mut Vec<usize>, out: &mut Vec<NodeRef>) {
+        let id = Arc::as_ptr(&self.inner) as *const () as usize;
+        if visited.contains(&id) {
+            return;
+        }
+        visited.push(id);
+
+        let nodes = {
+            let inner = self.inner.read();
+            let assigned = inner.assigned();
+            if assigned.is_empty() {
+                inner.fallback_nodes.clone()
+            } else {
+                assigned
+            }
+        };
+        for node in nodes {
+            match slot_for_node(&node) {
+                Some(nested) => nested.flatten_into(visited, out),
+                None => out.push(node),
+            }
+        }
+        visited.pop();
     }
 
     /// Get assigned elements (elements only)
-    pub fn assigned_elements(&self) -> Vec<ElementRef> {
-        let inner = self.inner.read();
-        // Simplified: proper implementation would need better NodeRef to ElementRef conversion
-        Vec::new()
+    ///
+    /// Same as [`assigned_nodes`](SlotElement::assigned_nodes) with non-element
+    /// nodes left out.
+    pub fn assigned_elements(&self, flatten: bool) -> Vec<ElementRef> {
+        self.assigned_nodes(flatten)
+            .iter()
+            .filter_map(downcast_element)
+            .collect()
     }
 
-    /// Manually assign nodes to this slot
+    /// Manually assign nodes to this slot (`slot.assign()`)
+    ///
+    /// Replaces any previous manual assignment. Once the slot belongs to a
+    /// shadow tree, nodes that are not children of the host are ignored, as
+    /// are duplicates, and the nodes are taken out of the other slots of the
+    /// tree. The list only takes effect when the shadow root uses
+    /// [`SlotAssignmentMode::Manual`], or while the slot is not yet in a
+    /// shadow tree.
     pub fn assign(&self, nodes: Vec<NodeRef>) {
+        let (host, children) = {
+            let inner = self.inner.read();
+            let host = inner.host.as_ref().and_then(Weak::upgrade);
+            (host, inner.host.is_some().then(|| inner.host_children()))
+        };
+        let mut accepted: Vec<NodeRef> = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let is_child = children
+                .as_ref()
+                .is_none_or(|children| children.iter().any(|c| Arc::ptr_eq(c, &node)));
+            if is_child && !accepted.iter().any(|n| Arc::ptr_eq(n, &node)) {
+                accepted.push(node);
+            }
+        }
+
+        // A node is manually assigned to one slot at a time
+        let siblings = host
+            .and_then(|host| ShadowRoot::of_host(&host))
+            .map(|shadow| shadow.slots())
+            .unwrap_or_default();
+        for slot in siblings {
+            if !Arc::ptr_eq(&slot.inner, &self.inner) {
+                slot.inner
+                    .write()
+                    .manual_nodes
+                    .retain(|node| !accepted.iter().any(|n| Arc::ptr_eq(n, node)));
+            }
+        }
+
+        let mut inner = self.inner.write();
+        inner.manual_nodes = accepted;
+        if inner.assignment_mode.is_none() {
+            inner.assigned_nodes = inner.manual_nodes.clone();
+        }
+    }
+
+    /// Get the shadow host of the tree this slot belongs to
+    ///
+    /// Only known once the slot has been added with [`ShadowRoot::add_slot`].
+    ///
+    /// [`ShadowRoot::add_slot`]: super::ShadowRoot::add_slot
+    pub fn host(&self) -> Option<ElementRef> {
+        let inner = self.inner.read();
+        inner.host.as_ref().and_then(|host| host.upgrade())
+    }
+
+    /// Set the shadow host and assignment mode of the tree this slot belongs to
+    pub(crate) fn set_host(&self, host: Weak<RwLock<Element>>, mode: SlotAssignmentMode) {
         let mut inner = self.inner.write();
-        inner.assigned_nodes = nodes;
+        inner.host = Some(host);
+        inner.assignment_mode = Some(mode);
     }
 
     /// Add a fallback node
@@ -99,11 +237,21 @@ impl SlotElement {
         inner.element.clone()
     }
 
+    /// Get the slot element as a tree node
+    pub fn node(&self) -> NodeRef {
+        let inner = self.inner.read();
+        inner.node.clone()
+    }
+
     /// Distribute content to this slot
     ///
-    /// This implements the slot distribution algorithm
+    /// This implements the slot distribution algorithm. Slots of a shadow
+    /// root in manual assignment mode ignore it.
     pub fn distribute(&self, available_nodes: &[NodeRef]) {
         let mut inner = self.inner.write();
+        if inner.assignment_mode == Some(SlotAssignmentMode::Manual) {
+            return;
+        }
 
         // Clear current assignments
         inner.assigned_nodes.clear();
@@ -134,7 +282,7 @@ impl SlotElement {
     /// Check if this slot has assigned content
     pub fn has_assigned_content(&self) -> bool {
         let inner = self.inner.read();
-        !inner.assigned_nodes.is_empty()
+        !inner.assigned().is_empty()
     }
 }
 
@@ -149,6 +297,36 @@ impl std::fmt::Debug for SlotElement {
     }
 }
 
+/// Get the slot a node is currently assigned to, if any
+///
+/// Only children of a shadow host are slotted, so the slot is looked up
+/// among the slots of the host's shadow root, in the order they were added.
+pub fn assigned_slot(node: &NodeRef) -> Option<SlotElement> {
+    let parent = node.read().parent_node()?;
+    let shadow = ShadowRoot::of_host(&downcast_element(&parent)?)?;
+    shadow.slots().into_iter().find(|slot| {
+        let inner = slot.inner.read();
+        inner
+            .assigned()
+            .iter()
+            .any(|n| std::ptr::addr_eq(Arc::as_ptr(n), Arc::as_ptr(node)))
+    })
+}
+
+/// Get the slot represented by `node`
+///
+/// Only slots added to the shadow root of the tree containing `node` are
+/// found.
+fn slot_for_node(node: &NodeRef) -> Option<SlotElement> {
+    if !node.read().node_name().eq_ignore_ascii_case("slot") {
+        return None;
+    }
+    ShadowRoot::containing(node)?
+        .slots()
+        .into_iter()
+        .find(|slot| std::ptr::addr_eq(Arc::as_ptr(&slot.node()), Arc::as_ptr(node)))
+}
+
 /// Helper to find slots in a shadow root
 pub fn find_slots_in_shadow_tree(root: &NodeRef) -> Vec<SlotElement> {
     let mut slots = Vec::new();
@@ -170,7 +348,7 @@ pub fn find_slots_in_shadow_tree(root: &NodeRef) -> Vec<SlotElement> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::Document;
+    use dom_core::{new_node_ref, Document};
 
     #[test]
     fn test_slot_creation() {
@@ -202,13 +380,13 @@ mod tests {
 
         let slot = SlotElement::new(slot_elem);
         // Convert ElementRef to NodeRef
-        let content_node = {
+        let content_node: NodeRef = {
             let element_clone = content.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
         slot.assign(vec![content_node]);
 
-        assert_eq!(slot.assigned_nodes().len(), 1);
+        assert_eq!(slot.assigned_nodes(false).len(), 1);
         assert!(slot.has_assigned_content());
     }
 
@@ -229,13 +407,13 @@ mod tests {
         let body = doc.create_element("div").unwrap();
 
         // Convert ElementRefs to NodeRefs
-        let header_node = {
+        let header_node: NodeRef = {
             let element_clone = header.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
-        let body_node = {
+        let body_node: NodeRef = {
             let element_clone = body.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
 
         let available = vec![header_node.clone(), body_node];
@@ -243,8 +421,8 @@ mod tests {
         slot.distribute(&available);
 
         // Only header should be assigned
-        assert_eq!(slot.assigned_nodes().len(), 1);
-        assert!(Arc::ptr_eq(&slot.assigned_nodes()[0], &header_node));
+        assert_eq!(slot.assigned_nodes(false).len(), 1);
+        assert!(Arc::ptr_eq(&slot.assigned_nodes(false)[0], &header_node));
     }
 
     #[test]
@@ -263,13 +441,13 @@ mod tests {
         header.write().set_attribute("slot", "header").unwrap();
 
         // Convert ElementRefs to NodeRefs
-        let content_node = {
+        let content_node: NodeRef = {
             let element_clone = content.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
-        let header_node = {
+        let header_node: NodeRef = {
             let element_clone = header.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
 
         let available = vec![content_node.clone(), header_node];
@@ -277,8 +455,300 @@ mod tests {
         slot.distribute(&available);
 
         // Only unnamed content should be assigned
-        assert_eq!(slot.assigned_nodes().len(), 1);
-        assert!(Arc::ptr_eq(&slot.assigned_nodes()[0], &content_node));
+        assert_eq!(slot.assigned_nodes(false).len(), 1);
+        assert!(Arc::ptr_eq(&slot.assigned_nodes(false)[0], &content_node));
+    }
+
+    #[test]
+    fn test_assigned_slot_lookup() {
+        let (host, slot, children) = manual_slot_with_children(1);
+        let other = named_slot(Some("other"));
+        crate::ShadowRoot::of_host(&host).unwrap().add_slot(&other);
+        let content_node = children[0].clone();
+        assert!(assigned_slot(&content_node).is_none());
+
+        slot.assign(vec![content_node.clone()]);
+        let found = assigned_slot(&content_node).unwrap();
+        assert!(Arc::ptr_eq(&found.element(), &slot.element()));
+
+        // Assigning elsewhere moves the node to the new slot
+        other.assign(vec![content_node.clone()]);
+        let found = assigned_slot(&content_node).unwrap();
+        assert!(Arc::ptr_eq(&found.element(), &other.element()));
+        assert!(!slot.has_assigned_content());
+
+        other.assign(Vec::new());
+        assert!(assigned_slot(&content_node).is_none());
+
+        // A slot outside any shadow tree slots nothing
+        let detached = named_slot(None);
+        detached.assign(vec![content_node.clone()]);
+        assert!(detached.has_assigned_content());
+        assert!(assigned_slot(&content_node).is_none());
+    }
+
+    #[test]
+    fn test_assigned_slot_is_scoped_to_the_host() {
+        let mut doc = Document::new();
+        let first_host = doc.create_element("div").unwrap();
+        let second_host = doc.create_element("div").unwrap();
+        let child = element_node("p", None);
+        first_host.write().append_child(child.clone()).unwrap();
+
+        let first = attach(&first_host);
+        let second = attach(&second_host);
+        let first_slot = named_slot(None);
+        let second_slot = named_slot(None);
+        first.add_slot(&first_slot);
+        second.add_slot(&second_slot);
+
+        // Distributing a node that is not a child of the host slots nothing
+        second_slot.distribute(std::slice::from_ref(&child));
+        assert!(assigned_slot(&child).is_none());
+
+        first_slot.distribute(std::slice::from_ref(&child));
+        let found = assigned_slot(&child).unwrap();
+        assert!(Arc::ptr_eq(&found.element(), &first_slot.element()));
+    }
+
+    fn element_node(tag: &str, slot: Option<&str>) -> NodeRef {
+        let mut element = Element::new(tag);
+        if let Some(slot) = slot {
+            element.set_attribute("slot", slot).unwrap();
+        }
+        new_node_ref(element)
+    }
+
+    fn named_slot(name: Option<&str>) -> SlotElement {
+        let mut element = Element::new("slot");
+        if let Some(name) = name {
+            element.set_attribute("name", name).unwrap();
+        }
+        SlotElement::new(Arc::new(parking_lot::RwLock::new(element)))
+    }
+
+    fn tag_names(elements: &[ElementRef]) -> Vec<String> {
+        elements
+            .iter()
+            .map(|element| element.read().tag_name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_assigned_nodes_fallback_only_when_flattened() {
+        let slot = named_slot(Some("icon"));
+        let fallback = element_node("svg", None);
+        slot.add_fallback(fallback.clone());
+        slot.distribute(&[element_node("p", None)]);
+
+        assert!(slot.assigned_nodes(false).is_empty());
+        let flattened = slot.assigned_nodes(true);
+        assert_eq!(flattened.len(), 1);
+        assert!(Arc::ptr_eq(&flattened[0], &fallback));
+
+        // Assigned content replaces the fallback
+        let icon = element_node("img", Some("icon"));
+        slot.distribute(std::slice::from_ref(&icon));
+        let flattened = slot.assigned_nodes(true);
+        assert_eq!(flattened.len(), 1);
+        assert!(Arc::ptr_eq(&flattened[0], &icon));
+    }
+
+    #[test]
+    fn test_assigned_elements_named_matching() {
+        let title = named_slot(Some("title"));
+        let text: NodeRef = Arc::new(parking_lot::RwLock::new(dom_core::Text::new("loose text")));
+        let available = vec![
+            element_node("h1", Some("title")),
+            element_node("p", None),
+            element_node("h2", Some("title")),
+            element_node("span", Some("subtitle")),
+            text,
+        ];
+        title.distribute(&available);
+        let elements = title.assigned_elements(false);
+        assert_eq!(tag_names(&elements), vec!["H1", "H2"]);
+
+        // The elements are the assigned nodes themselves, not copies
+        elements[0].write().set_attribute("title", "x").unwrap();
+        let node = available[0].read();
+        let h1 = node.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(h1.get_attribute("title"), Some("x"));
+        drop(node);
+
+        let default = named_slot(None);
+        default.distribute(&available);
+        assert_eq!(default.assigned_nodes(false).len(), 2);
+        assert_eq!(tag_names(&default.assigned_elements(false)), vec!["P"]);
+    }
+
+    /// Attach a shadow root in named assignment mode
+    fn attach(host: &ElementRef) -> ShadowRoot {
+        ShadowRoot::new(
+            host.clone(),
+            crate::ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        )
+    }
+
+    #[test]
+    fn test_flatten_resolves_nested_slot() {
+        // <x-page><article slot=body></x-page>, whose shadow tree forwards its
+        // "body" slot into the default slot of an <x-card>:
+        //   x-page shadow: <x-card><label/><slot name=body/></x-card>
+        //   x-card shadow: <slot/>
+        let mut doc = Document::new();
+        let page = doc.create_element("x-page").unwrap();
+        let article = doc.create_element("article").unwrap();
+        article.write().set_attribute("slot", "body").unwrap();
+        page.write().append_child(article.clone()).unwrap();
+        let page_shadow = attach(&page);
+
+        let card = doc.create_element("x-card").unwrap();
+        let label = doc.create_element("label").unwrap();
+        let forwarded_element = doc.create_element("slot").unwrap();
+        forwarded_element
+            .write()
+            .set_attribute("name", "body")
+            .unwrap();
+        card.write().append_child(label.clone()).unwrap();
+        card.write()
+            .append_child(forwarded_element.clone())
+            .unwrap();
+        page_shadow.append_child(card.clone()).unwrap();
+        let forwarded = SlotElement::new(forwarded_element);
+        page_shadow.add_slot(&forwarded);
+        forwarded.distribute(&page.read().child_nodes());
+
+        let card_shadow = attach(&card);
+        let outer = SlotElement::new(doc.create_element("slot").unwrap());
+        card_shadow.add_slot(&outer);
+        outer.distribute(&card.read().child_nodes());
+
+        let (article, label): (NodeRef, NodeRef) = (article, label);
+        let forwarded_node = forwarded.node();
+        let unflattened = outer.assigned_nodes(false);
+        assert_eq!(unflattened.len(), 2);
+        assert!(Arc::ptr_eq(&unflattened[1], &forwarded_node));
+
+        let flattened = outer.assigned_nodes(true);
+        assert_eq!(flattened.len(), 2);
+        assert!(Arc::ptr_eq(&flattened[0], &label));
+        assert!(Arc::ptr_eq(&flattened[1], &article));
+        assert_eq!(
+            tag_names(&outer.assigned_elements(true)),
+            vec!["LABEL", "ARTICLE"]
+        );
+
+        // An empty nested slot contributes its own fallback content
+        let placeholder = element_node("em", None);
+        forwarded.add_fallback(placeholder.clone());
+        forwarded.distribute(&[]);
+        let flattened = outer.assigned_nodes(true);
+        assert_eq!(flattened.len(), 2);
+        assert!(Arc::ptr_eq(&flattened[1], &placeholder));
+
+        // A slot outside any shadow root is not resolved
+        let detached = SlotElement::new(doc.create_element("slot").unwrap());
+        detached.assign(vec![forwarded_node.clone()]);
+        let loose = named_slot(None);
+        loose.assign(vec![detached.node()]);
+        let flattened = loose.assigned_nodes(true);
+        assert_eq!(flattened.len(), 1);
+        assert!(Arc::ptr_eq(&flattened[0], &detached.node()));
+    }
+
+    #[test]
+    fn test_flatten_stops_at_slot_cycles() {
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        let first = SlotElement::new(doc.create_element("slot").unwrap());
+        let second = SlotElement::new(doc.create_element("slot").unwrap());
+
+        // Assigned before joining the tree, so nothing checks the nodes
+        first.assign(vec![second.node()]);
+        second.assign(vec![first.node()]);
+
+        let shadow = attach(&host);
+        shadow.append_child(first.node()).unwrap();
+        shadow.append_child(second.node()).unwrap();
+        shadow.add_slot(&first);
+        shadow.add_slot(&second);
+
+        assert!(first.assigned_nodes(true).is_empty());
+        assert!(second.assigned_nodes(true).is_empty());
+        assert!(Arc::ptr_eq(&first.assigned_nodes(false)[0], &second.node()));
+    }
+
+    fn manual_slot_with_children(count: usize) -> (ElementRef, SlotElement, Vec<NodeRef>) {
+        let host = Arc::new(parking_lot::RwLock::new(Element::new("div")));
+        host.write().set_self_ref(Arc::downgrade(&host));
+        let children: Vec<NodeRef> = (0..count).map(|_| element_node("p", None)).collect();
+        for child in &children {
+            host.write().append_child(child.clone()).unwrap();
+        }
+        let shadow = crate::ShadowRoot::new(
+            host.clone(),
+            crate::ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Manual,
+        );
+        let slot = named_slot(None);
+        shadow.add_slot(&slot);
+        (host, slot, children)
+    }
+
+    #[test]
+    fn test_manual_assignment() {
+        let (_host, slot, children) = manual_slot_with_children(3);
+        assert!(!slot.has_assigned_content());
+
+        slot.assign(vec![children[2].clone(), children[0].clone()]);
+        let assigned = slot.assigned_nodes(false);
+        assert_eq!(assigned.len(), 2);
+        assert!(Arc::ptr_eq(&assigned[0], &children[2]));
+        assert!(Arc::ptr_eq(&assigned[1], &children[0]));
+        assert!(Arc::ptr_eq(
+            &assigned_slot(&children[2]).unwrap().element(),
+            &slot.element()
+        ));
+
+        // The slot attribute plays no part in manual mode
+        slot.distribute(&children);
+        assert_eq!(slot.assigned_nodes(false).len(), 2);
+    }
+
+    #[test]
+    fn test_manual_reassignment_replaces_previous() {
+        let (_host, slot, children) = manual_slot_with_children(2);
+        slot.assign(vec![children[0].clone()]);
+        slot.assign(vec![children[1].clone()]);
+
+        let assigned = slot.assigned_nodes(false);
+        assert_eq!(assigned.len(), 1);
+        assert!(Arc::ptr_eq(&assigned[0], &children[1]));
+        assert!(assigned_slot(&children[0]).is_none());
+
+        slot.assign(Vec::new());
+        assert!(!slot.has_assigned_content());
+    }
+
+    #[test]
+    fn test_manual_assignment_ignores_non_children() {
+        let (_host, slot, children) = manual_slot_with_children(1);
+        let stranger = element_node("span", None);
+
+        slot.assign(vec![
+            stranger.clone(),
+            children[0].clone(),
+            children[0].clone(),
+        ]);
+
+        let assigned = slot.assigned_nodes(false);
+        assert_eq!(assigned.len(), 1);
+        assert!(Arc::ptr_eq(&assigned[0], &children[0]));
+        assert!(assigned_slot(&stranger).is_none());
     }
 
     #[test]
@@ -291,7 +761,7 @@ mod tests {
         // Convert ElementRef to NodeRef
         let fallback_node = {
             let element_clone = fallback.read().clone();
-            Arc::new(parking_lot::RwLock::new(Box::new(element_clone) as Box<dyn dom_core::Node>))
+            Arc::new(parking_lot::RwLock::new(element_clone))
         };
         slot.add_fallback(fallback_node);
 
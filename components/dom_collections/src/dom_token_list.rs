@@ -2,13 +2,14 @@
 
 use dom_core::ElementRef;
 use dom_types::DomException;
-use std::collections::HashSet;
 use std::sync::Weak;
 
 /// DOMTokenList manages space-separated tokens (like CSS classes)
 ///
 /// This is typically used for class names but can be used for any
-/// space-separated attribute values.
+/// space-separated attribute values. The list is live: every read parses the
+/// attribute and every mutation writes it back, as an ordered set without
+/// duplicates.
 pub struct DOMTokenList {
     /// Weak reference to the element
     element: Weak<parking_lot::RwLock<dom_core::Element>>,
@@ -26,55 +27,48 @@ impl DOMTokenList {
         }
     }
 
-    /// Gets the current token list as a HashSet
-    fn get_tokens(&self) -> HashSet<String> {
+    /// Gets the current tokens in order, with duplicates collapsed
+    fn get_tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = Vec::new();
         if let Some(element) = self.element.upgrade() {
             if let Some(value) = element.read().get_attribute(&self.attribute_name) {
-                return value.split_whitespace().map(|s| s.to_string()).collect();
+                for token in value.split_whitespace() {
+                    if !tokens.iter().any(|t| t == token) {
+                        tokens.push(token.to_string());
+                    }
+                }
             }
         }
-        HashSet::new()
+        tokens
     }
 
-    /// Sets the token list from a HashSet
-    fn set_tokens(&mut self, tokens: &HashSet<String>) -> Result<(), DomException> {
+    /// Writes the tokens back to the attribute
+    ///
+    /// An absent attribute stays absent while there are no tokens to write.
+    fn set_tokens(&mut self, tokens: &[String]) -> Result<(), DomException> {
         if let Some(element) = self.element.upgrade() {
-            let mut tokens_vec: Vec<&str> = tokens.iter().map(|s| s.as_str()).collect();
-            tokens_vec.sort(); // Maintain consistent order
-            let value = tokens_vec.join(" ");
-            element.write().set_attribute(&self.attribute_name, value)?;
+            let mut element = element.write();
+            if tokens.is_empty() && !element.has_attribute(&self.attribute_name) {
+                return Ok(());
+            }
+            element.set_attribute(&self.attribute_name, tokens.join(" "))?;
         }
         Ok(())
     }
 
     /// Returns the number of tokens
     pub fn length(&self) -> usize {
-        if let Some(element) = self.element.upgrade() {
-            if let Some(value) = element.read().get_attribute(&self.attribute_name) {
-                return value.split_whitespace().count();
-            }
-        }
-        0
+        self.get_tokens().len()
     }
 
     /// Returns the token at the given index
     pub fn item(&self, index: usize) -> Option<String> {
-        if let Some(element) = self.element.upgrade() {
-            if let Some(value) = element.read().get_attribute(&self.attribute_name) {
-                return value.split_whitespace().nth(index).map(|s| s.to_string());
-            }
-        }
-        None
+        self.get_tokens().into_iter().nth(index)
     }
 
     /// Checks if the token exists
     pub fn contains(&self, token: &str) -> bool {
-        if let Some(element) = self.element.upgrade() {
-            if let Some(value) = element.read().get_attribute(&self.attribute_name) {
-                return value.split_whitespace().any(|t| t == token);
-            }
-        }
-        false
+        self.get_tokens().iter().any(|t| t == token)
     }
 
     /// Validates a token (no whitespace allowed)
@@ -98,7 +92,7 @@ impl DOMTokenList {
         let mut current_tokens = self.get_tokens();
 
         for token in tokens {
-            current_tokens.insert(token.to_string());
+            insert_token(&mut current_tokens, token);
         }
 
         self.set_tokens(&current_tokens)?;
@@ -114,9 +108,7 @@ impl DOMTokenList {
 
         let mut current_tokens = self.get_tokens();
 
-        for token in tokens {
-            current_tokens.remove(*token);
-        }
+        current_tokens.retain(|t| !tokens.contains(&t.as_str()));
 
         self.set_tokens(&current_tokens)?;
         Ok(())
@@ -128,21 +120,13 @@ impl DOMTokenList {
 
         let mut current_tokens = self.get_tokens();
 
-        let result = if let Some(forced) = force {
-            if forced {
-                current_tokens.insert(token.to_string());
-                true
-            } else {
-                current_tokens.remove(token);
-                false
-            }
-        } else if current_tokens.contains(token) {
-            current_tokens.remove(token);
-            false
+        let present = current_tokens.iter().any(|t| t == token);
+        let result = force.unwrap_or(!present);
+        if result {
+            insert_token(&mut current_tokens, token);
         } else {
-            current_tokens.insert(token.to_string());
-            true
-        };
+            current_tokens.retain(|t| t != token);
+        }
 
         self.set_tokens(&current_tokens)?;
         Ok(result)
@@ -155,13 +139,54 @@ impl DOMTokenList {
 
         let mut current_tokens = self.get_tokens();
 
-        if current_tokens.contains(old_token) {
-            current_tokens.remove(old_token);
-            current_tokens.insert(new_token.to_string());
-            self.set_tokens(&current_tokens)?;
-            Ok(true)
-        } else {
-            Ok(false)
+        let Some(index) = current_tokens.iter().position(|t| t == old_token) else {
+            return Ok(false);
+        };
+        // If the new token is already present, it keeps whichever place comes first
+        match current_tokens.iter().position(|t| t == new_token) {
+            Some(new_index) if new_index != index => {
+                current_tokens[index.min(new_index)] = new_token.to_string();
+                current_tokens.remove(index.max(new_index));
+            }
+            _ => current_tokens[index] = new_token.to_string(),
         }
+        self.set_tokens(&current_tokens)?;
+        Ok(true)
+    }
+}
+
+/// Appends `token` unless it is already in the list
+fn insert_token(tokens: &mut Vec<String>, token: &str) {
+    if !tokens.iter().any(|t| t == token) {
+        tokens.push(token.to_string());
+    }
+}
+
+/// Live class lists for element references
+///
+/// [`Element::class_list`](dom_core::Element::class_list) is a read-only
+/// snapshot, since `dom_core` cannot depend on this crate. Importing this
+/// trait gives an `ElementRef` a `class_list()` bound to its `class`
+/// attribute instead.
+///
+/// # Example
+///
+/// ```rust
+/// use dom_collections::ClassListExt;
+/// use dom_core::Document;
+///
+/// let mut doc = Document::new();
+/// let element = doc.create_element("div").unwrap();
+/// element.class_list().add(&["active", "active"]).unwrap();
+/// assert_eq!(element.read().get_attribute("class"), Some("active"));
+/// ```
+pub trait ClassListExt {
+    /// Gets a live token list over the `class` attribute
+    fn class_list(&self) -> DOMTokenList;
+}
+
+impl ClassListExt for ElementRef {
+    fn class_list(&self) -> DOMTokenList {
+        DOMTokenList::new(self.clone(), "class")
     }
 }
@@ -2,8 +2,9 @@
 
 use dom_core::{ElementRef, Node, NodeRef};
 use dom_types::NodeType;
-use parking_lot::RwLock;
-use std::cell::RefCell;
+use elsa::sync::FrozenMap;
+use parking_lot::{Mutex, RwLock};
+use std::ops::Index;
 use std::sync::{Arc, Weak};
 
 /// Live HTMLCollection that updates when the DOM changes
@@ -11,6 +12,12 @@ use std::sync::{Arc, Weak};
 /// This collection maintains a weak reference to the root and re-scans
 /// the tree on every access, making it "live" - it automatically reflects
 /// changes to the DOM.
+///
+/// Indexing with `collection[i]` panics when `i` is out of bounds, like a
+/// slice. Since the returned reference must outlive the scan that found it,
+/// each element reached by indexing stays alive until the collection is
+/// dropped, and each access to an element without a self-reference keeps
+/// another copy; use [`item`](Self::item) to avoid that.
 pub struct HTMLCollection {
     /// Weak reference to the root element (to avoid circular references)
     root: Weak<RwLock<dom_core::Element>>,
@@ -19,14 +26,20 @@ pub struct HTMLCollection {
     filter: Arc<dyn Fn(&ElementRef) -> bool + Send + Sync>,
 
     /// Cached items (updated on every access to reflect live DOM)
-    cached_items: RefCell<Vec<ElementRef>>,
+    cached_items: Mutex<Vec<ElementRef>>,
 
     /// Version counter (not used yet, but reserved for optimization)
     _version: u64,
 
     /// Track element refs separately for tree traversal
     /// This is a workaround for the NodeRef/ElementRef type mismatch
-    element_refs: RefCell<Vec<Weak<RwLock<dom_core::Element>>>>,
+    element_refs: Mutex<Vec<Weak<RwLock<dom_core::Element>>>>,
+
+    /// Elements handed out by indexing, keyed by address
+    ///
+    /// Entries are never removed, so the references `Index` returns stay valid
+    /// for as long as the collection is borrowed.
+    indexed: FrozenMap<usize, Box<ElementRef>>,
 }
 
 impl HTMLCollection {
@@ -38,9 +51,10 @@ impl HTMLCollection {
         let mut collection = HTMLCollection {
             root: Arc::downgrade(&root),
             filter: Arc::new(filter),
-            cached_items: RefCell::new(Vec::new()),
+            cached_items: Mutex::new(Vec::new()),
             _version: 0,
-            element_refs: RefCell::new(Vec::new()),
+            element_refs: Mutex::new(Vec::new()),
+            indexed: FrozenMap::new(),
         };
 
         // Register the root for tracking
@@ -129,8 +143,7 @@ impl HTMLCollection {
     /// );
     /// ```
     pub fn by_tag_name_ns(root: ElementRef, namespace: Option<String>, local_name: String) -> Self {
-        let target_local_name = local_name.to_uppercase();
-        let match_any_local_name = target_local_name == "*";
+        let match_any_local_name = local_name == "*";
         let match_any_namespace = namespace.as_deref() == Some("*");
 
         HTMLCollection::new(root, move |el: &ElementRef| {
@@ -139,8 +152,13 @@ impl HTMLCollection {
             // Check local name match
             let local_name_matches = if match_any_local_name {
                 true
+            } else if element.namespace_uri().is_none()
+                || element.namespace_uri() == Some(dom_core::HTML_NAMESPACE)
+            {
+                // HTML tag names are stored uppercased
+                element.tag_name().eq_ignore_ascii_case(&local_name)
             } else {
-                element.tag_name() == target_local_name
+                element.tag_name() == local_name
             };
 
             // Check namespace match
@@ -160,7 +178,7 @@ impl HTMLCollection {
 
     /// Register an element for tracking (used to build element tree)
     fn register_element(&mut self, element: Weak<RwLock<dom_core::Element>>) {
-        self.element_refs.borrow_mut().push(element);
+        self.element_refs.lock().push(element);
     }
 
     /// Updates the cache by traversing the DOM tree
@@ -173,7 +191,7 @@ impl HTMLCollection {
             self.collect_descendants(&root, &mut items);
         }
 
-        *self.cached_items.borrow_mut() = items;
+        *self.cached_items.lock() = items;
     }
 
     /// Collects matching elements from an element's descendants (not the element itself)
@@ -215,7 +233,14 @@ impl HTMLCollection {
     }
 
     /// Helper method to downcast a NodeRef to ElementRef
+    ///
+    /// Elements with a self-reference are returned as the tree's own ref;
+    /// any other element is copied into a new one.
     fn downcast_to_element(&self, node: &NodeRef) -> Option<ElementRef> {
+        if let Some(element) = dom_core::downcast_element(node) {
+            return Some(element);
+        }
+
         // Lock the node to access its data
         let node_guard = node.read();
 
@@ -234,20 +259,30 @@ impl HTMLCollection {
     /// Returns the number of elements in the collection
     pub fn length(&self) -> usize {
         self.update_cache();
-        self.cached_items.borrow().len()
+        self.cached_items.lock().len()
     }
 
     /// Returns the element at the given index
     pub fn item(&self, index: usize) -> Option<ElementRef> {
         self.update_cache();
-        self.cached_items.borrow().get(index).cloned()
+        self.cached_items.lock().get(index).cloned()
+    }
+
+    /// Returns the current elements as a plain vector
+    ///
+    /// The vector is a snapshot detached from liveness: later changes to the
+    /// DOM do not add, remove or reorder its entries, so it can be held while
+    /// the tree is mutated.
+    pub fn as_vec(&self) -> Vec<ElementRef> {
+        self.update_cache();
+        self.cached_items.lock().clone()
     }
 
     /// Returns the element with the given name or ID
     pub fn named_item(&self, name: &str) -> Option<ElementRef> {
         self.update_cache();
 
-        for item in self.cached_items.borrow().iter() {
+        for item in self.cached_items.lock().iter() {
             let element = item.read();
 
             // Check ID attribute
@@ -269,9 +304,30 @@ impl HTMLCollection {
     }
 }
 
-// Make HTMLCollection Send + Sync for thread safety
-unsafe impl Send for HTMLCollection {}
-unsafe impl Sync for HTMLCollection {}
+impl Index<usize> for HTMLCollection {
+    type Output = ElementRef;
+
+    /// Returns the element at `index`
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    fn index(&self, index: usize) -> &ElementRef {
+        self.update_cache();
+        let element = {
+            let items = self.cached_items.lock();
+            match items.get(index) {
+                Some(element) => element.clone(),
+                None => panic!(
+                    "index out of bounds: the len is {} but the index is {}",
+                    items.len(),
+                    index
+                ),
+            }
+        };
+        let address = Arc::as_ptr(&element) as usize;
+        self.indexed.insert(address, Box::new(element))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -108,12 +108,12 @@ pub mod node_list;
 pub mod tree_walker;
 
 // Re-exports
-pub use dom_token_list::DOMTokenList;
+pub use dom_token_list::{ClassListExt, DOMTokenList};
 pub use html_collection::HTMLCollection;
 pub use named_node_map::NamedNodeMap;
 pub use node_iterator::{
     FilterResult, NodeFilter, NodeIterator, SHOW_ALL, SHOW_COMMENT, SHOW_DOCUMENT, SHOW_ELEMENT,
     SHOW_TEXT,
 };
-pub use node_list::NodeList;
+pub use node_list::{ElementsByNameExt, NodeList};
 pub use tree_walker::TreeWalker;
@@ -12,7 +12,7 @@
 //!
 //! let mut doc = Document::new();
 //! let root = doc.create_element("div").unwrap();
-//! let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+//! let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
 //!
 //! // Iterate over all nodes
 //! let mut iter = NodeIterator::new(root_node.clone(), SHOW_ALL, None);
@@ -34,6 +34,29 @@ use std::sync::Arc;
 /// Node filter callback for NodeIterator
 ///
 /// A custom filter that can accept, reject, or skip nodes during traversal.
+/// It receives the candidate as a `NodeRef`, so generic node accessors are
+/// available directly and element attributes through `Element`:
+///
+/// ```rust
+/// use dom_collections::node_iterator::{FilterResult, NodeFilter};
+/// use dom_core::Element;
+/// use std::sync::Arc;
+///
+/// let external_links: NodeFilter = Some(Arc::new(|node| {
+///     let node = node.read();
+///     let external = node.node_name() == "A"
+///         && node
+///             .as_any()
+///             .downcast_ref::<Element>()
+///             .and_then(|link| link.get_attribute("href"))
+///             .is_some_and(|href| href.starts_with("https:"));
+///     if external {
+///         FilterResult::Accept
+///     } else {
+///         FilterResult::Skip
+///     }
+/// }));
+/// ```
 pub type NodeFilter = Option<Arc<dyn Fn(&NodeRef) -> FilterResult + Send + Sync>>;
 
 /// Result of a node filter
@@ -110,7 +133,7 @@ impl NodeIterator {
     /// # use std::sync::Arc;
     /// # let mut doc = Document::new();
     /// # let root = doc.create_element("div").unwrap();
-    /// # let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
     ///
     /// let mut iter = NodeIterator::new(root_node, SHOW_ELEMENT, None);
     /// ```
@@ -125,6 +148,38 @@ impl NodeIterator {
         }
     }
 
+    /// Creates a NodeIterator filtering through the closure `filter`
+    ///
+    /// Shorthand for [`new`](Self::new) that spares wrapping the closure in
+    /// `Some(Arc::new(..))`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dom_collections::node_iterator::{FilterResult, NodeIterator, SHOW_ELEMENT};
+    /// # use dom_core::{Document, Node};
+    /// # use parking_lot::RwLock;
+    /// # use std::sync::Arc;
+    /// # let mut doc = Document::new();
+    /// # let root = doc.create_element("div").unwrap();
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
+    ///
+    /// let mut iter = NodeIterator::with_filter_fn(root_node, SHOW_ELEMENT, |node| {
+    ///     if node.read().node_name() == "DIV" {
+    ///         FilterResult::Accept
+    ///     } else {
+    ///         FilterResult::Skip
+    ///     }
+    /// });
+    /// assert!(iter.next_node().is_some());
+    /// ```
+    pub fn with_filter_fn<F>(root: NodeRef, what_to_show: u32, filter: F) -> Self
+    where
+        F: Fn(&NodeRef) -> FilterResult + Send + Sync + 'static,
+    {
+        Self::new(root, what_to_show, Some(Arc::new(filter)))
+    }
+
     /// Returns the next node in document order
     ///
     /// Traverses the tree in depth-first pre-order, respecting the what_to_show
@@ -141,7 +196,7 @@ impl NodeIterator {
     /// # use std::sync::Arc;
     /// # let mut doc = Document::new();
     /// # let root = doc.create_element("div").unwrap();
-    /// # let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
     /// let mut iter = NodeIterator::new(root_node, SHOW_ALL, None);
     ///
     /// while let Some(node) = iter.next_node() {
@@ -193,7 +248,7 @@ impl NodeIterator {
     /// # use std::sync::Arc;
     /// # let mut doc = Document::new();
     /// # let root = doc.create_element("div").unwrap();
-    /// # let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
     /// let mut iter = NodeIterator::new(root_node, SHOW_ALL, None);
     ///
     /// // Move to end first
@@ -395,8 +450,8 @@ impl NodeIterator {
 
     /// Checks if two nodes are the same (pointer equality)
     fn is_same_node(&self, node1: &NodeRef, node2: &NodeRef) -> bool {
-        let ptr1 = &**node1.read() as *const dyn dom_core::Node;
-        let ptr2 = &**node2.read() as *const dyn dom_core::Node;
+        let ptr1 = &*node1.read() as *const dyn dom_core::Node;
+        let ptr2 = &*node2.read() as *const dyn dom_core::Node;
         ptr1 == ptr2
     }
 }
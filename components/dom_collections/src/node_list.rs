@@ -1,17 +1,32 @@
 //! NodeList implementation (live and static variants)
 
-use dom_core::NodeRef;
-use std::sync::Arc;
+use dom_core::{Document, DocumentRef, Element, Node, NodeRef};
+use parking_lot::RwLock;
+use std::sync::{Arc, Weak};
 
 /// NodeList can be live or static
 pub enum NodeList {
     /// Live list that updates with DOM changes
+    ///
+    /// Holds the matching nodes among the root and its descendants, in tree
+    /// order, re-scanned on every access.
     Live {
         /// Root node for traversal
-        root: Arc<parking_lot::RwLock<Box<dyn dom_core::Node>>>,
+        root: Arc<parking_lot::RwLock<dyn dom_core::Node>>,
         /// Filter function to determine which nodes to include
-        filter:
-            Arc<dyn Fn(&Arc<parking_lot::RwLock<Box<dyn dom_core::Node>>>) -> bool + Send + Sync>,
+        filter: Arc<dyn Fn(&Arc<parking_lot::RwLock<dyn dom_core::Node>>) -> bool + Send + Sync>,
+    },
+    /// Live list over the node tree of a document
+    ///
+    /// A document is shared as a `DocumentRef` rather than a `NodeRef`, so
+    /// it cannot be the root of a `Live` list. This variant scans the
+    /// document's child nodes and their descendants instead, and is empty
+    /// once the document is dropped.
+    LiveDocument {
+        /// Document whose tree is scanned
+        document: Weak<RwLock<Document>>,
+        /// Filter function to determine which nodes to include
+        filter: Arc<dyn Fn(&NodeRef) -> bool + Send + Sync>,
     },
     /// Static snapshot of nodes
     Static {
@@ -37,18 +52,22 @@ impl NodeList {
         }
     }
 
+    /// Creates a live NodeList over the node tree of `document`
+    pub fn new_live_document<F>(document: &DocumentRef, filter: F) -> Self
+    where
+        F: Fn(&NodeRef) -> bool + Send + Sync + 'static,
+    {
+        NodeList::LiveDocument {
+            document: Arc::downgrade(document),
+            filter: Arc::new(filter),
+        }
+    }
+
     /// Returns the number of nodes
     pub fn length(&self) -> usize {
         match self {
             NodeList::Static { nodes } => nodes.len(),
-            NodeList::Live { root, filter } => {
-                // TODO: Implement live traversal
-                let mut count = 0;
-                if (filter)(root) {
-                    count += 1;
-                }
-                count
-            }
+            _ => self.live_nodes().len(),
         }
     }
 
@@ -56,10 +75,77 @@ impl NodeList {
     pub fn item(&self, index: usize) -> Option<NodeRef> {
         match self {
             NodeList::Static { nodes } => nodes.get(index).cloned(),
-            NodeList::Live { .. } => {
-                // TODO: Implement live traversal
-                None
-            }
+            _ => self.live_nodes().into_iter().nth(index),
         }
     }
+
+    /// Calls `f` with each node and its index, in order
+    ///
+    /// A live list is scanned once up front, so nodes that `f` inserts or
+    /// removes do not change which nodes it visits.
+    pub fn for_each<F: FnMut(NodeRef, usize)>(&self, mut f: F) {
+        for (index, node) in self.entries() {
+            f(node, index);
+        }
+    }
+
+    /// Iterates over `(index, node)` pairs, in order
+    ///
+    /// Like [`for_each`](Self::for_each), this walks a snapshot of a live list
+    /// taken when it is called.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, NodeRef)> {
+        self.live_nodes().into_iter().enumerate()
+    }
+
+    /// Scans the tree for the current members of a live list
+    fn live_nodes(&self) -> Vec<NodeRef> {
+        match self {
+            NodeList::Static { nodes } => nodes.clone(),
+            NodeList::Live { root, filter } => collect_matching(vec![root.clone()], &**filter),
+            NodeList::LiveDocument { document, filter } => match document.upgrade() {
+                Some(document) => {
+                    let roots = document.read().child_nodes();
+                    collect_matching(roots, &**filter)
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+/// Collects the nodes of the subtrees at `roots` that pass `filter`, in tree order
+fn collect_matching(roots: Vec<NodeRef>, filter: &dyn Fn(&NodeRef) -> bool) -> Vec<NodeRef> {
+    let mut matches = Vec::new();
+    let mut stack: Vec<NodeRef> = roots.into_iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        // Release the node before the filter locks it again
+        let children = node.read().child_nodes();
+        if filter(&node) {
+            matches.push(node);
+        }
+        stack.extend(children.into_iter().rev());
+    }
+    matches
+}
+
+/// Live `getElementsByName` for shared documents
+///
+/// [`Document::get_elements_by_name`] returns a snapshot, since `dom_core`
+/// cannot depend on this crate. Importing this trait gives a `DocumentRef`
+/// a variant returning a live [`NodeList`].
+pub trait ElementsByNameExt {
+    /// Gets a live list of the elements whose `name` attribute is exactly `name`
+    fn get_elements_by_name(&self, name: &str) -> NodeList;
+}
+
+impl ElementsByNameExt for DocumentRef {
+    fn get_elements_by_name(&self, name: &str) -> NodeList {
+        let name = name.to_string();
+        NodeList::new_live_document(self, move |node| {
+            node.read()
+                .as_any()
+                .downcast_ref::<Element>()
+                .is_some_and(|element| element.get_attribute("name") == Some(name.as_str()))
+        })
+    }
 }
@@ -13,7 +13,7 @@
 //!
 //! let mut doc = Document::new();
 //! let root = doc.create_element("div").unwrap();
-//! let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+//! let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
 //!
 //! // Navigate with TreeWalker
 //! let mut walker = TreeWalker::new(root_node.clone(), SHOW_ALL, None);
@@ -37,6 +37,7 @@
 use crate::node_iterator::{FilterResult, NodeFilter, SHOW_ELEMENT, SHOW_TEXT, SHOW_COMMENT, SHOW_DOCUMENT};
 use dom_core::NodeRef;
 use dom_types::NodeType;
+use std::sync::Arc;
 
 /// TreeWalker provides bidirectional tree navigation
 ///
@@ -81,7 +82,7 @@ impl TreeWalker {
     /// # use std::sync::Arc;
     /// # let mut doc = Document::new();
     /// # let root = doc.create_element("div").unwrap();
-    /// # let root_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(root.read().clone())));
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
     ///
     /// let mut walker = TreeWalker::new(root_node, SHOW_ELEMENT, None);
     /// ```
@@ -95,6 +96,37 @@ impl TreeWalker {
         }
     }
 
+    /// Creates a TreeWalker filtering through the closure `filter`
+    ///
+    /// Equivalent to [`new`](Self::new) with `Some(Arc::new(filter))`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dom_collections::node_iterator::{FilterResult, SHOW_ELEMENT};
+    /// use dom_collections::tree_walker::TreeWalker;
+    /// # use dom_core::{Document, Node};
+    /// # use parking_lot::RwLock;
+    /// # use std::sync::Arc;
+    /// # let mut doc = Document::new();
+    /// # let root = doc.create_element("div").unwrap();
+    /// # let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
+    ///
+    /// let walker = TreeWalker::with_filter_fn(root_node, SHOW_ELEMENT, |node| {
+    ///     if node.read().child_nodes().is_empty() {
+    ///         FilterResult::Skip
+    ///     } else {
+    ///         FilterResult::Accept
+    ///     }
+    /// });
+    /// ```
+    pub fn with_filter_fn<F>(root: NodeRef, what_to_show: u32, filter: F) -> Self
+    where
+        F: Fn(&NodeRef) -> FilterResult + Send + Sync + 'static,
+    {
+        Self::new(root, what_to_show, Some(Arc::new(filter)))
+    }
+
     /// Returns the current node
     pub fn current_node(&self) -> NodeRef {
         self.current_node.clone()
@@ -237,13 +269,13 @@ impl TreeWalker {
         // Find current node in siblings
         let current_ptr = {
             let guard = self.current_node.read();
-            &**guard as *const dyn dom_core::Node
+            &*guard as *const dyn dom_core::Node
         };
 
         for (i, sibling) in siblings.iter().enumerate() {
             let sibling_ptr = {
                 let guard = sibling.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
             if sibling_ptr == current_ptr && i > 0 {
                 // Found current node, check previous siblings
@@ -285,13 +317,13 @@ impl TreeWalker {
         // Find current node in siblings
         let current_ptr = {
             let guard = self.current_node.read();
-            &**guard as *const dyn dom_core::Node
+            &*guard as *const dyn dom_core::Node
         };
 
         for (i, sibling) in siblings.iter().enumerate() {
             let sibling_ptr = {
                 let guard = sibling.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
             if sibling_ptr == current_ptr && i + 1 < siblings.len() {
                 // Found current node, check next siblings
@@ -487,13 +519,13 @@ impl TreeWalker {
             let siblings = parent.read().child_nodes();
             let current_ptr = {
                 let guard = current.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
 
             for (i, sibling) in siblings.iter().enumerate() {
                 let sibling_ptr = {
                     let guard = sibling.read();
-                    &**guard as *const dyn dom_core::Node
+                    &*guard as *const dyn dom_core::Node
                 };
                 if sibling_ptr == current_ptr {
                     // Found current node, check if there's a next sibling
@@ -524,13 +556,13 @@ impl TreeWalker {
         let siblings = parent.read().child_nodes();
         let node_ptr = {
             let guard = node.read();
-            &**guard as *const dyn dom_core::Node
+            &*guard as *const dyn dom_core::Node
         };
 
         for (i, sibling) in siblings.iter().enumerate() {
             let sibling_ptr = {
                 let guard = sibling.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
             if sibling_ptr == node_ptr {
                 // Found current node
@@ -576,13 +608,13 @@ impl TreeWalker {
             let siblings = parent.read().child_nodes();
             let current_ptr = {
                 let guard = current.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
 
             for (i, sibling) in siblings.iter().enumerate() {
                 let sibling_ptr = {
                     let guard = sibling.read();
-                    &**guard as *const dyn dom_core::Node
+                    &*guard as *const dyn dom_core::Node
                 };
                 if sibling_ptr == current_ptr {
                     // Found current node
@@ -613,13 +645,13 @@ impl TreeWalker {
         let siblings = parent.read().child_nodes();
         let node_ptr = {
             let guard = node.read();
-            &**guard as *const dyn dom_core::Node
+            &*guard as *const dyn dom_core::Node
         };
 
         for (i, sibling) in siblings.iter().enumerate() {
             let sibling_ptr = {
                 let guard = sibling.read();
-                &**guard as *const dyn dom_core::Node
+                &*guard as *const dyn dom_core::Node
             };
             if sibling_ptr == node_ptr {
                 // Found current node
@@ -638,8 +670,8 @@ impl TreeWalker {
 
     /// Checks if two nodes are the same (pointer equality)
     fn is_same_node(&self, node1: &NodeRef, node2: &NodeRef) -> bool {
-        let ptr1 = &**node1.read() as *const dyn dom_core::Node;
-        let ptr2 = &**node2.read() as *const dyn dom_core::Node;
+        let ptr1 = &*node1.read() as *const dyn dom_core::Node;
+        let ptr2 = &*node2.read() as *const dyn dom_core::Node;
         ptr1 == ptr2
     }
 }
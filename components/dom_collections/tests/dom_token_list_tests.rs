@@ -1,4 +1,4 @@
-use dom_collections::DOMTokenList;
+use dom_collections::{ClassListExt, DOMTokenList};
 use dom_core::Document;
 
 #[test]
@@ -82,3 +82,34 @@ fn test_dom_token_list_remove() {
     let result = token_list.remove(&["bar"]);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_class_list_writes_back_to_class_attribute() {
+    let mut doc = Document::new();
+    let element = doc.create_element("div").unwrap();
+    element.write().set_attribute("class", "card").unwrap();
+
+    element.class_list().add(&["active"]).unwrap();
+    assert_eq!(element.read().get_attribute("class"), Some("card active"));
+    assert_eq!(element.read().class_list(), ["card", "active"]);
+
+    assert!(!element.class_list().toggle("card", None).unwrap());
+    assert_eq!(element.read().get_attribute("class"), Some("active"));
+}
+
+#[test]
+fn test_class_list_collapses_duplicates() {
+    let mut doc = Document::new();
+    let element = doc.create_element("div").unwrap();
+    element.write().set_attribute("class", "b a b").unwrap();
+
+    let mut class_list = element.class_list();
+    assert_eq!(class_list.length(), 2);
+    assert_eq!(class_list.item(1), Some("a".to_string()));
+
+    class_list.add(&["a", "c", "c"]).unwrap();
+    assert_eq!(element.read().get_attribute("class"), Some("b a c"));
+
+    assert!(class_list.replace("c", "b").unwrap());
+    assert_eq!(element.read().get_attribute("class"), Some("b a"));
+}
@@ -1,5 +1,5 @@
 use dom_collections::HTMLCollection;
-use dom_core::{Document, Element, ElementRef, Node};
+use dom_core::{new_node_ref, Document, Element, ElementRef, Node};
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -18,12 +18,9 @@ fn test_by_tag_name_basic() {
     let p = doc.create_element("p").unwrap();
 
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
-        let p_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(p.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
+        let p_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(p.read().clone()));
         root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
         root.write().append_child(p_node).unwrap();
@@ -43,8 +40,7 @@ fn test_by_tag_name_case_insensitive() {
 
     let span = doc.create_element("span").unwrap();
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -68,12 +64,9 @@ fn test_by_tag_name_wildcard() {
     let section = doc.create_element("section").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
-        let p_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(p.read().clone())));
-        let section_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(section.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
+        let p_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(p.read().clone()));
+        let section_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(section.read().clone()));
         root.write().append_child(span_node).unwrap();
         root.write().append_child(p_node).unwrap();
         root.write().append_child(section_node).unwrap();
@@ -92,8 +85,7 @@ fn test_by_tag_name_no_matches() {
 
     let span = doc.create_element("span").unwrap();
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -111,8 +103,8 @@ fn test_by_tag_name_nested_elements() {
     let child_div = doc.create_element("div").unwrap();
     let nested_span = doc.create_element("span").unwrap();
     {
-        let nested_span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(nested_span.read().clone())));
+        let nested_span_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(nested_span.read().clone()));
         child_div.write().append_child(nested_span_node).unwrap();
     }
 
@@ -120,10 +112,9 @@ fn test_by_tag_name_nested_elements() {
     let direct_span = doc.create_element("span").unwrap();
 
     {
-        let child_div_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(child_div.read().clone())));
-        let direct_span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(direct_span.read().clone())));
+        let child_div_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(child_div.read().clone()));
+        let direct_span_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(direct_span.read().clone()));
         root.write().append_child(child_div_node).unwrap();
         root.write().append_child(direct_span_node).unwrap();
     }
@@ -134,6 +125,46 @@ fn test_by_tag_name_nested_elements() {
     assert_eq!(collection.length(), 2);
 }
 
+#[test]
+fn test_collections_match_element_queries() {
+    fn node(tag: &str, id: &str, class: &str) -> Arc<RwLock<dyn Node>> {
+        let mut element = Element::new(tag);
+        element.set_attribute("id", id).unwrap();
+        element.set_attribute("class", class).unwrap();
+        new_node_ref(element)
+    }
+    fn ids(elements: impl IntoIterator<Item = ElementRef>) -> Vec<String> {
+        elements
+            .into_iter()
+            .map(|element| element.read().id().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    // root > (section#a.x > (span#b.x > p#c.y), span#d.y)
+    let root: ElementRef = Arc::new(RwLock::new(Element::new("div")));
+    let section = node("section", "a", "x");
+    let span = node("span", "b", "x");
+    span.write().append_child(node("p", "c", "y")).unwrap();
+    section.write().append_child(span).unwrap();
+    root.write().append_child(section).unwrap();
+    root.write().append_child(node("span", "d", "y")).unwrap();
+
+    for tag in ["span", "p", "*"] {
+        let collection = HTMLCollection::by_tag_name(root.clone(), tag.to_string());
+        let live = ids((0..collection.length()).filter_map(|i| collection.item(i)));
+        assert_eq!(ids(root.read().get_elements_by_tag_name(tag)), live);
+    }
+    for class in ["x", "y", "x y"] {
+        let collection = HTMLCollection::by_class_name(root.clone(), class.to_string());
+        let live = ids((0..collection.length()).filter_map(|i| collection.item(i)));
+        assert_eq!(ids(root.read().get_elements_by_class_name(class)), live);
+    }
+    assert_eq!(
+        ids(root.read().get_elements_by_tag_name("*")),
+        ["a", "b", "c", "d"]
+    );
+}
+
 // =============================================================================
 // getElementsByClassName Tests
 // =============================================================================
@@ -150,10 +181,8 @@ fn test_by_class_name_single_class() {
     span2.write().set_attribute("class", "normal").unwrap();
 
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
     }
@@ -181,12 +210,9 @@ fn test_by_class_name_multiple_classes_required() {
     span3.write().set_attribute("class", "bar foo extra").unwrap();
 
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
-        let span3_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span3.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
+        let span3_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span3.read().clone()));
         root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
         root.write().append_child(span3_node).unwrap();
@@ -208,8 +234,7 @@ fn test_by_class_name_order_independent() {
     span.write().set_attribute("class", "alpha beta gamma").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -232,8 +257,7 @@ fn test_by_class_name_empty_string() {
     span.write().set_attribute("class", "test").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -252,8 +276,7 @@ fn test_by_class_name_whitespace_only() {
     span.write().set_attribute("class", "test").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -272,8 +295,7 @@ fn test_by_class_name_no_matches() {
     span.write().set_attribute("class", "foo bar").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -300,10 +322,9 @@ fn test_by_tag_name_ns_specific_namespace() {
     let html_rect = doc.create_element("rect").unwrap();
 
     {
-        let svg_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_rect_ref.read().clone())));
-        let html_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(html_rect.read().clone())));
+        let svg_rect_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_rect_ref.read().clone()));
+        let html_rect_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(html_rect.read().clone()));
         root.write().append_child(svg_rect_node).unwrap();
         root.write().append_child(html_rect_node).unwrap();
     }
@@ -332,10 +353,9 @@ fn test_by_tag_name_ns_any_namespace() {
     let html_rect = doc.create_element("rect").unwrap();
 
     {
-        let svg_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_rect_ref.read().clone())));
-        let html_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(html_rect.read().clone())));
+        let svg_rect_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_rect_ref.read().clone()));
+        let html_rect_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(html_rect.read().clone()));
         root.write().append_child(svg_rect_node).unwrap();
         root.write().append_child(html_rect_node).unwrap();
     }
@@ -364,10 +384,10 @@ fn test_by_tag_name_ns_any_local_name() {
     let svg_circle_ref: ElementRef = Arc::new(RwLock::new(svg_circle));
 
     {
-        let svg_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_rect_ref.read().clone())));
-        let svg_circle_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_circle_ref.read().clone())));
+        let svg_rect_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_rect_ref.read().clone()));
+        let svg_circle_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_circle_ref.read().clone()));
         root.write().append_child(svg_rect_node).unwrap();
         root.write().append_child(svg_circle_node).unwrap();
     }
@@ -397,10 +417,9 @@ fn test_by_tag_name_ns_null_namespace() {
     let svg_span_ref: ElementRef = Arc::new(RwLock::new(svg_span));
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
-        let svg_span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_span_ref.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
+        let svg_span_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_span_ref.read().clone()));
         root.write().append_child(span_node).unwrap();
         root.write().append_child(svg_span_node).unwrap();
     }
@@ -425,12 +444,10 @@ fn test_by_tag_name_ns_both_wildcards() {
     let svg_rect_ref: ElementRef = Arc::new(RwLock::new(svg_rect));
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
-        let p_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(p.read().clone())));
-        let svg_rect_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(svg_rect_ref.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
+        let p_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(p.read().clone()));
+        let svg_rect_node: Arc<RwLock<dyn Node>> =
+            Arc::new(RwLock::new(svg_rect_ref.read().clone()));
         root.write().append_child(span_node).unwrap();
         root.write().append_child(p_node).unwrap();
         root.write().append_child(svg_rect_node).unwrap();
@@ -465,8 +482,7 @@ fn test_by_tag_name_is_live() {
     // Add element
     let span = doc.create_element("span").unwrap();
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -489,8 +505,7 @@ fn test_by_class_name_is_live() {
     let span = doc.create_element("span").unwrap();
     span.write().set_attribute("class", "active").unwrap();
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
         root.write().append_child(span_node).unwrap();
     }
 
@@ -518,8 +533,7 @@ fn test_html_collection_is_live() {
     // Add span element
     let span1 = doc.create_element("span").unwrap();
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
         root.write().append_child(span1_node).unwrap();
     }
 
@@ -529,8 +543,7 @@ fn test_html_collection_is_live() {
     // Add another span
     let span2 = doc.create_element("span").unwrap();
     {
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         root.write().append_child(span2_node).unwrap();
     }
 
@@ -547,10 +560,8 @@ fn test_html_collection_item() {
     let span2 = doc.create_element("span").unwrap();
 
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
     }
@@ -582,11 +593,9 @@ fn test_html_collection_filters_correctly() {
     let div = doc.create_element("div").unwrap();
 
     {
-        let span_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span.read().clone())));
-        let p_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(p.read().clone())));
-        let div_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(div.read().clone())));
+        let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
+        let p_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(p.read().clone()));
+        let div_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(div.read().clone()));
         root.write().append_child(span_node).unwrap();
         root.write().append_child(p_node).unwrap();
         root.write().append_child(div_node).unwrap();
@@ -612,10 +621,8 @@ fn test_html_collection_named_item() {
     span2.write().set_attribute("name", "namedspan").unwrap();
 
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
     }
@@ -643,18 +650,15 @@ fn test_html_collection_nested_elements() {
     let child1 = doc.create_element("div").unwrap();
     let span1 = doc.create_element("span").unwrap();
     {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
         child1.write().append_child(span1_node).unwrap();
     }
 
     let span2 = doc.create_element("span").unwrap();
 
     {
-        let child1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(child1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let child1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(child1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         root.write().append_child(child1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
     }
@@ -676,10 +680,8 @@ fn test_html_collection_updates_on_removal() {
     let span2 = doc.create_element("span").unwrap();
 
     let child1_ref = {
-        let span1_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span1.read().clone())));
-        let span2_node: Arc<RwLock<Box<dyn Node>>> =
-            Arc::new(RwLock::new(Box::new(span2.read().clone())));
+        let span1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span1.read().clone()));
+        let span2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span2.read().clone()));
         let c1 = root.write().append_child(span1_node).unwrap();
         root.write().append_child(span2_node).unwrap();
         c1
@@ -697,3 +699,78 @@ fn test_html_collection_updates_on_removal() {
     // Collection should reflect the removal (LIVE)
     assert_eq!(collection.length(), 1);
 }
+
+fn append_element(root: &ElementRef, tag: &str, id: &str) -> Arc<RwLock<dyn Node>> {
+    let element = Document::new().create_element(tag).unwrap();
+    element.write().set_attribute("id", id).unwrap();
+    let node = new_node_ref(element.read().clone());
+    root.write().append_child(node.clone()).unwrap();
+    node
+}
+
+#[test]
+fn test_html_collection_index() {
+    let root = Arc::new(RwLock::new(Element::new("div")));
+    append_element(&root, "span", "first");
+    append_element(&root, "p", "skipped");
+    append_element(&root, "span", "second");
+
+    let collection = HTMLCollection::by_tag_name(root.clone(), "span".to_string());
+    assert_eq!(collection[0].read().id(), Some("first"));
+    assert_eq!(collection[1].read().id(), Some("second"));
+    assert!(collection.item(2).is_none());
+}
+
+#[test]
+fn test_html_collection_index_returns_tree_elements() {
+    let root = Arc::new(RwLock::new(Element::new("div")));
+    let span = append_element(&root, "span", "only");
+
+    let collection = HTMLCollection::by_tag_name(root.clone(), "span".to_string());
+    let first = &collection[0];
+    assert!(std::ptr::addr_eq(Arc::as_ptr(first), Arc::as_ptr(&span)));
+    // Indexing the same element again reuses its entry
+    assert!(std::ptr::eq(first, &collection[0]));
+
+    first.write().set_attribute("title", "edited").unwrap();
+    let span = span.read();
+    let span = span.as_any().downcast_ref::<Element>().unwrap();
+    assert_eq!(span.get_attribute("title"), Some("edited"));
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 1 but the index is 1")]
+fn test_html_collection_index_out_of_bounds_panics() {
+    let root = Arc::new(RwLock::new(Element::new("div")));
+    append_element(&root, "span", "only");
+
+    let collection = HTMLCollection::by_tag_name(root.clone(), "span".to_string());
+    let _ = &collection[1];
+}
+
+#[test]
+fn test_html_collection_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<HTMLCollection>();
+}
+
+#[test]
+fn test_html_collection_as_vec_is_snapshot() {
+    let root = Arc::new(RwLock::new(Element::new("div")));
+    let first = append_element(&root, "span", "first");
+    append_element(&root, "span", "second");
+
+    let collection = HTMLCollection::by_tag_name(root.clone(), "span".to_string());
+    let snapshot = collection.as_vec();
+
+    root.write().remove_child(first).unwrap();
+    append_element(&root, "span", "third");
+    append_element(&root, "span", "fourth");
+
+    assert_eq!(collection.length(), 3);
+    let ids: Vec<String> = snapshot
+        .iter()
+        .map(|element| element.read().id().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(ids, ["first", "second"]);
+}
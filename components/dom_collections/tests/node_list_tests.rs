@@ -1,5 +1,5 @@
-use dom_collections::NodeList;
-use dom_core::{Document, Node};
+use dom_collections::{ElementsByNameExt, NodeList};
+use dom_core::{new_node_ref, Document, DocumentRef, Element, Node, NodeRef};
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -10,8 +10,8 @@ fn test_static_node_list() {
     let elem2 = doc.create_element("span").unwrap();
 
     // Create node refs from elements
-    let node1: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(elem1.read().clone())));
-    let node2: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(elem2.read().clone())));
+    let node1: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(elem1.read().clone()));
+    let node2: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(elem2.read().clone()));
 
     let nodes = vec![node1, node2];
     let node_list = NodeList::new_static(nodes);
@@ -40,11 +40,102 @@ fn test_live_node_list() {
     let mut doc = Document::new();
     let root = doc.create_element("div").unwrap();
 
-    let root_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(root.read().clone())));
+    let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
 
     let node_list = NodeList::new_live(root_node.clone(), |_| true);
 
     // Live list should have at least the root
     assert!(node_list.length() >= 1);
 }
+
+fn element_node(tag: &str, name: Option<&str>) -> NodeRef {
+    let mut element = Element::new(tag);
+    if let Some(name) = name {
+        element.set_attribute("name", name).unwrap();
+    }
+    new_node_ref(element)
+}
+
+#[test]
+fn test_get_elements_by_name_is_live() {
+    let doc: DocumentRef = Arc::new(RwLock::new(Document::new()));
+    let form = element_node("form", None);
+    doc.write().append_child(form.clone()).unwrap();
+    for name in ["group", "group", "other", "group"] {
+        form.write()
+            .append_child(element_node("input", Some(name)))
+            .unwrap();
+    }
+    // `name` must match exactly
+    form.write()
+        .append_child(element_node("div", Some("Group")))
+        .unwrap();
+
+    let radios = doc.get_elements_by_name("group");
+    assert_eq!(radios.length(), 3);
+
+    let late = element_node("input", Some("group"));
+    form.write().append_child(late.clone()).unwrap();
+    assert_eq!(radios.length(), 4);
+    assert!(Arc::ptr_eq(&radios.item(3).unwrap(), &late));
+
+    form.write().remove_child(late).unwrap();
+    assert_eq!(radios.length(), 3);
+    assert!(radios.item(3).is_none());
+}
+
+#[test]
+fn test_live_node_list_scans_descendants_in_tree_order() {
+    let root = element_node("div", None);
+    let first = element_node("p", Some("a"));
+    let nested = element_node("span", Some("b"));
+    first.write().append_child(nested.clone()).unwrap();
+    root.write().append_child(first.clone()).unwrap();
+    root.write()
+        .append_child(element_node("p", Some("c")))
+        .unwrap();
+
+    let named = NodeList::new_live(root, |node| {
+        node.read()
+            .as_any()
+            .downcast_ref::<Element>()
+            .is_some_and(|element| element.has_attribute("name"))
+    });
+    assert_eq!(named.length(), 3);
+    assert!(Arc::ptr_eq(&named.item(0).unwrap(), &first));
+    assert!(Arc::ptr_eq(&named.item(1).unwrap(), &nested));
+}
+
+#[test]
+fn test_for_each_and_entries_pass_indices_in_order() {
+    let root = element_node("ul", None);
+    let items: Vec<NodeRef> = ["a", "b", "c"]
+        .into_iter()
+        .map(|name| element_node("li", Some(name)))
+        .collect();
+    for item in &items {
+        root.write().append_child(item.clone()).unwrap();
+    }
+    let list = NodeList::new_live(root.clone(), |node| node.read().node_name() == "LI");
+
+    // Items appended by the callback are not part of the walk
+    let mut seen = Vec::new();
+    list.for_each(|node, index| {
+        seen.push((index, node));
+        root.write().append_child(element_node("li", None)).unwrap();
+    });
+    assert_eq!(seen.len(), 3);
+    for ((index, node), (expected_index, expected)) in seen.iter().zip(items.iter().enumerate()) {
+        assert_eq!(*index, expected_index);
+        assert!(Arc::ptr_eq(node, expected));
+    }
+
+    let entries: Vec<(usize, NodeRef)> = NodeList::new_static(items.clone()).entries().collect();
+    let indices: Vec<usize> = entries.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indices, [0, 1, 2]);
+    assert!(entries
+        .iter()
+        .zip(&items)
+        .all(|((_, node), item)| Arc::ptr_eq(node, item)));
+    assert_eq!(list.entries().count(), 6);
+}
@@ -16,7 +16,7 @@ use std::sync::Arc;
 ///     ├── comment ("test comment")
 ///     └── p
 ///         └── text3 ("End")
-fn create_test_tree() -> Arc<RwLock<Box<dyn Node>>> {
+fn create_test_tree() -> Arc<RwLock<dyn Node>> {
     let mut doc = Document::new();
 
     // Create root element
@@ -32,8 +32,7 @@ fn create_test_tree() -> Arc<RwLock<Box<dyn Node>>> {
     span.write().append_child(text2).unwrap();
 
     // Convert span to NodeRef and add to root
-    let span_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(span.read().clone()) as Box<dyn Node>));
+    let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
     root.write().append_child(span_node).unwrap();
 
     // Create and add comment
@@ -46,13 +45,12 @@ fn create_test_tree() -> Arc<RwLock<Box<dyn Node>>> {
     p.write().append_child(text3).unwrap();
 
     // Convert p to NodeRef and add to root
-    let p_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(p.read().clone()) as Box<dyn Node>));
+    let p_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(p.read().clone()));
     root.write().append_child(p_node).unwrap();
 
     // Convert root to NodeRef and return
     let root_clone = root.read().clone();
-    Arc::new(RwLock::new(Box::new(root_clone) as Box<dyn Node>))
+    Arc::new(RwLock::new(root_clone))
 }
 
 #[test]
@@ -401,8 +399,7 @@ fn test_detach() {
 fn test_empty_tree() {
     let mut doc = Document::new();
     let root = doc.create_element("div").unwrap();
-    let root_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(root.read().clone())));
+    let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
 
     let mut iter = NodeIterator::new(root_node.clone(), SHOW_ALL, None);
 
@@ -423,17 +420,14 @@ fn test_single_level_tree() {
 
     // Add two children
     let child1 = doc.create_element("span").unwrap();
-    let child1_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(child1.read().clone())));
+    let child1_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(child1.read().clone()));
     root.write().append_child(child1_node).unwrap();
 
     let child2 = doc.create_element("p").unwrap();
-    let child2_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(child2.read().clone())));
+    let child2_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(child2.read().clone()));
     root.write().append_child(child2_node).unwrap();
 
-    let root_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(root.read().clone())));
+    let root_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
 
     let mut iter = NodeIterator::new(root_node.clone(), SHOW_ELEMENT, None);
 
@@ -466,18 +460,16 @@ fn test_deep_tree() {
 
     span.write().append_child(text).unwrap();
 
-    let span_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(span.read().clone())));
+    let span_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(span.read().clone()));
     li.write().append_child(span_node).unwrap();
 
-    let li_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(li.read().clone())));
+    let li_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(li.read().clone()));
     ul.write().append_child(li_node).unwrap();
 
-    let ul_node: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(ul.read().clone())));
+    let ul_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(ul.read().clone()));
     div.write().append_child(ul_node).unwrap();
 
-    let div_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(div.read().clone())));
+    let div_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(div.read().clone()));
 
     let mut iter = NodeIterator::new(div_node.clone(), SHOW_ELEMENT, None);
 
@@ -553,3 +545,34 @@ fn test_constants() {
     assert_eq!(SHOW_TEXT, 0x4);
     assert_eq!(SHOW_COMMENT, 0x80);
 }
+
+#[test]
+fn test_with_filter_fn_accepts_elements_with_class() {
+    let mut doc = Document::new();
+    let root = doc.create_element("ul").unwrap();
+    for class in ["item", "", "item selected"] {
+        let li = doc.create_element("li").unwrap();
+        li.write().set_attribute("class", class).unwrap();
+        let li: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(li.read().clone()));
+        root.write().append_child(li).unwrap();
+    }
+    let root: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(root.read().clone()));
+
+    let mut iter = NodeIterator::with_filter_fn(root, SHOW_ELEMENT, |node| {
+        let node = node.read();
+        match node.as_any().downcast_ref::<Element>() {
+            Some(element) if element.class_list().iter().any(|class| class == "item") => {
+                FilterResult::Accept
+            }
+            _ => FilterResult::Skip,
+        }
+    });
+
+    let mut classes = Vec::new();
+    while let Some(node) = iter.next_node() {
+        let node = node.read();
+        let element = node.as_any().downcast_ref::<Element>().unwrap();
+        classes.push(element.get_attribute("class").unwrap().to_string());
+    }
+    assert_eq!(classes, ["item", "item selected"]);
+}
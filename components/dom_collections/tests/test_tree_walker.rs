@@ -20,45 +20,45 @@ use std::sync::Arc;
 ///     └── p
 ///         ├── text3 ("End")
 ///         └── em
-fn create_test_tree() -> Arc<RwLock<Box<dyn Node>>> {
+fn create_test_tree() -> Arc<RwLock<dyn Node>> {
     use dom_core::{Text, Comment};
 
     // Create root element as NodeRef directly
-    let root: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("div")) as Box<dyn Node>));
+    let root: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("div")));
 
     // Create text1
-    let text1: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Text::new("Hello")) as Box<dyn Node>));
+    let text1: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Text::new("Hello")));
     text1.write().node_data_mut().set_parent(Some(Arc::downgrade(&root)));
     root.write().node_data_mut().add_child(text1);
 
     // Create span with text2 and b
-    let span: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("span")) as Box<dyn Node>));
+    let span: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("span")));
     span.write().node_data_mut().set_parent(Some(Arc::downgrade(&root)));
 
-    let text2: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Text::new("World")) as Box<dyn Node>));
+    let text2: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Text::new("World")));
     text2.write().node_data_mut().set_parent(Some(Arc::downgrade(&span)));
     span.write().node_data_mut().add_child(text2);
 
-    let b: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("b")) as Box<dyn Node>));
+    let b: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("b")));
     b.write().node_data_mut().set_parent(Some(Arc::downgrade(&span)));
     span.write().node_data_mut().add_child(b);
 
     root.write().node_data_mut().add_child(span);
 
     // Create comment
-    let comment: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Comment::new("test comment")) as Box<dyn Node>));
+    let comment: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Comment::new("test comment")));
     comment.write().node_data_mut().set_parent(Some(Arc::downgrade(&root)));
     root.write().node_data_mut().add_child(comment);
 
     // Create p with text3 and em
-    let p: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("p")) as Box<dyn Node>));
+    let p: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("p")));
     p.write().node_data_mut().set_parent(Some(Arc::downgrade(&root)));
 
-    let text3: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Text::new("End")) as Box<dyn Node>));
+    let text3: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Text::new("End")));
     text3.write().node_data_mut().set_parent(Some(Arc::downgrade(&p)));
     p.write().node_data_mut().add_child(text3);
 
-    let em: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("em")) as Box<dyn Node>));
+    let em: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("em")));
     em.write().node_data_mut().set_parent(Some(Arc::downgrade(&p)));
     p.write().node_data_mut().add_child(em);
 
@@ -72,13 +72,13 @@ fn create_test_tree() -> Arc<RwLock<Box<dyn Node>>> {
 ///   root (div)
 ///     ├── span
 ///     └── p
-fn create_simple_tree() -> Arc<RwLock<Box<dyn Node>>> {
+fn create_simple_tree() -> Arc<RwLock<dyn Node>> {
     // Create root as NodeRef directly
-    let root: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("div")) as Box<dyn Node>));
+    let root: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("div")));
 
     // Create children as NodeRefs
-    let span: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("span")) as Box<dyn Node>));
-    let p: Arc<RwLock<Box<dyn Node>>> = Arc::new(RwLock::new(Box::new(Element::new("p")) as Box<dyn Node>));
+    let span: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("span")));
+    let p: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(Element::new("p")));
 
     // Set parent references first (before adding to children)
     span.write().node_data_mut().set_parent(Some(Arc::downgrade(&root)));
@@ -496,7 +496,7 @@ fn test_custom_filter_accept() {
     let root = create_test_tree();
 
     // Filter that accepts only elements with name "P"
-    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<Box<dyn Node>>>| {
+    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<dyn Node>>| {
         if node.read().node_name() == "P" {
             FilterResult::Accept
         } else {
@@ -520,7 +520,7 @@ fn test_custom_filter_reject() {
     let root = create_test_tree();
 
     // Filter that rejects elements with name "SPAN" (and its descendants)
-    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<Box<dyn Node>>>| {
+    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<dyn Node>>| {
         if node.read().node_name() == "SPAN" {
             FilterResult::Reject
         } else {
@@ -549,7 +549,7 @@ fn test_custom_filter_skip() {
     let root = create_test_tree();
 
     // Filter that skips "SPAN" but accepts its children
-    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<Box<dyn Node>>>| {
+    let filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<dyn Node>>| {
         if node.read().node_name() == "SPAN" {
             FilterResult::Skip
         } else {
@@ -584,8 +584,7 @@ fn test_set_current_node_outside_root() {
     // Create a different node outside root subtree
     let mut doc = Document::new();
     let external = doc.create_element("external").unwrap();
-    let external_node: Arc<RwLock<Box<dyn Node>>> =
-        Arc::new(RwLock::new(Box::new(external.read().clone()) as Box<dyn Node>));
+    let external_node: Arc<RwLock<dyn Node>> = Arc::new(RwLock::new(external.read().clone()));
 
     // Set current_node to external node (should be allowed)
     walker.set_current_node(external_node.clone());
@@ -625,7 +624,7 @@ fn test_filter_result_skip_vs_reject() {
     let root = create_test_tree();
 
     // Test Skip: Skips node but checks children
-    let skip_filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<Box<dyn Node>>>| {
+    let skip_filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<dyn Node>>| {
         if node.read().node_name() == "SPAN" {
             FilterResult::Skip // Skip SPAN but allow B
         } else {
@@ -641,7 +640,7 @@ fn test_filter_result_skip_vs_reject() {
     assert_eq!(b.unwrap().read().node_name(), "B");
 
     // Test Reject: Rejects node AND its descendants
-    let reject_filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<Box<dyn Node>>>| {
+    let reject_filter: NodeFilter = Some(Arc::new(|node: &Arc<RwLock<dyn Node>>| {
         if node.read().node_name() == "SPAN" {
             FilterResult::Reject // Reject SPAN and all children
         } else {
@@ -657,3 +656,58 @@ fn test_filter_result_skip_vs_reject() {
     assert!(p.is_some());
     assert_eq!(p.unwrap().read().node_name(), "P");
 }
+
+/// Helper to create `ul > [li.item, li, li.item > span.item]`
+fn create_class_tree() -> Arc<RwLock<dyn Node>> {
+    let node = |tag: &str, class: Option<&str>| {
+        let mut element = Element::new(tag);
+        if let Some(class) = class {
+            element.set_attribute("class", class).unwrap();
+        }
+        Arc::new(RwLock::new(element)) as Arc<RwLock<dyn Node>>
+    };
+    let root = node("ul", None);
+    let last = node("li", Some("item"));
+    let span = node("span", Some("item"));
+    span.write()
+        .node_data_mut()
+        .set_parent(Some(Arc::downgrade(&last)));
+    last.write().node_data_mut().add_child(span);
+    for child in [node("li", Some("item")), node("li", None), last] {
+        child
+            .write()
+            .node_data_mut()
+            .set_parent(Some(Arc::downgrade(&root)));
+        root.write().node_data_mut().add_child(child);
+    }
+    root
+}
+
+#[test]
+fn test_with_filter_fn_accepts_elements_with_class() {
+    let root = create_class_tree();
+    let mut walker = TreeWalker::with_filter_fn(root, SHOW_ELEMENT, |node| {
+        let node = node.read();
+        let has_class = node
+            .as_any()
+            .downcast_ref::<Element>()
+            .is_some_and(|element| element.class_list().iter().any(|class| class == "item"));
+        if has_class {
+            FilterResult::Accept
+        } else {
+            FilterResult::Skip
+        }
+    });
+
+    let mut names = Vec::new();
+    while let Some(node) = walker.next_node() {
+        names.push(node.read().node_name().to_string());
+    }
+    assert_eq!(names, ["LI", "LI", "SPAN"]);
+
+    // Directional moves honor the closure too
+    walker.set_current_node(walker.root());
+    assert_eq!(walker.last_child().unwrap().read().node_name(), "LI");
+    assert_eq!(walker.previous_sibling().unwrap().read().node_name(), "LI");
+    assert!(walker.previous_sibling().is_none());
+}
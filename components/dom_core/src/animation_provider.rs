@@ -0,0 +1,109 @@
+//! Animation hook for `Element.getAnimations`
+//!
+//! The DOM does not run CSS transitions or animations itself; the style or
+//! animation engine installs an [`AnimationProvider`] on the document with
+//! [`Document::set_animation_provider`](crate::Document::set_animation_provider)
+//! to report what is currently running on its elements. Elements of a
+//! document without a provider have no animations.
+
+use crate::element::{Element, ElementRef};
+use crate::node::NodeRef;
+use std::fmt;
+use std::sync::Arc;
+
+/// Kind of animation reported by an [`AnimationHandle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationType {
+    /// A CSS transition (`CSSTransition`)
+    Transition,
+    /// A CSS animation (`CSSAnimation`)
+    Animation,
+}
+
+/// Play state of an animation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationPlayState {
+    /// Not yet started or cancelled
+    Idle,
+    /// Currently playing
+    #[default]
+    Running,
+    /// Paused
+    Paused,
+    /// Reached its end
+    Finished,
+}
+
+/// Handle to a transition or animation running on an element
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationHandle {
+    name: String,
+    animation_type: AnimationType,
+    play_state: AnimationPlayState,
+}
+
+impl AnimationHandle {
+    /// Creates a handle for a running transition of `property`
+    pub fn transition(property: impl Into<String>) -> Self {
+        Self::new(
+            property,
+            AnimationType::Transition,
+            AnimationPlayState::Running,
+        )
+    }
+
+    /// Creates a handle for a running animation named `animation_name`
+    pub fn animation(animation_name: impl Into<String>) -> Self {
+        Self::new(
+            animation_name,
+            AnimationType::Animation,
+            AnimationPlayState::Running,
+        )
+    }
+
+    /// Creates a handle with an explicit type and play state
+    pub fn new(
+        name: impl Into<String>,
+        animation_type: AnimationType,
+        play_state: AnimationPlayState,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            animation_type,
+            play_state,
+        }
+    }
+
+    /// Transitioned property or `animation-name`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this is a transition or an animation
+    pub fn animation_type(&self) -> AnimationType {
+        self.animation_type
+    }
+
+    /// Current play state
+    pub fn play_state(&self) -> AnimationPlayState {
+        self.play_state
+    }
+}
+
+/// Source of the animations running on elements, typically the style engine
+pub trait AnimationProvider: fmt::Debug + Send + Sync {
+    /// Gets the transitions and animations currently running on `element`
+    fn animations(&self, element: &ElementRef) -> Vec<AnimationHandle>;
+}
+
+/// Binds every element of the subtree rooted at `root` to `provider`
+pub(crate) fn bind_subtree(root: &NodeRef, provider: Option<&Arc<dyn AnimationProvider>>) {
+    let mut stack = vec![root.clone()];
+    while let Some(node) = stack.pop() {
+        let mut node = node.write();
+        if let Some(element) = node.as_any_mut().downcast_mut::<Element>() {
+            element.set_animation_provider(provider.cloned());
+        }
+        stack.extend(node.child_nodes());
+    }
+}
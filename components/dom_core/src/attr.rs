@@ -102,8 +102,37 @@ impl Attr {
     }
 
     /// Sets the attribute value
+    ///
+    /// While the attribute is present on its owner element, the element's
+    /// attribute is updated too, which write-locks the owner: never call this
+    /// while holding a lock on the owner element. Once the element no longer
+    /// has the attribute, the attribute loses its owner and only its own value
+    /// changes. If the owner rejects the value, neither value changes.
     pub fn set_value(&mut self, value: impl Into<String>) {
-        self.value = value.into();
+        let value = value.into();
+        if let Some(owner) = self.owner_element() {
+            let mut owner = owner.write();
+            let namespace = self.namespace_uri.as_deref();
+            let present = match namespace {
+                Some(namespace) => owner
+                    .get_attribute_ns(Some(namespace), &self.local_name)
+                    .is_some(),
+                None => owner.has_attribute(&self.name),
+            };
+            if present {
+                let applied = match namespace {
+                    Some(namespace) => owner.set_attribute_ns(Some(namespace), &self.name, &value),
+                    None => owner.set_attribute(self.name.as_str(), value.as_str()),
+                };
+                if applied.is_err() {
+                    return;
+                }
+            } else {
+                drop(owner);
+                self.owner_element = None;
+            }
+        }
+        self.value = value;
     }
 
     /// Gets the namespace URI
@@ -146,11 +175,7 @@ impl Node for Attr {
     }
 
     fn set_node_value(&mut self, value: Option<String>) {
-        if let Some(val) = value {
-            self.value = val;
-        } else {
-            self.value.clear();
-        }
+        self.set_value(value.unwrap_or_default());
     }
 
     fn text_content(&self) -> Option<String> {
@@ -158,7 +183,7 @@ impl Node for Attr {
     }
 
     fn set_text_content(&mut self, text: String) {
-        self.value = text;
+        self.set_value(text);
     }
 
     fn parent_node(&self) -> Option<NodeRef> {
@@ -193,7 +218,7 @@ impl Node for Attr {
 
     fn clone_node(&self, _deep: bool) -> NodeRef {
         let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(cloned))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -204,6 +229,16 @@ impl Node for Attr {
         &mut self.node_data
     }
 
+    fn is_equal_node(&self, other: &dyn Node) -> bool {
+        // Attributes are equal by namespace, local name and value; the
+        // prefix doesn't matter
+        other.as_any().downcast_ref::<Attr>().is_some_and(|other| {
+            self.namespace_uri() == other.namespace_uri()
+                && self.local_name() == other.local_name()
+                && self.value() == other.value()
+        })
+    }
+
     fn contains(&self, other: &dyn Node) -> bool {
         let self_ptr = self as *const _ as *const dyn Node;
         let other_ptr = other as *const dyn Node;
@@ -213,6 +248,10 @@ impl Node for Attr {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 /// Validates a qualified name according to XML naming rules
@@ -316,4 +355,21 @@ mod tests {
         let attr = Attr::new("test", "value");
         assert_eq!(attr.node_type(), NodeType::Attribute);
     }
+
+    #[test]
+    fn test_attr_equality_is_namespace_aware() {
+        const XLINK: &str = "http://www.w3.org/1999/xlink";
+        let xlink = Attr::new_ns(XLINK, "xlink:href", "#a").unwrap();
+        let other_ns = Attr::new_ns("urn:other", "xlink:href", "#a").unwrap();
+        let plain = Attr::new("xlink:href", "#a");
+
+        assert!(!xlink.is_equal_node(&other_ns));
+        assert!(!xlink.is_equal_node(&plain));
+
+        // The prefix is not compared, the value is
+        let renamed = Attr::new_ns(XLINK, "l:href", "#a").unwrap();
+        assert!(xlink.is_equal_node(&renamed));
+        let changed = Attr::new_ns(XLINK, "xlink:href", "#b").unwrap();
+        assert!(!xlink.is_equal_node(&changed));
+    }
 }
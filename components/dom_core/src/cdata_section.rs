@@ -15,10 +15,12 @@
 //! ]]></script>
 //! ```
 
+use crate::character_data::{edit_data, CharacterData};
 use crate::node::{Node, NodeData, NodeRef};
+use crate::tree_generation::{TreeGenerations, TreeGenerationsRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// CDATASection node implementation
 ///
@@ -40,6 +42,9 @@ pub struct CDATASection {
 
     /// Text content (the data inside <![CDATA[...]]>)
     data: String,
+
+    /// Counters of the document the node is in, told about data changes
+    tree_generations: Option<Weak<TreeGenerations>>,
 }
 
 /// Thread-safe reference to a CDATASection
@@ -60,9 +65,18 @@ impl CDATASection {
         Self {
             node_data: NodeData::new(NodeType::CDataSection, "#cdata-section"),
             data: data.into(),
+            tree_generations: None,
         }
     }
 
+    /// Binds the CDATA section to the mutation counters of its document
+    ///
+    /// Changes to its data are then reported to the document's character
+    /// data observers.
+    pub fn set_tree_generations(&mut self, generations: Option<&TreeGenerationsRef>) {
+        self.tree_generations = generations.map(Arc::downgrade);
+    }
+
     /// Gets the CDATA section data
     ///
     /// # Examples
@@ -89,10 +103,11 @@ impl CDATASection {
     /// assert_eq!(cdata.data(), "new");
     /// ```
     pub fn set_data(&mut self, data: impl Into<String>) {
-        self.data = data.into();
+        let data = data.into();
+        edit_data(self, |current| *current = data);
     }
 
-    /// Gets the length of the CDATA section data
+    /// Gets the length of the CDATA section data in UTF-16 code units
     ///
     /// # Examples
     ///
@@ -103,151 +118,19 @@ impl CDATASection {
     /// assert_eq!(cdata.length(), 5);
     /// ```
     pub fn length(&self) -> usize {
-        self.data.len()
-    }
-
-    /// Appends data to the existing CDATA section content
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dom_core::CDATASection;
-    ///
-    /// let mut cdata = CDATASection::new("Hello");
-    /// cdata.append_data(", World!");
-    /// assert_eq!(cdata.data(), "Hello, World!");
-    /// ```
-    pub fn append_data(&mut self, data: impl Into<String>) {
-        self.data.push_str(&data.into());
+        CharacterData::len(self)
     }
+}
 
-    /// Replaces data at specified offset
-    ///
-    /// # Arguments
-    ///
-    /// * `offset` - The character offset at which to start replacing
-    /// * `count` - The number of characters to replace
-    /// * `data` - The replacement data
-    ///
-    /// # Errors
-    ///
-    /// Returns `InvalidModificationError` if offset is greater than the length
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dom_core::CDATASection;
-    ///
-    /// let mut cdata = CDATASection::new("Hello, World!");
-    /// cdata.replace_data(7, 5, "Rust").unwrap();
-    /// assert_eq!(cdata.data(), "Hello, Rust!");
-    /// ```
-    pub fn replace_data(
-        &mut self,
-        offset: usize,
-        count: usize,
-        data: impl Into<String>,
-    ) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
-
-        let end = (offset + count).min(self.data.len());
-        self.data.replace_range(offset..end, &data.into());
-        Ok(())
-    }
-
-    /// Deletes data at specified offset
-    ///
-    /// # Arguments
-    ///
-    /// * `offset` - The character offset at which to start deleting
-    /// * `count` - The number of characters to delete
-    ///
-    /// # Errors
-    ///
-    /// Returns `InvalidModificationError` if offset is greater than the length
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dom_core::CDATASection;
-    ///
-    /// let mut cdata = CDATASection::new("Hello, World!");
-    /// cdata.delete_data(5, 7).unwrap();
-    /// assert_eq!(cdata.data(), "Hello!");
-    /// ```
-    pub fn delete_data(&mut self, offset: usize, count: usize) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
-
-        let end = (offset + count).min(self.data.len());
-        self.data.replace_range(offset..end, "");
-        Ok(())
+impl CharacterData for CDATASection {
+    fn data(&self) -> &str {
+        &self.data
     }
 
-    /// Inserts data at specified offset
-    ///
-    /// # Arguments
-    ///
-    /// * `offset` - The character offset at which to insert
-    /// * `data` - The data to insert
-    ///
-    /// # Errors
-    ///
-    /// Returns `InvalidModificationError` if offset is greater than the length
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dom_core::CDATASection;
-    ///
-    /// let mut cdata = CDATASection::new("Hello!");
-    /// cdata.insert_data(5, " World").unwrap();
-    /// assert_eq!(cdata.data(), "Hello World!");
-    /// ```
-    pub fn insert_data(
-        &mut self,
-        offset: usize,
-        data: impl Into<String>,
-    ) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
-
-        self.data.insert_str(offset, &data.into());
-        Ok(())
+    fn data_mut(&mut self) -> &mut String {
+        &mut self.data
     }
 
-    /// Extracts a substring from the CDATA section data
-    ///
-    /// # Arguments
-    ///
-    /// * `offset` - The character offset at which to start extracting
-    /// * `count` - The number of characters to extract
-    ///
-    /// # Errors
-    ///
-    /// Returns `InvalidModificationError` if offset is greater than the length
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dom_core::CDATASection;
-    ///
-    /// let cdata = CDATASection::new("Hello, World!");
-    /// let substr = cdata.substring_data(7, 5).unwrap();
-    /// assert_eq!(substr, "World");
-    /// ```
-    pub fn substring_data(&self, offset: usize, count: usize) -> Result<String, DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
-
-        let end = (offset + count).min(self.data.len());
-        Ok(self.data[offset..end].to_string())
-    }
 }
 
 impl Node for CDATASection {
@@ -264,11 +147,7 @@ impl Node for CDATASection {
     }
 
     fn set_node_value(&mut self, value: Option<String>) {
-        if let Some(val) = value {
-            self.data = val;
-        } else {
-            self.data.clear();
-        }
+        edit_data(self, |current| *current = value.unwrap_or_default());
     }
 
     fn text_content(&self) -> Option<String> {
@@ -276,7 +155,7 @@ impl Node for CDATASection {
     }
 
     fn set_text_content(&mut self, text: String) {
-        self.data = text;
+        edit_data(self, |current| *current = text);
     }
 
     fn parent_node(&self) -> Option<NodeRef> {
@@ -308,8 +187,9 @@ impl Node for CDATASection {
     }
 
     fn clone_node(&self, _deep: bool) -> NodeRef {
-        let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        let mut cloned = self.clone();
+        cloned.tree_generations = None;
+        Arc::new(RwLock::new(cloned))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -326,9 +206,17 @@ impl Node for CDATASection {
         std::ptr::addr_eq(self_ptr, other_ptr)
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        self.tree_generations.as_ref().and_then(Weak::upgrade)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -375,7 +263,7 @@ mod tests {
     fn test_cdata_no_children() {
         let mut cdata = CDATASection::new("test");
         let child = CDATASection::new("child");
-        let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+        let child_ref = Arc::new(RwLock::new(child));
 
         let result = cdata.append_child(child_ref);
         assert!(result.is_err());
@@ -0,0 +1,219 @@
+//! Shared behavior of character data nodes
+//!
+//! [`Text`](crate::Text), [`Comment`](crate::Comment) and
+//! [`CDATASection`](crate::CDATASection) all hold a string of character data.
+//! As in the DOM specification, lengths and offsets into that data are
+//! counted in UTF-16 code units rather than bytes.
+//!
+//! Mutation observers live in a crate above this one, so changes to the data
+//! of a node bound to a document are reported to the hooks registered with
+//! the document's [`TreeGenerations::observe_character_data`].
+//!
+//! [`TreeGenerations::observe_character_data`]: crate::TreeGenerations::observe_character_data
+
+use crate::node::{Node, NodeRef};
+use dom_types::DomException;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Change to the data of a character data node that is in a document
+#[derive(Debug, Clone)]
+pub struct CharacterDataChange {
+    /// Node whose data changed
+    pub target: NodeRef,
+    /// Parent of the node when it changed
+    pub parent: Option<NodeRef>,
+    /// Data before the change
+    pub old_value: String,
+}
+
+/// Callback told about every change to character data in one document
+///
+/// It runs while the changed node is write-locked, and while `normalize`
+/// holds its parent, so it must not lock any node.
+pub type CharacterDataObserver = Arc<dyn Fn(&CharacterDataChange) + Send + Sync>;
+
+/// Applies `edit` to the data of `node`, telling it the old value afterwards
+///
+/// The old value is only copied while the node's document has character
+/// data observers.
+pub(crate) fn edit_data<T: CharacterData + ?Sized>(node: &mut T, edit: impl FnOnce(&mut String)) {
+    let observed = node
+        .generations()
+        .is_some_and(|generations| generations.observes_character_data());
+    let old_value = observed.then(|| node.data().to_string());
+    edit(node.data_mut());
+    if let Some(old_value) = old_value {
+        node.data_changed(old_value);
+    }
+}
+
+/// Node holding character data
+///
+/// Implementors only provide access to their data; the editing operations are
+/// shared. Offsets past the end, or falling inside a surrogate pair, are
+/// rejected with `IndexSizeError`, while counts are clamped to the end.
+///
+/// # Examples
+///
+/// ```
+/// use dom_core::{CDATASection, CharacterData};
+///
+/// let mut cdata = CDATASection::new("Hello!");
+/// cdata.insert_data(5, ", World").unwrap();
+/// assert_eq!(cdata.substring_data(7, 5).unwrap(), "World");
+///
+/// cdata.replace_data(7, 5, "Rust").unwrap();
+/// cdata.delete_data(5, 1).unwrap();
+/// cdata.append_data("?");
+/// assert_eq!(cdata.data(), "Hello Rust!?");
+/// ```
+pub trait CharacterData: Node {
+    /// Gets the character data
+    fn data(&self) -> &str;
+
+    /// Gets mutable access to the character data
+    ///
+    /// Writes through this reference bypass [`data_changed`](Self::data_changed)
+    /// and so are not seen by mutation observers; the editing operations below
+    /// are.
+    fn data_mut(&mut self) -> &mut String;
+
+    /// Called after an editing operation changed the data from `old_value`
+    ///
+    /// Reports the change to the character data observers of the node's
+    /// document. Nodes without a self reference cannot be named in a change
+    /// and are skipped.
+    fn data_changed(&self, old_value: String) {
+        let (Some(generations), Some(target)) =
+            (self.generations(), self.node_data().get_self_node_ref())
+        else {
+            return;
+        };
+        generations.character_data_changed(&CharacterDataChange {
+            target,
+            parent: self.node_data().get_parent(),
+            old_value,
+        });
+    }
+
+    /// Gets the length of the data in UTF-16 code units
+    fn len(&self) -> usize {
+        self.data().encode_utf16().count()
+    }
+
+    /// Checks whether the data is the empty string
+    fn is_empty(&self) -> bool {
+        self.data().is_empty()
+    }
+
+    /// Appends `data` to the end
+    fn append_data(&mut self, data: &str) {
+        edit_data(self, |current| current.push_str(data));
+    }
+
+    /// Extracts `count` code units starting at `offset`
+    fn substring_data(&self, offset: usize, count: usize) -> Result<String, DomException> {
+        let range = byte_range(self.data(), offset, count)?;
+        Ok(self.data()[range].to_string())
+    }
+
+    /// Inserts `data` at `offset`
+    fn insert_data(&mut self, offset: usize, data: &str) -> Result<(), DomException> {
+        self.replace_data(offset, 0, data)
+    }
+
+    /// Removes `count` code units starting at `offset`
+    fn delete_data(&mut self, offset: usize, count: usize) -> Result<(), DomException> {
+        self.replace_data(offset, count, "")
+    }
+
+    /// Replaces `count` code units starting at `offset` with `data`
+    fn replace_data(
+        &mut self,
+        offset: usize,
+        count: usize,
+        data: &str,
+    ) -> Result<(), DomException> {
+        let range = byte_range(self.data(), offset, count)?;
+        edit_data(self, |current| current.replace_range(range, data));
+        Ok(())
+    }
+}
+
+/// Byte range of `count` UTF-16 code units at `offset`, clamped to the end
+fn byte_range(data: &str, offset: usize, count: usize) -> Result<Range<usize>, DomException> {
+    let start = utf16_to_byte_offset(data, offset).ok_or(DomException::IndexSizeError)?;
+    let end_offset = offset
+        .saturating_add(count)
+        .min(data.encode_utf16().count());
+    let end = utf16_to_byte_offset(data, end_offset).ok_or(DomException::IndexSizeError)?;
+    Ok(start..end)
+}
+
+/// Converts a UTF-16 code unit offset into a byte offset into `data`
+///
+/// Returns `None` if the offset is past the end or inside a surrogate pair.
+pub(crate) fn utf16_to_byte_offset(data: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (byte_index, ch) in data.char_indices() {
+        if units == offset {
+            return Some(byte_index);
+        }
+        units += ch.len_utf16();
+        if units > offset {
+            return None;
+        }
+    }
+    (units == offset).then_some(data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CDATASection, Comment, Text};
+
+    /// Runs the shared operations against a node built by `make`
+    fn check_operations<T: CharacterData>(make: fn(&'static str) -> T) {
+        for (offset, expected) in [(0, "-ab"), (1, "a-b"), (2, "ab-")] {
+            let mut node = make("ab");
+            node.insert_data(offset, "-").unwrap();
+            assert_eq!(node.data(), expected);
+        }
+
+        // "x😀y" is 4 code units; the emoji spans units 1 and 2
+        let node = make("x\u{1F600}y");
+        assert_eq!(node.substring_data(1, 2).unwrap(), "\u{1F600}");
+        assert_eq!(node.substring_data(3, 10).unwrap(), "y");
+        assert_eq!(node.substring_data(1, 1), Err(DomException::IndexSizeError));
+        assert_eq!(node.substring_data(2, 1), Err(DomException::IndexSizeError));
+
+        let mut node = make("hello");
+        node.replace_data(1, 3, "ipp").unwrap();
+        node.delete_data(4, 100).unwrap();
+        node.append_data("!");
+        assert_eq!(node.data(), "hipp!");
+
+        let out_of_range = DomException::IndexSizeError;
+        assert_eq!(node.substring_data(6, 1), Err(out_of_range.clone()));
+        assert_eq!(node.insert_data(6, "x"), Err(out_of_range.clone()));
+        assert_eq!(node.delete_data(6, 1), Err(out_of_range.clone()));
+        assert_eq!(node.replace_data(6, 0, "x"), Err(out_of_range));
+        assert_eq!(node.data(), "hipp!");
+    }
+
+    #[test]
+    fn test_text_character_data_operations() {
+        check_operations(Text::new);
+    }
+
+    #[test]
+    fn test_comment_character_data_operations() {
+        check_operations(Comment::new);
+    }
+
+    #[test]
+    fn test_cdata_section_character_data_operations() {
+        check_operations(CDATASection::new);
+    }
+}
@@ -1,9 +1,11 @@
 //! Comment node implementation
 
+use crate::character_data::{edit_data, CharacterData};
 use crate::node::{Node, NodeData, NodeRef};
+use crate::tree_generation::{TreeGenerations, TreeGenerationsRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// Comment node
 #[derive(Clone, Debug)]
@@ -13,17 +15,32 @@ pub struct Comment {
 
     /// Comment text
     data: String,
+
+    /// Counters of the document the node is in, told about data changes
+    tree_generations: Option<Weak<TreeGenerations>>,
 }
 
+/// Thread-safe reference to a Comment
+pub type CommentRef = Arc<RwLock<Comment>>;
+
 impl Comment {
     /// Creates a new comment node
     pub fn new(data: impl Into<String>) -> Self {
         Self {
             node_data: NodeData::new(NodeType::Comment, "#comment"),
             data: data.into(),
+            tree_generations: None,
         }
     }
 
+    /// Binds the comment to the mutation counters of its document
+    ///
+    /// Changes to its data are then reported to the document's character
+    /// data observers.
+    pub fn set_tree_generations(&mut self, generations: Option<&TreeGenerationsRef>) {
+        self.tree_generations = generations.map(Arc::downgrade);
+    }
+
     /// Gets the comment data
     pub fn data(&self) -> &str {
         &self.data
@@ -31,13 +48,37 @@ impl Comment {
 
     /// Sets the comment data
     pub fn set_data(&mut self, data: impl Into<String>) {
-        self.data = data.into();
+        let data = data.into();
+        edit_data(self, |current| *current = data);
     }
 
-    /// Gets the length of the comment
+    /// Gets the length of the comment in UTF-16 code units
     pub fn length(&self) -> usize {
-        self.data.len()
+        CharacterData::len(self)
+    }
+
+    /// Clones this node into a typed reference, outside of any tree
+    pub fn clone_comment(&self) -> CommentRef {
+        Arc::new(RwLock::new(self.clone_detached()))
+    }
+
+    fn clone_detached(&self) -> Self {
+        let mut cloned = self.clone();
+        cloned.node_data.clear_links();
+        cloned.tree_generations = None;
+        cloned
+    }
+}
+
+impl CharacterData for Comment {
+    fn data(&self) -> &str {
+        &self.data
     }
+
+    fn data_mut(&mut self) -> &mut String {
+        &mut self.data
+    }
+
 }
 
 impl Node for Comment {
@@ -54,11 +95,7 @@ impl Node for Comment {
     }
 
     fn set_node_value(&mut self, value: Option<String>) {
-        if let Some(val) = value {
-            self.data = val;
-        } else {
-            self.data.clear();
-        }
+        edit_data(self, |current| *current = value.unwrap_or_default());
     }
 
     fn text_content(&self) -> Option<String> {
@@ -66,7 +103,7 @@ impl Node for Comment {
     }
 
     fn set_text_content(&mut self, text: String) {
-        self.data = text;
+        edit_data(self, |current| *current = text);
     }
 
     fn parent_node(&self) -> Option<NodeRef> {
@@ -98,8 +135,7 @@ impl Node for Comment {
     }
 
     fn clone_node(&self, _deep: bool) -> NodeRef {
-        let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(self.clone_detached()))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -116,9 +152,17 @@ impl Node for Comment {
         self_ptr == other_ptr
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        self.tree_generations.as_ref().and_then(Weak::upgrade)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -141,11 +185,18 @@ mod tests {
         assert_eq!(comment.data(), "Updated comment");
     }
 
+    #[test]
+    fn test_comment_length_in_utf16_units() {
+        // "a😀b" is 6 bytes but 4 UTF-16 code units
+        let comment = Comment::new("a\u{1F600}b");
+        assert_eq!(comment.length(), 4);
+    }
+
     #[test]
     fn test_comment_no_children() {
         let mut comment = Comment::new("test");
         let child = Comment::new("child");
-        let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+        let child_ref = Arc::new(RwLock::new(child));
 
         let result = comment.append_child(child_ref);
         assert!(result.is_err());
@@ -0,0 +1,148 @@
+//! Element `dataset` views
+//!
+//! [`Element::dataset`] and [`Element::dataset_mut`] expose the element's
+//! `data-*` attributes under camelCase keys, so `data-user-name` reads as
+//! `userName`. The views keep no copy of their own: every read and write goes
+//! straight to the attributes, using the name conversions of [`DOMStringMap`].
+
+use crate::element::Element;
+use dom_types::{DOMStringMap, DomException};
+
+/// Read-only view of an element's `data-*` attributes
+#[derive(Debug, Clone, Copy)]
+pub struct Dataset<'a> {
+    element: &'a Element,
+}
+
+impl<'a> Dataset<'a> {
+    pub(crate) fn new(element: &'a Element) -> Self {
+        Self { element }
+    }
+
+    /// Gets the value stored under the camelCase `key`
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        let name = DOMStringMap::try_to_attribute_name(key).ok()?;
+        self.element.get_attribute(&name)
+    }
+
+    /// Checks whether a value is stored under `key`
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterates over the keys and values in attribute order
+    pub fn iter(&self) -> impl Iterator<Item = (String, &'a str)> + 'a {
+        self.element
+            .attributes()
+            .iter()
+            .filter_map(|(name, value)| {
+                DOMStringMap::from_attribute_name(name).map(|key| (key, value.as_str()))
+            })
+    }
+
+    /// Iterates over the keys in attribute order
+    pub fn keys(&self) -> impl Iterator<Item = String> + 'a {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Number of keys
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Checks whether the element has no `data-*` attributes with a key
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+/// Writable view of an element's `data-*` attributes
+#[derive(Debug)]
+pub struct DatasetMut<'a> {
+    element: &'a mut Element,
+}
+
+impl<'a> DatasetMut<'a> {
+    pub(crate) fn new(element: &'a mut Element) -> Self {
+        Self { element }
+    }
+
+    /// Borrows a read-only view of the same attributes
+    pub fn as_dataset(&self) -> Dataset<'_> {
+        Dataset::new(self.element)
+    }
+
+    /// Gets the value stored under the camelCase `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.as_dataset().get(key)
+    }
+
+    /// Stores `value` under `key`, writing the matching `data-*` attribute
+    ///
+    /// Fails with `SyntaxError` if `key` has a `-` followed by a lowercase
+    /// ASCII letter, and with the errors of [`Element::set_attribute`] if the
+    /// resulting attribute name is invalid.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) -> Result<(), DomException> {
+        let name = DOMStringMap::try_to_attribute_name(key)?;
+        self.element.set_attribute(name, value)
+    }
+
+    /// Removes the `data-*` attribute behind `key`, returning whether it existed
+    pub fn delete(&mut self, key: &str) -> bool {
+        let Ok(name) = DOMStringMap::try_to_attribute_name(key) else {
+            return false;
+        };
+        self.element.has_attribute(&name) && self.element.remove_attribute(&name).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_round_trips_through_attributes() {
+        let mut element = Element::new("div");
+        element.set_attribute("data-user-name", "Ada").unwrap();
+        element.set_attribute("data-foo-1", "one").unwrap();
+        element.set_attribute("title", "ignored").unwrap();
+
+        let dataset = element.dataset();
+        assert_eq!(dataset.get("userName"), Some("Ada"));
+        assert_eq!(dataset.get("foo-1"), Some("one"));
+        assert_eq!(dataset.keys().collect::<Vec<_>>(), ["userName", "foo-1"]);
+        assert_eq!(dataset.len(), 2);
+
+        let mut dataset = element.dataset_mut();
+        dataset.set("sessionId", "42").unwrap();
+        dataset.set("userName", "Grace").unwrap();
+        assert_eq!(element.get_attribute("data-session-id"), Some("42"));
+        assert_eq!(element.get_attribute("data-user-name"), Some("Grace"));
+    }
+
+    #[test]
+    fn test_dataset_rejects_keys_without_attribute() {
+        let mut element = Element::new("div");
+        element.set_attribute("data-foo-bar", "x").unwrap();
+
+        // `foo-bar` would be written as data-foo-bar, which reads back as fooBar
+        assert_eq!(element.dataset().get("foo-bar"), None);
+        assert!(matches!(
+            element.dataset_mut().set("foo-bar", "y"),
+            Err(DomException::SyntaxError(_))
+        ));
+        assert!(!element.dataset_mut().delete("foo-bar"));
+        assert_eq!(element.get_attribute("data-foo-bar"), Some("x"));
+    }
+
+    #[test]
+    fn test_dataset_delete_removes_attribute() {
+        let mut element = Element::new("div");
+        element.set_attribute("data-user-id", "7").unwrap();
+
+        assert!(element.dataset_mut().delete("userId"));
+        assert!(!element.has_attribute("data-user-id"));
+        assert!(!element.dataset_mut().delete("userId"));
+        assert!(element.dataset().is_empty());
+    }
+}
@@ -1,16 +1,28 @@
 //! Document node implementation
 
+use crate::animation_provider::{self, AnimationProvider};
 use crate::attr::{Attr, AttrRef};
 use crate::comment::Comment;
-use crate::element::{Element, ElementRef};
+use crate::document_fragment::DocumentFragment;
+use crate::element::{downcast_element, take_fragment_children, Element, ElementRef};
 use crate::event::{self, Event};
-use crate::node::{Node, NodeData, NodeRef};
-use crate::range::Range;
+use crate::hierarchy::validate_pre_insertion;
+use crate::hit_test::HitTestProvider;
+use crate::id_index::{IdIndex, IdIndexRef};
+use crate::namespaces::validate_namespace_and_qname;
+use crate::node::{new_node_ref, Node, NodeData, NodeRef};
+use crate::range::{Range, RangeRef};
+use crate::range_geometry::RangeGeometryProvider;
+use crate::ready_state::{DocumentReadyState, ReadyStateObserver};
+use crate::style_invalidation::{self, StyleInvalidationSink};
 use crate::text::Text;
+use crate::tree_depth;
+use crate::tree_generation::{
+    bind_subtree, TreeChange, TreeChangeObserver, TreeGenerations, TreeGenerationsRef,
+};
 use dom_types::{DomException, NodeType};
-use indexmap::IndexMap;
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 
 /// Document node implementation
 #[derive(Debug)]
@@ -21,14 +33,41 @@ pub struct Document {
     /// Document element (the root element, e.g., <html>)
     document_element: Option<ElementRef>,
 
-    /// Registry mapping element IDs to elements
-    id_map: IndexMap<String, Vec<ElementRef>>,
+    /// Id index for the document tree
+    id_index: IdIndexRef,
+
+    /// Structure and attribute mutation counters
+    generations: TreeGenerationsRef,
 
     /// Document URI
     url: String,
 
     /// Character encoding
     charset: String,
+
+    /// Layout hook answering `element_from_point` queries
+    hit_test: Option<Arc<dyn HitTestProvider>>,
+
+    /// Loading progress reported by `readyState`
+    ready_state: DocumentReadyState,
+
+    /// Hook told about each ready state transition
+    ready_state_observer: Option<Arc<dyn ReadyStateObserver>>,
+
+    /// Receiver of the class-change invalidations of the document's elements
+    style_sink: Option<Arc<dyn StyleInvalidationSink>>,
+
+    /// Source of the animations running on the document's elements
+    animation_provider: Option<Arc<dyn AnimationProvider>>,
+
+    /// Layout hook reporting the boxes of text for ranges
+    range_geometry: Option<Arc<dyn RangeGeometryProvider>>,
+
+    /// Maximum depth of the document tree, if limited
+    depth_limit: Option<usize>,
+
+    /// Node standing for the document in ranges while it is not shared
+    range_anchor: OnceLock<NodeRef>,
 }
 
 /// Thread-safe reference to a Document
@@ -37,25 +76,116 @@ pub type DocumentRef = Arc<RwLock<Document>>;
 impl Document {
     /// Creates a new empty document
     pub fn new() -> Self {
+        let mut node_data = NodeData::new(NodeType::Document, "#document");
+        // The document is not counted, so its children are roots at depth 1
+        node_data.depth = 0;
         Self {
-            node_data: NodeData::new(NodeType::Document, "#document"),
+            node_data,
             document_element: None,
-            id_map: IndexMap::new(),
+            id_index: IdIndex::new_ref(),
+            generations: TreeGenerations::new_ref(),
             url: String::from("about:blank"),
             charset: String::from("UTF-8"),
+            hit_test: None,
+            ready_state: DocumentReadyState::Loading,
+            ready_state_observer: None,
+            style_sink: None,
+            animation_provider: None,
+            range_geometry: None,
+            depth_limit: None,
+            range_anchor: OnceLock::new(),
         }
     }
 
+    /// Shares this document as a `DocumentRef` that knows itself
+    ///
+    /// The ranges of the shared document start at the document itself.
+    pub fn into_ref(self) -> DocumentRef {
+        let document = Arc::new(RwLock::new(self));
+        let weak: Weak<RwLock<Document>> = Arc::downgrade(&document);
+        document.write().node_data.set_self_node_ref(weak);
+        document
+    }
+
     /// Gets the document element (root element)
     pub fn document_element(&self) -> Option<ElementRef> {
         self.document_element.clone()
     }
 
     /// Sets the document element
+    ///
+    /// The element's subtree joins the document tree, so its ids can be
+    /// found with `get_element_by_id`.
     pub fn set_document_element(&mut self, element: ElementRef) {
+        self.bind_element(&element);
+        let node: NodeRef = element.clone();
+        IdIndex::bind_subtree(&node, Some(&self.id_index));
+        self.bind_hooks(&node);
+        tree_depth::place_moved(&node, 1, self.depth_limit);
         self.document_element = Some(element);
     }
 
+    /// Gets the id index of the document tree
+    ///
+    /// Elements are bound to it when they are inserted into the document and
+    /// unbound when they are removed.
+    pub fn id_index(&self) -> &IdIndexRef {
+        &self.id_index
+    }
+
+    /// Binds `element` to the document's mutation counters, style sink and
+    /// animation provider
+    fn bind_element(&self, element: &ElementRef) {
+        let mut element = element.write();
+        element.set_tree_generations(Some(&self.generations));
+        element.set_style_invalidation_sink(self.style_sink.clone());
+        element.set_animation_provider(self.animation_provider.clone());
+        element.set_depth_limit(self.depth_limit);
+    }
+
+    /// Binds the elements of the subtree at `node` to the style sink and
+    /// animation provider, if any
+    fn bind_hooks(&self, node: &NodeRef) {
+        if let Some(sink) = &self.style_sink {
+            style_invalidation::bind_subtree(node, Some(sink));
+        }
+        if let Some(provider) = &self.animation_provider {
+            animation_provider::bind_subtree(node, Some(provider));
+        }
+    }
+
+    /// Gets the top-level nodes of the document tree
+    fn tree_roots(&self) -> Vec<NodeRef> {
+        let mut roots = self.node_data.children.clone();
+        if let Some(element) = self.document_element.clone() {
+            roots.push(element);
+        }
+        roots
+    }
+
+    /// Gets the mutation counters shared with the document's elements
+    pub fn tree_generations(&self) -> &TreeGenerationsRef {
+        &self.generations
+    }
+
+    /// Counter bumped whenever a node is inserted, removed or moved
+    ///
+    /// Caches over the tree can compare it with the value they were built
+    /// at instead of tracking mutations themselves.
+    pub fn structure_generation(&self) -> u64 {
+        self.generations.structure()
+    }
+
+    /// Counter bumped whenever an element attribute is set or removed
+    pub fn attribute_generation(&self) -> u64 {
+        self.generations.attributes()
+    }
+
+    /// Runs `observer` after every structural or attribute mutation
+    pub fn subscribe_tree_changes(&self, observer: TreeChangeObserver) {
+        self.generations.subscribe(observer);
+    }
+
     /// Creates a new element
     pub fn create_element(
         &mut self,
@@ -73,35 +203,34 @@ impl Document {
         // Set self-reference so parent pointers work correctly
         element.write().set_self_ref(Arc::downgrade(&element));
 
-        // Register element if it has an ID
-        if let Some(id) = element.read().id() {
-            self.register_element_id(id, element.clone());
-        }
+        // Bind to the document so later id and tree changes are tracked
+        self.bind_element(&element);
 
         Ok(element)
     }
 
     /// Creates a new element with namespace
+    ///
+    /// `qualified_name` may carry a prefix (`svg:rect`), which must agree with
+    /// `namespace`. The tag name is uppercased only in the HTML namespace.
+    ///
+    /// # Errors
+    /// * `NamespaceError` - empty namespace or inconsistent prefix
+    /// * `InvalidCharacterError` - invalid prefix or local name
     pub fn create_element_ns(
         &mut self,
-        namespace: impl Into<String>,
-        qualified_name: impl Into<String>,
+        namespace: Option<&str>,
+        qualified_name: &str,
     ) -> Result<ElementRef, DomException> {
-        let name = qualified_name.into();
-        let ns = namespace.into();
-
-        if !is_valid_tag_name(&name) {
-            return Err(DomException::InvalidCharacterError);
-        }
-
-        let element = Arc::new(RwLock::new(Element::new_with_namespace(name, ns)));
+        let (prefix, local_name) = validate_namespace_and_qname(namespace, qualified_name)?;
+        let namespace = namespace.map(str::to_string);
+        let element = Element::new_qualified(namespace, prefix, local_name);
+        let element = Arc::new(RwLock::new(element));
 
         // Set self-reference so parent pointers work correctly
         element.write().set_self_ref(Arc::downgrade(&element));
 
-        if let Some(id) = element.read().id() {
-            self.register_element_id(id, element.clone());
-        }
+        self.bind_element(&element);
 
         Ok(element)
     }
@@ -109,22 +238,24 @@ impl Document {
     /// Creates a text node
     pub fn create_text_node(&mut self, data: impl Into<String>) -> NodeRef {
         let text = Text::new(data);
-        Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>))
+        Arc::new(RwLock::new(text))
     }
 
     /// Creates a comment node
     pub fn create_comment(&mut self, data: impl Into<String>) -> NodeRef {
         let comment = Comment::new(data);
-        Arc::new(RwLock::new(Box::new(comment) as Box<dyn Node>))
+        Arc::new(RwLock::new(comment))
     }
 
     /// Creates a document fragment
+    ///
+    /// The fragment is bound to the document's mutation counters, like the
+    /// elements the document creates.
     pub fn create_document_fragment(&mut self) -> NodeRef {
-        // For now, we'll use a simple element as a fragment
-        // In a full implementation, this would be a separate DocumentFragment type
-        let _fragment_data = NodeData::new(NodeType::DocumentFragment, "#document-fragment");
-        let fragment = Element::new("fragment");
-        Arc::new(RwLock::new(Box::new(fragment) as Box<dyn Node>))
+        let mut fragment = DocumentFragment::new();
+        fragment.set_tree_generations(Some(&self.generations));
+        fragment.set_depth_limit(self.depth_limit);
+        new_node_ref(fragment)
     }
 
     /// Creates a new Attr node
@@ -276,7 +407,8 @@ impl Document {
         }
 
         // Remove from old parent if it has one
-        if let Some(old_parent) = node.read().parent_node() {
+        let old_parent = node.read().parent_node();
+        if let Some(old_parent) = old_parent {
             old_parent.write().remove_child(node.clone())?;
         }
 
@@ -290,9 +422,7 @@ impl Document {
 
     /// Gets an element by its ID
     pub fn get_element_by_id(&self, id: &str) -> Option<ElementRef> {
-        self.id_map
-            .get(id)
-            .and_then(|elements| elements.first().cloned())
+        self.id_index.read().get(id)
     }
 
     /// Gets elements by tag name (searches entire document tree)
@@ -326,7 +456,9 @@ impl Document {
     /// * `name` - The name attribute value to search for
     ///
     /// # Returns
-    /// A vector of ElementRef matching the name attribute
+    /// A vector of ElementRef matching the name attribute. The vector is a
+    /// snapshot; `dom_collections::ElementsByNameExt` gives a `DocumentRef`
+    /// a live `NodeList` instead.
     ///
     /// # Example
     /// ```
@@ -382,27 +514,164 @@ impl Document {
 
     /// Creates a new Range object
     ///
-    /// The returned Range has both its boundary points set to the beginning
-    /// of the Document (or a dummy node if no document element exists).
+    /// This is the minimal [`Range`](crate::Range) of `dom_core`; the full
+    /// Range API lives in the `dom_advanced` crate. Both boundary points are
+    /// `(document, 0)`, and `set_start` and `set_end` accept the nodes of
+    /// this document. Until the document is shared as a node (see
+    /// [`into_ref`](Self::into_ref)), one document node sharing its tree
+    /// generations and id index stands for it in all of its ranges.
+    ///
+    /// The range does not follow mutations; see
+    /// [`create_live_range`](Self::create_live_range).
     ///
     /// # Returns
-    /// A new Range object with both boundary points at document start
+    /// A new collapsed Range object at the start of the document
     ///
     /// # Example
     /// ```
     /// use dom_core::Document;
     ///
-    /// let mut doc = Document::new();
+    /// let doc = Document::new();
     /// let range = doc.create_range();
     /// assert!(range.collapsed());
     /// ```
     pub fn create_range(&self) -> Range {
-        // Get the document element or its first child as the initial container
-        let initial_node = self.document_element.as_ref().map(|elem| {
-            Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>))
+        Range::new(Some(self.range_container()))
+    }
+
+    /// Creates a live Range, starting like [`create_range`](Self::create_range)
+    ///
+    /// When [`normalize`](Node::normalize) merges or removes text nodes of
+    /// this document, the range's boundary points move to where the text
+    /// they pointed at ends up.
+    pub fn create_live_range(&self) -> RangeRef {
+        let range = Arc::new(RwLock::new(self.create_range()));
+        self.generations.live_ranges().register(&range);
+        range
+    }
+
+    /// The node standing for this document as a range boundary
+    fn range_container(&self) -> NodeRef {
+        if let Some(document) = self.node_data.get_self_node_ref() {
+            return document;
+        }
+        let anchor = self.range_anchor.get_or_init(|| {
+            let mut anchor = Document::new();
+            anchor.id_index = self.id_index.clone();
+            anchor.generations = self.generations.clone();
+            new_node_ref(anchor)
         });
+        anchor.clone()
+    }
+
+    /// Installs the provider used for hit-testing, or removes it with `None`
+    pub fn set_hit_test_provider(&mut self, provider: Option<Arc<dyn HitTestProvider>>) {
+        self.hit_test = provider;
+    }
+
+    /// Returns the topmost element at viewport coordinates (`x`, `y`)
+    ///
+    /// Returns `None` when no hit-test provider is installed.
+    pub fn element_from_point(&self, x: f64, y: f64) -> Option<ElementRef> {
+        self.elements_from_point(x, y).into_iter().next()
+    }
+
+    /// Returns every element at viewport coordinates (`x`, `y`), topmost first
+    pub fn elements_from_point(&self, x: f64, y: f64) -> Vec<ElementRef> {
+        self.hit_test
+            .as_ref()
+            .map(|provider| provider.elements_at(x, y))
+            .unwrap_or_default()
+    }
 
-        Range::new(initial_node)
+    /// Installs the receiver of class-change invalidations, or removes it
+    /// with `None`
+    ///
+    /// Elements already in the document and elements created or inserted
+    /// later report their `class` changes to it.
+    pub fn set_style_invalidation_sink(&mut self, sink: Option<Arc<dyn StyleInvalidationSink>>) {
+        self.style_sink = sink;
+        for root in self.tree_roots() {
+            style_invalidation::bind_subtree(&root, self.style_sink.as_ref());
+        }
+    }
+
+    /// Installs the source of the animations running on the document's
+    /// elements, or removes it with `None`
+    ///
+    /// Elements already in the document and elements created or inserted
+    /// later report their animations through it.
+    pub fn set_animation_provider(&mut self, provider: Option<Arc<dyn AnimationProvider>>) {
+        self.animation_provider = provider;
+        for root in self.tree_roots() {
+            animation_provider::bind_subtree(&root, self.animation_provider.as_ref());
+        }
+    }
+
+    /// Installs the provider of text boxes for ranges, or removes it with
+    /// `None`
+    ///
+    /// Ranges pick up the provider installed when they are created.
+    pub fn set_range_geometry_provider(
+        &mut self,
+        provider: Option<Arc<dyn RangeGeometryProvider>>,
+    ) {
+        self.range_geometry = provider;
+    }
+
+    /// Limits how deep the document tree may grow, or lifts the limit with
+    /// `None`
+    ///
+    /// The document element is at depth 1. Elements and fragments already
+    /// in the document, and those it creates later, reject insertions that
+    /// would nest nodes below `limit` with `HierarchyRequestError`.
+    pub fn set_depth_limit(&mut self, limit: Option<usize>) {
+        self.depth_limit = limit;
+        for root in self.tree_roots() {
+            tree_depth::place_subtree(&root, 1, limit);
+        }
+    }
+
+    /// Gets the limit on how deep the document tree may grow, if any
+    pub fn depth_limit(&self) -> Option<usize> {
+        self.depth_limit
+    }
+
+    /// Gets the provider of text boxes for ranges, if installed
+    pub fn range_geometry_provider(&self) -> Option<&Arc<dyn RangeGeometryProvider>> {
+        self.range_geometry.as_ref()
+    }
+
+    /// Gets the loading progress of the document
+    pub fn ready_state(&self) -> DocumentReadyState {
+        self.ready_state
+    }
+
+    /// Installs the hook told about every ready state transition, or removes
+    /// it with `None`
+    pub fn set_ready_state_observer(&mut self, observer: Option<Arc<dyn ReadyStateObserver>>) {
+        self.ready_state_observer = observer;
+    }
+
+    /// Moves the document to `state`, notifying the ready state observer
+    ///
+    /// Readiness only moves forward: setting the current state again does
+    /// nothing, and going back, e.g. from `complete` to `loading`, fails with
+    /// `InvalidStateError`. Skipping ahead passes through the states in
+    /// between, so going from `loading` to `complete` still reports
+    /// `interactive` first.
+    pub fn set_ready_state(&mut self, state: DocumentReadyState) -> Result<(), DomException> {
+        if state < self.ready_state {
+            return Err(DomException::InvalidStateError);
+        }
+
+        while let Some(next) = self.ready_state.next().filter(|next| *next <= state) {
+            self.ready_state = next;
+            if let Some(observer) = &self.ready_state_observer {
+                observer.ready_state_changed(next);
+            }
+        }
+        Ok(())
     }
 
     /// Gets the document URL
@@ -420,12 +689,6 @@ impl Document {
         &self.charset
     }
 
-    /// Registers an element ID
-    fn register_element_id(&mut self, id: impl Into<String>, element: ElementRef) {
-        let id = id.into();
-        self.id_map.entry(id).or_insert_with(Vec::new).push(element);
-    }
-
     /// Collects elements by tag name recursively
     fn collect_elements_by_tag(
         &self,
@@ -540,32 +803,37 @@ impl Node for Document {
     }
 
     fn append_child(&mut self, child: NodeRef) -> Result<NodeRef, DomException> {
-        // Documents can only have certain child types
-        let child_type = child.read().node_type();
+        validate_pre_insertion(self, &child, None)?;
 
-        match child_type {
-            NodeType::Element | NodeType::Comment | NodeType::ProcessingInstruction => {
-                // Valid child type
-            }
-            _ => return Err(DomException::HierarchyRequestError),
+        // Document fragments are replaced by their children, in order
+        let child_type = child.read().node_type();
+        if child_type == NodeType::DocumentFragment {
+            return self.insert_before(child, None);
         }
 
         // Document can only have one element child
         if child_type == NodeType::Element && self.document_element.is_some() {
             return Err(DomException::HierarchyRequestError);
         }
+        tree_depth::check_insertion(0, self.depth_limit, &child)?;
 
         // Remove from old parent if exists
-        if let Some(old_parent) = child.read().parent_node() {
+        let old_parent = child.read().parent_node();
+        if let Some(old_parent) = old_parent {
             old_parent.write().remove_child(child.clone())?;
         }
 
         // Add to children
         self.node_data.add_child(child.clone());
+        bind_subtree(&child, Some(&self.generations));
+        IdIndex::bind_subtree(&child, Some(&self.id_index));
+        self.bind_hooks(&child);
+        tree_depth::place_moved(&child, 1, self.depth_limit);
+        self.generations.bump(TreeChange::Structure);
 
         // Set as document element if it's an element
         if child_type == NodeType::Element {
-            // Would need proper conversion here
+            self.document_element = downcast_element(&child);
         }
 
         Ok(child)
@@ -573,6 +841,9 @@ impl Node for Document {
 
     fn remove_child(&mut self, child: NodeRef) -> Result<NodeRef, DomException> {
         let removed = self.node_data.remove_child(&child)?;
+        IdIndex::bind_subtree(&removed, None);
+        bind_subtree(&removed, None);
+        self.generations.bump(TreeChange::Structure);
 
         // Clear document element if it was removed
         if removed.read().node_type() == NodeType::Element {
@@ -587,29 +858,41 @@ impl Node for Document {
         new_child: NodeRef,
         ref_child: Option<NodeRef>,
     ) -> Result<NodeRef, DomException> {
-        // Validate child type
-        let child_type = new_child.read().node_type();
+        validate_pre_insertion(self, &new_child, ref_child.as_ref())?;
 
-        match child_type {
-            NodeType::Element | NodeType::Comment | NodeType::ProcessingInstruction => {
-                // Valid
+        // Document fragments are replaced by their children, in order
+        let child_type = new_child.read().node_type();
+        if child_type == NodeType::DocumentFragment {
+            for child in take_fragment_children(&new_child) {
+                self.insert_before(child, ref_child.clone())?;
             }
-            _ => return Err(DomException::HierarchyRequestError),
+            return Ok(new_child);
         }
 
         // Document can only have one element child
         if child_type == NodeType::Element && self.document_element.is_some() {
             return Err(DomException::HierarchyRequestError);
         }
+        tree_depth::check_insertion(0, self.depth_limit, &new_child)?;
 
         // Remove from old parent
-        if let Some(old_parent) = new_child.read().parent_node() {
+        let old_parent = new_child.read().parent_node();
+        if let Some(old_parent) = old_parent {
             old_parent.write().remove_child(new_child.clone())?;
         }
 
         // Insert before reference child
         self.node_data
             .insert_child_before(new_child.clone(), ref_child.as_ref())?;
+        bind_subtree(&new_child, Some(&self.generations));
+        IdIndex::bind_subtree(&new_child, Some(&self.id_index));
+        self.bind_hooks(&new_child);
+        tree_depth::place_moved(&new_child, 1, self.depth_limit);
+        self.generations.bump(TreeChange::Structure);
+
+        if child_type == NodeType::Element {
+            self.document_element = downcast_element(&new_child);
+        }
 
         Ok(new_child)
     }
@@ -632,7 +915,7 @@ impl Node for Document {
             }
         }
 
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(cloned))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -643,26 +926,24 @@ impl Node for Document {
         &mut self.node_data
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        Some(self.generations.clone())
+    }
+
     fn contains(&self, other: &dyn Node) -> bool {
         let self_ptr = self as *const _ as *const dyn Node;
         let other_ptr = other as *const dyn Node;
 
-        if self_ptr == other_ptr {
-            return true;
-        }
-
-        for child in &self.node_data.children {
-            if child.read().contains(other) {
-                return true;
-            }
-        }
-
-        false
+        std::ptr::addr_eq(self_ptr, other_ptr) || self.node_data.contains(other_ptr)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 impl Default for Document {
@@ -676,9 +957,18 @@ impl Clone for Document {
         Self {
             node_data: self.node_data.clone(),
             document_element: self.document_element.clone(),
-            id_map: self.id_map.clone(),
+            id_index: Arc::new(RwLock::new(self.id_index.read().clone())),
             url: self.url.clone(),
             charset: self.charset.clone(),
+            generations: TreeGenerations::new_ref(), // Observers stay with the original
+            hit_test: None, // Layout belongs to the original document
+            ready_state: self.ready_state,
+            ready_state_observer: None, // Events belong to the original document
+            style_sink: None,           // Style belongs to the original document
+            animation_provider: None,
+            range_geometry: None,
+            depth_limit: self.depth_limit,
+            range_anchor: OnceLock::new(),
         }
     }
 }
@@ -742,4 +1032,38 @@ mod tests {
         doc.set_url("https://example.com");
         assert_eq!(doc.url(), "https://example.com");
     }
+
+    #[test]
+    fn test_depth_limit_applies_to_created_nodes() {
+        let mut doc = Document::new();
+        doc.set_depth_limit(Some(3));
+        let html: NodeRef = doc.create_element("html").unwrap();
+        let body: NodeRef = doc.create_element("body").unwrap();
+        html.write().append_child(body.clone()).unwrap();
+        doc.append_child(html).unwrap();
+
+        // body is at depth 2, so a fragment may add one level below it
+        let fragment = doc.create_document_fragment();
+        let div: NodeRef = doc.create_element("div").unwrap();
+        fragment.write().append_child(div.clone()).unwrap();
+        div.write()
+            .append_child(doc.create_element("p").unwrap())
+            .unwrap();
+        assert_eq!(
+            body.write().append_child(fragment.clone()).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+        let p = div.read().first_child().unwrap();
+        div.write().remove_child(p).unwrap();
+        body.write().append_child(fragment).unwrap();
+
+        // Lowering the limit binds the elements already in the tree
+        doc.set_depth_limit(Some(2));
+        assert_eq!(
+            body.write()
+                .append_child(doc.create_text_node("text"))
+                .unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+    }
 }
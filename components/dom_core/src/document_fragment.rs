@@ -1,15 +1,24 @@
 //! DocumentFragment implementation
 
-use crate::node::{Node, NodeData, NodeRef};
+use crate::element::{take_fragment_children, Element};
+use crate::hierarchy::validate_pre_insertion;
+use crate::node::{wrap_clone, Node, NodeData, NodeRef};
+use crate::tree_depth;
+use crate::tree_generation::{bind_subtree, TreeChange, TreeGenerations, TreeGenerationsRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// A DocumentFragment is a minimal document object that has no parent
 #[derive(Clone, Debug)]
 pub struct DocumentFragment {
     node_data: NodeData,
-    children: Vec<NodeRef>,
+    // Shadow host, when the fragment is the root of a shadow tree
+    host: Option<Weak<RwLock<Element>>>,
+    // Mutation counters of the document, if bound to one
+    tree_generations: Option<Weak<TreeGenerations>>,
+    // Maximum depth of the tree below the fragment, if limited
+    depth_limit: Option<usize>,
 }
 
 impl DocumentFragment {
@@ -17,30 +26,92 @@ impl DocumentFragment {
     pub fn new() -> Self {
         Self {
             node_data: NodeData::new(NodeType::DocumentFragment, "#document-fragment"),
-            children: Vec::new(),
+            host: None,
+            tree_generations: None,
+            depth_limit: None,
         }
     }
 
+    /// Get the shadow host of the tree this fragment is the root of
+    pub fn host(&self) -> Option<Arc<RwLock<Element>>> {
+        self.host.as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Set the shadow host, making the fragment the root of its shadow tree
+    pub fn set_host(&mut self, host: Option<Weak<RwLock<Element>>>) {
+        self.host = host;
+    }
+
+    /// Binds the fragment to the mutation counters of its document
+    ///
+    /// Insertions and removals then bump the counters, and inserted
+    /// elements are bound too.
+    pub fn set_tree_generations(&mut self, generations: Option<&TreeGenerationsRef>) {
+        self.tree_generations = generations.map(Arc::downgrade);
+    }
+
+    /// Limits how deep the tree may grow through insertions into this fragment
+    ///
+    /// Works like [`Element::set_depth_limit`], with the fragment as the
+    /// root of its tree. Fragments created by a document get the document's
+    /// limit.
+    pub fn set_depth_limit(&mut self, limit: Option<usize>) {
+        self.depth_limit = limit;
+        if limit.is_some() {
+            for child in &self.node_data.children {
+                tree_depth::place_subtree(child, self.node_data.depth + 1, limit);
+            }
+        }
+    }
+
+    /// Gets the limit on how deep the tree may grow, if any
+    pub fn depth_limit(&self) -> Option<usize> {
+        self.depth_limit
+    }
+
+    /// Binds `child`'s elements to our counters and records the insertion
+    fn record_insertion(&self, child: &NodeRef) {
+        tree_depth::place_moved(child, self.node_data.depth + 1, self.depth_limit);
+        if let Some(generations) = self.generations() {
+            bind_subtree(child, Some(&generations));
+            generations.bump(TreeChange::Structure);
+        }
+    }
+
+    /// Removes `child` from its old parent, which may be this fragment
+    fn detach(&mut self, child: &NodeRef) -> Result<(), DomException> {
+        if self.node_data.child_index(child).is_some() {
+            self.node_data.remove_child(child)?;
+            return Ok(());
+        }
+        let old_parent = child.read().parent_node();
+        if let Some(parent) = old_parent {
+            parent.write().remove_child(child.clone())?;
+        }
+        Ok(())
+    }
+
     /// Get the children
     pub fn children(&self) -> &[NodeRef] {
-        &self.children
+        &self.node_data.children
     }
 
     /// Append a child node
+    ///
+    /// The child is moved from its old parent, and a document fragment is
+    /// replaced by its children. Fails like [`Node::insert_before`].
     pub fn append_child(&mut self, child: NodeRef) -> Result<(), DomException> {
-        if !self.children.iter().any(|c| Arc::ptr_eq(c, &child)) {
-            self.children.push(child);
-        }
-        Ok(())
+        Node::insert_before(self, child, None).map(|_| ())
     }
 
     /// Get text content of all child nodes
     pub fn text_content(&self) -> Option<String> {
-        if self.children.is_empty() {
+        if self.node_data.children.is_empty() {
             return None;
         }
 
         let content: String = self
+            .node_data
             .children
             .iter()
             .filter_map(|child| child.read().text_content())
@@ -91,7 +162,7 @@ impl Node for DocumentFragment {
     }
 
     fn child_nodes(&self) -> Vec<NodeRef> {
-        self.children.clone()
+        self.node_data.children.clone()
     }
 
     fn append_child(&mut self, child: NodeRef) -> Result<NodeRef, DomException> {
@@ -100,14 +171,14 @@ impl Node for DocumentFragment {
     }
 
     fn remove_child(&mut self, child: NodeRef) -> Result<NodeRef, DomException> {
-        let index = self
-            .children
-            .iter()
-            .position(|c| Arc::ptr_eq(c, &child))
-            .ok_or(DomException::NotFoundError)?;
-
-        self.children.remove(index);
-        Ok(child)
+        let removed = self.node_data.remove_child(&child)?;
+        removed.write().node_data_mut().set_parent(None);
+        bind_subtree(&removed, None);
+        tree_depth::place_moved(&removed, 1, self.depth_limit);
+        if let Some(generations) = self.generations() {
+            generations.bump(TreeChange::Structure);
+        }
+        Ok(removed)
     }
 
     fn insert_before(
@@ -115,24 +186,43 @@ impl Node for DocumentFragment {
         new_child: NodeRef,
         ref_child: Option<NodeRef>,
     ) -> Result<NodeRef, DomException> {
-        if let Some(ref_node) = ref_child {
-            let index = self
-                .children
-                .iter()
-                .position(|c| Arc::ptr_eq(c, &ref_node))
-                .ok_or(DomException::NotFoundError)?;
+        validate_pre_insertion(self, &new_child, ref_child.as_ref())?;
+        tree_depth::check_insertion(self.node_data.depth, self.depth_limit, &new_child)?;
+
+        // Document fragments are replaced by their children, in order
+        if new_child.read().node_type() == NodeType::DocumentFragment {
+            for child in take_fragment_children(&new_child) {
+                self.insert_before(child, ref_child.clone())?;
+            }
+            return Ok(new_child);
+        }
 
-            self.children.insert(index, new_child.clone());
-        } else {
-            self.children.push(new_child.clone());
+        self.detach(&new_child)?;
+        self.node_data
+            .insert_child_before(new_child.clone(), ref_child.as_ref())?;
+        if let Some(self_ref) = self.node_data.get_self_node_ref() {
+            new_child
+                .write()
+                .node_data_mut()
+                .set_parent(Some(Arc::downgrade(&self_ref)));
         }
+        self.record_insertion(&new_child);
 
         Ok(new_child)
     }
 
-    fn clone_node(&self, _deep: bool) -> NodeRef {
-        let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+    fn clone_node(&self, deep: bool) -> NodeRef {
+        let mut cloned = DocumentFragment::new();
+        cloned.depth_limit = self.depth_limit;
+        if deep {
+            cloned.node_data.children = self
+                .node_data
+                .children
+                .iter()
+                .map(|child| child.read().clone_node(true))
+                .collect();
+        }
+        wrap_clone(cloned)
     }
 
     fn node_data(&self) -> &NodeData {
@@ -143,6 +233,10 @@ impl Node for DocumentFragment {
         &mut self.node_data
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        self.tree_generations.as_ref().and_then(Weak::upgrade)
+    }
+
     fn contains(&self, other: &dyn Node) -> bool {
         let self_ptr = self as *const _ as *const dyn Node;
         let other_ptr = other as *const dyn Node;
@@ -152,12 +246,17 @@ impl Node for DocumentFragment {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Text;
+    use parking_lot::RwLock;
 
     #[test]
     fn test_fragment_creation() {
@@ -166,29 +265,108 @@ mod tests {
         assert_eq!(fragment.node_name(), "#document-fragment");
     }
 
+    #[test]
+    fn test_fragment_host() {
+        let mut fragment = DocumentFragment::new();
+        assert!(fragment.host().is_none());
+
+        let host = Arc::new(RwLock::new(Element::new("div")));
+        fragment.set_host(Some(Arc::downgrade(&host)));
+        assert!(Arc::ptr_eq(&fragment.host().unwrap(), &host));
+
+        // The fragment does not keep its host alive
+        drop(host);
+        assert!(fragment.host().is_none());
+    }
+
     #[test]
     fn test_fragment_append_child() {
         let mut fragment = DocumentFragment::new();
         let text = Text::new("Hello");
-        let text_ref = Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>));
+        let text_ref = Arc::new(RwLock::new(text));
 
         fragment.append_child(text_ref.clone()).unwrap();
 
         assert_eq!(fragment.children().len(), 1);
     }
 
+    #[test]
+    fn test_fragment_validates_insertion() {
+        let mut document = crate::Document::new();
+        let fragment = document.create_document_fragment();
+        let inner = document.create_document_fragment();
+        let element: NodeRef = document.create_element("p").unwrap();
+        inner.write().append_child(element.clone()).unwrap();
+
+        let other_document: NodeRef = Arc::new(RwLock::new(crate::Document::new()));
+        for child in [other_document, fragment.clone()] {
+            let result = fragment.write().append_child(child);
+            assert_eq!(result.err(), Some(DomException::HierarchyRequestError));
+        }
+
+        // A nested fragment is spliced, and its children are reparented
+        fragment.write().append_child(inner.clone()).unwrap();
+        assert!(inner.read().child_nodes().is_empty());
+        let parent = element.read().parent_node().unwrap();
+        assert!(Arc::ptr_eq(&parent, &fragment));
+
+        let stranger: NodeRef = Arc::new(RwLock::new(Text::new("x")));
+        let result = fragment.write().insert_before(element, Some(stranger));
+        assert_eq!(result.err(), Some(DomException::NotFoundError));
+    }
+
     #[test]
     fn test_fragment_text_content() {
         let mut fragment = DocumentFragment::new();
         let text1 = Text::new("Hello");
         let text2 = Text::new(" World");
 
-        let text1_ref = Arc::new(RwLock::new(Box::new(text1) as Box<dyn Node>));
-        let text2_ref = Arc::new(RwLock::new(Box::new(text2) as Box<dyn Node>));
+        let text1_ref = Arc::new(RwLock::new(text1));
+        let text2_ref = Arc::new(RwLock::new(text2));
 
         fragment.append_child(text1_ref).unwrap();
         fragment.append_child(text2_ref).unwrap();
 
         assert_eq!(fragment.text_content(), Some("Hello World".to_string()));
     }
+
+    #[test]
+    fn test_fragment_clone_node() {
+        let section = crate::Element::new("section");
+        let section: NodeRef = Arc::new(RwLock::new(section));
+        let paragraph = crate::Element::new("p");
+        let paragraph: NodeRef = Arc::new(RwLock::new(paragraph));
+        section.write().append_child(paragraph).unwrap();
+        let mut fragment = DocumentFragment::new();
+        fragment.append_child(section.clone()).unwrap();
+
+        let shallow = fragment.clone_node(false);
+        assert_eq!(shallow.read().node_type(), NodeType::DocumentFragment);
+        assert!(shallow.read().child_nodes().is_empty());
+
+        let deep = fragment.clone_node(true);
+        let children = deep.read().child_nodes();
+        assert_eq!(children.len(), 1);
+        assert!(!Arc::ptr_eq(&children[0], &section));
+        assert!(Arc::ptr_eq(
+            &children[0].read().parent_node().unwrap(),
+            &deep
+        ));
+
+        // Grandchildren are copies too, parented to the copied section
+        let nested = children[0].read().child_nodes();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].read().node_name(), "P");
+        assert!(Arc::ptr_eq(
+            &nested[0].read().parent_node().unwrap(),
+            &children[0]
+        ));
+        assert!(!Arc::ptr_eq(&nested[0], &section.read().child_nodes()[0]));
+
+        // The clone is independent of the original
+        deep.write()
+            .append_child(Arc::new(RwLock::new(Text::new("x"))))
+            .unwrap();
+        assert_eq!(fragment.children().len(), 1);
+    }
 }
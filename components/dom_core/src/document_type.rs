@@ -26,6 +26,7 @@
 use crate::node::{Node, NodeData, NodeRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
+use std::fmt;
 use std::sync::Arc;
 
 /// DocumentType node implementation.
@@ -174,6 +175,33 @@ impl DocumentType {
     }
 }
 
+/// Serializes the declaration, e.g. `<!DOCTYPE html>`
+///
+/// A public id is written as `PUBLIC "..."` followed by the system id if
+/// there is one; a system id on its own is written as `SYSTEM "..."`.
+///
+/// # Examples
+///
+/// ```
+/// use dom_core::DocumentType;
+///
+/// assert_eq!(DocumentType::new_simple("html").to_string(), "<!DOCTYPE html>");
+/// ```
+impl fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!DOCTYPE {}", self.name)?;
+        if !self.public_id.is_empty() {
+            write!(f, " PUBLIC \"{}\"", self.public_id)?;
+            if !self.system_id.is_empty() {
+                write!(f, " \"{}\"", self.system_id)?;
+            }
+        } else if !self.system_id.is_empty() {
+            write!(f, " SYSTEM \"{}\"", self.system_id)?;
+        }
+        f.write_str(">")
+    }
+}
+
 impl Node for DocumentType {
     fn node_type(&self) -> NodeType {
         self.node_data.node_type
@@ -233,7 +261,7 @@ impl Node for DocumentType {
 
     fn clone_node(&self, _deep: bool) -> NodeRef {
         let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(cloned))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -255,6 +283,10 @@ impl Node for DocumentType {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -278,6 +310,43 @@ mod tests {
         assert_eq!(doctype.system_id(), "");
     }
 
+    #[test]
+    fn test_display_html5_doctype() {
+        assert_eq!(
+            DocumentType::new_simple("html").to_string(),
+            "<!DOCTYPE html>"
+        );
+    }
+
+    #[test]
+    fn test_display_public_and_system_ids() {
+        let doctype = DocumentType::new(
+            "html",
+            "-//W3C//DTD HTML 4.01//EN",
+            "http://www.w3.org/TR/html4/strict.dtd",
+        );
+        assert_eq!(
+            doctype.to_string(),
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\" \
+             \"http://www.w3.org/TR/html4/strict.dtd\">"
+        );
+
+        let public_only = DocumentType::new("html", "-//W3C//DTD HTML 3.2 Final//EN", "");
+        assert_eq!(
+            public_only.to_string(),
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 3.2 Final//EN\">"
+        );
+    }
+
+    #[test]
+    fn test_display_system_only_doctype() {
+        let doctype = DocumentType::new("greeting", "", "hello.dtd");
+        assert_eq!(
+            doctype.to_string(),
+            "<!DOCTYPE greeting SYSTEM \"hello.dtd\">"
+        );
+    }
+
     #[test]
     fn test_document_type_no_value() {
         let doctype = DocumentType::new_simple("html");
@@ -289,7 +358,7 @@ mod tests {
     fn test_document_type_no_children() {
         let mut doctype = DocumentType::new_simple("html");
         let child = DocumentType::new_simple("child");
-        let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+        let child_ref = Arc::new(RwLock::new(child));
 
         let result = doctype.append_child(child_ref);
         assert!(result.is_err());
@@ -153,7 +153,7 @@ impl DOMImplementation {
                     }
                 }
 
-                doc.create_element_ns(ns_str, &name)?
+                doc.create_element_ns(Some(&ns_str), &name)?
             } else {
                 doc.create_element(&name)?
             };
@@ -161,7 +161,7 @@ impl DOMImplementation {
             doc.set_document_element(root);
         }
 
-        Ok(Arc::new(RwLock::new(doc)))
+        Ok(doc.into_ref())
     }
 
     /// Create a new HTML Document
@@ -194,33 +194,27 @@ impl DOMImplementation {
         if let Some(title_text) = title {
             let title_elem = doc.create_element("title")?;
             let text = crate::text::Text::new(title_text.into());
-            let text_node =
-                Arc::new(RwLock::new(Box::new(text) as Box<dyn crate::node::Node>));
+            let text_node = Arc::new(RwLock::new(text));
             title_elem
                 .write()
                 .append_child(text_node)
                 .map_err(|_| DomException::HierarchyRequestError)?;
             head.write()
-                .append_child(
-                    Arc::new(RwLock::new(Box::new(title_elem.read().clone())
-                        as Box<dyn crate::node::Node>)),
-                )
+                .append_child(Arc::new(RwLock::new(title_elem.read().clone())))
                 .map_err(|_| DomException::HierarchyRequestError)?;
         }
 
         // Build document structure
         html.write()
-            .append_child(Arc::new(RwLock::new(Box::new(head.read().clone())
-                as Box<dyn crate::node::Node>)))
+            .append_child(Arc::new(RwLock::new(head.read().clone())))
             .map_err(|_| DomException::HierarchyRequestError)?;
         html.write()
-            .append_child(Arc::new(RwLock::new(Box::new(body.read().clone())
-                as Box<dyn crate::node::Node>)))
+            .append_child(Arc::new(RwLock::new(body.read().clone())))
             .map_err(|_| DomException::HierarchyRequestError)?;
 
         doc.set_document_element(html);
 
-        Ok(Arc::new(RwLock::new(doc)))
+        Ok(doc.into_ref())
     }
 }
 
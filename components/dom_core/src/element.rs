@@ -1,12 +1,26 @@
 //! Element node implementation
 
+use crate::animation_provider::{self, AnimationProvider};
 use crate::attr::{Attr, AttrRef};
-use crate::node::{Node, NodeData, NodeRef};
-use dom_types::{DomException, NodeType};
+use crate::dataset::{Dataset, DatasetMut};
+use crate::document_fragment::DocumentFragment;
+use crate::hierarchy::validate_pre_insertion;
+use crate::id_index::{IdIndex, IdIndexRef};
+use crate::namespaces::HTML_NAMESPACE;
+use crate::node::{new_node_ref, Node, NodeData, NodeRef, WeakNodeRef};
+use crate::serialize::{self, SerializeOptions, ShadowRootContent};
+use crate::style_invalidation::{self, ClassInvalidation, StyleInvalidationSink};
+use crate::tree_depth;
+use crate::tree_generation::{bind_subtree, TreeChange, TreeGenerations, TreeGenerationsRef};
+use dom_types::{DomException, LayoutMetrics, NodeType, ScrollState};
 use indexmap::IndexMap;
 use parking_lot::RwLock;
+use std::collections::HashSet;
 use std::sync::{Arc, Weak};
 
+/// Elements whose contents never render as text
+const NON_RENDERED_TAGS: &[&str] = &["SCRIPT", "STYLE", "TEMPLATE"];
+
 /// Key for namespaced attributes
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct NamespacedAttrKey {
@@ -20,12 +34,18 @@ pub struct Element {
     /// Base node data
     node_data: NodeData,
 
-    /// Element tag name (always uppercase)
+    /// Element tag name (uppercase for HTML elements)
     tag_name: String,
 
     /// Element namespace (e.g., "http://www.w3.org/1999/xhtml")
     namespace: Option<String>,
 
+    /// Namespace prefix of the qualified name (e.g., "svg" for "svg:rect")
+    prefix: Option<String>,
+
+    /// Qualified name without its prefix
+    local_name: String,
+
     /// Element attributes (preserves insertion order)
     attributes: IndexMap<String, String>,
 
@@ -40,48 +60,446 @@ pub struct Element {
 
     /// Self-reference for attribute owner tracking (weak to avoid cycles)
     self_ref: Option<Weak<RwLock<Element>>>,
+
+    /// Id index of the node tree this element belongs to
+    id_index: Option<Weak<RwLock<IdIndex>>>,
+
+    /// Mutation counters of the document this element belongs to
+    tree_generations: Option<Weak<TreeGenerations>>,
+
+    /// Shadow root attached to this element, if any
+    shadow_root: Option<Arc<dyn ShadowRootContent>>,
+
+    /// Box dimensions populated by layout
+    layout_metrics: LayoutMetrics,
+
+    /// Scroll position when this element is a scroll container
+    scroll_state: ScrollState,
+
+    /// Inert fragment holding a `<template>` element's contents
+    template_content: Option<NodeRef>,
+
+    /// Maximum number of attributes, if limited
+    attribute_limit: Option<usize>,
+
+    /// Maximum length of an attribute value in bytes, if limited
+    attribute_value_limit: Option<usize>,
+
+    /// Maximum number of children, if limited
+    child_limit: Option<usize>,
+
+    /// Maximum depth of the tree below the root, if limited
+    depth_limit: Option<usize>,
+
+    /// Receiver of targeted invalidations for class changes
+    style_sink: Option<Arc<dyn StyleInvalidationSink>>,
+
+    /// Source of the animations running on this element
+    animation_provider: Option<Arc<dyn AnimationProvider>>,
 }
 
 /// Thread-safe reference to an Element
 pub type ElementRef = Arc<RwLock<Element>>;
 
+/// Returns the `ElementRef` behind `node` if the node is an element
+///
+/// The result shares the node's allocation, so changes made through it are
+/// visible in the tree and pointer comparisons against the node succeed.
+/// Only elements with a self-reference (see [`new_node_ref`](crate::new_node_ref))
+/// are found.
+pub fn downcast_element(node: &NodeRef) -> Option<ElementRef> {
+    let element = node.read().as_any().downcast_ref::<Element>()?.self_ref()?;
+    std::ptr::addr_eq(Arc::as_ptr(&element), Arc::as_ptr(node)).then_some(element)
+}
+
+/// Argument to the variadic `ParentNode` methods (`append`, `prepend`)
+///
+/// Strings are converted to `Text` nodes before insertion.
+#[derive(Debug, Clone)]
+pub enum NodeOrString {
+    /// An existing node (document fragments are flattened)
+    Node(NodeRef),
+    /// Text to insert as a new `Text` node
+    String(String),
+}
+
+impl From<NodeRef> for NodeOrString {
+    fn from(node: NodeRef) -> Self {
+        NodeOrString::Node(node)
+    }
+}
+
+impl From<String> for NodeOrString {
+    fn from(text: String) -> Self {
+        NodeOrString::String(text)
+    }
+}
+
+impl From<&str> for NodeOrString {
+    fn from(text: &str) -> Self {
+        NodeOrString::String(text.to_string())
+    }
+}
+
 impl Element {
     /// Creates a new element with the given tag name
     pub fn new(tag_name: impl Into<String>) -> Self {
-        let tag = tag_name.into().to_uppercase();
+        let name = tag_name.into();
+        let tag = name.to_uppercase();
+        let template_content = (tag == "TEMPLATE").then(new_template_content);
         Self {
             node_data: NodeData::new(NodeType::Element, tag.clone()),
             tag_name: tag,
             namespace: None,
+            prefix: None,
+            local_name: name.to_lowercase(),
             attributes: IndexMap::new(),
             namespaced_attributes: IndexMap::new(),
             class_list: Vec::new(),
             id: None,
             self_ref: None,
+            id_index: None,
+            tree_generations: None,
+            shadow_root: None,
+            layout_metrics: LayoutMetrics::default(),
+            scroll_state: ScrollState::default(),
+            template_content,
+            attribute_limit: None,
+            attribute_value_limit: None,
+            child_limit: None,
+            depth_limit: None,
+            style_sink: None,
+            animation_provider: None,
         }
     }
 
     /// Creates a new element with namespace
+    ///
+    /// The tag name is uppercased only for the HTML namespace; other
+    /// namespaces keep the qualified name's case (e.g. SVG `linearGradient`).
     pub fn new_with_namespace(tag_name: impl Into<String>, namespace: impl Into<String>) -> Self {
-        let tag = tag_name.into().to_uppercase();
+        let name = tag_name.into();
+        let (prefix, local_name) = parse_qualified_name(&name);
+        let local_name = local_name.to_string();
+        Self::new_qualified(Some(namespace.into()), prefix, local_name)
+    }
+
+    /// Creates an element from an already split qualified name
+    ///
+    /// The tag name is the qualified name, uppercased only for the HTML
+    /// namespace. An element without a namespace keeps its case.
+    pub(crate) fn new_qualified(
+        namespace: Option<String>,
+        prefix: Option<String>,
+        local_name: String,
+    ) -> Self {
+        let qualified_name = match &prefix {
+            Some(prefix) => format!("{}:{}", prefix, local_name),
+            None => local_name.clone(),
+        };
+        let is_html = namespace.as_deref() == Some(HTML_NAMESPACE);
+        let tag = if is_html {
+            qualified_name.to_uppercase()
+        } else {
+            qualified_name
+        };
+        let template_content = (is_html && tag == "TEMPLATE").then(new_template_content);
         Self {
             node_data: NodeData::new(NodeType::Element, tag.clone()),
             tag_name: tag,
-            namespace: Some(namespace.into()),
+            namespace,
+            prefix,
+            local_name,
             attributes: IndexMap::new(),
             namespaced_attributes: IndexMap::new(),
             class_list: Vec::new(),
             id: None,
             self_ref: None,
+            id_index: None,
+            tree_generations: None,
+            shadow_root: None,
+            layout_metrics: LayoutMetrics::default(),
+            scroll_state: ScrollState::default(),
+            template_content,
+            attribute_limit: None,
+            attribute_value_limit: None,
+            child_limit: None,
+            depth_limit: None,
+            style_sink: None,
+            animation_provider: None,
+        }
+    }
+
+    /// Clones this element into a typed reference
+    ///
+    /// Same as [`clone_node`](Node::clone_node), but the clone can be used as
+    /// an `Element` without downcasting. Its self-reference is set, and the
+    /// children of a deep clone have it as their parent.
+    pub fn clone_element(&self, deep: bool) -> ElementRef {
+        let element_ref = Arc::new(RwLock::new(self.clone_detached(deep)));
+        let weak = Arc::downgrade(&element_ref);
+        let children = {
+            let mut element = element_ref.write();
+            element.set_self_ref(weak.clone());
+            element.child_nodes()
+        };
+        let parent: WeakNodeRef = weak;
+        for child in children {
+            child
+                .write()
+                .node_data_mut()
+                .set_parent(Some(parent.clone()));
         }
+        element_ref
     }
 
     /// Sets the self-reference for this element (called after wrapping in Arc<RwLock<>>)
+    ///
+    /// Also sets the node-level self-reference, so children appended to the
+    /// element point back at the same allocation.
     pub fn set_self_ref(&mut self, self_ref: Weak<RwLock<Element>>) {
+        self.node_data.set_self_node_ref(self_ref.clone());
         self.self_ref = Some(self_ref);
     }
 
-    /// Gets the tag name (always uppercase)
+    /// Gets the reference this element is shared through, if it has one
+    ///
+    /// Set for elements created by a [`Document`](crate::Document) or wrapped
+    /// with [`new_node_ref`](crate::new_node_ref).
+    pub fn self_ref(&self) -> Option<ElementRef> {
+        self.self_ref.as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Binds this element to the id index of its node tree
+    ///
+    /// The element's current id moves from the previous index to the new one,
+    /// and later `id` attribute changes keep the index up to date. Requires the
+    /// self-reference set by [`set_self_ref`](Element::set_self_ref).
+    pub fn set_id_index(&mut self, index: Option<&IdIndexRef>) {
+        let id = self.id.clone();
+        self.update_id_index(id.as_deref(), None);
+        self.id_index = index.map(Arc::downgrade);
+        self.update_id_index(None, id.as_deref());
+    }
+
+    /// Checks whether this element is bound to `index`, or to no index
+    /// when `index` is `None`
+    pub(crate) fn is_bound_to_id_index(&self, index: Option<&IdIndexRef>) -> bool {
+        match (self.id_index.as_ref().and_then(Weak::upgrade), index) {
+            (Some(bound), Some(index)) => Arc::ptr_eq(&bound, index),
+            (bound, index) => bound.is_none() && index.is_none(),
+        }
+    }
+
+    /// Binds this element to the mutation counters of its document
+    ///
+    /// Structural and attribute changes to the element then bump the
+    /// counters, and elements later inserted below it are bound too.
+    pub fn set_tree_generations(&mut self, generations: Option<&TreeGenerationsRef>) {
+        self.tree_generations = generations.map(Arc::downgrade);
+    }
+
+    /// Attaches (or detaches) the shadow root hosted by this element
+    pub fn set_shadow_root_content(&mut self, shadow_root: Option<Arc<dyn ShadowRootContent>>) {
+        self.shadow_root = shadow_root;
+    }
+
+    /// Gets the shadow root hosted by this element
+    pub fn shadow_root_content(&self) -> Option<&Arc<dyn ShadowRootContent>> {
+        self.shadow_root.as_ref()
+    }
+
+    /// Limits the number of attributes this element may carry
+    ///
+    /// Adding an attribute beyond the limit fails with `QuotaExceededError`;
+    /// replacing the value of an existing attribute is always allowed.
+    pub fn set_attribute_limit(&mut self, limit: Option<usize>) {
+        self.attribute_limit = limit;
+    }
+
+    /// Limits the length in bytes of this element's attribute values
+    ///
+    /// Setting a longer value fails with `QuotaExceededError` and leaves the
+    /// attribute unchanged.
+    pub fn set_attribute_value_limit(&mut self, limit: Option<usize>) {
+        self.attribute_value_limit = limit;
+    }
+
+    /// Limits the number of children this element may have
+    ///
+    /// Inserting a child beyond the limit fails with `QuotaExceededError`.
+    pub fn set_child_limit(&mut self, limit: Option<usize>) {
+        self.child_limit = limit;
+    }
+
+    /// Binds (or unbinds) the receiver of class-change invalidations
+    ///
+    /// While bound, every `class` change that adds or removes classes is
+    /// reported with this element as the invalidation root. Elements created
+    /// by a document get the document's sink, and nodes inserted into a
+    /// bound element are bound to its sink.
+    pub fn set_style_invalidation_sink(&mut self, sink: Option<Arc<dyn StyleInvalidationSink>>) {
+        self.style_sink = sink;
+    }
+
+    /// Binds (or unbinds) the source of the animations running on this element
+    ///
+    /// Elements created by a document get the document's provider, and nodes
+    /// inserted into a bound element are bound to its provider.
+    pub fn set_animation_provider(&mut self, provider: Option<Arc<dyn AnimationProvider>>) {
+        self.animation_provider = provider;
+    }
+
+    /// Gets the source of the animations running on this element, if bound
+    pub fn animation_provider(&self) -> Option<&Arc<dyn AnimationProvider>> {
+        self.animation_provider.as_ref()
+    }
+
+    /// Limits how deep the tree may grow through insertions into this element
+    ///
+    /// The root of a tree is at depth 1. Inserting a node whose deepest
+    /// descendant would end up below `limit` fails with
+    /// `HierarchyRequestError`. Elements created by a document get the
+    /// document's limit, nodes inserted into an element with a limit get
+    /// its limit, and a template's content gets the template's.
+    ///
+    /// The element's descendants get the limit too. Depths are only tracked
+    /// in limited trees, so setting a limit counts the element's ancestors
+    /// once.
+    pub fn set_depth_limit(&mut self, limit: Option<usize>) {
+        self.bind_depth_limit(limit);
+        if limit.is_none() {
+            return;
+        }
+        let mut depth = 1;
+        let mut current = self.node_data.get_parent();
+        while let Some(ancestor) = current {
+            let ancestor = ancestor.read();
+            // The document itself is not counted
+            if ancestor.node_type() == NodeType::Document {
+                break;
+            }
+            depth += 1;
+            current = ancestor.parent_node();
+        }
+        self.node_data.depth = depth;
+        for child in &self.node_data.children {
+            tree_depth::place_subtree(child, depth + 1, limit);
+        }
+    }
+
+    /// Sets the depth limit of this element alone, and of its template
+    /// content
+    pub(crate) fn bind_depth_limit(&mut self, limit: Option<usize>) {
+        self.depth_limit = limit;
+        if let Some(content) = &self.template_content {
+            let mut content = content.write();
+            if let Some(fragment) = content.as_any_mut().downcast_mut::<DocumentFragment>() {
+                fragment.set_depth_limit(limit);
+            }
+        }
+    }
+
+    /// Gets the limit on how deep the tree may grow, if any
+    pub fn depth_limit(&self) -> Option<usize> {
+        self.depth_limit
+    }
+
+    /// Fails if inserting `child` here would make the tree exceed the depth limit
+    fn check_depth_limit(&self, child: &NodeRef) -> Result<(), DomException> {
+        tree_depth::check_insertion(self.node_data.depth, self.depth_limit, child)
+    }
+
+    /// Fails if adding a new attribute `name` would exceed the attribute limit
+    fn check_attribute_quota(&self, name: &str) -> Result<(), DomException> {
+        match self.attribute_limit {
+            Some(limit)
+                if !self.attributes.contains_key(name) && self.attributes.len() >= limit =>
+            {
+                Err(DomException::QuotaExceededError)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if `value` is longer than the attribute value limit
+    fn check_attribute_value(&self, value: &str) -> Result<(), DomException> {
+        match self.attribute_value_limit {
+            Some(limit) if value.len() > limit => Err(DomException::QuotaExceededError),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if inserting `count` more children would exceed the child limit
+    fn check_child_quota(&self, count: usize) -> Result<(), DomException> {
+        match self.child_limit {
+            Some(limit) if self.node_data.children.len() + count > limit => {
+                Err(DomException::QuotaExceededError)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks whether `node` is one of this element's children
+    fn has_child(&self, node: &NodeRef) -> bool {
+        self.node_data.child_index(node).is_some()
+    }
+
+    /// Gets the contents of a `<template>` element
+    ///
+    /// Template contents live in a separate, inert `DocumentFragment` rather
+    /// than as children of the element. Returns `None` for other elements.
+    pub fn content(&self) -> Option<NodeRef> {
+        self.template_content.clone()
+    }
+
+    /// Gets the box dimensions last set by layout
+    pub fn layout_metrics(&self) -> &LayoutMetrics {
+        &self.layout_metrics
+    }
+
+    /// Records the box dimensions computed by layout
+    pub fn set_layout_metrics(&mut self, metrics: LayoutMetrics) {
+        self.layout_metrics = metrics;
+    }
+
+    /// Gets the scroll position
+    pub fn scroll_state(&self) -> &ScrollState {
+        &self.scroll_state
+    }
+
+    /// Gets the scroll position for modification
+    pub fn scroll_state_mut(&mut self) -> &mut ScrollState {
+        &mut self.scroll_state
+    }
+
+    /// Serializes this element and its light-DOM descendants
+    ///
+    /// Shadow content is never included; use
+    /// [`outer_html_with`](Element::outer_html_with) to opt in to
+    /// serializable shadow roots.
+    pub fn outer_html(&self) -> String {
+        self.outer_html_with(&SerializeOptions::default())
+    }
+
+    /// Serializes this element and its descendants with explicit options
+    pub fn outer_html_with(&self, options: &SerializeOptions) -> String {
+        serialize::serialize_node(self, options)
+    }
+
+    /// Serializes the light-DOM descendants of this element, without the
+    /// element's own tags
+    ///
+    /// For a `<template>` this is the markup of its content fragment.
+    pub fn inner_html(&self) -> String {
+        let options = SerializeOptions::default();
+        match self.content() {
+            Some(content) => serialize::serialize_children(&*content.read(), &options),
+            None => serialize::serialize_children(self, &options),
+        }
+    }
+
+    /// Gets the tag name (uppercase for HTML elements)
     pub fn tag_name(&self) -> &str {
         &self.tag_name
     }
@@ -91,6 +509,16 @@ impl Element {
         self.namespace.as_deref()
     }
 
+    /// Gets the namespace prefix, if the qualified name has one
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Gets the local name (the qualified name without its prefix)
+    pub fn local_name(&self) -> &str {
+        &self.local_name
+    }
+
     /// Gets an attribute value
     pub fn get_attribute(&self, name: &str) -> Option<&str> {
         self.attributes.get(name).map(|s| s.as_str())
@@ -109,30 +537,101 @@ impl Element {
         if !is_valid_attribute_name(&name) {
             return Err(DomException::InvalidCharacterError);
         }
+        self.check_attribute_quota(&name)?;
+        self.check_attribute_value(&value)?;
+
+        self.update_reflected_attribute(&name, Some(&value));
+        self.attributes.insert(name, value);
+        self.record_change(TreeChange::Attributes);
+        Ok(())
+    }
 
-        // Handle special attributes
-        if name == "class" {
-            self.update_class_list(&value);
-        } else if name == "id" {
-            self.id = Some(value.clone());
+    /// Sets several attributes at once
+    ///
+    /// All names and values are validated before anything changes, so
+    /// either every attribute is applied or, on error, none is. The class
+    /// list, id index and attribute generation are updated once for the
+    /// whole batch.
+    pub fn set_attributes<I>(&mut self, attrs: I) -> Result<(), DomException>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let attrs: Vec<(String, String)> = attrs.into_iter().collect();
+        if !attrs.iter().all(|(name, _)| is_valid_attribute_name(name)) {
+            return Err(DomException::InvalidCharacterError);
+        }
+        for (_, value) in &attrs {
+            self.check_attribute_value(value)?;
+        }
+        if let Some(limit) = self.attribute_limit {
+            let added: HashSet<&str> = attrs
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .filter(|name| !self.attributes.contains_key(*name))
+                .collect();
+            if self.attributes.len() + added.len() > limit {
+                return Err(DomException::QuotaExceededError);
+            }
+        }
+        if attrs.is_empty() {
+            return Ok(());
         }
 
-        self.attributes.insert(name, value);
+        let reflected: Vec<&str> = ["class", "id"]
+            .into_iter()
+            .filter(|reflected| attrs.iter().any(|(name, _)| name == reflected))
+            .collect();
+        for (name, value) in attrs {
+            self.attributes.insert(name, value);
+        }
+        for name in reflected {
+            let value = self.attributes.get(name).cloned();
+            self.update_reflected_attribute(name, value.as_deref());
+        }
+        self.record_change(TreeChange::Attributes);
         Ok(())
     }
 
     /// Removes an attribute
+    ///
+    /// An attribute set through `set_attribute_ns` is removed by its
+    /// qualified name, in whatever namespace it is.
     pub fn remove_attribute(&mut self, name: &str) -> Result<(), DomException> {
-        if name == "class" {
-            self.class_list.clear();
-        } else if name == "id" {
-            self.id = None;
-        }
+        self.update_reflected_attribute(name, None);
 
-        self.attributes.shift_remove(name);
+        self.namespaced_attributes
+            .retain(|_, (qualified_name, _)| qualified_name != name);
+        if self.attributes.shift_remove(name).is_some() {
+            self.record_change(TreeChange::Attributes);
+        }
         Ok(())
     }
 
+    /// Toggles a boolean attribute, returning whether it is now present
+    ///
+    /// With `force` set, the attribute is only ever added (`Some(true)`) or
+    /// removed (`Some(false)`). Added attributes get an empty value.
+    pub fn toggle_attribute(
+        &mut self,
+        name: &str,
+        force: Option<bool>,
+    ) -> Result<bool, DomException> {
+        if !is_valid_attribute_name(name) {
+            return Err(DomException::InvalidCharacterError);
+        }
+
+        let present = self.has_attribute(name);
+        let want = force.unwrap_or(!present);
+
+        if want && !present {
+            self.set_attribute(name, "")?;
+        } else if !want && present {
+            self.remove_attribute(name)?;
+        }
+
+        Ok(want)
+    }
+
     /// Checks if an attribute exists
     pub fn has_attribute(&self, name: &str) -> bool {
         self.attributes.contains_key(name)
@@ -161,6 +660,28 @@ impl Element {
         Some(attr_ref)
     }
 
+    /// Iterates over Attr nodes for all attributes, in order
+    ///
+    /// Namespaced attributes keep their namespace. Each node is owned by this
+    /// element if its self-reference is set, in which case
+    /// [`Attr::set_value`] writes through to the element; that locks the
+    /// element, so collect the nodes before editing them.
+    pub fn attr_nodes(&self) -> impl Iterator<Item = AttrRef> + '_ {
+        self.attributes.iter().map(|(name, value)| {
+            let attr = match self.attribute_namespace(name) {
+                Some(namespace) => {
+                    Attr::new_ns(namespace, name, value).unwrap_or_else(|_| Attr::new(name, value))
+                }
+                None => Attr::new(name, value),
+            };
+            let attr = Arc::new(RwLock::new(attr));
+            if let Some(ref self_weak) = self.self_ref {
+                attr.write().set_owner_element(Some(self_weak.clone()));
+            }
+            attr
+        })
+    }
+
     /// Sets an attribute node
     ///
     /// # Errors
@@ -192,19 +713,12 @@ impl Element {
         }
         drop(attr_guard);
 
-        // Get the old attribute value if it exists
-        let old_attr = if self.attributes.contains_key(&attr_name) {
-            // Create an Attr node for the old attribute
-            let old_value = self.attributes.get(&attr_name).unwrap();
-            let mut old_attr_node = Attr::new(&attr_name, old_value);
-            // Set owner element on old attr if we have self_ref
-            if let Some(ref self_weak) = self.self_ref {
-                old_attr_node.set_owner_element(Some(self_weak.clone()));
-            }
-            Some(Arc::new(RwLock::new(old_attr_node)))
-        } else {
-            None
-        };
+        // Get the old attribute value if it exists; the replaced attribute
+        // has no owner
+        let old_attr = self
+            .attributes
+            .get(&attr_name)
+            .map(|old_value| Arc::new(RwLock::new(Attr::new(&attr_name, old_value))));
 
         // Set the attribute using the existing set_attribute method
         self.set_attribute(&attr_name, &attr_value)?;
@@ -287,6 +801,9 @@ impl Element {
             return Err(DomException::NamespaceError);
         }
 
+        self.check_attribute_quota(qualified_name)?;
+        self.check_attribute_value(value)?;
+
         let key = NamespacedAttrKey {
             namespace: namespace.map(|s| s.to_string()),
             local_name: local_name.to_string(),
@@ -294,14 +811,43 @@ impl Element {
 
         self.namespaced_attributes
             .insert(key, (qualified_name.to_string(), value.to_string()));
+        if namespace.is_none() {
+            self.update_reflected_attribute(qualified_name, Some(value));
+        }
 
         // Also store in regular attributes for compatibility
         self.attributes
             .insert(qualified_name.to_string(), value.to_string());
+        self.record_change(TreeChange::Attributes);
 
         Ok(())
     }
 
+    /// Gets the attributes set with a namespace as (namespace, local name,
+    /// qualified name, value), in the order they were set
+    pub(crate) fn namespaced_attributes(
+        &self,
+    ) -> impl Iterator<Item = (Option<&str>, &str, &str, &str)> {
+        self.namespaced_attributes
+            .iter()
+            .map(|(key, (qualified_name, value))| {
+                (
+                    key.namespace.as_deref(),
+                    key.local_name.as_str(),
+                    qualified_name.as_str(),
+                    value.as_str(),
+                )
+            })
+    }
+
+    /// Gets the namespace of an attribute by its qualified name
+    pub(crate) fn attribute_namespace(&self, qualified_name: &str) -> Option<&str> {
+        self.namespaced_attributes
+            .iter()
+            .find(|(_, (name, _))| name == qualified_name)
+            .and_then(|(key, _)| key.namespace.as_deref())
+    }
+
     /// Removes a namespaced attribute
     ///
     /// # Arguments
@@ -319,8 +865,12 @@ impl Element {
 
         // Get the qualified name before removing
         if let Some((qualified_name, _)) = self.namespaced_attributes.shift_remove(&key) {
+            if namespace.is_none() {
+                self.update_reflected_attribute(&qualified_name, None);
+            }
             // Also remove from regular attributes
             self.attributes.shift_remove(&qualified_name);
+            self.record_change(TreeChange::Attributes);
         }
 
         Ok(())
@@ -340,6 +890,34 @@ impl Element {
         self.namespaced_attributes.contains_key(&key)
     }
 
+    /// Iterates over the attributes with local name `local_name` in any namespace
+    ///
+    /// Yields the namespace URI (None for no namespace) and value of each.
+    /// Attributes set without a namespace use their whole name as local name.
+    pub fn attributes_by_local_name<'a>(
+        &'a self,
+        local_name: &'a str,
+    ) -> impl Iterator<Item = (Option<&'a str>, &'a str)> + 'a {
+        let namespaced = self
+            .namespaced_attributes
+            .iter()
+            .filter(move |(key, _)| key.local_name == local_name)
+            .map(|(key, (_, value))| (key.namespace.as_deref(), value.as_str()));
+        let plain = self
+            .attributes
+            .get_key_value(local_name)
+            .filter(|(name, _)| !self.is_namespaced_attribute(name))
+            .map(|(_, value)| (None, value.as_str()));
+        namespaced.chain(plain)
+    }
+
+    /// Whether the plain attribute `qualified_name` was set through `set_attribute_ns`
+    fn is_namespaced_attribute(&self, qualified_name: &str) -> bool {
+        self.namespaced_attributes
+            .values()
+            .any(|(name, _)| name == qualified_name)
+    }
+
     /// Gets a namespaced attribute node
     ///
     /// # Arguments
@@ -405,6 +983,8 @@ impl Element {
             }
         }
         drop(attr_guard);
+        self.check_attribute_quota(&qualified_name)?;
+        self.check_attribute_value(&value)?;
 
         let key = NamespacedAttrKey {
             namespace: namespace.clone(),
@@ -419,20 +999,20 @@ impl Element {
                 Some(Attr::new(old_qname, old_value))
             };
 
-            old_attr_node.map(|mut a| {
-                if let Some(ref self_weak) = self.self_ref {
-                    a.set_owner_element(Some(self_weak.clone()));
-                }
-                Arc::new(RwLock::new(a))
-            })
+            // The replaced attribute has no owner
+            old_attr_node.map(|a| Arc::new(RwLock::new(a)))
         } else {
             None
         };
 
         // Set the new attribute
+        if namespace.is_none() {
+            self.update_reflected_attribute(&qualified_name, Some(&value));
+        }
         self.namespaced_attributes
             .insert(key, (qualified_name.clone(), value.clone()));
         self.attributes.insert(qualified_name, value);
+        self.record_change(TreeChange::Attributes);
 
         // Set the owner element on the new attr
         if let Some(ref self_weak) = self.self_ref {
@@ -447,80 +1027,297 @@ impl Element {
         &self.class_list
     }
 
+    /// Gets the `data-*` attributes keyed by camelCase name
+    pub fn dataset(&self) -> Dataset<'_> {
+        Dataset::new(self)
+    }
+
+    /// Gets the `data-*` attributes for writing through camelCase keys
+    pub fn dataset_mut(&mut self) -> DatasetMut<'_> {
+        DatasetMut::new(self)
+    }
+
     /// Gets the element ID
     pub fn id(&self) -> Option<&str> {
         self.id.as_deref()
     }
 
     /// Gets elements by tag name (returns descendants matching tag)
+    ///
+    /// `*` matches every element. Results are in tree order.
     pub fn get_elements_by_tag_name(&self, tag_name: &str) -> Vec<ElementRef> {
-        let mut result = Vec::new();
         let target = tag_name.to_uppercase();
-
-        for child in &self.node_data.children {
-            if child.read().node_type() == NodeType::Element {
-                let child_element = child.read();
-                if child_element.node_name() == target || target == "*" {
-                    // We need to return ElementRef, but we have NodeRef
-                    // This is a simplified version - in a full implementation,
-                    // we'd need proper type conversion
-                    result.push(Arc::new(RwLock::new(self.clone())));
-                }
-
-                // Recursively search children
-                // In full implementation, need proper Element extraction
-            }
-        }
-
-        result
+        self.descendant_elements(|element| target == "*" || element.node_name() == target)
     }
 
     /// Gets elements by class name
+    ///
+    /// Matches descendants that have every class in the space-separated
+    /// `class_names`; an empty list matches nothing.
     pub fn get_elements_by_class_name(&self, class_names: &str) -> Vec<ElementRef> {
-        let _target_classes: Vec<&str> = class_names.split_whitespace().collect();
-        let mut result = Vec::new();
+        let target_classes: Vec<&str> = class_names.split_whitespace().collect();
+        if target_classes.is_empty() {
+            return Vec::new();
+        }
+        self.descendant_elements(|element| {
+            target_classes
+                .iter()
+                .all(|class| element.class_list.iter().any(|own| own == class))
+        })
+    }
 
-        for child in &self.node_data.children {
-            if child.read().node_type() == NodeType::Element {
-                // Check if element has all target classes
-                // Simplified implementation
-                result.push(Arc::new(RwLock::new(self.clone())));
+    /// Collects the descendant elements matching `predicate`, in tree order
+    ///
+    /// The returned references are the elements in the tree, not copies.
+    fn descendant_elements(&self, predicate: impl Fn(&Element) -> bool) -> Vec<ElementRef> {
+        let mut result = Vec::new();
+        let mut stack: Vec<NodeRef> = self.node_data.children.iter().rev().cloned().collect();
+        while let Some(node) = stack.pop() {
+            let matched = {
+                let guard = node.read();
+                stack.extend(guard.node_data().children.iter().rev().cloned());
+                guard
+                    .as_any()
+                    .downcast_ref::<Element>()
+                    .is_some_and(&predicate)
+            };
+            if matched {
+                result.extend(downcast_element(&node));
             }
         }
-
         result
     }
 
-    /// Updates the class list from a space-separated string
-    fn update_class_list(&mut self, class_str: &str) {
-        self.class_list = class_str
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
+    /// Gets the text a user would see, as an alternative to `text_content`
+    ///
+    /// Skips the contents of `<script>`, `<style>` and `<template>` as well as
+    /// descendants with the `hidden` attribute, and collapses each run of
+    /// whitespace to a single space. CSS `display` is not taken into account.
+    pub fn inner_text(&self) -> String {
+        let mut text = String::new();
+        let mut stack: Vec<NodeRef> = self.node_data.children.iter().rev().cloned().collect();
+        while let Some(node) = stack.pop() {
+            let node = node.read();
+            match node.node_type() {
+                NodeType::Text | NodeType::CDataSection => {
+                    text.push_str(node.node_value().unwrap_or_default());
+                }
+                NodeType::Element => {
+                    let rendered = node
+                        .as_any()
+                        .downcast_ref::<Element>()
+                        .is_some_and(Element::renders_text);
+                    if rendered {
+                        stack.extend(node.node_data().children.iter().rev().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
     }
 
-    /// Helper to get this element as a NodeRef
-    fn as_node_ref(&self) -> NodeRef {
-        // IMPORTANT: Don't try to read through self_ref here, as this method
-        // may be called while holding a write lock on self (e.g., in append_child).
-        // parking_lot RwLock doesn't support recursive locking, so we must
-        // just clone self directly.
-        Arc::new(RwLock::new(Box::new(self.clone()) as Box<dyn Node>))
+    /// Whether the contents of this element are rendered as text
+    fn renders_text(&self) -> bool {
+        !self.has_attribute("hidden")
+            && !NON_RENDERED_TAGS
+                .iter()
+                .any(|tag| self.tag_name.eq_ignore_ascii_case(tag))
     }
-}
 
-impl Node for Element {
-    fn node_type(&self) -> NodeType {
-        self.node_data.node_type
+    /// Inserts `nodes` before the first child, preserving their order
+    pub fn prepend(&mut self, nodes: Vec<NodeOrString>) -> Result<(), DomException> {
+        let first_child = self.node_data.children.first().cloned();
+        for node in self.convert_nodes_into_list(nodes)? {
+            self.insert_before(node, first_child.clone())?;
+        }
+        Ok(())
     }
 
-    fn node_name(&self) -> &str {
-        &self.node_data.node_name
+    /// Inserts `nodes` after the last child, preserving their order
+    pub fn append(&mut self, nodes: Vec<NodeOrString>) -> Result<(), DomException> {
+        for node in self.convert_nodes_into_list(nodes)? {
+            self.append_child(node)?;
+        }
+        Ok(())
     }
 
-    fn parent_node(&self) -> Option<NodeRef> {
-        self.node_data.get_parent()
-    }
+    /// Converts `ParentNode` arguments into the list of nodes to insert
+    ///
+    /// Strings become new `Text` nodes and document fragments are replaced by
+    /// their children, which are removed from the fragment. Every node is
+    /// validated for insertion into this element first, so on error no
+    /// fragment has lost its children.
+    fn convert_nodes_into_list(
+        &self,
+        nodes: Vec<NodeOrString>,
+    ) -> Result<Vec<NodeRef>, DomException> {
+        let mut added = 0;
+        for node in &nodes {
+            let NodeOrString::Node(node) = node else {
+                added += 1;
+                continue;
+            };
+            validate_pre_insertion(self, node, None)?;
+            self.check_depth_limit(node)?;
+            let node_guard = node.read();
+            added += if node_guard.node_type() == NodeType::DocumentFragment {
+                node_guard.child_nodes().len()
+            } else {
+                usize::from(!self.has_child(node))
+            };
+        }
+        self.check_child_quota(added)?;
+
+        let mut result = Vec::new();
+        for node in nodes {
+            match node {
+                NodeOrString::String(text) => {
+                    let text_ref = new_node_ref(crate::Text::new(text));
+                    result.push(text_ref);
+                }
+                NodeOrString::Node(node) => {
+                    if node.read().node_type() == NodeType::DocumentFragment {
+                        result.extend(take_fragment_children(&node));
+                    } else {
+                        result.push(node);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Bumps the bound document's generation for `change`
+    fn record_change(&self, change: TreeChange) {
+        if let Some(generations) = self.tree_generations.as_ref().and_then(Weak::upgrade) {
+            generations.bump(change);
+        }
+    }
+
+    /// Records that `child` was inserted, binding its elements to our tree
+    fn record_insertion(&self, child: &NodeRef) {
+        let id_index = self.id_index.as_ref().and_then(Weak::upgrade);
+        IdIndex::bind_subtree(child, id_index.as_ref());
+        if let Some(sink) = &self.style_sink {
+            style_invalidation::bind_subtree(child, Some(sink));
+        }
+        if let Some(provider) = &self.animation_provider {
+            animation_provider::bind_subtree(child, Some(provider));
+        }
+        tree_depth::place_moved(child, self.node_data.depth + 1, self.depth_limit);
+        if let Some(generations) = self.tree_generations.as_ref().and_then(Weak::upgrade) {
+            bind_subtree(child, Some(&generations));
+            generations.bump(TreeChange::Structure);
+        }
+    }
+
+    /// Moves this element's entry in the bound id index from `old` to `new`
+    fn update_id_index(&self, old: Option<&str>, new: Option<&str>) {
+        let (Some(index), Some(this)) = (
+            self.id_index.as_ref().and_then(Weak::upgrade),
+            self.self_ref.as_ref().and_then(Weak::upgrade),
+        ) else {
+            return;
+        };
+
+        let mut index = index.write();
+        if let Some(old) = old {
+            index.remove(old, &this);
+        }
+        if let Some(new) = new {
+            index.insert(new, &this);
+        }
+    }
+
+    /// Keeps the class list and id index in step with a null-namespace attribute
+    ///
+    /// `value` is the attribute's new value, or `None` if it was removed.
+    fn update_reflected_attribute(&mut self, name: &str, value: Option<&str>) {
+        match name {
+            "class" => {
+                let classes = value.unwrap_or_default().split_whitespace();
+                self.set_class_list(classes.map(str::to_string).collect());
+            }
+            "id" => {
+                let old_id = std::mem::replace(&mut self.id, value.map(str::to_string));
+                self.update_id_index(old_id.as_deref(), value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces the class list, reporting the delta to the style sink
+    fn set_class_list(&mut self, class_list: Vec<String>) {
+        let old = std::mem::replace(&mut self.class_list, class_list);
+        if let Some(sink) = &self.style_sink {
+            let change = ClassInvalidation::between(&old, &self.class_list);
+            if !change.is_empty() {
+                sink.classes_changed(self, &change);
+            }
+        }
+    }
+
+    /// Clones this element outside of any tree, with or without its subtree
+    ///
+    /// Plain and namespaced attributes are copied together with the class
+    /// list and id read from them, so the clone needs no re-parsing. It has
+    /// no parent and is registered with no id index, but reports class
+    /// changes to the same style sink.
+    fn clone_detached(&self, deep: bool) -> Element {
+        let mut cloned = self.clone();
+        cloned.node_data.clear_links();
+
+        cloned.template_content = self.template_content.as_ref().map(|content| {
+            let fragment = new_template_content();
+            if deep {
+                for child in content.read().child_nodes() {
+                    let child = child.read().clone_node(true);
+                    let _ = fragment.write().append_child(child);
+                }
+            }
+            fragment
+        });
+
+        if !deep {
+            cloned.node_data.children.clear();
+        } else {
+            // Deep clone: clone all children recursively
+            let cloned_children: Vec<NodeRef> = self
+                .node_data
+                .children
+                .iter()
+                .map(|child| child.read().clone_node(true))
+                .collect();
+            cloned.node_data.children = cloned_children;
+        }
+
+        cloned.bind_depth_limit(self.depth_limit);
+        cloned
+    }
+
+    /// Helper to get this element as a NodeRef
+    fn as_node_ref(&self) -> NodeRef {
+        // IMPORTANT: Don't try to read through self_ref here, as this method
+        // may be called while holding a write lock on self (e.g., in append_child).
+        // parking_lot RwLock doesn't support recursive locking, so we must
+        // just clone self directly.
+        Arc::new(RwLock::new(self.clone()))
+    }
+}
+
+impl Node for Element {
+    fn node_type(&self) -> NodeType {
+        self.node_data.node_type
+    }
+
+    fn node_name(&self) -> &str {
+        &self.node_data.node_name
+    }
+
+    fn parent_node(&self) -> Option<NodeRef> {
+        self.node_data.get_parent()
+    }
 
     fn child_nodes(&self) -> Vec<NodeRef> {
         self.node_data.children.clone()
@@ -550,45 +1347,35 @@ impl Node for Element {
 
     fn set_text_content(&mut self, text: String) {
         // Clear all children first
-        self.node_data.children.clear();
+        for child in std::mem::take(&mut self.node_data.children) {
+            IdIndex::bind_subtree(&child, None);
+            bind_subtree(&child, None);
+        }
 
         // If text is not empty, create a Text node child
         if !text.is_empty() {
-            let text_node = crate::Text::new(&text);
-            let text_ref: NodeRef = Arc::new(RwLock::new(Box::new(text_node) as Box<dyn Node>));
-            self.node_data.add_child(text_ref);
+            let mut text_node = crate::Text::new(&text);
+            text_node.set_tree_generations(self.generations().as_ref());
+            self.node_data.add_child(new_node_ref(text_node));
         }
+        self.record_change(TreeChange::Structure);
     }
 
     fn append_child(&mut self, child: NodeRef) -> Result<NodeRef, DomException> {
-        // 1. Check for circular reference - can't append ourselves
-        {
-            let child_node = child.read();
-            let self_ptr = self as *const _ as *const dyn Node;
-            let child_ptr = &**child_node as *const dyn Node;
-
-            if std::ptr::addr_eq(self_ptr, child_ptr) {
-                return Err(DomException::HierarchyRequestError);
-            }
-        }
+        // 1-2. Reject cycles and children of the wrong type, before `child`
+        // is locked: it may be this element
+        validate_pre_insertion(self, &child, None)?;
 
-        // 2. Check if child is one of our ancestors (would create cycle)
-        // We walk UP through our parent chain instead of DOWN through child's descendants
-        // to avoid deadlock (we may hold locks on nodes that are descendants of child)
-        {
-            let child_ptr = &**child.read() as *const dyn Node;
-            let mut current = self.node_data.get_parent();
-            while let Some(ancestor) = current {
-                let ancestor_guard = ancestor.read();
-                let ancestor_ptr = &**ancestor_guard as *const dyn Node;
-                if std::ptr::addr_eq(ancestor_ptr, child_ptr) {
-                    return Err(DomException::HierarchyRequestError);
-                }
-                current = ancestor_guard.parent_node();
-            }
+        // Document fragments are replaced by their children, in order
+        if child.read().node_type() == NodeType::DocumentFragment {
+            return self.insert_before(child, None);
         }
 
         // 3. Remove from old parent if exists
+        if !self.has_child(&child) {
+            self.check_child_quota(1)?;
+        }
+        self.check_depth_limit(&child)?;
         let old_parent = child.read().parent_node();
         if let Some(parent) = old_parent {
             parent.write().remove_child(child.clone())?;
@@ -604,6 +1391,7 @@ impl Node for Element {
                 .node_data_mut()
                 .set_parent(Some(Arc::downgrade(&self_ref)));
         }
+        self.record_insertion(&child);
 
         Ok(child)
     }
@@ -614,6 +1402,10 @@ impl Node for Element {
 
         // Clear parent reference
         removed.write().node_data_mut().set_parent(None);
+        IdIndex::bind_subtree(&removed, None);
+        bind_subtree(&removed, None);
+        tree_depth::place_moved(&removed, 1, self.depth_limit);
+        self.record_change(TreeChange::Structure);
 
         Ok(removed)
     }
@@ -623,70 +1415,47 @@ impl Node for Element {
         new_child: NodeRef,
         ref_child: Option<NodeRef>,
     ) -> Result<NodeRef, DomException> {
-        // 1. Check for circular reference - can't insert ourselves
-        {
-            let child_node = new_child.read();
-            let self_ptr = self as *const _ as *const dyn Node;
-            let child_ptr = &**child_node as *const dyn Node;
-
-            if std::ptr::addr_eq(self_ptr, child_ptr) {
-                return Err(DomException::HierarchyRequestError);
+        // 1-2. Reject cycles, children of the wrong type and foreign references
+        validate_pre_insertion(self, &new_child, ref_child.as_ref())?;
+
+        // 3. Document fragments are replaced by their children, in order
+        if new_child.read().node_type() == NodeType::DocumentFragment {
+            self.check_child_quota(new_child.read().child_nodes().len())?;
+            self.check_depth_limit(&new_child)?;
+            for child in take_fragment_children(&new_child) {
+                self.insert_before(child, ref_child.clone())?;
             }
+            return Ok(new_child);
         }
 
-        // 2. Check if child is one of our ancestors (would create cycle)
-        {
-            let child_ptr = &**new_child.read() as *const dyn Node;
-            let mut current = self.node_data.get_parent();
-            while let Some(ancestor) = current {
-                let ancestor_guard = ancestor.read();
-                let ancestor_ptr = &**ancestor_guard as *const dyn Node;
-                if std::ptr::addr_eq(ancestor_ptr, child_ptr) {
-                    return Err(DomException::HierarchyRequestError);
-                }
-                current = ancestor_guard.parent_node();
-            }
+        // 4. Remove from old parent if exists
+        if !self.has_child(&new_child) {
+            self.check_child_quota(1)?;
         }
-
-        // 3. Remove from old parent if exists
+        self.check_depth_limit(&new_child)?;
         let old_parent = new_child.read().parent_node();
         if let Some(parent) = old_parent {
             parent.write().remove_child(new_child.clone())?;
         }
 
-        // 4. Insert before reference child
+        // 5. Insert before reference child
         self.node_data
             .insert_child_before(new_child.clone(), ref_child.as_ref())?;
 
-        // 5. Set parent using self_node_ref (the actual NodeRef that wraps us)
+        // 6. Set parent using self_node_ref (the actual NodeRef that wraps us)
         if let Some(self_ref) = self.node_data.get_self_node_ref() {
             new_child
                 .write()
                 .node_data_mut()
                 .set_parent(Some(Arc::downgrade(&self_ref)));
         }
+        self.record_insertion(&new_child);
 
         Ok(new_child)
     }
 
     fn clone_node(&self, deep: bool) -> NodeRef {
-        let mut cloned = self.clone();
-        cloned.node_data.parent = None;
-
-        if !deep {
-            cloned.node_data.children.clear();
-        } else {
-            // Deep clone: clone all children recursively
-            let cloned_children: Vec<NodeRef> = self
-                .node_data
-                .children
-                .iter()
-                .map(|child| child.read().clone_node(true))
-                .collect();
-            cloned.node_data.children = cloned_children;
-        }
-
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        self.clone_element(deep)
     }
 
     fn node_data(&self) -> &NodeData {
@@ -697,27 +1466,36 @@ impl Node for Element {
         &mut self.node_data
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        self.tree_generations.as_ref().and_then(Weak::upgrade)
+    }
+
+    fn is_equal_node(&self, other: &dyn Node) -> bool {
+        let Some(other) = other.as_any().downcast_ref::<Element>() else {
+            return false;
+        };
+        // IndexMap equality ignores insertion order
+        self.tag_name == other.tag_name
+            && self.namespace == other.namespace
+            && self.attributes == other.attributes
+            && self.namespaced_attributes == other.namespaced_attributes
+    }
+
     fn contains(&self, other: &dyn Node) -> bool {
         let self_ptr = self as *const _ as *const dyn Node;
         let other_ptr = other as *const dyn Node;
 
         // Use addr_eq to compare only the data addresses, not vtable pointers
-        if std::ptr::addr_eq(self_ptr, other_ptr) {
-            return true;
-        }
-
-        for child in &self.node_data.children {
-            if child.read().contains(other) {
-                return true;
-            }
-        }
-
-        false
+        std::ptr::addr_eq(self_ptr, other_ptr) || self.node_data.contains(other_ptr)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 impl Clone for Element {
@@ -726,15 +1504,43 @@ impl Clone for Element {
             node_data: self.node_data.clone(),
             tag_name: self.tag_name.clone(),
             namespace: self.namespace.clone(),
+            prefix: self.prefix.clone(),
+            local_name: self.local_name.clone(),
             attributes: self.attributes.clone(),
             namespaced_attributes: self.namespaced_attributes.clone(),
             class_list: self.class_list.clone(),
             id: self.id.clone(),
             self_ref: None, // Don't clone self-reference
+            id_index: None, // Clones are not part of any tree yet
+            tree_generations: None,
+            shadow_root: None, // Shadow roots are not cloned with their host
+            layout_metrics: LayoutMetrics::default(),
+            scroll_state: ScrollState::default(),
+            template_content: self.template_content.clone(),
+            attribute_limit: self.attribute_limit,
+            attribute_value_limit: self.attribute_value_limit,
+            child_limit: self.child_limit,
+            depth_limit: self.depth_limit,
+            style_sink: self.style_sink.clone(), // Clones keep their node document
+            animation_provider: self.animation_provider.clone(),
         }
     }
 }
 
+/// Creates the empty content fragment of a `<template>` element
+fn new_template_content() -> NodeRef {
+    new_node_ref(DocumentFragment::new())
+}
+
+/// Removes and returns the children of a document fragment, in order
+pub(crate) fn take_fragment_children(fragment: &NodeRef) -> Vec<NodeRef> {
+    let children = fragment.read().child_nodes();
+    for child in &children {
+        let _ = fragment.write().remove_child(child.clone());
+    }
+    children
+}
+
 /// Validates an attribute name
 fn is_valid_attribute_name(name: &str) -> bool {
     if name.is_empty() {
@@ -801,6 +1607,34 @@ mod tests {
         assert_eq!(elem.node_type(), NodeType::Element);
     }
 
+    #[test]
+    fn test_is_equal_node_compares_attribute_sets() {
+        let element = |attributes: &[(&str, &str)]| {
+            let mut element = Element::new("input");
+            for (name, value) in attributes {
+                element.set_attribute(*name, *value).unwrap();
+            }
+            element
+        };
+
+        let a = element(&[("type", "text"), ("name", "q")]);
+        let reordered = element(&[("name", "q"), ("type", "text")]);
+        assert!(a.is_equal_node(&reordered));
+
+        assert!(!a.is_equal_node(&element(&[("type", "text"), ("name", "x")])));
+        assert!(!a.is_equal_node(&element(&[("type", "text")])));
+        assert!(!a.is_equal_node(&Element::new_with_namespace("input", "urn:x")));
+
+        let link = |namespace: &str| {
+            let mut element = Element::new("a");
+            let result = element.set_attribute_ns(Some(namespace), "p:href", "#");
+            result.unwrap();
+            element
+        };
+        assert!(link("urn:one").is_equal_node(&link("urn:one")));
+        assert!(!link("urn:one").is_equal_node(&link("urn:two")));
+    }
+
     #[test]
     fn test_attribute_operations() {
         let mut elem = Element::new("div");
@@ -833,6 +1667,717 @@ mod tests {
         assert_eq!(result.unwrap_err(), DomException::InvalidCharacterError);
     }
 
+    #[test]
+    fn test_set_attributes_applies_batch() {
+        let mut elem = Element::new("input");
+        elem.set_attribute("class", "old").unwrap();
+        let attrs = [("type", "text"), ("class", "field wide"), ("id", "q")];
+
+        elem.set_attributes(attrs.map(|(name, value)| (name.to_string(), value.to_string())))
+            .unwrap();
+
+        let names: Vec<&str> = elem.attributes().keys().map(String::as_str).collect();
+        assert_eq!(names, ["class", "type", "id"]);
+        assert_eq!(elem.get_attribute("type"), Some("text"));
+        assert_eq!(elem.class_list(), ["field", "wide"]);
+        assert_eq!(elem.id(), Some("q"));
+    }
+
+    #[test]
+    fn test_set_attributes_is_atomic() {
+        let mut elem = Element::new("input");
+        elem.set_attribute("class", "old").unwrap();
+        let attrs = [("id", "q"), ("class", "new"), ("bad name", "x")];
+
+        assert_eq!(
+            elem.set_attributes(attrs.map(|(name, value)| (name.to_string(), value.to_string()))),
+            Err(DomException::InvalidCharacterError)
+        );
+
+        assert_eq!(elem.attributes().len(), 1);
+        assert_eq!(elem.get_attribute("class"), Some("old"));
+        assert_eq!(elem.class_list(), ["old"]);
+        assert_eq!(elem.id(), None);
+
+        elem.set_attribute_value_limit(Some(4));
+        let attrs = [("id", "q"), ("class", "new"), ("value", "too long")];
+        assert_eq!(
+            elem.set_attributes(attrs.map(|(name, value)| (name.to_string(), value.to_string()))),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(elem.attributes().len(), 1);
+        assert_eq!(elem.class_list(), ["old"]);
+        assert_eq!(elem.id(), None);
+    }
+
+    #[test]
+    fn test_toggle_attribute() {
+        let mut elem = Element::new("button");
+
+        assert!(elem.toggle_attribute("disabled", None).unwrap());
+        assert_eq!(elem.get_attribute("disabled"), Some(""));
+
+        assert!(!elem.toggle_attribute("disabled", None).unwrap());
+        assert!(!elem.has_attribute("disabled"));
+    }
+
+    #[test]
+    fn test_toggle_attribute_force() {
+        let mut elem = Element::new("div");
+
+        assert!(elem.toggle_attribute("hidden", Some(true)).unwrap());
+        assert!(elem.toggle_attribute("hidden", Some(true)).unwrap());
+        assert!(elem.has_attribute("hidden"));
+
+        assert!(!elem.toggle_attribute("hidden", Some(false)).unwrap());
+        assert!(!elem.toggle_attribute("hidden", Some(false)).unwrap());
+        assert!(!elem.has_attribute("hidden"));
+    }
+
+    #[test]
+    fn test_toggle_attribute_keeps_class_and_id_in_sync() {
+        let mut elem = Element::new("div");
+        elem.set_attribute("class", "foo bar").unwrap();
+        elem.set_attribute("id", "main").unwrap();
+
+        // Forcing on an existing attribute keeps its value
+        assert!(elem.toggle_attribute("class", Some(true)).unwrap());
+        assert_eq!(elem.class_list(), ["foo", "bar"]);
+
+        assert!(!elem.toggle_attribute("class", None).unwrap());
+        assert!(elem.class_list().is_empty());
+
+        assert!(!elem.toggle_attribute("id", Some(false)).unwrap());
+        assert_eq!(elem.id(), None);
+    }
+
+    #[test]
+    fn test_toggle_attribute_invalid_name() {
+        let mut elem = Element::new("div");
+        assert_eq!(
+            elem.toggle_attribute("1nvalid", None),
+            Err(DomException::InvalidCharacterError)
+        );
+    }
+
+    fn element_node(tag: &str) -> NodeRef {
+        new_node_ref(Element::new(tag))
+    }
+
+    fn child_names(node: &NodeRef) -> Vec<String> {
+        node.read()
+            .child_nodes()
+            .iter()
+            .map(|child| {
+                let child = child.read();
+                match child.node_value() {
+                    Some(text) => text.to_string(),
+                    None => child.node_name().to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn as_element(node: &NodeRef) -> parking_lot::MappedRwLockWriteGuard<'_, Element> {
+        parking_lot::RwLockWriteGuard::map(node.write(), |n| {
+            n.as_any_mut().downcast_mut::<Element>().unwrap()
+        })
+    }
+
+    #[test]
+    fn test_inner_text_skips_scripts_styles_and_hidden_subtrees() {
+        let container = element_node("div");
+        let child = |tag: &str, text: &str| {
+            let node = element_node(tag);
+            as_element(&node).append(vec![text.into()]).unwrap();
+            node
+        };
+        let hidden = child("section", "secret ");
+        as_element(&hidden).set_attribute("hidden", "").unwrap();
+        as_element(&hidden)
+            .append(vec![child("p", "nested").into()])
+            .unwrap();
+        as_element(&container)
+            .append(vec![
+                "Hello\n  ".into(),
+                child("script", "track();").into(),
+                hidden.into(),
+                child("b", "  big   world ").into(),
+                child("style", "p { color: red }").into(),
+            ])
+            .unwrap();
+
+        let text_content = container.read().text_content().unwrap();
+        assert!(text_content.contains("track();"));
+        assert!(text_content.contains("secret nested"));
+        assert_eq!(as_element(&container).inner_text(), "Hello big world");
+    }
+
+    #[test]
+    fn test_prepend_preserves_order() {
+        let parent = element_node("div");
+        parent.write().append_child(element_node("p")).unwrap();
+
+        let a = element_node("a");
+        let b = element_node("b");
+        as_element(&parent)
+            .prepend(vec![a.clone().into(), b.clone().into()])
+            .unwrap();
+
+        assert_eq!(child_names(&parent), vec!["A", "B", "P"]);
+        assert!(Arc::ptr_eq(&a.read().parent_node().unwrap(), &parent));
+    }
+
+    #[test]
+    fn test_append_mixed_nodes_and_text() {
+        let parent = element_node("div");
+        parent.write().append_child(element_node("p")).unwrap();
+
+        as_element(&parent)
+            .append(vec![element_node("span").into(), "hello".into()])
+            .unwrap();
+
+        assert_eq!(child_names(&parent), vec!["P", "SPAN", "hello"]);
+        let text = parent.read().last_child().unwrap();
+        assert_eq!(text.read().node_type(), NodeType::Text);
+        assert!(Arc::ptr_eq(&text.read().parent_node().unwrap(), &parent));
+    }
+
+    #[test]
+    fn test_append_flattens_fragment() {
+        let parent = element_node("div");
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        fragment.write().append_child(element_node("i")).unwrap();
+        fragment.write().append_child(element_node("b")).unwrap();
+
+        as_element(&parent)
+            .append(vec!["x".into(), fragment.clone().into()])
+            .unwrap();
+
+        assert_eq!(child_names(&parent), vec!["x", "I", "B"]);
+        assert!(fragment.read().child_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_append_validates_before_emptying_fragments() {
+        let grandparent = element_node("section");
+        let parent = element_node("div");
+        grandparent.write().append_child(parent.clone()).unwrap();
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        fragment.write().append_child(element_node("i")).unwrap();
+
+        // An ancestor can't be appended, so nothing is
+        assert_eq!(
+            as_element(&parent)
+                .append(vec![fragment.clone().into(), grandparent.into()])
+                .unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+        assert_eq!(child_names(&fragment), vec!["I"]);
+        assert!(parent.read().child_nodes().is_empty());
+
+        // Nor can more children than the limit allows
+        as_element(&parent).set_child_limit(Some(1));
+        assert_eq!(
+            as_element(&parent)
+                .prepend(vec![fragment.clone().into(), "text".into()])
+                .unwrap_err(),
+            DomException::QuotaExceededError
+        );
+        assert_eq!(child_names(&fragment), vec!["I"]);
+        assert!(parent.read().child_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_insert_before_keeps_fragment_order() {
+        let parent = element_node("div");
+        let first = element_node("h1");
+        let last = element_node("footer");
+        parent.write().append_child(first.clone()).unwrap();
+        parent.write().append_child(last.clone()).unwrap();
+
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        let comment: NodeRef = Arc::new(RwLock::new(crate::Comment::new("note")));
+        let moved = vec![
+            element_node("p"),
+            Arc::new(RwLock::new(crate::Text::new("text"))),
+            comment,
+        ];
+        for node in &moved {
+            fragment.write().append_child(node.clone()).unwrap();
+        }
+
+        let returned = parent
+            .write()
+            .insert_before(fragment.clone(), Some(last.clone()))
+            .unwrap();
+        assert!(Arc::ptr_eq(&returned, &fragment));
+        assert!(fragment.read().child_nodes().is_empty());
+
+        let children = parent.read().child_nodes();
+        assert_eq!(children.len(), 5);
+        assert!(Arc::ptr_eq(&children[0], &first));
+        for (child, node) in children[1..4].iter().zip(&moved) {
+            assert!(Arc::ptr_eq(child, node));
+            assert!(Arc::ptr_eq(&node.read().parent_node().unwrap(), &parent));
+        }
+        assert!(Arc::ptr_eq(&children[4], &last));
+        assert_eq!(
+            child_names(&parent),
+            vec!["H1", "P", "text", "note", "FOOTER"]
+        );
+    }
+
+    #[test]
+    fn test_append_child_splices_fragment_children() {
+        let parent = element_node("ul");
+        let existing = element_node("li");
+        parent.write().append_child(existing.clone()).unwrap();
+
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        let items = vec![element_node("li"), element_node("li"), element_node("li")];
+        for item in &items {
+            fragment.write().append_child(item.clone()).unwrap();
+        }
+
+        let returned = parent.write().append_child(fragment.clone()).unwrap();
+        assert!(Arc::ptr_eq(&returned, &fragment));
+        assert!(fragment.read().child_nodes().is_empty());
+
+        let children = parent.read().child_nodes();
+        assert_eq!(children.len(), 4);
+        assert!(Arc::ptr_eq(&children[0], &existing));
+        for (child, item) in children[1..].iter().zip(&items) {
+            assert!(Arc::ptr_eq(child, item));
+            assert!(Arc::ptr_eq(&item.read().parent_node().unwrap(), &parent));
+        }
+    }
+
+    #[test]
+    fn test_insert_fragment_before_missing_child_keeps_fragment() {
+        let parent = element_node("div");
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        fragment.write().append_child(element_node("p")).unwrap();
+
+        let result = parent
+            .write()
+            .insert_before(fragment.clone(), Some(element_node("span")));
+        assert_eq!(result.unwrap_err(), DomException::NotFoundError);
+        assert_eq!(fragment.read().child_nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_attribute_limit() {
+        let mut element = Element::new("div");
+        element.set_attribute_limit(Some(2));
+        element.set_attribute("id", "a").unwrap();
+        element
+            .set_attribute_ns(Some(crate::namespaces::XLINK_NAMESPACE), "xlink:href", "#x")
+            .unwrap();
+        // Replacing an existing attribute is always allowed
+        element.set_attribute("id", "b").unwrap();
+
+        assert_eq!(
+            element.set_attribute("title", "c"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(
+            element.set_attribute_ns(None, "lang", "en"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(element.attributes().len(), 2);
+
+        element.remove_attribute("id").unwrap();
+        element.set_attribute("title", "c").unwrap();
+    }
+
+    #[test]
+    fn test_attribute_value_limit() {
+        let element_ref = downcast_element(&element_node("a")).unwrap();
+        let mut element = element_ref.write();
+        element.set_attribute_value_limit(Some(4));
+        element.set_attribute("title", "abcd").unwrap();
+
+        assert_eq!(
+            element.set_attribute("title", "abcde"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(
+            element.set_attribute_ns(
+                Some(crate::namespaces::XLINK_NAMESPACE),
+                "xlink:href",
+                "#long"
+            ),
+            Err(DomException::QuotaExceededError)
+        );
+        let attr = Attr::new_ns(crate::namespaces::XLINK_NAMESPACE, "xlink:href", "#long");
+        assert_eq!(
+            element
+                .set_attribute_node_ns(Arc::new(RwLock::new(attr.unwrap())))
+                .err(),
+            Some(DomException::QuotaExceededError)
+        );
+        let attr = element.get_attribute_node("title").unwrap();
+        drop(element);
+
+        // Edits through an owned Attr node are rejected on both sides
+        attr.write().set_value("abcde");
+        assert_eq!(attr.read().value(), "abcd");
+        let element = element_ref.read();
+        assert_eq!(element.get_attribute("title"), Some("abcd"));
+        assert!(!element.has_attribute("xlink:href"));
+    }
+
+    #[test]
+    fn test_child_limit() {
+        let parent = element_node("ul");
+        as_element(&parent).set_child_limit(Some(2));
+        let first = element_node("li");
+        parent.write().append_child(first.clone()).unwrap();
+        parent.write().append_child(element_node("li")).unwrap();
+
+        assert_eq!(
+            parent.write().append_child(element_node("li")).unwrap_err(),
+            DomException::QuotaExceededError
+        );
+        assert_eq!(
+            parent
+                .write()
+                .insert_before(element_node("li"), Some(first.clone()))
+                .unwrap_err(),
+            DomException::QuotaExceededError
+        );
+        assert_eq!(parent.read().child_nodes().len(), 2);
+
+        let fragment: NodeRef = Arc::new(RwLock::new(crate::DocumentFragment::new()));
+        fragment.write().append_child(element_node("li")).unwrap();
+        parent.write().remove_child(first).unwrap();
+        fragment.write().append_child(element_node("li")).unwrap();
+        assert_eq!(
+            parent
+                .write()
+                .insert_before(fragment.clone(), None)
+                .unwrap_err(),
+            DomException::QuotaExceededError
+        );
+        assert_eq!(fragment.read().child_nodes().len(), 2);
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingSink {
+        invalidations: parking_lot::Mutex<Vec<(String, ClassInvalidation)>>,
+    }
+
+    impl StyleInvalidationSink for RecordingSink {
+        fn classes_changed(&self, root: &Element, change: &ClassInvalidation) {
+            let root = format!("{}#{}", root.tag_name(), root.id().unwrap_or_default());
+            self.invalidations.lock().push((root, change.clone()));
+        }
+    }
+
+    #[test]
+    fn test_class_change_reports_delta() {
+        let sink = Arc::new(RecordingSink::default());
+        let mut element = Element::new("section");
+        element.set_attribute("id", "menu").unwrap();
+        element.set_attribute("class", "panel").unwrap();
+        element.set_style_invalidation_sink(Some(sink.clone()));
+
+        element.set_attribute("class", "panel open").unwrap();
+        // Only whitespace and order change: nothing to invalidate
+        element.set_attribute("class", " open  panel").unwrap();
+        element.set_attribute("class", "closed").unwrap();
+        element.remove_attribute("class").unwrap();
+        // Other attributes never trigger class invalidation
+        element.set_attribute("title", "x").unwrap();
+
+        let delta = |added: &[&str], removed: &[&str]| ClassInvalidation {
+            added: added.iter().map(|c| c.to_string()).collect(),
+            removed: removed.iter().map(|c| c.to_string()).collect(),
+        };
+        assert_eq!(
+            *sink.invalidations.lock(),
+            vec![
+                ("SECTION#menu".to_string(), delta(&["open"], &[])),
+                (
+                    "SECTION#menu".to_string(),
+                    delta(&["closed"], &["open", "panel"])
+                ),
+                ("SECTION#menu".to_string(), delta(&[], &["closed"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_document_binds_style_sink() {
+        let mut doc = crate::Document::new();
+        let root = doc.create_element("html").unwrap();
+        root.write().set_attribute("id", "root").unwrap();
+        doc.set_document_element(root.clone());
+
+        // Installed after the tree was built
+        let sink = Arc::new(RecordingSink::default());
+        doc.set_style_invalidation_sink(Some(sink.clone()));
+        root.write().set_attribute("class", "dark").unwrap();
+
+        // Created by the document, and inserted from outside it
+        let created = doc.create_element("nav").unwrap();
+        created.write().set_attribute("class", "menu").unwrap();
+        let outsider = element_node("p");
+        root.write().append_child(outsider.clone()).unwrap();
+        as_element(&outsider)
+            .set_attribute("class", "note")
+            .unwrap();
+
+        // Clones stay in the document
+        let mut clone = root.read().clone();
+        clone.set_attribute("class", "light").unwrap();
+
+        let roots: Vec<String> = sink
+            .invalidations
+            .lock()
+            .iter()
+            .map(|(root, _)| root.clone())
+            .collect();
+        assert_eq!(roots, ["HTML#root", "NAV#", "P#", "HTML#root"]);
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limited = |tag: &str| {
+            let node = element_node(tag);
+            as_element(&node).set_depth_limit(Some(3));
+            node
+        };
+        let root = limited("div");
+        let middle = limited("section");
+        let leaf = limited("p");
+        root.write().append_child(middle.clone()).unwrap();
+        middle.write().append_child(leaf.clone()).unwrap();
+
+        // The chain is at the limit, so nothing can go below the leaf
+        assert_eq!(
+            leaf.write().append_child(element_node("span")).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+
+        // Breadth is not limited
+        for _ in 0..5 {
+            middle.write().append_child(element_node("p")).unwrap();
+            root.write()
+                .insert_before(element_node("aside"), Some(middle.clone()))
+                .unwrap();
+        }
+
+        // The inserted subtree's own depth counts too
+        let nested = element_node("ul");
+        nested.write().append_child(element_node("li")).unwrap();
+        assert_eq!(
+            middle.write().append_child(nested.clone()).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+        root.write().append_child(nested).unwrap();
+
+        // A limit set inside an unlimited tree counts the ancestors
+        let outer = element_node("div");
+        let inner = element_node("div");
+        outer.write().append_child(inner.clone()).unwrap();
+        as_element(&inner).set_depth_limit(Some(2));
+        assert_eq!(
+            inner.write().append_child(element_node("p")).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+    }
+
+    #[test]
+    fn test_template_content_fragment() {
+        let template = Element::new("template");
+        let content = template.content().unwrap();
+        assert_eq!(content.read().node_type(), NodeType::DocumentFragment);
+        assert!(Element::new("div").content().is_none());
+        let foreign = Element::new_with_namespace("template", "urn:x");
+        assert!(foreign.content().is_none());
+    }
+
+    #[test]
+    fn test_clone_template_copies_content() {
+        let template = Element::new("template");
+        let content = template.content().unwrap();
+        content.write().append_child(element_node("p")).unwrap();
+
+        let deep = template.clone_node(true);
+        let deep_content = deep
+            .read()
+            .as_any()
+            .downcast_ref::<Element>()
+            .unwrap()
+            .content()
+            .unwrap();
+        assert!(!Arc::ptr_eq(&deep_content, &content));
+        assert_eq!(child_names(&deep_content), vec!["P"]);
+        assert!(!Arc::ptr_eq(
+            &deep_content.read().child_nodes()[0],
+            &content.read().child_nodes()[0]
+        ));
+        assert!(deep.read().child_nodes().is_empty());
+
+        let shallow = template.clone_node(false);
+        let shallow = shallow.read();
+        let shallow = shallow.as_any().downcast_ref::<Element>().unwrap();
+        assert!(shallow.content().unwrap().read().child_nodes().is_empty());
+    }
+
+    /// `<div><section id=a class=x><span id=b class="x y"/></section><span id=c class=y/></div>`
+    fn search_tree() -> (NodeRef, NodeRef) {
+        let root = element_node("div");
+        let section = element_node("section");
+        let inner = element_node("span");
+        let outer = element_node("span");
+        for (node, id, class) in [
+            (&section, "a", "x"),
+            (&inner, "b", "x y"),
+            (&outer, "c", "y"),
+        ] {
+            as_element(node).set_attribute("id", id).unwrap();
+            as_element(node).set_attribute("class", class).unwrap();
+        }
+        section.write().append_child(inner.clone()).unwrap();
+        root.write().append_child(section).unwrap();
+        root.write().append_child(outer).unwrap();
+        (root, inner)
+    }
+
+    fn ids(elements: &[ElementRef]) -> Vec<String> {
+        elements
+            .iter()
+            .map(|element| element.read().id().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_elements_by_tag_name_finds_descendants() {
+        let (root, inner) = search_tree();
+        let root = as_element(&root);
+
+        assert_eq!(ids(&root.get_elements_by_tag_name("span")), ["b", "c"]);
+        assert_eq!(ids(&root.get_elements_by_tag_name("*")), ["a", "b", "c"]);
+        assert!(root.get_elements_by_tag_name("p").is_empty());
+
+        // The matches are the elements in the tree, not copies
+        let sections = root.get_elements_by_tag_name("SECTION");
+        assert_eq!(ids(&sections), ["a"]);
+        assert!(Arc::ptr_eq(&sections[0].read().child_nodes()[0], &inner));
+        let spans = root.get_elements_by_tag_name("span");
+        spans[0].write().set_attribute("title", "live").unwrap();
+        assert_eq!(as_element(&inner).get_attribute("title"), Some("live"));
+    }
+
+    #[test]
+    fn test_downcast_element_shares_the_allocation() {
+        let node = element_node("div");
+        let element = downcast_element(&node).unwrap();
+        assert!(std::ptr::addr_eq(Arc::as_ptr(&element), Arc::as_ptr(&node)));
+        assert_eq!(Arc::strong_count(&node), 2);
+
+        let text: NodeRef = Arc::new(RwLock::new(crate::Text::new("x")));
+        assert!(downcast_element(&text).is_none());
+
+        // A copy of the element is a different node
+        let copy: NodeRef = Arc::new(RwLock::new(element.read().clone()));
+        assert!(downcast_element(&copy).is_none());
+    }
+
+    #[test]
+    fn test_get_elements_by_class_name_checks_each_candidate() {
+        let (root, _) = search_tree();
+        let root = as_element(&root);
+
+        assert_eq!(ids(&root.get_elements_by_class_name("x")), ["a", "b"]);
+        assert_eq!(ids(&root.get_elements_by_class_name("y")), ["b", "c"]);
+        assert_eq!(ids(&root.get_elements_by_class_name(" y  x ")), ["b"]);
+        assert!(root.get_elements_by_class_name("z").is_empty());
+        assert!(root.get_elements_by_class_name("  ").is_empty());
+    }
+
+    #[test]
+    fn test_clone_element_is_typed_and_detached() {
+        let parent = element_node("section");
+        let original = element_node("div");
+        as_element(&original).set_attribute("id", "card").unwrap();
+        original.write().append_child(element_node("span")).unwrap();
+        parent.write().append_child(original.clone()).unwrap();
+
+        let shallow = as_element(&original).clone_element(false);
+        shallow.write().set_attribute("title", "copy").unwrap();
+        assert_eq!(shallow.read().get_attribute("id"), Some("card"));
+        assert!(shallow.read().parent_node().is_none());
+        assert!(shallow.read().child_nodes().is_empty());
+        assert!(!as_element(&original).has_attribute("title"));
+
+        let deep = as_element(&original).clone_element(true);
+        let children = deep.read().child_nodes();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].read().node_name(), "SPAN");
+        assert!(!Arc::ptr_eq(
+            &children[0],
+            &original.read().child_nodes()[0]
+        ));
+        let deep: NodeRef = deep;
+        assert!(Arc::ptr_eq(
+            &children[0].read().parent_node().unwrap(),
+            &deep
+        ));
+
+        // Through the trait, the clone is an element with its self-reference
+        let cloned = original.read().clone_node(true);
+        let child = cloned.read().first_child().unwrap();
+        assert!(Arc::ptr_eq(&child.read().parent_node().unwrap(), &cloned));
+        assert!(as_element(&cloned).self_ref().is_some());
+    }
+
+    #[test]
+    fn test_deep_clone_preserves_attributes_and_namespaces() {
+        use crate::namespaces::XLINK_NAMESPACE;
+        let ns = Some(XLINK_NAMESPACE);
+        let parent = element_node("svg");
+        let original = element_node("a");
+        {
+            let mut link = as_element(&original);
+            link.set_attribute_ns(ns, "xlink:href", "#target").unwrap();
+            link.set_attribute("class", "nav active").unwrap();
+            link.set_attribute("id", "home").unwrap();
+        }
+        original.write().append_child(element_node("span")).unwrap();
+        parent.write().append_child(original.clone()).unwrap();
+
+        let clone = original.read().clone_node(true);
+        assert!(clone.read().parent_node().is_none());
+        {
+            let copy = as_element(&clone);
+            let href = copy.get_attribute_ns(ns, "href");
+            assert_eq!(href.as_deref(), Some("#target"));
+            assert_eq!(copy.get_attribute("xlink:href"), Some("#target"));
+            assert_eq!(copy.class_list(), ["nav", "active"]);
+            assert_eq!(copy.id(), Some("home"));
+        }
+        assert_eq!(child_names(&clone), ["SPAN"]);
+
+        // Changing the clone leaves the original untouched
+        {
+            let mut copy = as_element(&clone);
+            copy.set_attribute_ns(ns, "xlink:href", "#other").unwrap();
+            copy.set_attribute("class", "nav").unwrap();
+            copy.set_attribute("id", "copy").unwrap();
+        }
+        clone.write().append_child(element_node("em")).unwrap();
+        assert_eq!(child_names(&original), ["SPAN"]);
+        let link = as_element(&original);
+        let href = link.get_attribute_ns(ns, "href");
+        assert_eq!(href.as_deref(), Some("#target"));
+        assert_eq!(link.class_list(), ["nav", "active"]);
+        assert_eq!(link.id(), Some("home"));
+    }
+
     // ==================== Namespaced Attribute Tests ====================
 
     #[test]
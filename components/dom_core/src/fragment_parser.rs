@@ -0,0 +1,353 @@
+//! Minimal HTML fragment parsing
+//!
+//! [`parse_fragment`] turns markup such as an `innerHTML` value into nodes.
+//! It is a small recursive-descent reader of tags, text and comments, not
+//! the HTML tree builder: there are no implied elements or foster parenting,
+//! and markup it cannot make sense of is kept as text or dropped. It never
+//! fails, so arbitrary input is safe to feed it.
+//!
+//! - End tags close the innermost open element with that name, along with
+//!   any elements opened inside it; stray end tags are ignored.
+//! - Elements left open at the end of the input are closed there.
+//! - Void elements and `/>` have no children. The contents of `<script>`,
+//!   `<style>`, `<textarea>` and `<title>` are read as text up to their end
+//!   tag.
+//! - Character references are decoded for the named ones that
+//!   [`serialize`](crate::serialize) produces plus numeric references.
+//! - Doctypes and processing instructions are skipped.
+
+use crate::comment::Comment;
+use crate::element::Element;
+use crate::node::{wrap_clone, Node, NodeRef};
+use crate::serialize::VOID_ELEMENTS;
+use crate::text::Text;
+
+/// Elements whose contents are text up to the matching end tag
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Parses `html` into a list of detached nodes, in document order
+///
+/// The children of a `<template>` go into its content fragment.
+pub fn parse_fragment(html: &str) -> Vec<NodeRef> {
+    let mut parser = Parser {
+        input: html,
+        pos: 0,
+        open: Vec::new(),
+    };
+    parser.parse_nodes()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Lowercase names of the elements being parsed, outermost first
+    open: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    /// Parses nodes until the end of the input or an end tag closing one of
+    /// the open elements
+    fn parse_nodes(&mut self) -> Vec<NodeRef> {
+        let mut nodes = Vec::new();
+        while !self.rest().is_empty() {
+            let rest = self.rest();
+            if let Some(after) = rest.strip_prefix("</") {
+                let name = tag_name(after).to_ascii_lowercase();
+                if self.open.contains(&name) {
+                    // Consume only our own end tag; outer ones close in turn
+                    if self.open.last() == Some(&name) {
+                        self.skip_past('>');
+                    }
+                    return nodes;
+                }
+                self.skip_past('>');
+            } else if let Some(after) = rest.strip_prefix("<!--") {
+                let (data, consumed) = match after.find("-->") {
+                    Some(end) => (&after[..end], end + 3),
+                    None => (after, after.len()),
+                };
+                nodes.push(wrap(Comment::new(data)));
+                self.pos += 4 + consumed;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.skip_past('>');
+            } else if starts_start_tag(rest) {
+                nodes.push(self.parse_element());
+            } else {
+                nodes.push(wrap(Text::new(decode_references(&self.take_text()))));
+            }
+        }
+        nodes
+    }
+
+    /// Reads text up to the next markup, keeping a `<` that starts none
+    fn take_text(&mut self) -> String {
+        let rest = self.rest();
+        let bytes = rest.as_bytes();
+        let mut end = 1;
+        while end < bytes.len() {
+            let starts_markup = bytes[end] == b'<'
+                && bytes
+                    .get(end + 1)
+                    .is_some_and(|next| next.is_ascii_alphabetic() || b"/!?".contains(next));
+            if starts_markup {
+                break;
+            }
+            end += 1;
+        }
+        let text = rest[..end].to_string();
+        self.pos += end;
+        text
+    }
+
+    /// Parses a start tag at the cursor and the element's contents
+    fn parse_element(&mut self) -> NodeRef {
+        self.pos += 1;
+        let name = tag_name(self.rest()).to_ascii_lowercase();
+        self.pos += name.len();
+        let mut element = Element::new(name.as_str());
+        let self_closing = self.parse_attributes(&mut element);
+        let element = wrap(element);
+
+        if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+            return element;
+        }
+        let children = if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            self.take_raw_text(&name)
+                .into_iter()
+                .map(|text| wrap(Text::new(text)))
+                .collect()
+        } else {
+            self.open.push(name);
+            let children = self.parse_nodes();
+            self.open.pop();
+            children
+        };
+
+        let content = element
+            .read()
+            .as_any()
+            .downcast_ref::<Element>()
+            .and_then(Element::content);
+        let parent = content.unwrap_or_else(|| element.clone());
+        for child in children {
+            let _ = parent.write().append_child(child);
+        }
+        element
+    }
+
+    /// Reads attributes up to the end of the start tag, returning whether it
+    /// ended with `/>`
+    ///
+    /// Attributes the element rejects, such as those with invalid names or
+    /// repeated ones, are dropped.
+    fn parse_attributes(&mut self, element: &mut Element) -> bool {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.is_empty() {
+                return false;
+            }
+            if let Some(after) = rest.strip_prefix("/>") {
+                self.pos = self.input.len() - after.len();
+                return true;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return false;
+            }
+
+            let name_len = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+                .unwrap_or(rest.len())
+                .max(1);
+            let name = rest[..name_len].to_ascii_lowercase();
+            self.pos += name_len;
+            self.skip_whitespace();
+
+            let mut value = String::new();
+            if self.rest().starts_with('=') {
+                self.pos += 1;
+                self.skip_whitespace();
+                value = decode_references(&self.take_attribute_value());
+            }
+            if !element.has_attribute(&name) {
+                let _ = element.set_attribute(name, value);
+            }
+        }
+    }
+
+    fn take_attribute_value(&mut self) -> String {
+        let rest = self.rest();
+        let (value, consumed) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => match rest[1..].find(quote) {
+                Some(end) => (&rest[1..end + 1], end + 2),
+                None => (&rest[1..], rest.len()),
+            },
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+        let value = value.to_string();
+        self.pos += consumed;
+        value
+    }
+
+    /// Reads the contents of a raw text element and its end tag
+    fn take_raw_text(&mut self, name: &str) -> Option<String> {
+        let rest = self.rest();
+        let end_tag = format!("</{}", name);
+        let end = rest
+            .to_ascii_lowercase()
+            .find(&end_tag)
+            .unwrap_or(rest.len());
+        let text = rest[..end].to_string();
+        self.pos += end;
+        self.skip_past('>');
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves the cursor past the next `c`, or to the end of the input
+    fn skip_past(&mut self, c: char) {
+        self.pos = match self.rest().find(c) {
+            Some(index) => self.pos + index + c.len_utf8(),
+            None => self.input.len(),
+        };
+    }
+}
+
+fn wrap(node: impl Node + 'static) -> NodeRef {
+    wrap_clone(node)
+}
+
+/// Whether `s` starts with `<` and a letter
+fn starts_start_tag(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.first() == Some(&b'<') && bytes.get(1).is_some_and(u8::is_ascii_alphabetic)
+}
+
+/// The tag name at the start of `s`
+fn tag_name(s: &str) -> &str {
+    let end = s
+        .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Replaces character references in `text`, leaving unknown ones as written
+fn decode_references(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest[1..]
+            .find(';')
+            .map(|end| &rest[1..end + 1])
+            .and_then(|name| Some((decode_reference(name)?, name.len() + 2)));
+        match reference {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes the reference `name`, written between `&` and `;`
+fn decode_reference(name: &str) -> Option<char> {
+    let code = match name {
+        "amp" => return Some('&'),
+        "lt" => return Some('<'),
+        "gt" => return Some('>'),
+        "quot" => return Some('"'),
+        "apos" => return Some('\''),
+        "nbsp" => return Some('\u{a0}'),
+        _ => name.strip_prefix('#')?,
+    };
+    let value = match code.strip_prefix(['x', 'X']) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => code.parse().ok()?,
+    };
+    char::from_u32(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize::serialize_html;
+
+    fn round_trip(html: &str) -> String {
+        parse_fragment(html).iter().map(serialize_html).collect()
+    }
+
+    #[test]
+    fn test_parses_nested_elements_text_and_comments() {
+        let nodes = parse_fragment("<p class=\"a\" id=x>Hi <b>there</b><!-- c --></p>tail");
+        assert_eq!(nodes.len(), 2);
+
+        let p = nodes[0].read();
+        let element = p.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(element.get_attribute("class"), Some("a"));
+        assert_eq!(element.get_attribute("id"), Some("x"));
+        let children = p.child_nodes();
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0].read().node_value(), Some("Hi "));
+        assert_eq!(children[1].read().node_name(), "B");
+        assert_eq!(children[2].read().node_value(), Some(" c "));
+        assert!(children[1].read().parent_node().is_some());
+        assert_eq!(nodes[1].read().node_value(), Some("tail"));
+    }
+
+    #[test]
+    fn test_decodes_references_and_reads_raw_text() {
+        assert_eq!(
+            round_trip("<a title='&quot;x&quot;'>1 &lt; 2 &#38; &#x33; &bogus;</a>"),
+            "<a title=\"&quot;x&quot;\">1 &lt; 2 &amp; 3 &amp;bogus;</a>"
+        );
+        let nodes = parse_fragment("<script>if (a < b) { x() }</script>");
+        let script = nodes[0].read();
+        assert_eq!(script.text_content().as_deref(), Some("if (a < b) { x() }"));
+    }
+
+    #[test]
+    fn test_recovers_from_malformed_markup() {
+        let cases = [
+            ("<ul><li>a<li>b</ul>", "<ul><li>a<li>b</li></li></ul>"),
+            ("a < b</i> <br/><img src=x>", "a &lt; b <br><img src=\"x\">"),
+            ("<div><span>open", "<div><span>open</span></div>"),
+            ("<!DOCTYPE html><p x=\"1", "<p x=\"1\"></p>"),
+            ("<!-- never closed", "<!-- never closed-->"),
+            ("<", "&lt;"),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(round_trip(html), expected, "parsing {html:?}");
+        }
+    }
+
+    #[test]
+    fn test_template_children_go_into_content() {
+        let nodes = parse_fragment("<template><td>cell</td></template>");
+        let template = nodes[0].read();
+        assert!(template.child_nodes().is_empty());
+        let element = template.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(element.inner_html(), "<td>cell</td>");
+    }
+}
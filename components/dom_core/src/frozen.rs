@@ -0,0 +1,305 @@
+//! Immutable subtree snapshots
+//!
+//! Reading the live tree means taking a lock per node. A rendering thread
+//! that only needs to read can instead take a [`FrozenNode`] snapshot with
+//! [`Node::snapshot`]: a deep copy of the subtree's names, attributes and
+//! text that is detached from the tree and can be shared freely.
+
+use crate::element::Element;
+use crate::node::{Node, NodeRef};
+use dom_types::NodeType;
+
+/// Deep, read-only copy of a node and its descendants
+///
+/// Shadow trees and `<template>` contents are not part of the snapshot,
+/// just as they are not children of their host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrozenNode {
+    node_type: NodeType,
+    node_name: String,
+    node_value: Option<String>,
+    attributes: Vec<(String, String)>,
+    namespaced_attributes: Vec<FrozenNamespacedAttribute>,
+    children: Vec<FrozenNode>,
+}
+
+/// An attribute that was set with a namespace
+#[derive(Debug, Clone, PartialEq)]
+struct FrozenNamespacedAttribute {
+    namespace: Option<String>,
+    local_name: String,
+    qualified_name: String,
+    value: String,
+}
+
+impl FrozenNode {
+    /// Copies `node` and its descendants
+    ///
+    /// Walks the subtree with an explicit stack, so very deep trees cannot
+    /// overflow the call stack.
+    pub(crate) fn capture<N: Node + ?Sized>(node: &N) -> Self {
+        let mut root = Self::capture_node(node);
+
+        // Copies in tree order, each with the index of its parent's copy
+        // (None for the root)
+        let mut captured: Vec<(FrozenNode, Option<usize>)> = Vec::new();
+        let mut stack: Vec<(NodeRef, Option<usize>)> = node
+            .child_nodes()
+            .into_iter()
+            .rev()
+            .map(|child| (child, None))
+            .collect();
+        while let Some((child, parent)) = stack.pop() {
+            let index = captured.len();
+            let child = child.read();
+            captured.push((Self::capture_node(&*child), parent));
+            let children = child.child_nodes().into_iter().rev();
+            stack.extend(children.map(|grandchild| (grandchild, Some(index))));
+        }
+
+        // Descendants come after their ancestors, so each copy is complete
+        // by the time it is moved into its parent. Children arrive last
+        // first and are put back in order.
+        while let Some((mut done, parent)) = captured.pop() {
+            done.children.reverse();
+            match parent {
+                Some(parent) => captured[parent].0.children.push(done),
+                None => root.children.push(done),
+            }
+        }
+        root.children.reverse();
+        root
+    }
+
+    /// Copies `node` without its children
+    fn capture_node<N: Node + ?Sized>(node: &N) -> Self {
+        let element = node.as_any().downcast_ref::<Element>();
+        let attributes = element
+            .map(|element| {
+                element
+                    .attributes()
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let namespaced_attributes = element
+            .map(|element| {
+                element
+                    .namespaced_attributes()
+                    .map(|(namespace, local_name, qualified_name, value)| {
+                        FrozenNamespacedAttribute {
+                            namespace: namespace.map(str::to_string),
+                            local_name: local_name.to_string(),
+                            qualified_name: qualified_name.to_string(),
+                            value: value.to_string(),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            node_type: node.node_type(),
+            node_name: node.node_name().to_string(),
+            node_value: node.node_value().map(str::to_string),
+            attributes,
+            namespaced_attributes,
+            children: Vec::new(),
+        }
+    }
+
+    /// Gets the type of the node
+    pub fn node_type(&self) -> NodeType {
+        self.node_type
+    }
+
+    /// Gets the node name, e.g. the tag name of an element or `#text`
+    pub fn node_name(&self) -> &str {
+        &self.node_name
+    }
+
+    /// Gets the data of text-like nodes
+    pub fn node_value(&self) -> Option<&str> {
+        self.node_value.as_deref()
+    }
+
+    /// Gets the attributes of an element in document order
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Gets the value of the attribute `name`
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attr_name, _)| attr_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Gets the value of the attribute `local_name` in `namespace`
+    pub fn get_attribute_ns(&self, namespace: Option<&str>, local_name: &str) -> Option<&str> {
+        self.namespaced_attributes
+            .iter()
+            .find(|attr| attr.namespace.as_deref() == namespace && attr.local_name == local_name)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Gets the namespace of the attribute `qualified_name`, if it was set
+    /// with one
+    pub fn attribute_namespace(&self, qualified_name: &str) -> Option<&str> {
+        self.namespaced_attributes
+            .iter()
+            .find(|attr| attr.qualified_name == qualified_name)
+            .and_then(|attr| attr.namespace.as_deref())
+    }
+
+    /// Gets the child snapshots
+    pub fn children(&self) -> &[FrozenNode] {
+        &self.children
+    }
+
+    /// Concatenates the data of all descendant text nodes
+    ///
+    /// For a text-like node this is its own data.
+    pub fn text_content(&self) -> String {
+        if let Some(value) = &self.node_value {
+            return value.clone();
+        }
+        let mut text = String::new();
+        let mut stack: Vec<&FrozenNode> = self.children.iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            if node.node_type == NodeType::Text || node.node_type == NodeType::CDataSection {
+                text.push_str(node.node_value().unwrap_or_default());
+            }
+            stack.extend(node.children.iter().rev());
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{new_node_ref, NodeRef};
+    use crate::text::Text;
+    use std::sync::Arc;
+
+    fn element(tag: &str, attributes: &[(&str, &str)]) -> NodeRef {
+        let mut element = Element::new(tag);
+        for (name, value) in attributes {
+            element.set_attribute(*name, *value).unwrap();
+        }
+        new_node_ref(element)
+    }
+
+    #[test]
+    fn test_snapshot_is_detached_from_live_tree() {
+        let list = element("ul", &[("class", "menu")]);
+        let item = element("li", &[("id", "first")]);
+        item.write()
+            .append_child(new_node_ref(Text::new("Home")))
+            .unwrap();
+        list.write().append_child(item.clone()).unwrap();
+
+        let snapshot = list.read().snapshot();
+
+        // Mutate the live tree in every way the snapshot records
+        list.write().append_child(element("li", &[])).unwrap();
+        item.write().set_text_content("Away".to_string());
+        if let Some(item) = item.write().as_any_mut().downcast_mut::<Element>() {
+            item.set_attribute("id", "moved").unwrap();
+        }
+
+        assert_eq!(snapshot.node_name(), "UL");
+        assert_eq!(snapshot.get_attribute("class"), Some("menu"));
+        assert_eq!(snapshot.children().len(), 1);
+        let frozen_item = &snapshot.children()[0];
+        assert_eq!(frozen_item.attributes(), [("id".into(), "first".into())]);
+        assert_eq!(frozen_item.children()[0].node_type(), NodeType::Text);
+        assert_eq!(snapshot.text_content(), "Home");
+    }
+
+    #[test]
+    fn test_snapshot_keeps_namespaced_attributes_and_child_order() {
+        use crate::namespaces::XLINK_NAMESPACE;
+        let mut link = Element::new("a");
+        link.set_attribute_ns(Some(XLINK_NAMESPACE), "xlink:href", "#top")
+            .unwrap();
+        let link = new_node_ref(link);
+        for tag in ["b", "i", "u"] {
+            link.write().append_child(element(tag, &[])).unwrap();
+        }
+
+        let snapshot = link.read().snapshot();
+        assert_eq!(
+            snapshot.get_attribute_ns(Some(XLINK_NAMESPACE), "href"),
+            Some("#top")
+        );
+        assert_eq!(snapshot.get_attribute_ns(None, "href"), None);
+        assert_eq!(
+            snapshot.attribute_namespace("xlink:href"),
+            Some(XLINK_NAMESPACE)
+        );
+        let names: Vec<&str> = snapshot
+            .children()
+            .iter()
+            .map(FrozenNode::node_name)
+            .collect();
+        assert_eq!(names, ["B", "I", "U"]);
+    }
+
+    #[test]
+    fn test_snapshot_of_deep_tree() {
+        const DEPTH: usize = 10_000;
+        let mut chain = vec![new_node_ref(Text::new("leaf"))];
+        for _ in 1..DEPTH {
+            let parent = element("div", &[]);
+            parent
+                .write()
+                .append_child(chain.last().unwrap().clone())
+                .unwrap();
+            chain.push(parent);
+        }
+
+        let snapshot = chain.last().unwrap().read().snapshot();
+        let mut depth = 1;
+        let mut current = &snapshot;
+        while let Some(child) = current.children().first() {
+            depth += 1;
+            current = child;
+        }
+        assert_eq!(depth, DEPTH);
+        assert_eq!(current.node_value(), Some("leaf"));
+
+        // Free the snapshot and the chain one level at a time
+        let mut levels = vec![snapshot];
+        while let Some(mut level) = levels.pop() {
+            levels.append(&mut level.children);
+        }
+        while chain.pop().is_some() {}
+    }
+
+    #[test]
+    fn test_snapshot_is_readable_from_other_threads() {
+        let paragraph = element("p", &[("lang", "en")]);
+        paragraph
+            .write()
+            .append_child(new_node_ref(Text::new("shared")))
+            .unwrap();
+        let snapshot = Arc::new(paragraph.read().snapshot());
+
+        // Hold the live node locked: readers of the snapshot never need it
+        let _guard = paragraph.write();
+        let reader = {
+            let snapshot = snapshot.clone();
+            std::thread::spawn(move || {
+                let lang = snapshot.get_attribute("lang").map(str::to_string);
+                (lang, snapshot.text_content())
+            })
+        };
+        let (lang, text) = reader.join().unwrap();
+        assert_eq!(lang.as_deref(), Some("en"));
+        assert_eq!(text, "shared");
+    }
+}
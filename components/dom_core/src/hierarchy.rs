@@ -0,0 +1,264 @@
+//! Pre-insertion validity
+//!
+//! Every way of inserting a node into a tree first runs the checks of the
+//! spec's "ensure pre-insertion validity" algorithm, so that no tree ever
+//! contains a cycle, a node in a position its type does not allow, or a
+//! document with two root elements. [`validate_pre_insertion`] is the single
+//! home of those checks.
+
+use crate::document_fragment::DocumentFragment;
+use crate::node::{Node, NodeRef};
+use dom_types::{DomException, NodeType};
+use std::sync::Arc;
+
+/// Checks that `node` may be inserted into `parent` before `ref_child`
+///
+/// A `ref_child` of `None` means appending. Fails with:
+///
+/// * `HierarchyRequestError` if `parent` cannot have children, if `node` is
+///   `parent` or one of its ancestors, shadow hosts included, if `node` is a `Document`, an `Attr`
+///   or another type that never appears in a tree, or if its type is not
+///   allowed under `parent`: text and a second element or doctype in a
+///   document, or a doctype anywhere but a document
+/// * `NotFoundError` if `ref_child` is not a child of `parent`
+///
+/// `parent` is usually locked by the caller, so it is never locked here;
+/// its ancestors and `node` are read-locked.
+pub fn validate_pre_insertion(
+    parent: &dyn Node,
+    node: &NodeRef,
+    ref_child: Option<&NodeRef>,
+) -> Result<(), DomException> {
+    let parent_type = parent.node_type();
+    if !matches!(
+        parent_type,
+        NodeType::Document | NodeType::DocumentFragment | NodeType::Element
+    ) {
+        return Err(DomException::HierarchyRequestError);
+    }
+
+    if is_inclusive_ancestor(node, parent) {
+        return Err(DomException::HierarchyRequestError);
+    }
+
+    if ref_child.is_some_and(|ref_child| parent.node_data().child_index(ref_child).is_none()) {
+        return Err(DomException::NotFoundError);
+    }
+
+    let node_type = node.read().node_type();
+    match node_type {
+        NodeType::DocumentFragment
+        | NodeType::DocumentType
+        | NodeType::Element
+        | NodeType::Text
+        | NodeType::CDataSection
+        | NodeType::Comment
+        | NodeType::ProcessingInstruction => {}
+        _ => return Err(DomException::HierarchyRequestError),
+    }
+
+    let is_text = matches!(node_type, NodeType::Text | NodeType::CDataSection);
+    let in_document = parent_type == NodeType::Document;
+    if (is_text && in_document) || (node_type == NodeType::DocumentType && !in_document) {
+        return Err(DomException::HierarchyRequestError);
+    }
+
+    if in_document {
+        validate_document_child(parent, node, node_type, ref_child)?;
+    }
+    Ok(())
+}
+
+/// Whether `node` is `parent` or one of its ancestors
+fn is_inclusive_ancestor(node: &NodeRef, parent: &dyn Node) -> bool {
+    match parent.node_data().get_self_node_ref() {
+        Some(parent_ref) if Arc::ptr_eq(&parent_ref, node) => return true,
+        Some(_) => {}
+        // Without a handle on itself the parent can only be told apart by address
+        None => {
+            let parent_ptr = parent as *const dyn Node;
+            if std::ptr::addr_eq(parent_ptr, &*node.read() as *const dyn Node) {
+                return true;
+            }
+        }
+    }
+
+    // Walk up from the parent rather than down from the node: the caller may
+    // hold locks on the node's descendants
+    let mut current = parent_or_host(parent);
+    while let Some(ancestor) = current {
+        if Arc::ptr_eq(&ancestor, node) {
+            return true;
+        }
+        current = parent_or_host(&*ancestor.read());
+    }
+    false
+}
+
+/// The parent of `node`, or its host if it is the root of a shadow tree
+fn parent_or_host(node: &dyn Node) -> Option<NodeRef> {
+    node.parent_node().or_else(|| {
+        let fragment = node.as_any().downcast_ref::<DocumentFragment>()?;
+        fragment.host().map(|host| host as NodeRef)
+    })
+}
+
+/// Keeps a document at one doctype followed by at most one element
+fn validate_document_child(
+    document: &dyn Node,
+    node: &NodeRef,
+    node_type: NodeType,
+    ref_child: Option<&NodeRef>,
+) -> Result<(), DomException> {
+    let children: Vec<(NodeRef, NodeType)> = document
+        .child_nodes()
+        .into_iter()
+        .map(|child| {
+            let child_type = child.read().node_type();
+            (child, child_type)
+        })
+        .collect();
+    let ref_index = ref_child.and_then(|ref_child| document.node_data().child_index(ref_child));
+    let has_element = has_type(&children, NodeType::Element);
+    let ref_is_doctype = ref_index.is_some_and(|i| children[i].1 == NodeType::DocumentType);
+    // A doctype at or after the reference child would end up after the element
+    let doctype_follows =
+        ref_index.is_some_and(|i| has_type(&children[i..], NodeType::DocumentType));
+    let element_precedes = match ref_index {
+        Some(i) => has_type(&children[..i], NodeType::Element),
+        None => has_element,
+    };
+
+    let element_fits = !has_element && !ref_is_doctype && !doctype_follows;
+    let fits = match node_type {
+        NodeType::Element => element_fits,
+        NodeType::DocumentType => !has_type(&children, NodeType::DocumentType) && !element_precedes,
+        NodeType::DocumentFragment => {
+            let fragment_types: Vec<NodeType> = node
+                .read()
+                .child_nodes()
+                .iter()
+                .map(|child| child.read().node_type())
+                .collect();
+            let elements = fragment_types
+                .iter()
+                .filter(|t| **t == NodeType::Element)
+                .count();
+            // Text would end up directly in the document
+            let has_text = fragment_types
+                .iter()
+                .any(|t| matches!(t, NodeType::Text | NodeType::CDataSection));
+            !has_text && (elements == 0 || (elements == 1 && element_fits))
+        }
+        _ => true,
+    };
+    if fits {
+        Ok(())
+    } else {
+        Err(DomException::HierarchyRequestError)
+    }
+}
+
+/// Whether any of `children` is of type `wanted`
+fn has_type(children: &[(NodeRef, NodeType)], wanted: NodeType) -> bool {
+    children.iter().any(|(_, t)| *t == wanted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attr::Attr;
+    use crate::document::Document;
+    use crate::document_type::DocumentType;
+    use crate::element::Element;
+    use crate::node::new_node_ref;
+    use crate::text::Text;
+
+    fn hierarchy_error(result: Result<NodeRef, DomException>) -> bool {
+        matches!(result, Err(DomException::HierarchyRequestError))
+    }
+
+    #[test]
+    fn test_rejects_document_child() {
+        let div = new_node_ref(Element::new("div"));
+        let document = new_node_ref(Document::new());
+
+        assert!(hierarchy_error(div.write().append_child(document.clone())));
+        assert!(hierarchy_error(div.write().insert_before(document, None)));
+        assert!(div.read().child_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_self_and_ancestors() {
+        let outer = new_node_ref(Element::new("div"));
+        let inner = new_node_ref(Element::new("section"));
+        let leaf = new_node_ref(Element::new("p"));
+        outer.write().append_child(inner.clone()).unwrap();
+        inner.write().append_child(leaf.clone()).unwrap();
+
+        assert!(hierarchy_error(leaf.write().append_child(outer.clone())));
+        let inner_again = leaf.write().insert_before(inner.clone(), None);
+        assert!(hierarchy_error(inner_again));
+        assert!(hierarchy_error(inner.write().append_child(inner.clone())));
+        assert!(leaf.read().child_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_attr_child() {
+        let div = new_node_ref(Element::new("div"));
+        let attr = new_node_ref(Attr::new("id", "main"));
+
+        assert!(hierarchy_error(div.write().append_child(attr)));
+    }
+
+    #[test]
+    fn test_rejects_doctype_outside_single_document_slot() {
+        let div = new_node_ref(Element::new("div"));
+        let doctype = new_node_ref(DocumentType::new_simple("html"));
+        assert!(hierarchy_error(div.write().append_child(doctype)));
+
+        let document = new_node_ref(Document::new());
+        let text = new_node_ref(Text::new("stray"));
+        assert!(hierarchy_error(document.write().append_child(text)));
+
+        let doctype = new_node_ref(DocumentType::new_simple("html"));
+        document.write().append_child(doctype).unwrap();
+        let second = new_node_ref(DocumentType::new_simple("html"));
+        assert!(hierarchy_error(document.write().append_child(second)));
+        assert_eq!(document.read().child_nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_reference_child_of_other_parent() {
+        let div = new_node_ref(Element::new("div"));
+        let elsewhere = new_node_ref(Element::new("span"));
+        let result = div
+            .write()
+            .insert_before(new_node_ref(Text::new("x")), Some(elsewhere));
+
+        assert!(matches!(result, Err(DomException::NotFoundError)));
+    }
+
+    #[test]
+    fn test_accepts_valid_insertions() {
+        let document = new_node_ref(Document::new());
+        let html = new_node_ref(Element::new("html"));
+        let doctype = new_node_ref(DocumentType::new_simple("html"));
+        document.write().append_child(html.clone()).unwrap();
+        document
+            .write()
+            .insert_before(doctype.clone(), Some(html.clone()))
+            .unwrap();
+
+        let body = new_node_ref(Element::new("body"));
+        html.write().append_child(body.clone()).unwrap();
+        body.write()
+            .append_child(new_node_ref(Text::new("hello")))
+            .unwrap();
+
+        let children = document.read().child_nodes();
+        assert!(Arc::ptr_eq(&children[0], &doctype));
+        assert!(Arc::ptr_eq(&children[1], &html));
+        assert_eq!(body.read().text_content().as_deref(), Some("hello"));
+    }
+}
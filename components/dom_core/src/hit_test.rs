@@ -0,0 +1,18 @@
+//! Hit-testing hook for `Document.elementFromPoint`
+//!
+//! The DOM has no geometry of its own; which element lies under a point is
+//! decided by layout. A [`HitTestProvider`] installed on the document with
+//! [`Document::set_hit_test_provider`](crate::Document::set_hit_test_provider)
+//! answers those queries for pointer event routing, tooltips and the like.
+
+use crate::element::ElementRef;
+use std::fmt;
+
+/// Answers which elements are under a viewport point, typically the layout engine
+pub trait HitTestProvider: fmt::Debug + Send + Sync {
+    /// Elements at viewport coordinates (`x`, `y`), topmost first
+    ///
+    /// Returns an empty list if the point is outside the viewport or nothing
+    /// is rendered there.
+    fn elements_at(&self, x: f64, y: f64) -> Vec<ElementRef>;
+}
@@ -0,0 +1,137 @@
+//! Per-tree id index
+//!
+//! Element ids are scoped to their node tree, so each tree root (a
+//! [`Document`](crate::Document) or a shadow root) owns its own [`IdIndex`].
+//! Elements are bound to the index of the tree they are inserted into and
+//! unbound when removed; while bound they keep their entry up to date as
+//! their `id` attribute changes.
+
+use crate::element::{Element, ElementRef};
+use crate::node::NodeRef;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::sync::{Arc, Weak};
+
+/// Thread-safe reference to an IdIndex
+pub type IdIndexRef = Arc<RwLock<IdIndex>>;
+
+/// Map from id to the elements of one node tree carrying that id
+#[derive(Debug, Clone, Default)]
+pub struct IdIndex {
+    entries: IndexMap<String, Vec<Weak<RwLock<Element>>>>,
+}
+
+impl IdIndex {
+    /// Creates an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty index wrapped for sharing with elements
+    pub fn new_ref() -> IdIndexRef {
+        Arc::new(RwLock::new(Self::new()))
+    }
+
+    /// Adds `element` under `id`
+    pub fn insert(&mut self, id: &str, element: &ElementRef) {
+        let elements = self.entries.entry(id.to_string()).or_default();
+        elements.retain(|e| e.strong_count() > 0);
+        if !elements.iter().any(|e| e.as_ptr() == Arc::as_ptr(element)) {
+            elements.push(Arc::downgrade(element));
+        }
+    }
+
+    /// Removes `element` from under `id`
+    pub fn remove(&mut self, id: &str, element: &ElementRef) {
+        if let Some(elements) = self.entries.get_mut(id) {
+            elements.retain(|e| e.strong_count() > 0 && e.as_ptr() != Arc::as_ptr(element));
+            if elements.is_empty() {
+                self.entries.shift_remove(id);
+            }
+        }
+    }
+
+    /// Gets the first live element registered under `id`
+    pub fn get(&self, id: &str) -> Option<ElementRef> {
+        self.entries
+            .get(id)
+            .and_then(|elements| elements.iter().find_map(Weak::upgrade))
+    }
+
+    /// Checks whether any live element is registered under `id`
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Binds every element of the subtree rooted at `root` to `index`
+    ///
+    /// Tree roots call this when a subtree is inserted; `None` unbinds a
+    /// subtree that was removed, so only connected elements are found by id.
+    pub fn bind_subtree(root: &NodeRef, index: Option<&IdIndexRef>) {
+        // Subtrees are bound as a whole, so a root that is already bound to
+        // `index` needs no walk
+        let bound = root
+            .read()
+            .as_any()
+            .downcast_ref::<Element>()
+            .is_some_and(|element| element.is_bound_to_id_index(index));
+        if bound {
+            return;
+        }
+        let mut stack = vec![root.clone()];
+        while let Some(node) = stack.pop() {
+            let mut node = node.write();
+            if let Some(element) = node.as_any_mut().downcast_mut::<Element>() {
+                element.set_id_index(index);
+            }
+            stack.extend(node.child_nodes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element_with_id(index: &IdIndexRef, id: &str) -> ElementRef {
+        let element = Arc::new(RwLock::new(Element::new("div")));
+        element.write().set_self_ref(Arc::downgrade(&element));
+        element.write().set_id_index(Some(index));
+        element.write().set_attribute("id", id).unwrap();
+        element
+    }
+
+    #[test]
+    fn test_index_follows_id_changes() {
+        let index = IdIndex::new_ref();
+        let element = element_with_id(&index, "a");
+        assert!(Arc::ptr_eq(&index.read().get("a").unwrap(), &element));
+
+        element.write().set_attribute("id", "b").unwrap();
+        assert!(!index.read().contains("a"));
+        assert!(Arc::ptr_eq(&index.read().get("b").unwrap(), &element));
+
+        element.write().remove_attribute("id").unwrap();
+        assert!(!index.read().contains("b"));
+    }
+
+    #[test]
+    fn test_rebinding_moves_entry() {
+        let first = IdIndex::new_ref();
+        let second = IdIndex::new_ref();
+        let element = element_with_id(&first, "x");
+
+        element.write().set_id_index(Some(&second));
+        assert!(!first.read().contains("x"));
+        assert!(Arc::ptr_eq(&second.read().get("x").unwrap(), &element));
+    }
+
+    #[test]
+    fn test_dropped_elements_are_skipped() {
+        let index = IdIndex::new_ref();
+        drop(element_with_id(&index, "dup"));
+        let kept = element_with_id(&index, "dup");
+
+        assert!(Arc::ptr_eq(&index.read().get("dup").unwrap(), &kept));
+    }
+}
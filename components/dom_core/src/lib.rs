@@ -68,7 +68,7 @@
 //!
 //! # Reference Types
 //!
-//! - [`NodeRef`] - `Arc<RwLock<Box<dyn Node>>>` for generic nodes
+//! - [`NodeRef`] - `Arc<RwLock<dyn Node>>` for generic nodes
 //! - [`ElementRef`] - `Arc<RwLock<Element>>` for elements
 //! - [`DocumentRef`] - `Arc<RwLock<Document>>` for documents
 //! - [`WeakNodeRef`] - Non-owning reference to prevent cycles
@@ -84,35 +84,63 @@
 #![warn(missing_docs)]
 #![allow(dead_code)] // Allow during development
 
+pub mod animation_provider;
 pub mod attr;
 pub mod cdata_section;
+pub mod character_data;
 pub mod comment;
+pub mod dataset;
 pub mod document;
 pub mod document_fragment;
 pub mod document_type;
 pub mod dom_implementation;
 pub mod element;
 pub mod event;
+pub mod fragment_parser;
+pub mod frozen;
+pub mod hierarchy;
+pub mod hit_test;
+pub mod id_index;
 pub mod namespaces;
 pub mod node;
 pub mod processing_instruction;
 pub mod range;
+pub mod range_geometry;
+pub mod ready_state;
+pub mod serialize;
+pub mod style_invalidation;
 pub mod text;
+pub mod tree_depth;
+pub mod tree_generation;
 pub mod tree_order;
 
 // Re-exports
+pub use animation_provider::{
+    AnimationHandle, AnimationPlayState, AnimationProvider, AnimationType,
+};
 pub use attr::{Attr, AttrRef};
 pub use cdata_section::{CDATASection, CDATASectionRef};
-pub use comment::Comment;
+pub use character_data::{CharacterData, CharacterDataChange, CharacterDataObserver};
+pub use comment::{Comment, CommentRef};
+pub use dataset::{Dataset, DatasetMut};
 pub use document::{Document, DocumentRef};
 pub use document_fragment::DocumentFragment;
 pub use document_type::{DocumentType, DocumentTypeRef};
 pub use dom_implementation::DOMImplementation;
-pub use element::{Element, ElementRef};
+pub use element::{downcast_element, Element, ElementRef, NodeOrString};
 pub use event::{Event, EventInit, EventPhase, EventRef};
+pub use frozen::FrozenNode;
+pub use hierarchy::validate_pre_insertion;
+pub use hit_test::HitTestProvider;
+pub use id_index::{IdIndex, IdIndexRef};
 pub use namespaces::*;
-pub use node::{Node, NodeData, NodeRef, WeakNodeRef};
+pub use node::{new_node_ref, Node, NodeData, NodeRef, WeakNodeRef};
 pub use processing_instruction::{ProcessingInstruction, ProcessingInstructionRef};
-pub use range::{Range, RangeRef};
-pub use text::Text;
+pub use range::{LiveRanges, Range, RangeRef};
+pub use range_geometry::RangeGeometryProvider;
+pub use ready_state::{DocumentReadyState, ReadyStateObserver};
+pub use serialize::{AttributeQuote, SerializeOptions, ShadowRootContent};
+pub use style_invalidation::{ClassInvalidation, StyleInvalidationSink};
+pub use text::{Text, TextRef};
+pub use tree_generation::{TreeChange, TreeChangeObserver, TreeGenerations, TreeGenerationsRef};
 pub use tree_order::*;
@@ -2,6 +2,8 @@
 //!
 //! Provides standard namespace URIs and validation utilities for XML, HTML, SVG, MathML, etc.
 
+use dom_types::DomException;
+
 /// XML namespace URI
 pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
 
@@ -42,49 +44,28 @@ pub fn is_valid_namespace_uri(uri: &str) -> bool {
 ///
 /// # Returns
 /// * `Ok((prefix, local_name))` - If valid, returns the prefix (if any) and local name
-/// * `Err(message)` - If invalid, returns error message
-pub fn validate_qualified_name(qualified_name: &str) -> Result<(Option<String>, String), String> {
-    if qualified_name.is_empty() {
-        return Err("Qualified name cannot be empty".to_string());
-    }
-
-    // Check for multiple colons (invalid)
-    let colon_count = qualified_name.matches(':').count();
-    if colon_count > 1 {
-        return Err("Qualified name cannot contain multiple colons".to_string());
-    }
-
+/// * `Err(NamespaceError)` - If a colon is misplaced: more than one, or an
+///   empty prefix or local name around it
+/// * `Err(InvalidCharacterError)` - If the prefix or local name is not a valid NCName
+pub fn validate_qualified_name(
+    qualified_name: &str,
+) -> Result<(Option<String>, String), DomException> {
     // Split by colon if present
-    if colon_count == 1 {
-        let parts: Vec<&str> = qualified_name.split(':').collect();
-        let prefix = parts[0];
-        let local_name = parts[1];
-
-        // Validate prefix
-        if prefix.is_empty() {
-            return Err("Prefix cannot be empty".to_string());
-        }
-        if !is_valid_ncname(prefix) {
-            return Err(format!("Invalid prefix: {}", prefix));
-        }
-
-        // Validate local name
-        if local_name.is_empty() {
-            return Err("Local name cannot be empty".to_string());
-        }
-        if !is_valid_ncname(local_name) {
-            return Err(format!("Invalid local name: {}", local_name));
-        }
-
-        Ok((Some(prefix.to_string()), local_name.to_string()))
-    } else {
-        // No prefix, just validate local name
-        if !is_valid_ncname(qualified_name) {
-            return Err(format!("Invalid name: {}", qualified_name));
+    let (prefix, local_name) = match qualified_name.split_once(':') {
+        Some((prefix, local_name)) => {
+            if prefix.is_empty() || local_name.is_empty() || local_name.contains(':') {
+                return Err(DomException::NamespaceError);
+            }
+            (Some(prefix), local_name)
         }
+        None => (None, qualified_name),
+    };
 
-        Ok((None, qualified_name.to_string()))
+    if prefix.is_some_and(|prefix| !is_valid_ncname(prefix)) || !is_valid_ncname(local_name) {
+        return Err(DomException::InvalidCharacterError);
     }
+
+    Ok((prefix.map(str::to_string), local_name.to_string()))
 }
 
 /// Validate an NCName (non-colonized name) according to XML specification
@@ -115,45 +96,43 @@ fn is_valid_ncname(name: &str) -> bool {
 
 /// Validate namespace and qualified name combination
 ///
-/// Checks for invalid combinations like "xml" prefix without XML namespace
+/// Used by `createElementNS`: checks the namespace, the qualified name and
+/// their combination, such as an "xml" prefix without the XML namespace, and
+/// splits the name into its prefix and local name.
+///
+/// # Errors
+/// * `NamespaceError` - empty namespace, misplaced colon, or a prefix that
+///   doesn't agree with the namespace
+/// * `InvalidCharacterError` - the prefix or local name isn't a valid NCName
 pub fn validate_namespace_and_qname(
     namespace_uri: Option<&str>,
     qualified_name: &str,
-) -> Result<(), String> {
-    let (prefix, _local_name) = validate_qualified_name(qualified_name)?;
-
-    // Check namespace-prefix combinations
-    if let Some(ref prefix_str) = prefix {
-        match prefix_str.as_str() {
-            "xml" => {
-                // xml prefix must have XML namespace
-                if namespace_uri != Some(XML_NAMESPACE) {
-                    return Err(
-                        "xml prefix must be used with XML namespace".to_string()
-                    );
-                }
-            }
-            "xmlns" => {
-                // xmlns prefix is reserved and cannot be used
-                return Err("xmlns prefix is reserved and cannot be used".to_string());
-            }
-            _ => {}
-        }
+) -> Result<(Option<String>, String), DomException> {
+    // The empty string is not a namespace
+    if namespace_uri == Some("") {
+        return Err(DomException::NamespaceError);
     }
 
-    // If namespace is XML namespace, prefix must be "xml"
-    if namespace_uri == Some(XML_NAMESPACE) {
-        if prefix.as_deref() != Some("xml") {
-            return Err("XML namespace requires xml prefix".to_string());
-        }
-    }
+    let (prefix, local_name) = validate_qualified_name(qualified_name)?;
 
-    // If namespace is XMLNS namespace, this is invalid
-    if namespace_uri == Some(XMLNS_NAMESPACE) {
-        return Err("XMLNS namespace cannot be used for elements".to_string());
+    // Check namespace-prefix combinations
+    let prefix_is_consistent = match (prefix.as_deref(), namespace_uri) {
+        // A prefix needs a namespace
+        (Some(_), None) => false,
+        // xml prefix must have XML namespace, and XML namespace requires it
+        (Some("xml"), namespace) => namespace == Some(XML_NAMESPACE),
+        (_, Some(XML_NAMESPACE)) => false,
+        // xmlns names go with the XMLNS namespace, and only they do
+        (prefix, namespace) => {
+            let is_xmlns = prefix == Some("xmlns") || (prefix.is_none() && local_name == "xmlns");
+            is_xmlns == (namespace == Some(XMLNS_NAMESPACE))
+        }
+    };
+    if !prefix_is_consistent {
+        return Err(DomException::NamespaceError);
     }
 
-    Ok(())
+    Ok((prefix, local_name))
 }
 
 #[cfg(test)]
@@ -261,7 +240,7 @@ mod tests {
 
     #[test]
     fn test_validate_namespace_and_qname_xmlns_prefix() {
-        // xmlns prefix is always invalid
+        // xmlns prefix is reserved for the XMLNS namespace
         let result = validate_namespace_and_qname(Some(HTML_NAMESPACE), "xmlns:foo");
         assert!(result.is_err());
     }
@@ -298,10 +277,14 @@ mod tests {
     fn test_validate_namespace_and_qname_no_namespace() {
         // No namespace with simple name - valid
         let result = validate_namespace_and_qname(None, "div");
-        assert!(result.is_ok());
+        assert_eq!(result, Ok((None, "div".to_string())));
 
-        // No namespace with prefixed name - also valid (HTML can have prefixes)
+        // No namespace with prefixed name - a prefix needs a namespace
         let result = validate_namespace_and_qname(None, "custom:element");
-        assert!(result.is_ok());
+        assert_eq!(result, Err(DomException::NamespaceError));
+
+        // The empty string is not a namespace
+        let result = validate_namespace_and_qname(Some(""), "div");
+        assert_eq!(result, Err(DomException::NamespaceError));
     }
 }
@@ -1,18 +1,25 @@
 //! Core Node trait and base implementation
 
+use crate::character_data::CharacterData;
+use crate::element::ElementRef;
+use crate::frozen::FrozenNode;
+use crate::range::LiveRanges;
+use crate::text::Text;
+use crate::tree_generation::{TreeChange, TreeGenerations, TreeGenerationsRef};
 use crate::tree_order::compare_document_position;
 // Re-export DocumentPosition for use by callers
 pub use crate::tree_order::DocumentPosition;
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
+use std::any::Any;
 use std::fmt;
 use std::sync::{Arc, Weak};
 
 /// Thread-safe reference to a DOM node
-pub type NodeRef = Arc<RwLock<Box<dyn Node>>>;
+pub type NodeRef = Arc<RwLock<dyn Node>>;
 
 /// Weak reference to a DOM node (non-owning)
-pub type WeakNodeRef = Weak<RwLock<Box<dyn Node>>>;
+pub type WeakNodeRef = Weak<RwLock<dyn Node>>;
 
 /// Core node trait that all DOM nodes must implement
 pub trait Node: Send + Sync + std::fmt::Debug {
@@ -106,8 +113,32 @@ pub trait Node: Send + Sync + std::fmt::Debug {
     fn clone_node(&self, deep: bool) -> NodeRef;
 
     /// Normalizes the node tree (combines adjacent text nodes)
+    ///
+    /// Walks the subtree with an explicit stack, so very deep trees cannot
+    /// overflow the call stack. The document's live ranges are moved off the
+    /// text nodes that are merged away.
     fn normalize(&mut self) {
-        // Default implementation
+        let generations = self.generations();
+        let ranges = generations.as_deref().map(TreeGenerations::live_ranges);
+        let (mut changed, mut stack) = self.node_data_mut().normalize_children(ranges);
+        while let Some(node) = stack.pop() {
+            let (merged, children) = node.write().node_data_mut().normalize_children(ranges);
+            changed |= merged;
+            stack.extend(children);
+        }
+        if changed {
+            if let Some(generations) = generations {
+                generations.bump(TreeChange::Structure);
+            }
+        }
+    }
+
+    /// Deep-copies this node and its descendants into an immutable snapshot
+    ///
+    /// The snapshot is detached from the tree: later mutations don't affect
+    /// it, and it can be read from any thread without locking.
+    fn snapshot(&self) -> FrozenNode {
+        FrozenNode::capture(self)
     }
 
     /// Checks if two nodes are equal
@@ -142,14 +173,172 @@ pub trait Node: Send + Sync + std::fmt::Debug {
         compare_document_position(self_ref, other)
     }
 
+    /// Renders this node's subtree as compact indented text for diagnostics
+    ///
+    /// One line per node, two spaces per level. Elements appear as
+    /// `div#main.container` followed by their other attributes; text and
+    /// comment data is truncated.
+    fn debug_tree(&self) -> String {
+        let mut out = debug_label(self.as_any(), self.node_type(), self.node_name());
+        out.push('\n');
+        for child in self.child_nodes() {
+            write_debug_tree(&*child.read(), 1, &mut out);
+        }
+        out
+    }
+
+    /// Builds an XPath-like locator for this node for diagnostics, such as
+    /// `/html[1]/body[1]/div[2]/text()[1]`
+    ///
+    /// `self_ref` must be the reference wrapping this node. Each step counts
+    /// from 1 among the preceding siblings of the same kind: elements by
+    /// tag name, text and comments as `text()` and `comment()`. The path
+    /// starts at the topmost ancestor reachable through `parent_node`.
+    fn node_path(&self, self_ref: &NodeRef) -> String {
+        let mut steps = Vec::new();
+        let mut step = path_step(self.as_any(), self.node_type(), self.node_name());
+        let mut current = self_ref.clone();
+        let mut parent = self.parent_node();
+        while let Some(node) = parent {
+            let siblings = node.read().child_nodes();
+            let position = siblings
+                .iter()
+                .take_while(|sibling| !Arc::ptr_eq(sibling, &current))
+                .filter(|sibling| {
+                    let sibling = sibling.read();
+                    path_step(sibling.as_any(), sibling.node_type(), sibling.node_name()) == step
+                })
+                .count();
+            steps.push(format!("{}[{}]", step, position + 1));
+
+            let guard = node.read();
+            step = path_step(guard.as_any(), guard.node_type(), guard.node_name());
+            parent = guard.parent_node();
+            drop(guard);
+            current = node;
+        }
+        steps.push(format!("{}[1]", step));
+        steps.reverse();
+        format!("/{}", steps.join("/"))
+    }
+
     /// Access to internal node data
     fn node_data(&self) -> &NodeData;
 
     /// Mutable access to internal node data
     fn node_data_mut(&mut self) -> &mut NodeData;
 
+    /// Mutation counters this node reports its tree changes to, if bound
+    ///
+    /// Documents, and the elements and fragments bound to them, share the
+    /// document's counters.
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        None
+    }
+
     /// Downcast to concrete type (for type checking)
     fn as_any(&self) -> &dyn std::any::Any;
+
+    /// Mutable downcast to concrete type
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+}
+
+/// Longest text or attribute value shown by [`Node::debug_tree`]
+const DEBUG_TREE_TEXT_LIMIT: usize = 24;
+
+fn write_debug_tree(node: &dyn Node, depth: usize, out: &mut String) {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(&debug_label(
+        node.as_any(),
+        node.node_type(),
+        node.node_name(),
+    ));
+    out.push('\n');
+    for child in node.child_nodes() {
+        write_debug_tree(&*child.read(), depth + 1, out);
+    }
+}
+
+fn debug_label(node: &dyn std::any::Any, node_type: NodeType, node_name: &str) -> String {
+    if let Some(element) = node.downcast_ref::<crate::element::Element>() {
+        let mut label = element.tag_name().to_ascii_lowercase();
+        if let Some(id) = element.id() {
+            label.push('#');
+            label.push_str(id);
+        }
+        for class in element.class_list() {
+            label.push('.');
+            label.push_str(class);
+        }
+        for (name, value) in element.attributes() {
+            if name != "id" && name != "class" {
+                label.push_str(&format!(" {}=\"{}\"", name, truncate_for_debug(value)));
+            }
+        }
+        return label;
+    }
+
+    let data = match node_type {
+        NodeType::Text => node.downcast_ref::<crate::text::Text>().map(|t| t.data()),
+        NodeType::Comment => node
+            .downcast_ref::<crate::comment::Comment>()
+            .map(|c| c.data()),
+        _ => None,
+    };
+    match (node_type, data) {
+        (NodeType::Text, Some(data)) => format!("\"{}\"", truncate_for_debug(data)),
+        (NodeType::Comment, Some(data)) => format!("<!--{}-->", truncate_for_debug(data)),
+        _ => node_name.to_string(),
+    }
+}
+
+/// Names a node in a [`Node::node_path`] step
+fn path_step(node: &dyn std::any::Any, node_type: NodeType, node_name: &str) -> String {
+    if let Some(element) = node.downcast_ref::<crate::element::Element>() {
+        return element.tag_name().to_ascii_lowercase();
+    }
+    match node_type {
+        NodeType::Text | NodeType::CDataSection => "text()".to_string(),
+        NodeType::Comment => "comment()".to_string(),
+        NodeType::ProcessingInstruction => "processing-instruction()".to_string(),
+        _ => node_name.to_string(),
+    }
+}
+
+fn truncate_for_debug(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= DEBUG_TREE_TEXT_LIMIT {
+        collapsed
+    } else {
+        let truncated: String = collapsed.chars().take(DEBUG_TREE_TEXT_LIMIT).collect();
+        format!("{}…", truncated.trim_end())
+    }
+}
+
+/// Wraps `node` in a new `NodeRef` whose self-reference points at itself
+///
+/// Elements also get their typed self-reference, so
+/// [`downcast_element`](crate::downcast_element) finds them.
+pub fn new_node_ref<N: Node + 'static>(node: N) -> NodeRef {
+    let node_ref = Arc::new(RwLock::new(node));
+    if let Some(element) = (&node_ref as &dyn Any).downcast_ref::<ElementRef>() {
+        element.write().set_self_ref(Arc::downgrade(element));
+    } else {
+        let weak: Weak<RwLock<N>> = Arc::downgrade(&node_ref);
+        node_ref.write().node_data_mut().set_self_node_ref(weak);
+    }
+    node_ref
+}
+
+/// Wraps a freshly cloned node, pointing its children's parent at the new reference
+pub(crate) fn wrap_clone(node: impl Node + 'static) -> NodeRef {
+    let node_ref = new_node_ref(node);
+    let weak = Arc::downgrade(&node_ref);
+    let children = node_ref.read().child_nodes();
+    for child in children {
+        child.write().node_data_mut().set_parent(Some(weak.clone()));
+    }
+    node_ref
 }
 
 /// Common data shared by all node types
@@ -170,6 +359,12 @@ pub struct NodeData {
     /// Self-reference to the NodeRef that wraps this node (set after construction)
     /// This is needed so that append_child can set the correct parent reference
     pub self_node_ref: Option<WeakNodeRef>,
+
+    /// Depth in the tree, 1 for a root, kept up to date by insertions and
+    /// removals in trees with a depth limit
+    ///
+    /// A clone keeps the depth of its original until it is inserted.
+    pub depth: usize,
 }
 
 impl NodeData {
@@ -181,11 +376,12 @@ impl NodeData {
             parent: None,
             children: Vec::new(),
             self_node_ref: None,
+            depth: 1,
         }
     }
 
     /// Sets the self-reference to the NodeRef that wraps this node
-    /// This MUST be called after wrapping the node in Arc<RwLock<Box<dyn Node>>>
+    /// This MUST be called after wrapping the node in Arc<RwLock<dyn Node>>
     pub fn set_self_node_ref(&mut self, self_ref: WeakNodeRef) {
         self.self_node_ref = Some(self_ref);
     }
@@ -200,6 +396,74 @@ impl NodeData {
         self.parent = parent;
     }
 
+    /// Forgets the parent and self-reference, as for a freshly cloned node
+    pub fn clear_links(&mut self) {
+        self.parent = None;
+        self.self_node_ref = None;
+    }
+
+    /// Removes empty text children and merges runs of adjacent text
+    /// children into the first of the run
+    ///
+    /// Removed nodes are detached from this node, and the boundary points of
+    /// `ranges` in or next to them move as the DOM's normalize steps
+    /// require. Returns whether any child was removed, along with the
+    /// remaining children that are not text, whose own children still need
+    /// normalizing.
+    pub(crate) fn normalize_children(
+        &mut self,
+        ranges: Option<&LiveRanges>,
+    ) -> (bool, Vec<NodeRef>) {
+        let parent = self.get_self_node_ref();
+        let mut changed = false;
+        let mut containers = Vec::new();
+        for child in std::mem::take(&mut self.children) {
+            // The index the child has while the earlier ones are removed
+            let index = self.children.len();
+            let data = {
+                let guard = child.read();
+                let text = guard.as_any().downcast_ref::<Text>();
+                text.map(|text| text.data().to_string())
+            };
+            let Some(data) = data else {
+                containers.push(child.clone());
+                self.children.push(child);
+                continue;
+            };
+            match self.append_to_last_text(&data) {
+                Some((last, length)) => {
+                    if let Some(ranges) = ranges {
+                        ranges.text_merged(parent.as_ref(), index, &child, &last, length);
+                    }
+                }
+                None if data.is_empty() => {}
+                None => {
+                    self.children.push(child);
+                    continue;
+                }
+            }
+            child.write().node_data_mut().set_parent(None);
+            if let Some(ranges) = ranges {
+                ranges.child_removed(parent.as_ref(), index, &child);
+            }
+            changed = true;
+        }
+        (changed, containers)
+    }
+
+    /// Appends `data` to the last child if it's a text node, returning that
+    /// child and its length in UTF-16 code units before the append
+    fn append_to_last_text(&self, data: &str) -> Option<(NodeRef, usize)> {
+        let last = self.children.last()?;
+        let mut guard = last.write();
+        let text = guard.as_any_mut().downcast_mut::<Text>()?;
+        let length = text.len();
+        if !data.is_empty() {
+            text.append_data(data);
+        }
+        Some((last.clone(), length))
+    }
+
     /// Gets the parent node
     pub fn get_parent(&self) -> Option<NodeRef> {
         self.parent.as_ref().and_then(|weak| weak.upgrade())
@@ -210,18 +474,20 @@ impl NodeData {
         self.children.push(child);
     }
 
+    /// Gets the position of `child` among the children
+    ///
+    /// Nodes are identified by their `NodeRef` allocation, never by content,
+    /// so a clone of a child is not found. No child lock is taken.
+    pub fn child_index(&self, child: &NodeRef) -> Option<usize> {
+        self.children.iter().position(|c| Arc::ptr_eq(c, child))
+    }
+
     /// Removes a child node
+    ///
+    /// Returns `NotFoundError` if `child` is not one of the children.
     pub fn remove_child(&mut self, child: &NodeRef) -> Result<NodeRef, DomException> {
-        let child_ptr = &**child.read() as *const dyn Node;
-
-        if let Some(pos) = self.children.iter().position(|c| {
-            let c_ptr = &**c.read() as *const dyn Node;
-            c_ptr == child_ptr
-        }) {
-            Ok(self.children.remove(pos))
-        } else {
-            Err(DomException::NotFoundError)
-        }
+        let pos = self.child_index(child).ok_or(DomException::NotFoundError)?;
+        Ok(self.children.remove(pos))
     }
 
     /// Inserts a child before a reference child
@@ -231,17 +497,11 @@ impl NodeData {
         ref_child: Option<&NodeRef>,
     ) -> Result<(), DomException> {
         if let Some(ref_child) = ref_child {
-            let ref_ptr = &**ref_child.read() as *const dyn Node;
-
-            if let Some(pos) = self.children.iter().position(|c| {
-                let c_ptr = &**c.read() as *const dyn Node;
-                c_ptr == ref_ptr
-            }) {
-                self.children.insert(pos, new_child);
-                Ok(())
-            } else {
-                Err(DomException::NotFoundError)
-            }
+            let pos = self
+                .child_index(ref_child)
+                .ok_or(DomException::NotFoundError)?;
+            self.children.insert(pos, new_child);
+            Ok(())
         } else {
             // No reference child means append
             self.children.push(new_child);
@@ -250,15 +510,17 @@ impl NodeData {
     }
 
     /// Checks if this node contains another node
-    pub fn contains(&self, other_ptr: *const dyn Node) -> bool {
-        for child in &self.children {
-            let child_ptr = &**child.read() as *const dyn Node;
-            if child_ptr == other_ptr {
-                return true;
-            }
-            if child.read().node_data().contains(other_ptr) {
+    ///
+    /// Walks the descendants with an explicit stack, so arbitrarily deep
+    /// trees are safe, and stops at the first match.
+    pub fn contains(&self, other_ptr: *const (dyn Node + '_)) -> bool {
+        let mut stack: Vec<NodeRef> = self.children.clone();
+        while let Some(node) = stack.pop() {
+            let node = node.read();
+            if std::ptr::addr_eq(&*node as *const dyn Node, other_ptr) {
                 return true;
             }
+            stack.extend(node.node_data().children.iter().cloned());
         }
         false
     }
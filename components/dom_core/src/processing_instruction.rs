@@ -3,6 +3,7 @@
 use crate::node::{Node, NodeData, NodeRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// ProcessingInstruction node implementation
@@ -109,6 +110,51 @@ impl ProcessingInstruction {
     pub fn set_data(&mut self, data: impl Into<String>) {
         self.data = data.into();
     }
+
+    /// Parses the data as `name="value"` pseudo-attributes
+    ///
+    /// Values may use single or double quotes, with whitespace allowed around
+    /// `=`. Parsing stops at the first malformed pair, so invalid data yields
+    /// the pairs before it (possibly none).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_core::ProcessingInstruction;
+    ///
+    /// let pi = ProcessingInstruction::new("xml-stylesheet", "type='text/css' href=\"s.css\"");
+    /// let attributes = pi.pseudo_attributes();
+    /// assert_eq!(attributes["type"], "text/css");
+    /// assert_eq!(attributes["href"], "s.css");
+    /// ```
+    pub fn pseudo_attributes(&self) -> HashMap<String, String> {
+        let mut attributes = HashMap::new();
+        let mut rest = self.data.trim_start();
+
+        while !rest.is_empty() {
+            let name_end = rest
+                .find(|c: char| c == '=' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let (name, after_name) = rest.split_at(name_end);
+            let Some(after_eq) = after_name.trim_start().strip_prefix('=') else {
+                break;
+            };
+            let after_eq = after_eq.trim_start();
+            let Some(quote) = after_eq.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                break;
+            };
+            let Some((value, after_value)) = after_eq[1..].split_once(quote) else {
+                break;
+            };
+            if name.is_empty() {
+                break;
+            }
+            attributes.insert(name.to_string(), value.to_string());
+            rest = after_value.trim_start();
+        }
+
+        attributes
+    }
 }
 
 impl Node for ProcessingInstruction {
@@ -177,7 +223,7 @@ impl Node for ProcessingInstruction {
         // Clone this processing instruction
         // The `deep` parameter is ignored as ProcessingInstruction has no children
         let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(cloned))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -198,6 +244,10 @@ impl Node for ProcessingInstruction {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -225,7 +275,7 @@ mod tests {
     fn test_processing_instruction_no_children() {
         let mut pi = ProcessingInstruction::new("xml", "version='1.0'");
         let child = ProcessingInstruction::new("child", "data");
-        let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+        let child_ref = Arc::new(RwLock::new(child));
 
         let result = pi.append_child(child_ref);
         assert!(result.is_err());
@@ -234,6 +284,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pseudo_attributes_of_stylesheet() {
+        let pi = ProcessingInstruction::new("xml-stylesheet", "type=\"text/css\" href=\"s.css\"");
+        let attributes = pi.pseudo_attributes();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes["type"], "text/css");
+        assert_eq!(attributes["href"], "s.css");
+
+        let data = "  title = 'Dark \"mode\"'\thref='a b.css' ";
+        let pi = ProcessingInstruction::new("xml-stylesheet", data);
+        let attributes = pi.pseudo_attributes();
+        assert_eq!(attributes["title"], "Dark \"mode\"");
+        assert_eq!(attributes["href"], "a b.css");
+    }
+
+    #[test]
+    fn test_pseudo_attributes_of_malformed_data() {
+        let partial = |data: &str| {
+            let mut pairs: Vec<_> = ProcessingInstruction::new("x", data)
+                .pseudo_attributes()
+                .into_iter()
+                .collect();
+            pairs.sort();
+            pairs
+        };
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+        assert_eq!(partial("a='1' b"), [pair("a", "1")]);
+        assert_eq!(partial("a='1' b=2"), [pair("a", "1")]);
+        assert_eq!(partial("a='1' b=\"unterminated"), [pair("a", "1")]);
+        assert!(partial("echo 'hi';").is_empty());
+        assert!(partial("='x'").is_empty());
+        assert!(partial("").is_empty());
+    }
+
     #[test]
     fn test_xml_declaration() {
         let pi = ProcessingInstruction::new("xml", "version=\"1.0\" encoding=\"UTF-8\"");
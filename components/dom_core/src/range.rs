@@ -6,13 +6,15 @@
 use crate::node::NodeRef;
 use crate::text::Text;
 use dom_types::DomException;
-use parking_lot::RwLock;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use std::fmt;
+use std::sync::{Arc, Weak};
 
 /// A basic Range represents a fragment of a document
 ///
 /// This is a minimal implementation for Document.createRange().
-/// For full Range functionality, see the dom_advanced crate.
+/// For full Range functionality, see the dom_advanced crate. Offsets into
+/// character data count UTF-16 code units, as in `CharacterData`.
 #[derive(Debug, Clone)]
 pub struct Range {
     /// The node that contains the start of the range
@@ -50,7 +52,7 @@ impl Range {
         let container = document_node.unwrap_or_else(|| {
             // Create a dummy text node for initialization
             let text = Text::new("");
-            Arc::new(RwLock::new(Box::new(text) as Box<dyn crate::node::Node>))
+            Arc::new(RwLock::new(text))
         });
 
         Self {
@@ -147,6 +149,18 @@ impl Range {
         }
     }
 
+    /// Moves each boundary point for which `relocate` returns a new one
+    fn relocate(&mut self, relocate: impl Fn(&NodeRef, usize) -> Option<(NodeRef, usize)>) {
+        if let Some((node, offset)) = relocate(&self.start_container, self.start_offset) {
+            self.start_container = node;
+            self.start_offset = offset;
+        }
+        if let Some((node, offset)) = relocate(&self.end_container, self.end_offset) {
+            self.end_container = node;
+            self.end_offset = offset;
+        }
+    }
+
     /// Get the length of a node for boundary validation
     fn get_node_length(&self, node: &NodeRef) -> usize {
         use dom_types::NodeType;
@@ -154,13 +168,121 @@ impl Range {
         let node_guard = node.read();
         match node_guard.node_type() {
             NodeType::Text | NodeType::Comment | NodeType::ProcessingInstruction => {
-                node_guard.text_content().map(|s| s.len()).unwrap_or(0)
+                let data = node_guard.text_content().unwrap_or_default();
+                data.encode_utf16().count()
             }
             _ => node_guard.child_nodes().len(),
         }
     }
 }
 
+/// The live ranges of one document
+///
+/// [`Document::create_live_range`](crate::Document::create_live_range)
+/// registers the ranges it hands out with its [`TreeGenerations`](crate::TreeGenerations),
+/// so that [`Node::normalize`](crate::node::Node::normalize) can move their
+/// boundary points off the text nodes it merges away. Ranges are held
+/// weakly and forgotten once dropped.
+#[derive(Default)]
+pub struct LiveRanges {
+    ranges: Mutex<Vec<Weak<RwLock<Range>>>>,
+}
+
+impl LiveRanges {
+    /// Registers `range` to follow the document's mutations
+    pub fn register(&self, range: &RangeRef) {
+        let mut ranges = self.ranges.lock();
+        ranges.retain(|range| range.strong_count() > 0);
+        ranges.push(Arc::downgrade(range));
+    }
+
+    /// Moves the boundary points in `merged`, or before it in `parent`, into
+    /// `into`, whose first `length` UTF-16 code units precede the merged data
+    ///
+    /// `merged` is the child of `parent` at `index`.
+    pub(crate) fn text_merged(
+        &self,
+        parent: Option<&NodeRef>,
+        index: usize,
+        merged: &NodeRef,
+        into: &NodeRef,
+        length: usize,
+    ) {
+        self.relocate(|node, offset| {
+            if same_node(node, merged) {
+                Some((into.clone(), offset + length))
+            } else if parent.is_some_and(|parent| same_node(node, parent)) && offset == index {
+                Some((into.clone(), length))
+            } else {
+                None
+            }
+        });
+    }
+
+    /// Moves the boundary points past `offset` in `split`, the child of
+    /// `parent` at `index`, into `new_node`, the child inserted after it
+    ///
+    /// Boundary points in `parent` after `split` move past `new_node`.
+    pub(crate) fn text_split(
+        &self,
+        parent: &NodeRef,
+        index: usize,
+        split: &NodeRef,
+        new_node: &NodeRef,
+        offset: usize,
+    ) {
+        self.relocate(|node, node_offset| {
+            if same_node(node, split) && node_offset > offset {
+                Some((new_node.clone(), node_offset - offset))
+            } else if same_node(node, parent) && node_offset > index {
+                Some((parent.clone(), node_offset + 1))
+            } else {
+                None
+            }
+        });
+    }
+
+    /// Moves the boundary points in `removed`, or after it in `parent`, as
+    /// the removal of the child of `parent` at `index` requires
+    pub(crate) fn child_removed(&self, parent: Option<&NodeRef>, index: usize, removed: &NodeRef) {
+        let Some(parent) = parent else {
+            return;
+        };
+        self.relocate(|node, offset| {
+            if same_node(node, removed) {
+                Some((parent.clone(), index))
+            } else if same_node(node, parent) && offset > index {
+                Some((parent.clone(), offset - 1))
+            } else {
+                None
+            }
+        });
+    }
+
+    fn relocate(&self, relocate: impl Fn(&NodeRef, usize) -> Option<(NodeRef, usize)>) {
+        let ranges: Vec<RangeRef> = {
+            let mut ranges = self.ranges.lock();
+            ranges.retain(|range| range.strong_count() > 0);
+            ranges.iter().filter_map(Weak::upgrade).collect()
+        };
+        for range in ranges {
+            range.write().relocate(&relocate);
+        }
+    }
+}
+
+impl fmt::Debug for LiveRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LiveRanges")
+            .field("ranges", &self.ranges.lock().len())
+            .finish()
+    }
+}
+
+fn same_node(a: &NodeRef, b: &NodeRef) -> bool {
+    std::ptr::addr_eq(Arc::as_ptr(a), Arc::as_ptr(b))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,7 +298,7 @@ mod tests {
     #[test]
     fn test_range_collapse() {
         let text = Text::new("Hello World");
-        let node: NodeRef = Arc::new(RwLock::new(Box::new(text) as Box<dyn crate::node::Node>));
+        let node: NodeRef = Arc::new(RwLock::new(text));
 
         let mut range = Range::new(Some(node.clone()));
         range.set_start(node.clone(), 0).unwrap();
@@ -193,7 +315,7 @@ mod tests {
     #[test]
     fn test_range_set_boundaries() {
         let text = Text::new("Hello");
-        let node: NodeRef = Arc::new(RwLock::new(Box::new(text) as Box<dyn crate::node::Node>));
+        let node: NodeRef = Arc::new(RwLock::new(text));
 
         let mut range = Range::new(Some(node.clone()));
         range.set_start(node.clone(), 1).unwrap();
@@ -207,7 +329,7 @@ mod tests {
     #[test]
     fn test_range_invalid_offset() {
         let text = Text::new("Hi");
-        let node: NodeRef = Arc::new(RwLock::new(Box::new(text) as Box<dyn crate::node::Node>));
+        let node: NodeRef = Arc::new(RwLock::new(text));
 
         let mut range = Range::new(Some(node.clone()));
 
@@ -0,0 +1,23 @@
+//! Text geometry hook for `Range.getClientRects`
+//!
+//! Elements carry their own layout metrics, but the boxes of a run of text
+//! depend on line breaking, which only layout knows. A
+//! [`RangeGeometryProvider`] installed on the document with
+//! [`Document::set_range_geometry_provider`](crate::Document::set_range_geometry_provider)
+//! reports them for the ranges created from that document.
+
+use crate::node::NodeRef;
+use dom_types::DOMRect;
+use std::fmt;
+
+/// Source of the rendered boxes of text, typically the layout engine
+pub trait RangeGeometryProvider: fmt::Debug + Send + Sync {
+    /// Gets the boxes of the characters `start..end` of the text node `text`,
+    /// one per line fragment
+    fn text_rects(&self, text: &NodeRef, start: usize, end: usize) -> Vec<DOMRect>;
+
+    /// Gets the caret box at a boundary point, if the point is rendered
+    fn caret_rect(&self, _node: &NodeRef, _offset: usize) -> Option<DOMRect> {
+        None
+    }
+}
@@ -0,0 +1,59 @@
+//! Document readiness
+//!
+//! A document starts out `loading`, becomes `interactive` once parsing is
+//! done and `complete` once its subresources have loaded. The events that
+//! scripts wait on, `readystatechange` and `DOMContentLoaded`, belong to the
+//! event system, which `dom_core` cannot depend on; a [`ReadyStateObserver`]
+//! installed with [`Document::set_ready_state_observer`] is told about each
+//! transition and dispatches them.
+//!
+//! [`Document::set_ready_state_observer`]: crate::Document::set_ready_state_observer
+
+use std::fmt;
+
+/// Loading progress of a document, as reported by `document.readyState`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum DocumentReadyState {
+    /// The document is still being parsed
+    #[default]
+    Loading,
+    /// Parsing has finished but subresources are still loading
+    Interactive,
+    /// The document and its subresources have loaded
+    Complete,
+}
+
+impl DocumentReadyState {
+    /// Gets the `readyState` string: `loading`, `interactive` or `complete`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentReadyState::Loading => "loading",
+            DocumentReadyState::Interactive => "interactive",
+            DocumentReadyState::Complete => "complete",
+        }
+    }
+
+    /// The state following this one, if any
+    pub(crate) fn next(self) -> Option<Self> {
+        match self {
+            DocumentReadyState::Loading => Some(DocumentReadyState::Interactive),
+            DocumentReadyState::Interactive => Some(DocumentReadyState::Complete),
+            DocumentReadyState::Complete => None,
+        }
+    }
+}
+
+impl fmt::Display for DocumentReadyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Told about each ready state transition, typically to dispatch events
+pub trait ReadyStateObserver: fmt::Debug + Send + Sync {
+    /// Called after the document entered `state`
+    ///
+    /// The document is still borrowed for the transition, so observers must
+    /// not try to lock it.
+    fn ready_state_changed(&self, state: DocumentReadyState);
+}
@@ -0,0 +1,684 @@
+//! HTML and XML fragment serialization
+//!
+//! Serializes a node subtree to markup. Shadow trees are not part of the
+//! light DOM and are left out, except that a shadow root marked serializable
+//! is emitted as a declarative `<template shadowrootmode>` when
+//! [`SerializeOptions::serializable_shadow_roots`] is set.
+//!
+//! [`serialize_node_to_writer`] streams the same HTML output to an
+//! [`io::Write`] instead of building a `String`.
+//!
+//! [`serialize_xml`] tracks the namespace declarations in scope at each
+//! element and only emits `xmlns`/`xmlns:prefix` where a namespace is first
+//! introduced.
+
+use crate::document_type::DocumentType;
+use crate::element::Element;
+use crate::namespaces::{HTML_NAMESPACE, XML_NAMESPACE};
+use crate::node::{Node, NodeRef};
+use crate::processing_instruction::ProcessingInstruction;
+use dom_types::NodeType;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+/// Elements that have no end tag and never serialize children
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose text children serialize verbatim, without escaping
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+    "script",
+    "style",
+    "xmp",
+];
+
+/// Shadow root attached to an element, as seen by the serializer and by
+/// focus handling
+///
+/// `dom_core` has no shadow DOM of its own; the crate providing shadow roots
+/// implements this and attaches it with [`Element::set_shadow_root_content`].
+pub trait ShadowRootContent: fmt::Debug + Send + Sync {
+    /// Mode keyword, `"open"` or `"closed"`
+    fn mode(&self) -> &'static str;
+
+    /// Whether the shadow root opted into serialization
+    fn is_serializable(&self) -> bool;
+
+    /// Children of the shadow root
+    fn child_nodes(&self) -> Vec<crate::node::NodeRef>;
+
+    /// Whether focusing the host moves focus into the shadow tree
+    fn delegates_focus(&self) -> bool {
+        false
+    }
+
+    /// The shadow root as `Any`, for the providing crate to downcast
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+/// Quote character used around serialized attribute values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttributeQuote {
+    /// `name="value"`, with `"` in the value written as `&quot;`
+    #[default]
+    Double,
+    /// `name='value'`, with `'` in the value written as `&#39;`
+    Single,
+}
+
+impl AttributeQuote {
+    fn as_char(self) -> char {
+        match self {
+            AttributeQuote::Double => '"',
+            AttributeQuote::Single => '\'',
+        }
+    }
+}
+
+/// Options controlling serialization
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    /// Include shadow roots that are marked serializable
+    pub serializable_shadow_roots: bool,
+
+    /// Quote character used around attribute values
+    pub attribute_quote: AttributeQuote,
+}
+
+/// Destination of serialized markup
+trait Output {
+    fn push_str(&mut self, text: &str);
+
+    fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl Output for String {
+    fn push_str(&mut self, text: &str) {
+        String::push_str(self, text);
+    }
+}
+
+/// Output forwarding to a writer, keeping the first write error
+struct WriterOutput<'a, W: io::Write> {
+    writer: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Output for WriterOutput<'_, W> {
+    fn push_str(&mut self, text: &str) {
+        if self.error.is_none() {
+            self.error = self.writer.write_all(text.as_bytes()).err();
+        }
+    }
+}
+
+/// Serializes `node` and its descendants
+pub fn serialize_node(node: &dyn Node, options: &SerializeOptions) -> String {
+    let mut out = String::new();
+    write_node(node, options, &mut out);
+    out
+}
+
+/// Serializes a shared node and its descendants as HTML, like `outerHTML`
+///
+/// Uses the default options, so shadow trees are left out.
+pub fn serialize_html(node: &NodeRef) -> String {
+    serialize_node(&*node.read(), &SerializeOptions::default())
+}
+
+/// Serializes `node` and its descendants into `writer`
+///
+/// Produces the same bytes as [`serialize_node`] without holding the whole
+/// document in memory. Output is written in many small pieces, so slow
+/// writers such as files or sockets should be wrapped in a `BufWriter`.
+pub fn serialize_node_to_writer<W: io::Write>(
+    node: &dyn Node,
+    options: &SerializeOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut out = WriterOutput {
+        writer,
+        error: None,
+    };
+    write_node(node, options, &mut out);
+    out.error.map_or(Ok(()), Err)
+}
+
+/// Serializes the children of `node`, excluding `node` itself
+pub fn serialize_children(node: &dyn Node, options: &SerializeOptions) -> String {
+    let mut out = String::new();
+    write_children(node, options, &mut out);
+    out
+}
+
+fn write_children(node: &dyn Node, options: &SerializeOptions, out: &mut impl Output) {
+    for child in node.child_nodes() {
+        write_node(&*child.read(), options, out);
+    }
+}
+
+fn write_node(node: &dyn Node, options: &SerializeOptions, out: &mut impl Output) {
+    match node.node_type() {
+        NodeType::Element => {
+            if let Some(element) = node.as_any().downcast_ref::<Element>() {
+                write_element(element, options, out);
+            }
+        }
+        NodeType::Text | NodeType::CDataSection => {
+            out.push_str(&escape_text(node.node_value().unwrap_or_default()));
+        }
+        NodeType::Comment => {
+            out.push_str("<!--");
+            out.push_str(node.node_value().unwrap_or_default());
+            out.push_str("-->");
+        }
+        NodeType::ProcessingInstruction => {
+            if let Some(pi) = node.as_any().downcast_ref::<ProcessingInstruction>() {
+                out.push_str("<?");
+                out.push_str(pi.target());
+                out.push(' ');
+                out.push_str(pi.data());
+                out.push('>');
+            }
+        }
+        NodeType::DocumentType => write_doctype(node, out),
+        NodeType::Document | NodeType::DocumentFragment => {
+            write_children(node, options, out);
+        }
+        _ => {}
+    }
+}
+
+fn write_doctype(node: &dyn Node, out: &mut impl Output) {
+    if let Some(doctype) = node.as_any().downcast_ref::<DocumentType>() {
+        out.push_str(&doctype.to_string());
+    }
+}
+
+fn write_element(element: &Element, options: &SerializeOptions, out: &mut impl Output) {
+    let tag = element.tag_name().to_ascii_lowercase();
+    out.push('<');
+    out.push_str(&tag);
+    for (name, value) in element.attributes() {
+        write_attribute(name, value, options.attribute_quote, out);
+    }
+    out.push('>');
+
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+        return;
+    }
+
+    if options.serializable_shadow_roots {
+        if let Some(shadow) = element
+            .shadow_root_content()
+            .filter(|shadow| shadow.is_serializable())
+        {
+            out.push_str("<template shadowrootmode=\"");
+            out.push_str(shadow.mode());
+            out.push_str("\" shadowrootserializable=\"\">");
+            for child in shadow.child_nodes() {
+                write_node(&*child.read(), options, out);
+            }
+            out.push_str("</template>");
+        }
+    }
+
+    // A template's markup lives in its content fragment
+    if let Some(content) = element.content() {
+        write_children(&*content.read(), options, out);
+    }
+    if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) {
+        for child in element.child_nodes() {
+            let child = child.read();
+            if child.node_type() == NodeType::Text {
+                out.push_str(child.node_value().unwrap_or_default());
+            } else {
+                write_node(&*child, options, out);
+            }
+        }
+    } else {
+        write_children(element, options, out);
+    }
+    out.push_str("</");
+    out.push_str(&tag);
+    out.push('>');
+}
+
+/// Namespace declarations in scope: prefix (`None` for the default) to URI
+type NamespaceScope = HashMap<Option<String>, Option<String>>;
+
+/// Serializes `node` and its descendants as XML
+///
+/// Each element declares its own namespace (and those of its prefixed
+/// attributes) only when the declaration in scope differs, so nested
+/// elements reuse their ancestors' declarations.
+pub fn serialize_xml(node: &dyn Node) -> String {
+    let mut scope = NamespaceScope::new();
+    scope.insert(Some("xml".to_string()), Some(XML_NAMESPACE.to_string()));
+    let mut out = String::new();
+    write_xml_node(node, &scope, &mut out);
+    out
+}
+
+fn write_xml_node(node: &dyn Node, scope: &NamespaceScope, out: &mut String) {
+    match node.node_type() {
+        NodeType::Element => {
+            if let Some(element) = node.as_any().downcast_ref::<Element>() {
+                write_xml_element(element, scope, out);
+            }
+        }
+        NodeType::Text => {
+            out.push_str(&escape_xml_text(node.node_value().unwrap_or_default()));
+        }
+        NodeType::Comment => {
+            out.push_str("<!--");
+            out.push_str(node.node_value().unwrap_or_default());
+            out.push_str("-->");
+        }
+        NodeType::DocumentType => write_doctype(node, out),
+        NodeType::Document | NodeType::DocumentFragment => {
+            for child in node.child_nodes() {
+                write_xml_node(&*child.read(), scope, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn write_xml_element(element: &Element, parent_scope: &NamespaceScope, out: &mut String) {
+    let mut scope = parent_scope.clone();
+    let namespace = element.namespace_uri().map(str::to_string);
+    let name = if namespace.is_none() || namespace.as_deref() == Some(HTML_NAMESPACE) {
+        element.tag_name().to_ascii_lowercase()
+    } else {
+        element.tag_name().to_string()
+    };
+
+    // Declarations written as attributes are already in scope
+    for (attr_name, value) in element.attributes() {
+        if attr_name == "xmlns" {
+            scope.insert(None, Some(value.clone()).filter(|v| !v.is_empty()));
+        } else if let Some(prefix) = attr_name.strip_prefix("xmlns:") {
+            scope.insert(Some(prefix.to_string()), Some(value.clone()));
+        }
+    }
+
+    let mut declarations = Vec::new();
+    let prefix = name.split_once(':').map(|(prefix, _)| prefix.to_string());
+    declare_namespace(&mut scope, prefix, namespace, &mut declarations);
+    for attr_name in element.attributes().keys() {
+        if let Some((prefix, _)) = attr_name.split_once(':') {
+            if prefix != "xmlns" {
+                let attr_namespace = element.attribute_namespace(attr_name).map(str::to_string);
+                declare_namespace(
+                    &mut scope,
+                    Some(prefix.to_string()),
+                    attr_namespace,
+                    &mut declarations,
+                );
+            }
+        }
+    }
+
+    out.push('<');
+    out.push_str(&name);
+    for (attr_name, value) in declarations
+        .iter()
+        .map(|(name, value)| (name, value))
+        .chain(element.attributes())
+    {
+        out.push(' ');
+        out.push_str(attr_name);
+        out.push_str("=\"");
+        out.push_str(&escape_xml_attribute(value));
+        out.push('"');
+    }
+
+    let children = element.child_nodes();
+    if children.is_empty() {
+        out.push_str("/>");
+        return;
+    }
+    out.push('>');
+    for child in children {
+        write_xml_node(&*child.read(), &scope, out);
+    }
+    out.push_str("</");
+    out.push_str(&name);
+    out.push('>');
+}
+
+/// Records an `xmlns` declaration unless `namespace` is already bound to `prefix`
+fn declare_namespace(
+    scope: &mut NamespaceScope,
+    prefix: Option<String>,
+    namespace: Option<String>,
+    declarations: &mut Vec<(String, String)>,
+) {
+    let in_scope = scope.get(&prefix).cloned().flatten();
+    if in_scope == namespace {
+        return;
+    }
+    let attr_name = match &prefix {
+        Some(prefix) => format!("xmlns:{}", prefix),
+        None => "xmlns".to_string(),
+    };
+    declarations.push((attr_name, namespace.clone().unwrap_or_default()));
+    scope.insert(prefix, namespace);
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('\u{a0}', "&nbsp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes XML character data, which has no named entity for U+00A0
+fn escape_xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes a double-quoted XML attribute value, where a bare `<` is invalid
+fn escape_xml_attribute(value: &str) -> String {
+    escape_xml_text(value).replace('"', "&quot;")
+}
+
+fn write_attribute(name: &str, value: &str, quote: AttributeQuote, out: &mut impl Output) {
+    out.push(' ');
+    out.push_str(name);
+    out.push('=');
+    out.push(quote.as_char());
+    out.push_str(&escape_attribute(value, quote));
+    out.push(quote.as_char());
+}
+
+/// Escapes `value` so it round-trips inside `quote`
+///
+/// Only the active quote character is escaped; the other one is left as is.
+fn escape_attribute(value: &str, quote: AttributeQuote) -> String {
+    let escaped = value.replace('&', "&amp;").replace('\u{a0}', "&nbsp;");
+    match quote {
+        AttributeQuote::Double => escaped.replace('"', "&quot;"),
+        AttributeQuote::Single => escaped.replace('\'', "&#39;"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::NodeRef;
+    use crate::text::Text;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct FakeShadow {
+        serializable: bool,
+        children: Vec<NodeRef>,
+    }
+
+    impl ShadowRootContent for FakeShadow {
+        fn mode(&self) -> &'static str {
+            "open"
+        }
+
+        fn is_serializable(&self) -> bool {
+            self.serializable
+        }
+
+        fn child_nodes(&self) -> Vec<NodeRef> {
+            self.children.clone()
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    fn node(node: impl Node + 'static) -> NodeRef {
+        Arc::new(RwLock::new(node))
+    }
+
+    fn host_with_shadow(serializable: bool) -> Element {
+        let mut host = Element::new("div");
+        host.set_attribute("title", "a \"b\" & c").unwrap();
+        host.append_child(node(Text::new("light"))).unwrap();
+        host.set_shadow_root_content(Some(Arc::new(FakeShadow {
+            serializable,
+            children: vec![node(Text::new("shadow"))],
+        })));
+        host
+    }
+
+    #[test]
+    fn test_outer_html_excludes_shadow_content() {
+        let host = host_with_shadow(true);
+        assert_eq!(
+            host.outer_html(),
+            "<div title=\"a &quot;b&quot; &amp; c\">light</div>"
+        );
+    }
+
+    #[test]
+    fn test_serializable_shadow_root_included_when_requested() {
+        let options = SerializeOptions {
+            serializable_shadow_roots: true,
+            ..Default::default()
+        };
+
+        let host = host_with_shadow(true);
+        assert_eq!(
+            serialize_children(&host, &options),
+            "light",
+            "children alone never include the host's shadow tree"
+        );
+        assert_eq!(
+            serialize_node(&host, &options),
+            "<div title=\"a &quot;b&quot; &amp; c\">\
+             <template shadowrootmode=\"open\" shadowrootserializable=\"\">shadow</template>\
+             light</div>"
+        );
+
+        let host = host_with_shadow(false);
+        assert!(!serialize_node(&host, &options).contains("shadow"));
+    }
+
+    #[test]
+    fn test_void_elements_and_escaping() {
+        let mut p = Element::new("p");
+        p.append_child(node(Text::new("1 < 2 & 3"))).unwrap();
+        p.append_child(node(Element::new("br"))).unwrap();
+        assert_eq!(p.outer_html(), "<p>1 &lt; 2 &amp; 3<br></p>");
+    }
+
+    #[test]
+    fn test_serialize_html_nested_tree() {
+        let mut list = Element::new("ul");
+        list.set_attribute("id", "menu").unwrap();
+        list.set_attribute("class", "nav").unwrap();
+        list.set_attribute("data-count", "2").unwrap();
+        let mut item = Element::new("li");
+        item.set_attribute("title", "Fish & \"Chips\"").unwrap();
+        let text = Text::new("Fish <& Chips>");
+        item.append_child(node(text)).unwrap();
+        item.append_child(node(Element::new("br"))).unwrap();
+        let comment = crate::Comment::new(" more soon ");
+        list.append_child(node(item)).unwrap();
+        list.append_child(node(comment)).unwrap();
+        let list = node(list);
+
+        let inner = "<li title=\"Fish &amp; &quot;Chips&quot;\">\
+                     Fish &lt;&amp; Chips&gt;<br></li><!-- more soon -->";
+        let open = "<ul id=\"menu\" class=\"nav\" data-count=\"2\">";
+        assert_eq!(serialize_html(&list), format!("{open}{inner}</ul>"));
+
+        let guard = list.read();
+        let list = guard.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(list.inner_html(), inner);
+        assert_eq!(list.outer_html(), format!("{open}{inner}</ul>"));
+    }
+
+    #[test]
+    fn test_template_content_and_processing_instructions() {
+        let template = Element::new("template");
+        let content = template.content().unwrap();
+        let cell = node(Element::new("td"));
+        content.write().append_child(cell).unwrap();
+        assert_eq!(template.inner_html(), "<td></td>");
+        assert_eq!(template.outer_html(), "<template><td></td></template>");
+
+        let mut fragment = crate::DocumentFragment::new();
+        let pi = ProcessingInstruction::new("xml-stylesheet", "href=\"a.css\"");
+        fragment.append_child(node(pi)).unwrap();
+        let html = serialize_node(&fragment, &SerializeOptions::default());
+        assert_eq!(html, "<?xml-stylesheet href=\"a.css\">");
+    }
+
+    #[test]
+    fn test_doctype_round_trips() {
+        let mut document = crate::Document::new();
+        let doctype = DocumentType::new("html", "-//W3C//DTD HTML 4.01//EN", "");
+        document.append_child(node(doctype)).unwrap();
+        document.append_child(node(Element::new("html"))).unwrap();
+
+        let expected = "<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\"><html></html>";
+        let html = serialize_node(&document, &SerializeOptions::default());
+        assert_eq!(html, expected);
+    }
+
+    #[test]
+    fn test_writer_output_matches_string_and_reports_errors() {
+        let host = host_with_shadow(true);
+        let options = SerializeOptions {
+            serializable_shadow_roots: true,
+            ..Default::default()
+        };
+        let mut bytes = Vec::new();
+        serialize_node_to_writer(&host, &options, &mut bytes).unwrap();
+        assert_eq!(bytes, serialize_node(&host, &options).into_bytes());
+
+        let mut full = [0u8; 8];
+        let error = serialize_node_to_writer(&host, &options, &mut &mut full[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_attribute_quoting_policy() {
+        let mut span = Element::new("span");
+        span.set_attribute("title", r#"say "hi" & it's"#).unwrap();
+
+        assert_eq!(
+            serialize_node(&span, &SerializeOptions::default()),
+            r#"<span title="say &quot;hi&quot; &amp; it's"></span>"#
+        );
+
+        let single = SerializeOptions {
+            attribute_quote: AttributeQuote::Single,
+            ..Default::default()
+        };
+        assert_eq!(
+            serialize_node(&span, &single),
+            r#"<span title='say "hi" &amp; it&#39;s'></span>"#
+        );
+    }
+
+    #[test]
+    fn test_raw_text_children_are_not_escaped() {
+        let mut script = Element::new("script");
+        script
+            .append_child(node(Text::new("if (a < b && c > d) {}")))
+            .unwrap();
+        assert_eq!(
+            script.outer_html(),
+            "<script>if (a < b && c > d) {}</script>"
+        );
+
+        let mut style = Element::new("style");
+        style.append_child(node(Text::new("a > b { }"))).unwrap();
+        assert_eq!(style.outer_html(), "<style>a > b { }</style>");
+    }
+
+    #[test]
+    fn test_xml_escaping_of_nbsp_and_attributes() {
+        let mut svg = svg_element("svg");
+        svg.set_attribute("data-expr", "a < b & \"c\"").unwrap();
+        svg.append_child(node(Text::new("x\u{a0}<y>"))).unwrap();
+
+        assert_eq!(
+            serialize_xml(&svg),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+             data-expr=\"a &lt; b &amp; &quot;c&quot;\">x\u{a0}&lt;y&gt;</svg>"
+        );
+    }
+
+    fn svg_element(tag: &str) -> Element {
+        Element::new_with_namespace(tag, crate::namespaces::SVG_NAMESPACE)
+    }
+
+    #[test]
+    fn test_xml_declares_namespace_once_at_root() {
+        let mut g = svg_element("g");
+        g.append_child(node(svg_element("circle"))).unwrap();
+        g.append_child(node(svg_element("linearGradient"))).unwrap();
+        let mut svg = svg_element("svg");
+        svg.append_child(node(g)).unwrap();
+
+        assert_eq!(
+            serialize_xml(&svg),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\"><g><circle/><linearGradient/></g></svg>"
+        );
+    }
+
+    #[test]
+    fn test_xml_mixed_namespaces_declared_where_introduced() {
+        let math_ns = crate::namespaces::MATHML_NAMESPACE;
+        let xlink_ns = crate::namespaces::XLINK_NAMESPACE;
+
+        let mut math = Element::new_with_namespace("math", math_ns);
+        math.append_child(node(Element::new_with_namespace("mi", math_ns)))
+            .unwrap();
+        let mut foreign = svg_element("foreignObject");
+        foreign.append_child(node(math)).unwrap();
+
+        let mut first_use = svg_element("use");
+        first_use
+            .set_attribute_ns(Some(xlink_ns), "xlink:href", "#a")
+            .unwrap();
+        let mut second_use = svg_element("use");
+        second_use
+            .set_attribute_ns(Some(xlink_ns), "xlink:href", "#b")
+            .unwrap();
+        let mut g = svg_element("g");
+        g.set_attribute_ns(Some(xlink_ns), "xlink:title", "group")
+            .unwrap();
+        g.append_child(node(first_use)).unwrap();
+        g.append_child(node(second_use)).unwrap();
+
+        let mut svg = svg_element("svg");
+        svg.append_child(node(foreign)).unwrap();
+        svg.append_child(node(g)).unwrap();
+
+        assert_eq!(
+            serialize_xml(&svg),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\">\
+             <foreignObject>\
+             <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi/></math>\
+             </foreignObject>\
+             <g xmlns:xlink=\"http://www.w3.org/1999/xlink\" xlink:title=\"group\">\
+             <use xlink:href=\"#a\"/><use xlink:href=\"#b\"/>\
+             </g></svg>"
+        );
+    }
+}
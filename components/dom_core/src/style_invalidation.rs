@@ -0,0 +1,89 @@
+//! Targeted style invalidation for class changes
+//!
+//! Recomputing style for a whole subtree on every `class` change is costly on
+//! large pages. Instead, an element bound to a [`StyleInvalidationSink`]
+//! reports exactly which classes were added and removed, so the style engine
+//! can restrict recalculation to descendants whose selectors mention them.
+
+use crate::element::Element;
+use crate::node::NodeRef;
+use std::fmt;
+use std::sync::Arc;
+
+/// Classes added to and removed from an element by a `class` change
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClassInvalidation {
+    /// Classes present after the change but not before, in new order
+    pub added: Vec<String>,
+    /// Classes present before the change but not after, in old order
+    pub removed: Vec<String>,
+}
+
+impl ClassInvalidation {
+    /// Computes the delta between two class lists
+    pub fn between(old: &[String], new: &[String]) -> Self {
+        let mut added: Vec<String> = Vec::new();
+        for class in new {
+            if !old.contains(class) && !added.contains(class) {
+                added.push(class.clone());
+            }
+        }
+        let mut removed: Vec<String> = Vec::new();
+        for class in old {
+            if !new.contains(class) && !removed.contains(class) {
+                removed.push(class.clone());
+            }
+        }
+        Self { added, removed }
+    }
+
+    /// Whether the change left the set of classes untouched
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Receiver of style invalidations, typically the style engine
+pub trait StyleInvalidationSink: fmt::Debug + Send + Sync {
+    /// Called after the classes of `root` changed
+    ///
+    /// `root` is the element whose `class` attribute changed; only it and its
+    /// descendants can be affected by the delta.
+    fn classes_changed(&self, root: &Element, change: &ClassInvalidation);
+}
+
+/// Binds every element of the subtree rooted at `root` to `sink`
+pub(crate) fn bind_subtree(root: &NodeRef, sink: Option<&Arc<dyn StyleInvalidationSink>>) {
+    let mut stack = vec![root.clone()];
+    while let Some(node) = stack.pop() {
+        let mut node = node.write();
+        if let Some(element) = node.as_any_mut().downcast_mut::<Element>() {
+            element.set_style_invalidation_sink(sink.cloned());
+        }
+        stack.extend(node.child_nodes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|class| class.to_string()).collect()
+    }
+
+    #[test]
+    fn test_delta_between_class_lists() {
+        let change = ClassInvalidation::between(
+            &classes(&["card", "open", "dark"]),
+            &classes(&["card", "closed", "dark", "closed"]),
+        );
+        assert_eq!(change.added, ["closed"]);
+        assert_eq!(change.removed, ["open"]);
+        assert!(!change.is_empty());
+
+        // Reordering is not a change
+        let change = ClassInvalidation::between(&classes(&["a", "b"]), &classes(&["b", "a"]));
+        assert!(change.is_empty());
+    }
+}
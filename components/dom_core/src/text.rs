@@ -1,9 +1,11 @@
 //! Text node implementation
 
-use crate::node::{Node, NodeData, NodeRef};
+use crate::character_data::{edit_data, utf16_to_byte_offset, CharacterData};
+use crate::node::{new_node_ref, Node, NodeData, NodeRef};
+use crate::tree_generation::{TreeGenerations, TreeGenerationsRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// Text node containing character data
 #[derive(Clone, Debug)]
@@ -13,17 +15,32 @@ pub struct Text {
 
     /// Text content
     data: String,
+
+    /// Counters of the document the node is in, told about data changes
+    tree_generations: Option<Weak<TreeGenerations>>,
 }
 
+/// Thread-safe reference to a Text
+pub type TextRef = Arc<RwLock<Text>>;
+
 impl Text {
     /// Creates a new text node
     pub fn new(data: impl Into<String>) -> Self {
         Self {
             node_data: NodeData::new(NodeType::Text, "#text"),
             data: data.into(),
+            tree_generations: None,
         }
     }
 
+    /// Binds the text to the mutation counters of its document
+    ///
+    /// Changes to its data are then reported to the document's character
+    /// data observers.
+    pub fn set_tree_generations(&mut self, generations: Option<&TreeGenerationsRef>) {
+        self.tree_generations = generations.map(Arc::downgrade);
+    }
+
     /// Gets the text data
     pub fn data(&self) -> &str {
         &self.data
@@ -31,65 +48,124 @@ impl Text {
 
     /// Sets the text data
     pub fn set_data(&mut self, data: impl Into<String>) {
-        self.data = data.into();
+        let data = data.into();
+        edit_data(self, |current| *current = data);
     }
 
-    /// Gets the length of the text
+    /// Gets the length of the text in UTF-16 code units
     pub fn length(&self) -> usize {
-        self.data.len()
+        CharacterData::len(self)
     }
 
-    /// Appends data to the text
-    pub fn append_data(&mut self, data: &str) {
-        self.data.push_str(data);
+    /// Clones this node into a typed reference, outside of any tree
+    pub fn clone_text(&self) -> TextRef {
+        Arc::new(RwLock::new(self.clone_detached()))
     }
 
-    /// Inserts data at an offset
-    pub fn insert_data(&mut self, offset: usize, data: &str) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
-
-        self.data.insert_str(offset, data);
-        Ok(())
+    fn clone_detached(&self) -> Self {
+        let mut cloned = self.clone();
+        cloned.node_data.clear_links();
+        cloned.tree_generations = None;
+        cloned
     }
 
-    /// Deletes data
-    pub fn delete_data(&mut self, offset: usize, count: usize) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
+    /// Splits this node at `offset` (in UTF-16 code units)
+    ///
+    /// The data after `offset` is moved into a new `Text` node which is
+    /// inserted as the next sibling, through the parent's `insert_before`,
+    /// when this node has a parent. The boundary points of the document's
+    /// live ranges past `offset` move into the new node. Locating the node
+    /// within its parent relies on the self node reference, so the caller
+    /// may hold a write lock on this node while splitting.
+    ///
+    /// Returns `IndexSizeError` if `offset` is greater than the length, or if
+    /// it falls between the two halves of a surrogate pair, and the error of
+    /// the insertion otherwise; the data is left unchanged on error.
+    pub fn split_text(&mut self, offset: usize) -> Result<NodeRef, DomException> {
+        let byte_offset =
+            utf16_to_byte_offset(&self.data, offset).ok_or(DomException::IndexSizeError)?;
+        let new_node = new_node_ref(Text::new(&self.data[byte_offset..]));
+
+        if let Some(parent) = self.node_data.get_parent() {
+            let this = self
+                .node_data
+                .get_self_node_ref()
+                .ok_or(DomException::NotFoundError)?;
+            let index = self.index_in(&parent).ok_or(DomException::NotFoundError)?;
+            let next = parent.read().node_data().children.get(index + 1).cloned();
+            parent.write().insert_before(new_node.clone(), next)?;
+            let generations = parent.read().generations();
+            if let Some(generations) = generations {
+                generations
+                    .live_ranges()
+                    .text_split(&parent, index, &this, &new_node, offset);
+            }
         }
 
-        let end = (offset + count).min(self.data.len());
-        self.data.replace_range(offset..end, "");
-        Ok(())
+        edit_data(self, |current| current.truncate(byte_offset));
+        Ok(new_node)
     }
 
-    /// Replaces data
-    pub fn replace_data(
-        &mut self,
-        offset: usize,
-        count: usize,
-        data: &str,
-    ) -> Result<(), DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
+    /// Returns the data of this node and all logically adjacent `Text` siblings
+    ///
+    /// `CDATASection` siblings are text nodes too, and are included.
+    pub fn whole_text(&self) -> String {
+        let Some(parent) = self.node_data.get_parent() else {
+            return self.data.clone();
+        };
+        let Some(position) = self.index_in(&parent) else {
+            return self.data.clone();
+        };
+
+        let siblings = parent.read().child_nodes();
+        let is_text = |node: &NodeRef| {
+            matches!(
+                node.read().node_type(),
+                NodeType::Text | NodeType::CDataSection
+            )
+        };
+
+        let start = siblings[..position]
+            .iter()
+            .rposition(|node| !is_text(node))
+            .map_or(0, |i| i + 1);
+        let end = siblings[position + 1..]
+            .iter()
+            .position(|node| !is_text(node))
+            .map_or(siblings.len(), |i| position + 1 + i);
+
+        let mut result = String::new();
+        for (i, node) in siblings[start..end].iter().enumerate() {
+            if start + i == position {
+                result.push_str(&self.data);
+            } else if let Some(data) = node.read().node_value() {
+                result.push_str(data);
+            }
         }
+        result
+    }
 
-        let end = (offset + count).min(self.data.len());
-        self.data.replace_range(offset..end, data);
-        Ok(())
+    /// Finds this node's index in `parent` without locking this node
+    fn index_in(&self, parent: &NodeRef) -> Option<usize> {
+        let self_ref = self.node_data.get_self_node_ref()?;
+        parent
+            .read()
+            .node_data()
+            .children
+            .iter()
+            .position(|child| Arc::ptr_eq(child, &self_ref))
     }
+}
 
-    /// Extracts a substring
-    pub fn substring_data(&self, offset: usize, count: usize) -> Result<String, DomException> {
-        if offset > self.data.len() {
-            return Err(DomException::InvalidModificationError);
-        }
+impl CharacterData for Text {
+    fn data(&self) -> &str {
+        &self.data
+    }
 
-        let end = (offset + count).min(self.data.len());
-        Ok(self.data[offset..end].to_string())
+    fn data_mut(&mut self) -> &mut String {
+        &mut self.data
     }
+
 }
 
 impl Node for Text {
@@ -106,11 +182,7 @@ impl Node for Text {
     }
 
     fn set_node_value(&mut self, value: Option<String>) {
-        if let Some(val) = value {
-            self.data = val;
-        } else {
-            self.data.clear();
-        }
+        edit_data(self, |current| *current = value.unwrap_or_default());
     }
 
     fn text_content(&self) -> Option<String> {
@@ -118,7 +190,7 @@ impl Node for Text {
     }
 
     fn set_text_content(&mut self, text: String) {
-        self.data = text;
+        edit_data(self, |current| *current = text);
     }
 
     fn parent_node(&self) -> Option<NodeRef> {
@@ -150,8 +222,7 @@ impl Node for Text {
     }
 
     fn clone_node(&self, _deep: bool) -> NodeRef {
-        let cloned = self.clone();
-        Arc::new(RwLock::new(Box::new(cloned) as Box<dyn Node>))
+        Arc::new(RwLock::new(self.clone_detached()))
     }
 
     fn node_data(&self) -> &NodeData {
@@ -168,9 +239,17 @@ impl Node for Text {
         self_ptr == other_ptr
     }
 
+    fn generations(&self) -> Option<TreeGenerationsRef> {
+        self.tree_generations.as_ref().and_then(Weak::upgrade)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -224,11 +303,42 @@ mod tests {
         assert_eq!(substr, "world");
     }
 
+    #[test]
+    fn test_character_data_len_in_utf16_units() {
+        // "a😀b" is 6 bytes but 4 UTF-16 code units
+        let text = Text::new("a\u{1F600}b");
+        assert_eq!(CharacterData::len(&text), 4);
+        assert_eq!(text.length(), 4);
+        assert!(!CharacterData::is_empty(&text));
+
+        let empty = Text::new("");
+        assert_eq!(CharacterData::len(&empty), 0);
+        assert!(CharacterData::is_empty(&empty));
+    }
+
+    #[test]
+    fn test_character_data_len_matches_substring_offsets() {
+        let text = Text::new("a\u{1F600}b");
+        let len = CharacterData::len(&text);
+
+        assert_eq!(text.substring_data(0, len).unwrap(), text.data());
+        assert_eq!(text.substring_data(1, 2).unwrap(), "\u{1F600}");
+        assert_eq!(text.substring_data(len, 1).unwrap(), "");
+        assert_eq!(
+            text.substring_data(len + 1, 1).unwrap_err(),
+            DomException::IndexSizeError
+        );
+        assert_eq!(
+            text.substring_data(2, 1).unwrap_err(),
+            DomException::IndexSizeError
+        );
+    }
+
     #[test]
     fn test_text_no_children() {
         let mut text = Text::new("Hello");
         let child = Text::new("child");
-        let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+        let child_ref = Arc::new(RwLock::new(child));
 
         let result = text.append_child(child_ref);
         assert!(result.is_err());
@@ -236,4 +346,176 @@ mod tests {
             assert!(matches!(e, DomException::HierarchyRequestError));
         }
     }
+
+    fn text_ref(data: &str) -> NodeRef {
+        new_node_ref(Text::new(data))
+    }
+
+    fn text_data(node: &NodeRef) -> String {
+        node.read().node_value().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn test_split_text_ascii() {
+        let mut text = Text::new("Hello, world!");
+        let tail = text.split_text(5).unwrap();
+
+        assert_eq!(text.data(), "Hello");
+        assert_eq!(text_data(&tail), ", world!");
+        assert!(tail.read().parent_node().is_none());
+    }
+
+    #[test]
+    fn test_split_text_emoji_boundary() {
+        // "a😀b": the emoji is a surrogate pair occupying code units 1 and 2
+        let mut text = Text::new("a\u{1F600}b");
+        let tail = text.split_text(3).unwrap();
+        assert_eq!(text.data(), "a\u{1F600}");
+        assert_eq!(text_data(&tail), "b");
+
+        let mut text = Text::new("a\u{1F600}b");
+        let tail = text.split_text(1).unwrap();
+        assert_eq!(text.data(), "a");
+        assert_eq!(text_data(&tail), "\u{1F600}b");
+
+        let mut text = Text::new("a\u{1F600}b");
+        assert_eq!(
+            text.split_text(2).unwrap_err(),
+            DomException::IndexSizeError
+        );
+        assert_eq!(text.data(), "a\u{1F600}b");
+    }
+
+    #[test]
+    fn test_split_text_out_of_range() {
+        let mut text = Text::new("a\u{1F600}b");
+        // Length is 4 UTF-16 code units, so splitting at the end is allowed
+        assert!(text.split_text(4).is_ok());
+        assert_eq!(text.data(), "a\u{1F600}b");
+
+        assert_eq!(
+            text.split_text(5).unwrap_err(),
+            DomException::IndexSizeError
+        );
+    }
+
+    #[test]
+    fn test_split_text_inserts_sibling() {
+        let parent = crate::Element::new("p");
+        let parent = new_node_ref(parent);
+
+        let first = text_ref("Hello world");
+        let last = text_ref("!");
+        parent.write().append_child(first.clone()).unwrap();
+        parent.write().append_child(last.clone()).unwrap();
+
+        let tail = {
+            let mut guard = first.write();
+            let text = guard.as_any_mut().downcast_mut::<Text>().unwrap();
+            text.split_text(5).unwrap()
+        };
+
+        let children = parent.read().child_nodes();
+        assert_eq!(children.len(), 3);
+        assert!(Arc::ptr_eq(&children[0], &first));
+        assert!(Arc::ptr_eq(&children[1], &tail));
+        assert!(Arc::ptr_eq(&children[2], &last));
+        assert!(Arc::ptr_eq(&tail.read().parent_node().unwrap(), &parent));
+        assert_eq!(text_data(&first), "Hello");
+        assert_eq!(text_data(&tail), " world");
+    }
+
+    fn split(node: &NodeRef, offset: usize) -> Result<NodeRef, DomException> {
+        let mut guard = node.write();
+        let text = guard.as_any_mut().downcast_mut::<Text>().unwrap();
+        text.split_text(offset)
+    }
+
+    #[test]
+    fn test_split_text_moves_live_ranges() {
+        let mut doc = crate::Document::new();
+        let p: NodeRef = doc.create_element("p").unwrap();
+        let text = new_node_ref(Text::new("Hello world"));
+        p.write().append_child(text.clone()).unwrap();
+        let range = doc.create_live_range();
+        range.write().set_start(text.clone(), 2).unwrap();
+        range.write().set_end(text.clone(), 8).unwrap();
+        let after = doc.create_live_range();
+        after.write().set_start(p.clone(), 1).unwrap();
+
+        let tail = split(&text, 5).unwrap();
+
+        let range = range.read();
+        assert!(Arc::ptr_eq(range.start_container(), &text));
+        assert_eq!(range.start_offset(), 2);
+        assert!(Arc::ptr_eq(range.end_container(), &tail));
+        assert_eq!(range.end_offset(), 3);
+        let after = after.read();
+        assert!(Arc::ptr_eq(after.start_container(), &p));
+        assert_eq!(after.start_offset(), 2);
+    }
+
+    #[test]
+    fn test_split_text_respects_child_limit() {
+        let mut element = crate::Element::new("p");
+        element.set_child_limit(Some(1));
+        let parent = new_node_ref(element);
+        let text = text_ref("Hello world");
+        parent.write().append_child(text.clone()).unwrap();
+
+        assert_eq!(
+            split(&text, 5).unwrap_err(),
+            DomException::QuotaExceededError
+        );
+        assert_eq!(text_data(&text), "Hello world");
+        assert_eq!(parent.read().child_nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_whole_text() {
+        let parent = crate::Element::new("p");
+        let parent = new_node_ref(parent);
+
+        let before = text_ref("ignored");
+        let separator: NodeRef = Arc::new(RwLock::new(crate::Element::new("br")));
+        let a = text_ref("Hello");
+        let b = text_ref(", ");
+        let c = text_ref("world");
+        for child in [&before, &separator, &a, &b, &c] {
+            parent.write().append_child(child.clone()).unwrap();
+        }
+
+        let whole = {
+            let guard = b.read();
+            guard.as_any().downcast_ref::<Text>().unwrap().whole_text()
+        };
+        assert_eq!(whole, "Hello, world");
+
+        let cdata = new_node_ref(crate::CDATASection::new("!"));
+        parent.write().append_child(cdata).unwrap();
+        let whole = {
+            let guard = b.read();
+            guard.as_any().downcast_ref::<Text>().unwrap().whole_text()
+        };
+        assert_eq!(whole, "Hello, world!");
+
+        assert_eq!(Text::new("alone").whole_text(), "alone");
+    }
+
+    #[test]
+    fn test_clone_text_is_detached() {
+        let parent = crate::Element::new("p");
+        let parent = new_node_ref(parent);
+        let original = text_ref("Hello");
+        parent.write().append_child(original.clone()).unwrap();
+
+        let clone = {
+            let guard = original.read();
+            guard.as_any().downcast_ref::<Text>().unwrap().clone_text()
+        };
+        assert!(clone.read().parent_node().is_none());
+        clone.write().append_data(", world");
+        assert_eq!(clone.read().data(), "Hello, world");
+        assert_eq!(text_data(&original), "Hello");
+    }
 }
@@ -0,0 +1,75 @@
+//! Tree depth limit
+//!
+//! Deeply nested trees can exhaust the stack of code that walks them
+//! recursively, such as a layout or style engine. A
+//! [`Document`](crate::Document) given a limit with
+//! [`Document::set_depth_limit`](crate::Document::set_depth_limit) binds it
+//! to its elements and fragments, which reject insertions that would nest
+//! nodes deeper than that.
+//!
+//! Nodes of a limited tree cache their depth, so an insertion only walks the
+//! inserted subtree: once to measure it and once to place it at its new
+//! depth. Depths are not tracked in trees without a limit, which pay nothing
+//! for moving subtrees around.
+
+use crate::element::Element;
+use crate::node::NodeRef;
+use dom_types::{DomException, NodeType};
+
+/// Fails if inserting `node` below a parent at `depth` would nest the tree
+/// deeper than `limit`
+pub(crate) fn check_insertion(
+    depth: usize,
+    limit: Option<usize>,
+    node: &NodeRef,
+) -> Result<(), DomException> {
+    match limit {
+        Some(limit) if depth + inserted_height(node) > limit => {
+            Err(DomException::HierarchyRequestError)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Number of levels `node` adds when inserted (1 for a leaf)
+///
+/// A document fragment is replaced by its children, so it adds one level
+/// less than its own height. Walks the subtree with an explicit stack so
+/// very deep trees cannot overflow the call stack.
+fn inserted_height(node: &NodeRef) -> usize {
+    let (children, fragment) = {
+        let node = node.read();
+        let fragment = node.node_type() == NodeType::DocumentFragment;
+        (node.child_nodes(), fragment)
+    };
+    let mut height = usize::from(!fragment);
+    let mut stack: Vec<(NodeRef, usize)> = children.into_iter().map(|c| (c, height + 1)).collect();
+    while let Some((node, depth)) = stack.pop() {
+        height = height.max(depth);
+        let children = node.read().child_nodes();
+        stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+    }
+    height
+}
+
+/// Places a subtree inserted below, or removed from, a parent with `limit`
+pub(crate) fn place_moved(root: &NodeRef, depth: usize, limit: Option<usize>) {
+    if limit.is_some() {
+        place_subtree(root, depth, limit);
+    }
+}
+
+/// Places the subtree rooted at `root` at `depth`, binding its elements to
+/// `limit`
+pub(crate) fn place_subtree(root: &NodeRef, depth: usize, limit: Option<usize>) {
+    let mut stack = vec![(root.clone(), depth)];
+    while let Some((node, depth)) = stack.pop() {
+        let mut node = node.write();
+        node.node_data_mut().depth = depth;
+        if let Some(element) = node.as_any_mut().downcast_mut::<Element>() {
+            element.bind_depth_limit(limit);
+        }
+        let children = node.child_nodes();
+        stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+    }
+}
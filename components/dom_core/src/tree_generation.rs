@@ -0,0 +1,221 @@
+//! Tree mutation generations
+//!
+//! Live collections, the id index and query or geometry caches all need to
+//! know whether the tree changed since they last looked. Rather than each
+//! subsystem hooking the mutation methods itself, a [`Document`](crate::Document)
+//! owns one [`TreeGenerations`] whose counters go up on every change, and
+//! elements bound to it with [`Element::set_tree_generations`] report their
+//! mutations there. The document's live ranges, which must follow those
+//! mutations, are registered there too, as are the hooks told about changes
+//! to the data of its text, comment and CDATA section nodes.
+//!
+//! [`Element::set_tree_generations`]: crate::Element::set_tree_generations
+
+use crate::cdata_section::CDATASection;
+use crate::character_data::{CharacterDataChange, CharacterDataObserver};
+use crate::comment::Comment;
+use crate::element::Element;
+use crate::node::NodeRef;
+use crate::range::LiveRanges;
+use crate::text::Text;
+use parking_lot::RwLock;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Thread-safe reference to a document's TreeGenerations
+pub type TreeGenerationsRef = Arc<TreeGenerations>;
+
+/// Callback run after each tree change with the kind and the new generation
+pub type TreeChangeObserver = Arc<dyn Fn(TreeChange, u64) + Send + Sync>;
+
+/// Kind of mutation that bumped a generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeChange {
+    /// A node was inserted, removed or moved
+    Structure,
+    /// An attribute was set or removed
+    Attributes,
+}
+
+/// Monotonic mutation counters of one document
+#[derive(Default)]
+pub struct TreeGenerations {
+    structure: AtomicU64,
+    attributes: AtomicU64,
+    observers: RwLock<Vec<TreeChangeObserver>>,
+    live_ranges: LiveRanges,
+    character_data_observers: RwLock<Vec<CharacterDataObserver>>,
+}
+
+impl TreeGenerations {
+    /// Creates counters starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates counters wrapped for sharing with elements
+    pub fn new_ref() -> TreeGenerationsRef {
+        Arc::new(Self::new())
+    }
+
+    /// Number of structural mutations so far
+    pub fn structure(&self) -> u64 {
+        self.structure.load(Ordering::Acquire)
+    }
+
+    /// Number of attribute mutations so far
+    pub fn attributes(&self) -> u64 {
+        self.attributes.load(Ordering::Acquire)
+    }
+
+    /// Ranges that follow the document's mutations
+    pub fn live_ranges(&self) -> &LiveRanges {
+        &self.live_ranges
+    }
+
+    /// Registers `observer` to run after every bump
+    ///
+    /// Observers run while the mutated node is still locked, so they should
+    /// only record the change and must not touch the tree.
+    pub fn subscribe(&self, observer: TreeChangeObserver) {
+        self.observers.write().push(observer);
+    }
+
+    /// Registers `observer` to be told about every change to character data
+    /// in the document
+    pub fn observe_character_data(&self, observer: CharacterDataObserver) {
+        self.character_data_observers.write().push(observer);
+    }
+
+    /// Unregisters an observer added with
+    /// [`observe_character_data`](Self::observe_character_data)
+    pub fn unobserve_character_data(&self, observer: &CharacterDataObserver) {
+        self.character_data_observers
+            .write()
+            .retain(|registered| !Arc::ptr_eq(registered, observer));
+    }
+
+    /// Whether any character data observer is registered
+    pub(crate) fn observes_character_data(&self) -> bool {
+        !self.character_data_observers.read().is_empty()
+    }
+
+    /// Tells the character data observers about `change`
+    pub(crate) fn character_data_changed(&self, change: &CharacterDataChange) {
+        let observers = self.character_data_observers.read().clone();
+        for observer in observers {
+            observer(change);
+        }
+    }
+
+    /// Records a mutation, returning the new generation of that kind
+    pub fn bump(&self, change: TreeChange) -> u64 {
+        let counter = match change {
+            TreeChange::Structure => &self.structure,
+            TreeChange::Attributes => &self.attributes,
+        };
+        let generation = counter.fetch_add(1, Ordering::AcqRel) + 1;
+
+        let observers = self.observers.read().clone();
+        for observer in observers {
+            observer(change, generation);
+        }
+        generation
+    }
+}
+
+impl fmt::Debug for TreeGenerations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeGenerations")
+            .field("structure", &self.structure())
+            .field("attributes", &self.attributes())
+            .field("observers", &self.observers.read().len())
+            .field("live_ranges", &self.live_ranges)
+            .field(
+                "character_data_observers",
+                &self.character_data_observers.read().len(),
+            )
+            .finish()
+    }
+}
+
+/// Binds every element and character data node of the subtree rooted at
+/// `root` to `generations`, or unbinds them with `None`
+pub(crate) fn bind_subtree(root: &NodeRef, generations: Option<&TreeGenerationsRef>) {
+    let mut stack = vec![root.clone()];
+    while let Some(node) = stack.pop() {
+        let mut node = node.write();
+        let any = node.as_any_mut();
+        if let Some(element) = any.downcast_mut::<Element>() {
+            element.set_tree_generations(generations);
+        } else if let Some(text) = any.downcast_mut::<Text>() {
+            text.set_tree_generations(generations);
+        } else if let Some(comment) = any.downcast_mut::<Comment>() {
+            comment.set_tree_generations(generations);
+        } else if let Some(cdata) = any.downcast_mut::<CDATASection>() {
+            cdata.set_tree_generations(generations);
+        }
+        stack.extend(node.child_nodes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::Document;
+    use crate::node::new_node_ref;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_bump_counts_per_kind_and_notifies() {
+        let generations = TreeGenerations::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        generations.subscribe(Arc::new(move |change, generation| {
+            log.lock().unwrap().push((change, generation));
+        }));
+
+        assert_eq!(generations.bump(TreeChange::Structure), 1);
+        assert_eq!(generations.bump(TreeChange::Attributes), 1);
+        assert_eq!(generations.bump(TreeChange::Structure), 2);
+
+        assert_eq!(generations.structure(), 2);
+        assert_eq!(generations.attributes(), 1);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                (TreeChange::Structure, 1),
+                (TreeChange::Attributes, 1),
+                (TreeChange::Structure, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_character_data_observers_see_bound_nodes_only() {
+        let document = new_node_ref(Document::new());
+        let root = new_node_ref(Element::new("p"));
+        document.write().append_child(root.clone()).unwrap();
+        let text = new_node_ref(Text::new("Hello"));
+        root.write().append_child(text.clone()).unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let observer: CharacterDataObserver = Arc::new(move |change| {
+            log.lock().unwrap().push(change.old_value.clone());
+        });
+        let generations = document.read().generations().unwrap();
+        generations.observe_character_data(observer.clone());
+
+        text.write().set_text_content("Hi".to_string());
+        root.write().remove_child(text.clone()).unwrap();
+        text.write().set_text_content("Hey".to_string());
+        assert_eq!(*seen.lock().unwrap(), ["Hello"]);
+
+        root.write().append_child(text.clone()).unwrap();
+        generations.unobserve_character_data(&observer);
+        text.write().set_text_content("Ho".to_string());
+        assert_eq!(*seen.lock().unwrap(), ["Hello"]);
+    }
+}
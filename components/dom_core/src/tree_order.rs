@@ -29,6 +29,11 @@ pub enum DocumentPosition {
 /// Returns a bitmask indicating the relationship between the nodes.
 /// Multiple flags can be set (e.g., DISCONNECTED | IMPLEMENTATION_SPECIFIC for attributes).
 ///
+/// Nodes in different trees are `DISCONNECTED | IMPLEMENTATION_SPECIFIC` plus
+/// `PRECEDING` or `FOLLOWING`, decided by the addresses of the two tree
+/// roots. The choice is consistent for as long as both trees live, so
+/// sorting nodes from several trees by this function gives a total order.
+///
 /// # Arguments
 /// * `node` - The reference node
 /// * `other` - The node to compare against
@@ -68,12 +73,27 @@ pub fn compare_document_position(node: &NodeRef, other: &NodeRef) -> u16 {
             }
         }
         None => {
-            // Nodes are disconnected
-            DocumentPosition::Disconnected as u16 | DocumentPosition::ImplementationSpecific as u16
+            // Order whole trees by their roots so every node of one tree
+            // sorts the same way against every node of the other
+            let direction = if root_address(&node_chain) < root_address(&other_chain) {
+                DocumentPosition::Following
+            } else {
+                DocumentPosition::Preceding
+            };
+            DocumentPosition::Disconnected as u16
+                | DocumentPosition::ImplementationSpecific as u16
+                | direction as u16
         }
     }
 }
 
+/// Address of the root at the end of an ancestor chain
+fn root_address(chain: &[NodeRef]) -> usize {
+    chain
+        .last()
+        .map_or(0, |root| Arc::as_ptr(root) as *const () as usize)
+}
+
 /// Check if a node contains another node
 ///
 /// # Arguments
@@ -189,12 +209,12 @@ fn find_child_under_ancestor(ancestor: &NodeRef, node: &NodeRef) -> Option<NodeR
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Document, Element};
+    use crate::{new_node_ref, Document, Element};
     use parking_lot::RwLock;
     use std::sync::Arc;
 
     fn create_element_ref(tag: &str) -> NodeRef {
-        Arc::new(RwLock::new(Box::new(Element::new(tag)) as Box<dyn Node>))
+        Arc::new(RwLock::new(Element::new(tag)))
     }
 
     #[test]
@@ -213,6 +233,58 @@ mod tests {
         assert!(result & DocumentPosition::Disconnected as u16 != 0);
     }
 
+    #[test]
+    fn test_disconnected_nodes_sort_in_a_total_order() {
+        use std::cmp::Ordering;
+
+        let tree = |tag: &str| {
+            let root = new_node_ref(Element::new(tag));
+            let children = [create_element_ref("a"), create_element_ref("b")];
+            for child in &children {
+                root.write().append_child(child.clone()).unwrap();
+            }
+            (root, children)
+        };
+        let (_root1, [a1, b1]) = tree("div");
+        let (_root2, [a2, b2]) = tree("section");
+        let loose = create_element_ref("p");
+
+        let order = |x: &NodeRef, y: &NodeRef| {
+            let position = compare_document_position(x, y);
+            if position == 0 {
+                Ordering::Equal
+            } else if position & DocumentPosition::Following as u16 != 0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        };
+
+        let nodes = [&a1, &b1, &a2, &b2, &loose];
+        for x in nodes {
+            for y in nodes {
+                assert_eq!(order(x, y), order(y, x).reverse());
+            }
+        }
+        let disconnected = compare_document_position(&a1, &b2);
+        let expected = DocumentPosition::Disconnected as u16;
+        let specific = DocumentPosition::ImplementationSpecific as u16;
+        assert_eq!(disconnected & (expected | specific), expected | specific);
+        assert_eq!(order(&a1, &b2), order(&b1, &a2));
+
+        let mut forward: Vec<NodeRef> = [&a1, &b2, &loose, &b1, &a2].map(Arc::clone).to_vec();
+        let mut backward: Vec<NodeRef> = forward.iter().rev().cloned().collect();
+        forward.sort_by(order);
+        backward.sort_by(order);
+        let same = |(x, y): (&NodeRef, &NodeRef)| Arc::ptr_eq(x, y);
+        assert!(forward.iter().zip(&backward).all(same));
+
+        // Each tree stays contiguous and in tree order
+        let index = |node: &NodeRef| forward.iter().position(|n| Arc::ptr_eq(n, node)).unwrap();
+        assert_eq!(index(&b1), index(&a1) + 1);
+        assert_eq!(index(&b2), index(&a2) + 1);
+    }
+
     #[test]
     fn test_compare_document_position_contains() {
         // For now, skip this test - requires proper parent-child relationships
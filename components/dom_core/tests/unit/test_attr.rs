@@ -128,7 +128,7 @@ fn test_attr_owner_element_initially_none() {
 #[test]
 fn test_attr_clone_node() {
     let attr = Attr::new("data-value", "42");
-    let attr_ref = Arc::new(RwLock::new(Box::new(attr) as Box<dyn Node>));
+    let attr_ref = Arc::new(RwLock::new(attr));
 
     let cloned = attr_ref.read().clone_node(false);
 
@@ -141,7 +141,7 @@ fn test_attr_clone_node() {
 fn test_attr_cannot_append_children() {
     let mut attr = Attr::new("id", "test");
     let child_attr = Attr::new("child", "value");
-    let child_ref = Arc::new(RwLock::new(Box::new(child_attr) as Box<dyn Node>));
+    let child_ref = Arc::new(RwLock::new(child_attr));
 
     // Attributes cannot have children
     let result = attr.append_child(child_ref);
@@ -1,6 +1,6 @@
 //! Unit tests for CDATASection node
 
-use dom_core::{CDATASection, Node};
+use dom_core::{CDATASection, CharacterData, Node};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -96,7 +96,7 @@ fn test_cdata_insert_data_invalid_offset() {
     let mut cdata = CDATASection::new("Hello");
     let result = cdata.insert_data(10, " World");
     assert!(result.is_err());
-    assert_eq!(result, Err(DomException::InvalidModificationError));
+    assert_eq!(result, Err(DomException::IndexSizeError));
 }
 
 #[test]
@@ -112,7 +112,7 @@ fn test_cdata_delete_data_invalid_offset() {
     let mut cdata = CDATASection::new("Hello");
     let result = cdata.delete_data(10, 5);
     assert!(result.is_err());
-    assert_eq!(result, Err(DomException::InvalidModificationError));
+    assert_eq!(result, Err(DomException::IndexSizeError));
 }
 
 #[test]
@@ -136,7 +136,7 @@ fn test_cdata_replace_data_invalid_offset() {
     let mut cdata = CDATASection::new("Hello");
     let result = cdata.replace_data(10, 5, "test");
     assert!(result.is_err());
-    assert_eq!(result, Err(DomException::InvalidModificationError));
+    assert_eq!(result, Err(DomException::IndexSizeError));
 }
 
 #[test]
@@ -160,7 +160,7 @@ fn test_cdata_substring_data_invalid_offset() {
     let cdata = CDATASection::new("Hello");
     let result = cdata.substring_data(10, 5);
     assert!(result.is_err());
-    assert_eq!(result, Err(DomException::InvalidModificationError));
+    assert_eq!(result, Err(DomException::IndexSizeError));
 }
 
 #[test]
@@ -208,13 +208,15 @@ fn test_cdata_multiline_content() {
 fn test_cdata_unicode() {
     let cdata = CDATASection::new("Hello 世界 🌍");
     assert_eq!(cdata.data(), "Hello 世界 🌍");
+    // The globe is a surrogate pair, so the length is counted in UTF-16 units
+    assert_eq!(cdata.length(), 11);
 }
 
 #[test]
 fn test_cdata_cannot_have_children() {
     let mut cdata = CDATASection::new("test");
     let child = CDATASection::new("child");
-    let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+    let child_ref = Arc::new(RwLock::new(child));
 
     let result = cdata.append_child(child_ref);
     assert!(result.is_err());
@@ -1,7 +1,11 @@
 //! Unit tests for Document methods (create_attribute, create_attribute_ns, import_node, adopt_node)
 
-use dom_core::node::{Node, NodeRef};
-use dom_core::{Attr, Comment, Document, Element, Text};
+use dom_core::namespaces::{HTML_NAMESPACE, SVG_NAMESPACE};
+use dom_core::node::{new_node_ref, Node, NodeRef};
+use dom_core::{
+    Attr, Comment, Document, DocumentReadyState, Element, ElementRef, HitTestProvider,
+    ReadyStateObserver, Text, TreeChange,
+};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -224,7 +228,7 @@ fn test_import_node_element_shallow() {
     elem.write().set_attribute("id", "test").unwrap();
 
     // Convert ElementRef to NodeRef
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
 
     let imported = doc2.import_node(elem_node.clone(), false).unwrap();
 
@@ -245,7 +249,7 @@ fn test_import_node_element_with_children_shallow() {
     parent.write().append_child(child).unwrap();
 
     // Convert to NodeRef
-    let parent_node: NodeRef = Arc::new(RwLock::new(Box::new(parent.read().clone()) as Box<dyn Node>));
+    let parent_node: NodeRef = Arc::new(RwLock::new(parent.read().clone()));
 
     // Shallow import - no children
     let imported = doc2.import_node(parent_node, false).unwrap();
@@ -262,7 +266,7 @@ fn test_import_node_element_with_children_deep() {
     let parent = doc1.create_element("div").unwrap();
     let child1 = doc1.create_text_node("Text 1");
     let child2_elem = doc1.create_element("span").unwrap();
-    let child2: NodeRef = Arc::new(RwLock::new(Box::new(child2_elem.read().clone()) as Box<dyn Node>));
+    let child2: NodeRef = Arc::new(RwLock::new(child2_elem.read().clone()));
     let child3 = doc1.create_text_node("Text 2");
 
     parent.write().append_child(child1).unwrap();
@@ -270,7 +274,7 @@ fn test_import_node_element_with_children_deep() {
     parent.write().append_child(child3).unwrap();
 
     // Convert to NodeRef
-    let parent_node: NodeRef = Arc::new(RwLock::new(Box::new(parent.read().clone()) as Box<dyn Node>));
+    let parent_node: NodeRef = Arc::new(RwLock::new(parent.read().clone()));
 
     // Deep import - with children
     let imported = doc2.import_node(parent_node, true).unwrap();
@@ -309,14 +313,14 @@ fn test_import_node_deep_nested_tree() {
     let level2_elem = doc1.create_element("level2").unwrap();
     let text = doc1.create_text_node("Deep text");
 
-    let level2: NodeRef = Arc::new(RwLock::new(Box::new(level2_elem.read().clone()) as Box<dyn Node>));
-    let level1: NodeRef = Arc::new(RwLock::new(Box::new(level1_elem.read().clone()) as Box<dyn Node>));
+    let level2: NodeRef = Arc::new(RwLock::new(level2_elem.read().clone()));
+    let level1: NodeRef = Arc::new(RwLock::new(level1_elem.read().clone()));
 
     level2.write().append_child(text).unwrap();
     level1.write().append_child(level2).unwrap();
     root.write().append_child(level1).unwrap();
 
-    let root_node: NodeRef = Arc::new(RwLock::new(Box::new(root.read().clone()) as Box<dyn Node>));
+    let root_node: NodeRef = Arc::new(RwLock::new(root.read().clone()));
 
     // Deep import
     let imported = doc2.import_node(root_node, true).unwrap();
@@ -358,7 +362,7 @@ fn test_adopt_node_element() {
     elem.write().set_attribute("id", "test").unwrap();
 
     // Convert to NodeRef
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
 
     let adopted = doc2.adopt_node(elem_node.clone()).unwrap();
 
@@ -374,12 +378,12 @@ fn test_adopt_node_with_children() {
     let parent = doc1.create_element("parent").unwrap();
     let child1 = doc1.create_text_node("Child 1");
     let child2_elem = doc1.create_element("child2").unwrap();
-    let child2: NodeRef = Arc::new(RwLock::new(Box::new(child2_elem.read().clone()) as Box<dyn Node>));
+    let child2: NodeRef = Arc::new(RwLock::new(child2_elem.read().clone()));
 
     parent.write().append_child(child1).unwrap();
     parent.write().append_child(child2).unwrap();
 
-    let parent_node: NodeRef = Arc::new(RwLock::new(Box::new(parent.read().clone()) as Box<dyn Node>));
+    let parent_node: NodeRef = Arc::new(RwLock::new(parent.read().clone()));
 
     let adopted = doc2.adopt_node(parent_node.clone()).unwrap();
 
@@ -406,14 +410,9 @@ fn test_adopt_node_removes_from_old_parent() {
     // Adopt the child (same instance that was appended)
     let result = doc2.adopt_node(child);
 
-    // Verify adoption succeeds
-    assert!(result.is_ok());
-
-    // Note: Due to the current implementation's architecture where Element.append_child
-    // creates a new NodeRef for the parent (as_node_ref clones), the parent reference
-    // in the child doesn't point to the actual parent Element. This means remove_child
-    // is called on a different Arc. This is a known limitation.
-    // In a production implementation, parent tracking would need to be redesigned.
+    // Verify adoption succeeds and detaches the child
+    assert!(parent.read().child_nodes().is_empty());
+    assert!(result.unwrap().read().parent_node().is_none());
 }
 
 #[test]
@@ -439,12 +438,12 @@ fn test_adopt_node_nested_tree() {
     let child1_elem = doc1.create_element("child1").unwrap();
     let grandchild = doc1.create_text_node("Grandchild");
 
-    let child1: NodeRef = Arc::new(RwLock::new(Box::new(child1_elem.read().clone()) as Box<dyn Node>));
+    let child1: NodeRef = Arc::new(RwLock::new(child1_elem.read().clone()));
 
     child1.write().append_child(grandchild).unwrap();
     root.write().append_child(child1).unwrap();
 
-    let root_node: NodeRef = Arc::new(RwLock::new(Box::new(root.read().clone()) as Box<dyn Node>));
+    let root_node: NodeRef = Arc::new(RwLock::new(root.read().clone()));
 
     // Adopt entire tree
     let adopted = doc2.adopt_node(root_node).unwrap();
@@ -460,7 +459,7 @@ fn test_adopt_node_already_in_same_document() {
     let mut doc = Document::new();
 
     let elem = doc.create_element("div").unwrap();
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
 
     // Adopting node that's already in same document
     let adopted = doc.adopt_node(elem_node.clone()).unwrap();
@@ -492,7 +491,7 @@ fn test_import_then_append() {
     let mut doc2 = Document::new();
 
     let elem = doc1.create_element("imported").unwrap();
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
     let imported = doc2.import_node(elem_node, false).unwrap();
 
     let parent = doc2.create_element("parent").unwrap();
@@ -507,7 +506,7 @@ fn test_adopt_then_append() {
     let mut doc2 = Document::new();
 
     let elem = doc1.create_element("adopted").unwrap();
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
     let adopted = doc2.adopt_node(elem_node).unwrap();
 
     let parent = doc2.create_element("parent").unwrap();
@@ -526,17 +525,66 @@ fn test_get_element_by_id_found() {
     let elem = doc.create_element("div").unwrap();
     elem.write().set_attribute("id", "my-element").unwrap();
 
-    // Must set document element for id_map to work
+    // Only elements in the document tree are found
+    assert!(doc.get_element_by_id("my-element").is_none());
+    let node: NodeRef = elem.clone();
+    doc.append_child(node.clone()).unwrap();
+    let found = doc.get_element_by_id("my-element").unwrap();
+    assert!(Arc::ptr_eq(&found, &elem));
+
+    elem.write().set_attribute("id", "renamed").unwrap();
+    assert!(doc.get_element_by_id("my-element").is_none());
+    assert!(doc.get_element_by_id("renamed").is_some());
+
+    // Descendants join and leave with their ancestor
+    let child = doc.create_element("span").unwrap();
+    child.write().set_attribute("id", "inner").unwrap();
+    elem.write().append_child(child.clone()).unwrap();
+    assert!(Arc::ptr_eq(
+        &doc.get_element_by_id("inner").unwrap(),
+        &child
+    ));
+
+    doc.remove_child(node).unwrap();
+    assert!(doc.get_element_by_id("renamed").is_none());
+    assert!(doc.get_element_by_id("inner").is_none());
+}
+
+#[test]
+fn test_get_element_by_id_tracks_namespaced_id() {
+    let mut doc = Document::new();
+    let elem = doc.create_element("div").unwrap();
+    doc.set_document_element(elem.clone());
+
+    elem.write().set_attribute_ns(None, "id", "x").unwrap();
+    let found = doc.get_element_by_id("x").unwrap();
+    assert!(Arc::ptr_eq(&found, &elem));
+
+    elem.write().remove_attribute_ns(None, "id").unwrap();
+    assert!(doc.get_element_by_id("x").is_none());
+    assert_eq!(elem.read().id(), None);
+}
+
+#[test]
+fn test_get_element_by_id_tracks_attr_nodes() {
+    let mut doc = Document::new();
+    let elem = doc.create_element("div").unwrap();
     doc.set_document_element(elem.clone());
 
-    // Note: getElementById uses the id_map which is populated during create_element
-    // when the element has an id. We set the id after creation, so we need to
-    // re-register. For this test, we verify the mechanism works.
-    let found = doc.get_element_by_id("my-element");
-    // Note: The current implementation registers IDs at element creation,
-    // so we need to create an element with ID already set or re-register.
-    // This tests the API is callable.
-    assert!(found.is_none() || found.is_some());
+    let attr = doc.create_attribute("id").unwrap();
+    attr.write().set_value("plain");
+    elem.write().set_attribute_node(attr.clone()).unwrap();
+    assert!(doc.get_element_by_id("plain").is_some());
+
+    // Editing the attached node renames the element
+    attr.write().set_value("edited");
+    assert!(doc.get_element_by_id("plain").is_none());
+    assert!(doc.get_element_by_id("edited").is_some());
+
+    let attr = Arc::new(RwLock::new(Attr::new("id", "namespaced")));
+    elem.write().set_attribute_node_ns(attr).unwrap();
+    assert!(doc.get_element_by_id("edited").is_none());
+    assert!(doc.get_element_by_id("namespaced").is_some());
 }
 
 #[test]
@@ -669,7 +717,7 @@ fn test_create_range_set_boundaries() {
 
     // Create a text node to use as container
     let text = Text::new("Hello World");
-    let text_ref: NodeRef = Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>));
+    let text_ref: NodeRef = Arc::new(RwLock::new(text));
 
     range.set_start(text_ref.clone(), 0).unwrap();
     range.set_end(text_ref, 5).unwrap();
@@ -685,7 +733,7 @@ fn test_create_range_collapse() {
     let mut range = doc.create_range();
 
     let text = Text::new("Test");
-    let text_ref: NodeRef = Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>));
+    let text_ref: NodeRef = Arc::new(RwLock::new(text));
 
     range.set_start(text_ref.clone(), 1).unwrap();
     range.set_end(text_ref, 3).unwrap();
@@ -698,3 +746,431 @@ fn test_create_range_collapse() {
     assert_eq!(range.start_offset(), 1);
     assert_eq!(range.end_offset(), 1);
 }
+
+#[test]
+fn test_create_range_starts_at_document() {
+    let doc = new_node_ref(Document::new());
+    let comment: NodeRef = Arc::new(RwLock::new(Comment::new("head")));
+    let html = element_node("html");
+    doc.write().append_child(comment.clone()).unwrap();
+    doc.write().append_child(html.clone()).unwrap();
+
+    let mut range = doc
+        .read()
+        .as_any()
+        .downcast_ref::<Document>()
+        .unwrap()
+        .create_range();
+    assert!(Arc::ptr_eq(range.start_container(), &doc));
+    assert!(Arc::ptr_eq(range.end_container(), &doc));
+    assert!(range.collapsed());
+
+    range.set_start(comment.clone(), 2).unwrap();
+    range.set_end(html.clone(), 0).unwrap();
+    assert!(Arc::ptr_eq(range.start_container(), &comment));
+    assert!(Arc::ptr_eq(range.end_container(), &html));
+    assert!(!range.collapsed());
+}
+
+#[test]
+fn test_create_range_on_unshared_document() {
+    let doc = Document::new();
+    let first = doc.create_range();
+    let second = doc.create_range();
+    assert_eq!(
+        first.start_container().read().node_type(),
+        NodeType::Document
+    );
+    assert!(Arc::ptr_eq(first.start_container(), second.end_container()));
+
+    // Once shared, the document itself is the boundary container
+    let doc = doc.into_ref();
+    let node: NodeRef = doc.clone();
+    let range = doc.read().create_range();
+    assert!(Arc::ptr_eq(range.start_container(), &node));
+}
+
+#[test]
+fn test_create_live_range_follows_normalize() {
+    let mut doc = Document::new();
+    let p: NodeRef = doc.create_element("p").unwrap();
+    let texts: Vec<NodeRef> = ["", "ab", "", "cd", "ef"]
+        .into_iter()
+        .map(|data| doc.create_text_node(data))
+        .collect();
+    for text in &texts {
+        p.write().append_child(text.clone()).unwrap();
+    }
+    let set = |start: (&NodeRef, usize), end: (&NodeRef, usize)| {
+        let range = doc.create_live_range();
+        range.write().set_start(start.0.clone(), start.1).unwrap();
+        range.write().set_end(end.0.clone(), end.1).unwrap();
+        range
+    };
+    // In the removed empty text, and between "cd" and "ef"
+    let outer = set((&texts[0], 0), (&p, 4));
+    // Inside "cd", and in the empty text merged into "ab"
+    let inner = set((&texts[2], 0), (&texts[3], 1));
+
+    p.write().normalize();
+
+    let ab = &texts[1];
+    assert_eq!(p.read().text_content().as_deref(), Some("abcdef"));
+    let outer = outer.read();
+    assert!(Arc::ptr_eq(outer.start_container(), &p));
+    assert_eq!(outer.start_offset(), 0);
+    assert!(Arc::ptr_eq(outer.end_container(), ab));
+    assert_eq!(outer.end_offset(), 4);
+    let inner = inner.read();
+    assert!(Arc::ptr_eq(inner.start_container(), ab));
+    assert_eq!(inner.start_offset(), 2);
+    assert!(Arc::ptr_eq(inner.end_container(), ab));
+    assert_eq!(inner.end_offset(), 3);
+}
+
+#[test]
+fn test_live_range_offsets_after_normalize_count_utf16() {
+    let mut doc = Document::new();
+    let p: NodeRef = doc.create_element("p").unwrap();
+    let first = doc.create_text_node("h\u{e9}\u{1f600}");
+    let second = doc.create_text_node("x");
+    p.write().append_child(first.clone()).unwrap();
+    p.write().append_child(second.clone()).unwrap();
+    let range = doc.create_live_range();
+    range.write().set_start(first.clone(), 4).unwrap();
+    range.write().set_end(second.clone(), 1).unwrap();
+
+    p.write().normalize();
+
+    // "h", "\u{e9}" and the surrogate pair make 4 UTF-16 code units
+    let range = range.read();
+    assert!(Arc::ptr_eq(range.start_container(), &first));
+    assert_eq!(range.start_offset(), 4);
+    assert!(Arc::ptr_eq(range.end_container(), &first));
+    assert_eq!(range.end_offset(), 5);
+}
+
+// ============================================================================
+// Tests for element_from_point() / elements_from_point()
+// ============================================================================
+
+/// Hit-test provider with a fixed 100x100 box stacking `button` over `body`
+#[derive(Debug)]
+struct BoxHitTest {
+    body: ElementRef,
+    button: ElementRef,
+}
+
+impl HitTestProvider for BoxHitTest {
+    fn elements_at(&self, x: f64, y: f64) -> Vec<ElementRef> {
+        if !(0.0..100.0).contains(&x) || !(0.0..100.0).contains(&y) {
+            return Vec::new();
+        }
+        if x < 50.0 {
+            vec![self.button.clone(), self.body.clone()]
+        } else {
+            vec![self.body.clone()]
+        }
+    }
+}
+
+#[test]
+fn test_element_from_point_uses_provider() {
+    let mut doc = Document::new();
+    let body = doc.create_element("body").unwrap();
+    let button = doc.create_element("button").unwrap();
+
+    // No provider installed: nothing can be hit
+    assert!(doc.element_from_point(10.0, 10.0).is_none());
+    assert!(doc.elements_from_point(10.0, 10.0).is_empty());
+
+    doc.set_hit_test_provider(Some(Arc::new(BoxHitTest {
+        body: body.clone(),
+        button: button.clone(),
+    })));
+
+    let hit = doc.element_from_point(10.0, 10.0).unwrap();
+    assert!(Arc::ptr_eq(&hit, &button));
+    let stack = doc.elements_from_point(10.0, 10.0);
+    assert_eq!(stack.len(), 2);
+    assert!(Arc::ptr_eq(&stack[0], &button));
+    assert!(Arc::ptr_eq(&stack[1], &body));
+
+    assert!(Arc::ptr_eq(
+        &doc.element_from_point(75.0, 10.0).unwrap(),
+        &body
+    ));
+    assert!(doc.element_from_point(150.0, 10.0).is_none());
+
+    doc.set_hit_test_provider(None);
+    assert!(doc.element_from_point(10.0, 10.0).is_none());
+}
+
+// ============================================================================
+// Tests for create_element_ns()
+// ============================================================================
+
+#[test]
+fn test_create_element_ns_svg_prefixed() {
+    let mut doc = Document::new();
+    let rect = doc
+        .create_element_ns(Some(SVG_NAMESPACE), "svg:rect")
+        .unwrap();
+
+    let rect = rect.read();
+    assert_eq!(rect.namespace_uri(), Some(SVG_NAMESPACE));
+    assert_eq!(rect.prefix(), Some("svg"));
+    assert_eq!(rect.local_name(), "rect");
+    assert_eq!(rect.tag_name(), "svg:rect");
+
+    let gradient = doc
+        .create_element_ns(Some(SVG_NAMESPACE), "linearGradient")
+        .unwrap();
+    assert_eq!(gradient.read().prefix(), None);
+    assert_eq!(gradient.read().local_name(), "linearGradient");
+}
+
+#[test]
+fn test_create_element_ns_rejects_invalid_names() {
+    use DomException::{InvalidCharacterError, NamespaceError};
+    const SVG: Option<&str> = Some(SVG_NAMESPACE);
+
+    let mut doc = Document::new();
+    let cases = [
+        // A prefix needs a namespace
+        (None, "svg:rect", NamespaceError),
+        // The empty string is not a namespace
+        (Some(""), "rect", NamespaceError),
+        (SVG, "xml:rect", NamespaceError),
+        (SVG, "xmlns", NamespaceError),
+        (SVG, "a:b:c", NamespaceError),
+        (SVG, "1rect", InvalidCharacterError),
+        (SVG, "", InvalidCharacterError),
+    ];
+    for (namespace, name, expected) in cases {
+        assert_eq!(
+            doc.create_element_ns(namespace, name).unwrap_err(),
+            expected,
+            "{:?} {:?}",
+            namespace,
+            name
+        );
+    }
+
+    let plain = doc.create_element_ns(None, "div").unwrap();
+    assert_eq!(plain.read().namespace_uri(), None);
+    assert_eq!(plain.read().local_name(), "div");
+    assert_eq!(plain.read().tag_name(), "div");
+
+    let html = doc.create_element_ns(Some(HTML_NAMESPACE), "div").unwrap();
+    assert_eq!(html.read().tag_name(), "DIV");
+}
+
+fn element_node(tag: &str) -> NodeRef {
+    new_node_ref(Element::new(tag))
+}
+
+fn set_attr(node: &NodeRef, name: &str, value: &str) {
+    let mut node = node.write();
+    let element = node.as_any_mut().downcast_mut::<Element>().unwrap();
+    element.set_attribute(name, value).unwrap();
+}
+
+fn remove_attr(node: &NodeRef, name: &str) {
+    let mut node = node.write();
+    let element = node.as_any_mut().downcast_mut::<Element>().unwrap();
+    element.remove_attribute(name).unwrap();
+}
+
+#[test]
+fn test_structure_generation_tracks_tree_mutations() {
+    let mut doc = Document::new();
+    let root = doc.create_element("div").unwrap();
+    let (first, second, item) = (
+        element_node("section"),
+        element_node("section"),
+        element_node("p"),
+    );
+
+    let start = doc.structure_generation();
+    root.write().append_child(first.clone()).unwrap();
+    root.write().append_child(second.clone()).unwrap();
+    assert_eq!(doc.structure_generation(), start + 2);
+
+    // Inserted elements report their own mutations
+    first.write().append_child(item.clone()).unwrap();
+    let before_move = doc.structure_generation();
+    assert_eq!(before_move, start + 3);
+    second.write().append_child(item.clone()).unwrap();
+    assert!(doc.structure_generation() > before_move);
+
+    let before_remove = doc.structure_generation();
+    second.write().remove_child(item.clone()).unwrap();
+    assert!(doc.structure_generation() > before_remove);
+
+    // Attribute changes leave the structure counter alone
+    let settled = doc.structure_generation();
+    set_attr(&item, "class", "note");
+    root.write().set_attribute("id", "main").unwrap();
+    assert_eq!(doc.structure_generation(), settled);
+}
+
+#[test]
+fn test_removed_subtrees_stop_counting() {
+    let mut doc = Document::new();
+    let root = doc.create_element("div").unwrap();
+    let item = element_node("ul");
+    root.write().append_child(item.clone()).unwrap();
+
+    root.write().remove_child(item.clone()).unwrap();
+    let detached = doc.structure_generation();
+    item.write().append_child(element_node("li")).unwrap();
+    set_attr(&item, "class", "list");
+    assert_eq!(doc.structure_generation(), detached);
+    assert_eq!(doc.attribute_generation(), 0);
+
+    // Elements removed from the document itself stop counting too
+    let html: NodeRef = doc.create_element("html").unwrap();
+    doc.append_child(html.clone()).unwrap();
+    doc.remove_child(html.clone()).unwrap();
+    let removed = doc.structure_generation();
+    html.write().append_child(element_node("body")).unwrap();
+    assert_eq!(doc.structure_generation(), removed);
+}
+
+#[test]
+fn test_normalize_and_split_text_count_as_structure_changes() {
+    let mut doc = Document::new();
+    let root = doc.create_element("p").unwrap();
+    let text = new_node_ref(Text::new("hello"));
+    root.write().append_child(text.clone()).unwrap();
+
+    let start = doc.structure_generation();
+    {
+        let mut guard = text.write();
+        let text = guard.as_any_mut().downcast_mut::<Text>().unwrap();
+        text.split_text(2).unwrap();
+    }
+    assert_eq!(root.read().child_nodes().len(), 2);
+    assert_eq!(doc.structure_generation(), start + 1);
+
+    root.write().normalize();
+    assert_eq!(root.read().child_nodes().len(), 1);
+    assert_eq!(doc.structure_generation(), start + 2);
+
+    // Nothing left to merge
+    root.write().normalize();
+    assert_eq!(doc.structure_generation(), start + 2);
+}
+
+#[test]
+fn test_fragment_mutations_are_counted() {
+    let mut doc = Document::new();
+    let fragment = doc.create_document_fragment();
+    assert_eq!(fragment.read().node_type(), NodeType::DocumentFragment);
+
+    let item = element_node("li");
+    let start = doc.structure_generation();
+    fragment.write().append_child(item.clone()).unwrap();
+    assert_eq!(doc.structure_generation(), start + 1);
+
+    // The inserted element is bound to the document
+    item.write().append_child(element_node("span")).unwrap();
+    assert_eq!(doc.structure_generation(), start + 2);
+
+    fragment.write().remove_child(item.clone()).unwrap();
+    assert_eq!(doc.structure_generation(), start + 3);
+}
+
+#[test]
+fn test_document_splices_fragments() {
+    let mut doc = Document::new();
+    let fragment = doc.create_document_fragment();
+    let html = element_node("html");
+    fragment.write().append_child(html.clone()).unwrap();
+
+    doc.append_child(fragment.clone()).unwrap();
+    assert!(fragment.read().child_nodes().is_empty());
+    assert_eq!(doc.child_nodes().len(), 1);
+    let root: NodeRef = doc.document_element().unwrap();
+    assert!(Arc::ptr_eq(&root, &html));
+
+    // A second element would not fit, so nothing is moved
+    let body = element_node("body");
+    fragment.write().append_child(body).unwrap();
+    let result = doc.insert_before(fragment.clone(), Some(html));
+    assert_eq!(result.err(), Some(DomException::HierarchyRequestError));
+    assert_eq!(fragment.read().child_nodes().len(), 1);
+}
+
+#[test]
+fn test_attribute_generation_tracks_attribute_changes() {
+    let mut doc = Document::new();
+    let root = doc.create_element("div").unwrap();
+    let child = element_node("span");
+    root.write().append_child(child.clone()).unwrap();
+
+    let seen = Arc::new(RwLock::new(Vec::new()));
+    let log = seen.clone();
+    doc.subscribe_tree_changes(Arc::new(move |change, _| log.write().push(change)));
+
+    let start = doc.attribute_generation();
+    root.write().set_attribute("title", "x").unwrap();
+    set_attr(&child, "data-id", "1");
+    assert_eq!(doc.attribute_generation(), start + 2);
+
+    remove_attr(&child, "data-id");
+    assert_eq!(doc.attribute_generation(), start + 3);
+
+    // Removing a missing attribute changes nothing
+    remove_attr(&child, "data-id");
+    assert_eq!(doc.attribute_generation(), start + 3);
+    assert_eq!(seen.read().len(), 3);
+    assert!(seen.read().iter().all(|c| *c == TreeChange::Attributes));
+}
+
+/// Records the states a document reports
+#[derive(Debug, Default)]
+struct ReadyStateLog(RwLock<Vec<DocumentReadyState>>);
+
+impl ReadyStateObserver for ReadyStateLog {
+    fn ready_state_changed(&self, state: DocumentReadyState) {
+        self.0.write().push(state);
+    }
+}
+
+#[test]
+fn test_ready_state_only_moves_forward() {
+    use DocumentReadyState::{Complete, Interactive, Loading};
+
+    let mut doc = Document::new();
+    let log = Arc::new(ReadyStateLog::default());
+    doc.set_ready_state_observer(Some(log.clone()));
+    assert_eq!(doc.ready_state(), Loading);
+    assert_eq!(doc.ready_state().as_str(), "loading");
+
+    doc.set_ready_state(Interactive).unwrap();
+    doc.set_ready_state(Interactive).unwrap();
+    doc.set_ready_state(Complete).unwrap();
+    assert_eq!(doc.ready_state().to_string(), "complete");
+    assert_eq!(
+        doc.set_ready_state(Loading),
+        Err(DomException::InvalidStateError)
+    );
+    assert_eq!(*log.0.read(), [Interactive, Complete]);
+
+    // Skipping ahead still reports the state in between
+    let mut skipping = Document::new();
+    let log = Arc::new(ReadyStateLog::default());
+    skipping.set_ready_state_observer(Some(log.clone()));
+    skipping.set_ready_state(Complete).unwrap();
+    assert_eq!(*log.0.read(), [Interactive, Complete]);
+
+    // Without the hook, transitions are not reported
+    let mut detached = Document::new();
+    let log = Arc::new(ReadyStateLog::default());
+    detached.set_ready_state_observer(Some(log.clone()));
+    detached.set_ready_state_observer(None);
+    detached.set_ready_state(Complete).unwrap();
+    assert!(log.0.read().is_empty());
+}
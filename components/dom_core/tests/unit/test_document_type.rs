@@ -9,7 +9,7 @@ use std::sync::Arc;
 /// Helper to create a DocumentType node reference
 fn create_doctype_ref(name: &str, public_id: &str, system_id: &str) -> NodeRef {
     let doctype = DocumentType::new(name, public_id, system_id);
-    Arc::new(RwLock::new(Box::new(doctype) as Box<dyn Node>))
+    Arc::new(RwLock::new(doctype))
 }
 
 #[test]
@@ -2,6 +2,7 @@
 
 use dom_core::attr::Attr;
 use dom_core::element::{Element, ElementRef};
+use dom_core::XLINK_NAMESPACE as XLINK;
 use dom_types::DomException;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -107,6 +108,7 @@ fn test_set_attribute_node_returns_old_attr() {
     let old_attr_lock = old_attr_ref.read();
     assert_eq!(old_attr_lock.name(), "data-value");
     assert_eq!(old_attr_lock.value(), "original");
+    assert!(old_attr_lock.owner_element().is_none());
 }
 
 #[test]
@@ -261,3 +263,96 @@ fn test_remove_attribute_then_get_attribute_node() {
     let element = element_ref.read();
     assert!(element.get_attribute_node("temp").is_none());
 }
+
+#[test]
+fn test_attr_nodes_yields_owned_nodes_in_order() {
+    let element_ref = create_element_with_ref("a");
+    {
+        let mut element = element_ref.write();
+        element.set_attribute("id", "home").unwrap();
+        element
+            .set_attribute_ns(Some(XLINK), "xlink:href", "#top")
+            .unwrap();
+        element.set_attribute("class", "nav").unwrap();
+    }
+
+    let attrs: Vec<_> = element_ref.read().attr_nodes().collect();
+
+    let entries: Vec<_> = attrs
+        .iter()
+        .map(|attr| {
+            let attr = attr.read();
+            let namespace = attr.namespace_uri().map(str::to_string);
+            (attr.name().to_string(), attr.value().to_string(), namespace)
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("id".into(), "home".into(), None),
+            ("xlink:href".into(), "#top".into(), Some(XLINK.into())),
+            ("class".into(), "nav".into(), None),
+        ]
+    );
+    for attr in &attrs {
+        let owner = attr.read().owner_element().unwrap();
+        assert!(Arc::ptr_eq(&owner, &element_ref));
+    }
+}
+
+#[test]
+fn test_attr_nodes_edits_reflect_on_element() {
+    let element_ref = create_element_with_ref("a");
+    element_ref.write().set_attribute("class", "nav").unwrap();
+    element_ref
+        .write()
+        .set_attribute_ns(Some(XLINK), "xlink:href", "#top")
+        .unwrap();
+
+    let attrs: Vec<_> = element_ref.read().attr_nodes().collect();
+    attrs[0].write().set_value("nav active");
+    attrs[1].write().set_value("#bottom");
+
+    let element = element_ref.read();
+    assert_eq!(element.get_attribute("class"), Some("nav active"));
+    assert_eq!(element.class_list(), ["nav", "active"]);
+    assert_eq!(
+        element.get_attribute_ns(Some(XLINK), "href").as_deref(),
+        Some("#bottom")
+    );
+}
+
+#[test]
+fn test_attr_set_value_after_removal_does_not_restore() {
+    let element_ref = create_element_with_ref("div");
+    element_ref.write().set_attribute("title", "old").unwrap();
+    let attr = element_ref.read().get_attribute_node("title").unwrap();
+
+    element_ref.write().remove_attribute("title").unwrap();
+    attr.write().set_value("new");
+
+    assert_eq!(attr.read().value(), "new");
+    assert!(attr.read().owner_element().is_none());
+    assert!(!element_ref.read().has_attribute("title"));
+
+    // Re-adding the attribute does not reattach the detached node
+    element_ref.write().set_attribute("title", "again").unwrap();
+    attr.write().set_value("newer");
+    assert_eq!(element_ref.read().get_attribute("title"), Some("again"));
+}
+
+#[test]
+fn test_attr_set_value_waits_for_owner_lock() {
+    let element_ref = create_element_with_ref("div");
+    element_ref.write().set_attribute("title", "old").unwrap();
+    let attr = element_ref.read().get_attribute_node("title").unwrap();
+
+    let guard = element_ref.read();
+    let writer = std::thread::spawn(move || attr.write().set_value("new"));
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert_eq!(guard.get_attribute("title"), Some("old"));
+    drop(guard);
+
+    writer.join().unwrap();
+    assert_eq!(element_ref.read().get_attribute("title"), Some("new"));
+}
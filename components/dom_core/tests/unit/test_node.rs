@@ -1,16 +1,14 @@
 //! Unit tests for Node trait and base implementation
 
 use dom_core::node::{Node, NodeData, NodeRef};
-use dom_core::Element;
+use dom_core::{new_node_ref, Element};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
 use std::sync::{Arc, Weak};
 
 fn create_element_node(tag: &str) -> NodeRef {
-    let node_ref: NodeRef = Arc::new(RwLock::new(Box::new(Element::new(tag)) as Box<dyn Node>));
     // Set self_node_ref so that append_child can set correct parent references
-    node_ref.write().node_data_mut().set_self_node_ref(Arc::downgrade(&node_ref));
-    node_ref
+    new_node_ref(Element::new(tag))
 }
 
 #[test]
@@ -48,6 +46,33 @@ fn test_remove_child() {
     assert_eq!(parent.read().child_nodes().len(), 0);
 }
 
+#[test]
+fn test_remove_child_matches_identity_only() {
+    let parent = create_element_node("div");
+    let child = create_element_node("span");
+    child.write().set_text_content("same".to_string());
+    parent.write().append_child(child.clone()).unwrap();
+
+    let stranger = create_element_node("p");
+    assert_eq!(
+        parent.write().remove_child(stranger).unwrap_err(),
+        DomException::NotFoundError
+    );
+
+    // A deep clone is structurally equal but a different node
+    let twin = child.read().clone_node(true);
+    assert!(twin.read().is_equal_node(&*child.read()));
+    assert_eq!(
+        parent.write().remove_child(twin).unwrap_err(),
+        DomException::NotFoundError
+    );
+    assert_eq!(parent.read().child_nodes().len(), 1);
+
+    let removed = parent.write().remove_child(child.clone()).unwrap();
+    assert!(Arc::ptr_eq(&removed, &child));
+    assert!(parent.read().child_nodes().is_empty());
+}
+
 #[test]
 fn test_parent_child_relationship() {
     let parent = create_element_node("div");
@@ -169,10 +194,43 @@ fn test_contains() {
     parent.write().append_child(child.clone()).unwrap();
 
     // Grandparent contains child
-    assert!(grandparent.read().contains(&**child.read()));
+    assert!(grandparent.read().contains(&*child.read()));
 
     // Child does not contain grandparent
-    assert!(!child.read().contains(&**grandparent.read()));
+    assert!(!child.read().contains(&*grandparent.read()));
+}
+
+#[test]
+fn test_contains_deep_chain() {
+    const DEPTH: usize = 10_000;
+
+    // Built bottom-up so each append only checks a detached parent
+    let mut chain = vec![create_element_node("p")];
+    for _ in 1..DEPTH {
+        let parent = create_element_node("div");
+        parent
+            .write()
+            .append_child(chain.last().unwrap().clone())
+            .unwrap();
+        chain.push(parent);
+    }
+    let leaf = chain[0].clone();
+    let root = chain[DEPTH - 1].clone();
+    let middle = chain[DEPTH / 2].clone();
+    let stranger = create_element_node("span");
+
+    assert!(root.read().contains(&*leaf.read()));
+    assert!(root.read().contains(&*middle.read()));
+    assert!(root
+        .read()
+        .node_data()
+        .contains(&*leaf.read() as *const dyn Node));
+    assert!(!leaf.read().contains(&*root.read()));
+    assert!(!root.read().contains(&*stranger.read()));
+
+    // Release the root first so the chain is freed one level at a time
+    drop((leaf, root, middle));
+    while chain.pop().is_some() {}
 }
 
 #[test]
@@ -191,3 +249,128 @@ fn test_node_reparenting() {
     assert_eq!(parent1.read().child_nodes().len(), 0);
     assert_eq!(parent2.read().child_nodes().len(), 1);
 }
+
+fn create_text_node(data: &str) -> NodeRef {
+    Arc::new(RwLock::new(dom_core::Text::new(data)))
+}
+
+fn child_values(node: &NodeRef) -> Vec<Option<String>> {
+    node.read()
+        .child_nodes()
+        .iter()
+        .map(|child| child.read().node_value().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_normalize_merges_adjacent_text() {
+    let parent = create_element_node("p");
+    let first = create_text_node("Hello");
+    let empty = create_text_node("");
+    let second = create_text_node(", ");
+    let third = create_text_node("world");
+    for child in [&first, &empty, &second, &third] {
+        parent.write().append_child(child.clone()).unwrap();
+    }
+
+    parent.write().normalize();
+
+    assert_eq!(child_values(&parent), [Some("Hello, world".to_string())]);
+    assert!(Arc::ptr_eq(&parent.read().child_nodes()[0], &first));
+    for removed in [&empty, &second, &third] {
+        assert!(removed.read().parent_node().is_none());
+    }
+    assert!(first.read().parent_node().is_some());
+}
+
+#[test]
+fn test_normalize_recurses_and_keeps_runs_apart() {
+    let root = create_element_node("div");
+    let inner = create_element_node("span");
+    inner.write().append_child(create_text_node("")).unwrap();
+    inner.write().append_child(create_text_node("a")).unwrap();
+    inner.write().append_child(create_text_node("b")).unwrap();
+    for child in [
+        create_text_node("x"),
+        create_text_node("y"),
+        inner.clone(),
+        create_text_node("z"),
+    ] {
+        root.write().append_child(child).unwrap();
+    }
+
+    root.write().normalize();
+
+    assert_eq!(
+        child_values(&root),
+        [Some("xy".to_string()), None, Some("z".to_string())]
+    );
+    assert_eq!(child_values(&inner), [Some("ab".to_string())]);
+
+    let empty = create_element_node("div");
+    empty.write().append_child(create_text_node("")).unwrap();
+    empty.write().normalize();
+    assert!(empty.read().child_nodes().is_empty());
+}
+
+#[test]
+fn test_debug_tree() {
+    let root = create_element_node("div");
+    {
+        let mut guard = root.write();
+        let element = guard.as_any_mut().downcast_mut::<Element>().unwrap();
+        element.set_attribute("id", "main").unwrap();
+        element.set_attribute("class", "container wide").unwrap();
+    }
+
+    let link = create_element_node("a");
+    {
+        let mut guard = link.write();
+        let element = guard.as_any_mut().downcast_mut::<Element>().unwrap();
+        element.set_attribute("href", "/home").unwrap();
+    }
+    let text: NodeRef = Arc::new(RwLock::new(dom_core::Text::new(
+        "a long run of text that will not fit on one line",
+    )));
+    link.write().append_child(text).unwrap();
+
+    let comment: NodeRef = Arc::new(RwLock::new(dom_core::Comment::new("note")));
+    root.write().append_child(link).unwrap();
+    root.write().append_child(comment).unwrap();
+
+    assert_eq!(
+        root.read().debug_tree(),
+        "div#main.container.wide\n\
+         \x20 a href=\"/home\"\n\
+         \x20   \"a long run of text that…\"\n\
+         \x20 <!--note-->\n"
+    );
+}
+
+#[test]
+fn test_node_path() {
+    let html = create_element_node("html");
+    let body = create_element_node("body");
+    let first = create_element_node("div");
+    let second = create_element_node("div");
+    let span = create_element_node("span");
+    let comment: NodeRef = Arc::new(RwLock::new(dom_core::Comment::new("note")));
+    let before = create_text_node("before ");
+    let after = create_text_node(" after");
+
+    let head = create_element_node("head");
+    html.write().append_child(head).unwrap();
+    html.write().append_child(body.clone()).unwrap();
+    for child in [&first, &comment, &second] {
+        body.write().append_child(child.clone()).unwrap();
+    }
+    for child in [&before, &span, &after] {
+        second.write().append_child(child.clone()).unwrap();
+    }
+
+    let path = |node: &NodeRef| node.read().node_path(node);
+    assert_eq!(path(&html), "/html[1]");
+    assert_eq!(path(&span), "/html[1]/body[1]/div[2]/span[1]");
+    assert_eq!(path(&after), "/html[1]/body[1]/div[2]/text()[2]");
+    assert_eq!(path(&comment), "/html[1]/body[1]/comment()[1]");
+}
@@ -111,7 +111,7 @@ fn test_processing_instruction_php() {
 fn test_processing_instruction_no_children() {
     let mut pi = ProcessingInstruction::new("xml", "version='1.0'");
     let child = ProcessingInstruction::new("child", "data");
-    let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+    let child_ref = Arc::new(RwLock::new(child));
 
     let result = pi.append_child(child_ref);
     assert!(result.is_err());
@@ -130,7 +130,7 @@ fn test_processing_instruction_child_nodes_empty() {
 fn test_processing_instruction_remove_child_error() {
     let mut pi = ProcessingInstruction::new("xml", "version='1.0'");
     let child = ProcessingInstruction::new("child", "data");
-    let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+    let child_ref = Arc::new(RwLock::new(child));
 
     let result = pi.remove_child(child_ref);
     assert!(result.is_err());
@@ -143,7 +143,7 @@ fn test_processing_instruction_remove_child_error() {
 fn test_processing_instruction_insert_before_error() {
     let mut pi = ProcessingInstruction::new("xml", "version='1.0'");
     let child = ProcessingInstruction::new("child", "data");
-    let child_ref = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+    let child_ref = Arc::new(RwLock::new(child));
 
     let result = pi.insert_before(child_ref, None);
     assert!(result.is_err());
@@ -26,7 +26,7 @@ fn test_append_child_basic() {
     let child = doc.create_element("span").unwrap();
 
     // Create wrapped versions for Arc::ptr_eq
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
 
     parent.write().append_child(child_wrapped.clone()).unwrap();
 
@@ -49,8 +49,8 @@ fn test_append_child_updates_last_child() {
     let child1 = doc.create_element("span").unwrap();
     let child2 = doc.create_element("p").unwrap();
 
-    let child1_wrapped = Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>));
-    let child2_wrapped = Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>));
+    let child1_wrapped = Arc::new(RwLock::new(child1.read().clone()));
+    let child2_wrapped = Arc::new(RwLock::new(child2.read().clone()));
 
     parent.write().append_child(child1_wrapped).unwrap();
     parent.write().append_child(child2_wrapped).unwrap();
@@ -69,12 +69,12 @@ fn test_append_child_updates_child_nodes_length() {
     assert_eq!(parent.read().child_nodes().len(), 0);
 
     let child1 = doc.create_element("span").unwrap();
-    let child1_wrapped = Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>));
+    let child1_wrapped = Arc::new(RwLock::new(child1.read().clone()));
     parent.write().append_child(child1_wrapped).unwrap();
     assert_eq!(parent.read().child_nodes().len(), 1);
 
     let child2 = doc.create_element("p").unwrap();
-    let child2_wrapped = Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>));
+    let child2_wrapped = Arc::new(RwLock::new(child2.read().clone()));
     parent.write().append_child(child2_wrapped).unwrap();
     assert_eq!(parent.read().child_nodes().len(), 2);
 }
@@ -91,7 +91,7 @@ fn test_remove_child_basic() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_wrapped.clone()).unwrap();
 
     assert_eq!(parent.read().child_nodes().len(), 1);
@@ -110,8 +110,8 @@ fn test_remove_child_updates_first_child() {
     let child1 = doc.create_element("span").unwrap();
     let child2 = doc.create_element("p").unwrap();
 
-    let child1_wrapped = Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>));
-    let child2_wrapped = Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>));
+    let child1_wrapped = Arc::new(RwLock::new(child1.read().clone()));
+    let child2_wrapped = Arc::new(RwLock::new(child2.read().clone()));
 
     parent.write().append_child(child1_wrapped.clone()).unwrap();
     parent.write().append_child(child2_wrapped).unwrap();
@@ -135,8 +135,8 @@ fn test_insert_before_basic() {
     let child1 = doc.create_element("span").unwrap();
     let child2 = doc.create_element("p").unwrap();
 
-    let child1_wrapped = Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>));
-    let child2_wrapped = Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>));
+    let child1_wrapped = Arc::new(RwLock::new(child1.read().clone()));
+    let child2_wrapped = Arc::new(RwLock::new(child2.read().clone()));
 
     parent.write().append_child(child1_wrapped.clone()).unwrap();
     parent.write().insert_before(child2_wrapped, Some(child1_wrapped)).unwrap();
@@ -155,7 +155,7 @@ fn test_insert_before_null_appends() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
 
     parent.write().insert_before(child_wrapped, None).unwrap();
 
@@ -177,8 +177,8 @@ fn test_replace_child_basic() {
     let old_child = doc.create_element("span").unwrap();
     let new_child = doc.create_element("p").unwrap();
 
-    let old_child_wrapped = Arc::new(RwLock::new(Box::new(old_child.read().clone()) as Box<dyn Node>));
-    let new_child_wrapped = Arc::new(RwLock::new(Box::new(new_child.read().clone()) as Box<dyn Node>));
+    let old_child_wrapped = Arc::new(RwLock::new(old_child.read().clone()));
+    let new_child_wrapped = Arc::new(RwLock::new(new_child.read().clone()));
 
     parent.write().append_child(old_child_wrapped.clone()).unwrap();
     parent.write().replace_child(new_child_wrapped, old_child_wrapped).unwrap();
@@ -209,7 +209,7 @@ fn test_has_child_nodes_after_append() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_wrapped).unwrap();
 
     // Use child_nodes().len() to check if has children
@@ -238,7 +238,7 @@ fn test_child_nodes_multiple_children() {
 
     for tag in ["span", "p", "a"] {
         let child = doc.create_element(tag).unwrap();
-        let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+        let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
         parent.write().append_child(child_wrapped).unwrap();
     }
 
@@ -277,7 +277,7 @@ fn test_first_last_child_single() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_wrapped).unwrap();
 
     let first = parent.read().first_child().unwrap();
@@ -374,7 +374,7 @@ fn test_clone_node_shallow() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_wrapped).unwrap();
 
     let clone = parent.read().clone_node(false);
@@ -391,7 +391,7 @@ fn test_clone_node_deep() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_wrapped = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_wrapped = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_wrapped).unwrap();
 
     let clone = parent.read().clone_node(true);
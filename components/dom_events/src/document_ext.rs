@@ -3,13 +3,20 @@
 //! This module provides extension methods for Document to create events.
 //! These are separate from dom_core to avoid circular dependencies.
 
-use crate::event::{Event, EventInit};
+use crate::event::{Event, EventInit, EventRef};
+use crate::event_dispatcher::EventDispatcher;
+use crate::event_target::EventTargetRef;
 use crate::event_types::{
     CompositionEvent, CompositionEventInit, CustomEvent, CustomEventInit, FocusEvent,
     FocusEventInit, InputEvent, InputEventInit, KeyboardEvent, KeyboardEventInit, MouseEvent,
     MouseEventInit, UIEvent, UIEventInit, WheelEvent, WheelEventInit,
 };
+use crate::scheduler::SchedulerRef;
+use dom_core::{DocumentReadyState, ReadyStateObserver};
 use dom_types::DomException;
+use parking_lot::RwLock;
+use std::fmt;
+use std::sync::Arc;
 
 /// Event creation methods for Document
 ///
@@ -39,45 +46,87 @@ pub trait DocumentEventExt {
 // Note: The actual implementation would be in an impl block for Document
 // when both dom_core and dom_events are available. For now, we provide
 // helper functions that can be used to create events.
+//
+// Each helper applies the per-type defaults of `EventInit::for_type`, so e.g.
+// `create_mouse_event("click")` bubbles without spelling out an init.
+
+/// UI event init with the defaults of `event_type`
+fn ui_init(event_type: &str) -> UIEventInit {
+    UIEventInit {
+        event_init: EventInit::for_type(event_type),
+        ..Default::default()
+    }
+}
 
 /// Create a UI event
 pub fn create_ui_event(event_type: &str) -> UIEvent {
-    UIEvent::new(event_type, UIEventInit::default())
+    UIEvent::new(event_type, ui_init(event_type))
 }
 
 /// Create a mouse event
 pub fn create_mouse_event(event_type: &str) -> MouseEvent {
-    MouseEvent::new(event_type, MouseEventInit::default())
+    let init = MouseEventInit {
+        ui_event_init: ui_init(event_type),
+        ..Default::default()
+    };
+    MouseEvent::new(event_type, init)
 }
 
 /// Create a keyboard event
 pub fn create_keyboard_event(event_type: &str) -> KeyboardEvent {
-    KeyboardEvent::new(event_type, KeyboardEventInit::default())
+    let init = KeyboardEventInit {
+        ui_event_init: ui_init(event_type),
+        ..Default::default()
+    };
+    KeyboardEvent::new(event_type, init)
 }
 
 /// Create a focus event
 pub fn create_focus_event(event_type: &str) -> FocusEvent {
-    FocusEvent::new(event_type, FocusEventInit::default())
+    let init = FocusEventInit {
+        ui_event_init: ui_init(event_type),
+        ..Default::default()
+    };
+    FocusEvent::new(event_type, init)
 }
 
 /// Create an input event
 pub fn create_input_event(event_type: &str) -> InputEvent {
-    InputEvent::new(event_type, InputEventInit::default())
+    let init = InputEventInit {
+        ui_event_init: ui_init(event_type),
+        ..Default::default()
+    };
+    InputEvent::new(event_type, init)
 }
 
 /// Create a wheel event
 pub fn create_wheel_event(event_type: &str) -> WheelEvent {
-    WheelEvent::new(event_type, WheelEventInit::default())
+    let init = WheelEventInit {
+        mouse_event_init: MouseEventInit {
+            ui_event_init: ui_init(event_type),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    WheelEvent::new(event_type, init)
 }
 
 /// Create a custom event
 pub fn create_custom_event(event_type: &str) -> CustomEvent {
-    CustomEvent::new(event_type, CustomEventInit::default())
+    let init = CustomEventInit {
+        event_init: EventInit::for_type(event_type),
+        ..Default::default()
+    };
+    CustomEvent::new(event_type, init)
 }
 
 /// Create a composition event
 pub fn create_composition_event(event_type: &str) -> CompositionEvent {
-    CompositionEvent::new(event_type, CompositionEventInit::default())
+    let init = CompositionEventInit {
+        ui_event_init: ui_init(event_type),
+        ..Default::default()
+    };
+    CompositionEvent::new(event_type, init)
 }
 
 /// Create an event based on the legacy event type string
@@ -131,9 +180,131 @@ pub fn create_event_by_interface(event_interface: &str) -> Result<Event, DomExce
     }
 }
 
+/// Dispatches the document readiness events at the document's event target
+///
+/// Each transition fires `readystatechange` right away. Becoming
+/// `interactive` then queues a task on the scheduler firing
+/// `DOMContentLoaded`, and becoming `complete` one firing `load`. Install it
+/// with [`Document::set_ready_state_observer`](dom_core::Document::set_ready_state_observer).
+///
+/// # Example
+/// ```
+/// use dom_core::{Document, DocumentReadyState};
+/// use dom_events::{EventTargetData, ManualScheduler, ReadyStateEvents};
+/// use parking_lot::RwLock;
+/// use std::sync::Arc;
+///
+/// let target = Arc::new(RwLock::new(EventTargetData::new()));
+/// let scheduler = ManualScheduler::new_ref();
+/// let mut doc = Document::new();
+/// let events = ReadyStateEvents::new(target, scheduler.clone());
+/// doc.set_ready_state_observer(Some(Arc::new(events)));
+/// doc.set_ready_state(DocumentReadyState::Interactive).unwrap();
+/// assert_eq!(scheduler.run_tasks(), 1);
+/// ```
+pub struct ReadyStateEvents {
+    target: EventTargetRef,
+    scheduler: SchedulerRef,
+}
+
+impl ReadyStateEvents {
+    /// Create an observer dispatching at `target`, queuing tasks on `scheduler`
+    pub fn new(target: EventTargetRef, scheduler: SchedulerRef) -> Self {
+        Self { target, scheduler }
+    }
+
+    fn event(event_type: &str) -> EventRef {
+        Arc::new(RwLock::new(Event::new(
+            event_type,
+            EventInit::for_type(event_type),
+        )))
+    }
+
+    fn fire(&self, event_type: &str) {
+        // Observers cannot report errors; a failed dispatch reaches no listener
+        let _ = EventDispatcher::dispatch(Self::event(event_type), self.target.clone());
+    }
+
+    fn queue(&self, event_type: &str) {
+        EventDispatcher::queue_dispatch(
+            &*self.scheduler,
+            Self::event(event_type),
+            self.target.clone(),
+        );
+    }
+}
+
+impl ReadyStateObserver for ReadyStateEvents {
+    fn ready_state_changed(&self, state: DocumentReadyState) {
+        self.fire("readystatechange");
+        match state {
+            DocumentReadyState::Interactive => self.queue("DOMContentLoaded"),
+            DocumentReadyState::Complete => self.queue("load"),
+            DocumentReadyState::Loading => {}
+        }
+    }
+}
+
+impl fmt::Debug for ReadyStateEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadyStateEvents").finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use crate::scheduler::ManualScheduler;
+    use dom_core::Document;
+
+    #[test]
+    fn test_interactive_queues_dom_content_loaded_once() {
+        use DocumentReadyState::{Complete, Interactive, Loading};
+
+        let log = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let mut target = EventTargetData::new();
+        for event_type in ["readystatechange", "DOMContentLoaded", "load"] {
+            let log = log.clone();
+            target.add_event_listener(
+                event_type,
+                EventListener::from_fn(move |event| {
+                    log.lock().push(event.event_type().to_string());
+                }),
+                AddEventListenerOptions::default(),
+            );
+        }
+        let target = Arc::new(RwLock::new(target));
+        let scheduler = ManualScheduler::new_ref();
+        let mut doc = Document::new();
+        let events = ReadyStateEvents::new(target, scheduler.clone());
+        doc.set_ready_state_observer(Some(Arc::new(events)));
+        assert_eq!(doc.ready_state(), Loading);
+
+        doc.set_ready_state(Interactive).unwrap();
+        doc.set_ready_state(Interactive).unwrap();
+        assert_eq!(*log.lock(), ["readystatechange"]);
+        assert_eq!(scheduler.run_tasks(), 1);
+        assert_eq!(*log.lock(), ["readystatechange", "DOMContentLoaded"]);
+
+        doc.set_ready_state(Complete).unwrap();
+        assert_eq!(doc.ready_state(), Complete);
+        assert_eq!(
+            *log.lock(),
+            ["readystatechange", "DOMContentLoaded", "readystatechange"]
+        );
+        assert_eq!(scheduler.run_tasks(), 1);
+        assert_eq!(
+            *log.lock(),
+            [
+                "readystatechange",
+                "DOMContentLoaded",
+                "readystatechange",
+                "load"
+            ]
+        );
+    }
 
     #[test]
     fn test_create_event_by_interface_events() {
@@ -194,5 +365,28 @@ mod tests {
     fn test_create_custom_event() {
         let event = create_custom_event("my-event");
         assert_eq!(event.event().event_type(), "my-event");
+        assert!(!event.event().bubbles());
+    }
+
+    #[test]
+    fn test_factories_apply_per_type_defaults() {
+        let click = create_mouse_event("click");
+        let click = click.ui_event().event();
+        assert!(click.bubbles());
+        assert!(click.cancelable());
+        assert!(click.composed());
+
+        let focus = create_focus_event("focus");
+        let focus = focus.ui_event().event();
+        assert!(!focus.bubbles());
+        assert!(!focus.cancelable());
+        assert!(focus.composed());
+
+        let input = create_input_event("input");
+        assert!(input.ui_event().event().bubbles());
+        assert!(!input.ui_event().event().cancelable());
+
+        let wheel = create_wheel_event("wheel");
+        assert!(wheel.mouse_event().ui_event().event().cancelable());
     }
 }
@@ -1,8 +1,13 @@
 //! Event implementation with all properties and methods
 
+use dom_types::JsValue;
 use parking_lot::RwLock;
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 /// Event phase enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,7 +23,7 @@ pub enum EventPhase {
 }
 
 /// Event initialization options
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EventInit {
     /// Whether the event bubbles up through the DOM
     pub bubbles: bool,
@@ -28,6 +33,113 @@ pub struct EventInit {
     pub composed: bool,
 }
 
+/// Start of the event clock: a monotonic instant and its Unix time in milliseconds
+static TIME_ORIGIN: OnceLock<(Instant, f64)> = OnceLock::new();
+
+/// Reads the event clock
+///
+/// The clock reads the Unix time at which the first event of the process was
+/// created, plus the monotonic time elapsed since. Unlike the system clock
+/// it never goes backwards, so later events never get earlier time stamps.
+fn event_clock() -> f64 {
+    let (origin, origin_unix_ms) = TIME_ORIGIN.get_or_init(|| {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH);
+        let unix_ms = since_epoch.unwrap_or_default().as_secs_f64() * 1000.0;
+        (Instant::now(), unix_ms)
+    });
+    origin_unix_ms + origin.elapsed().as_secs_f64() * 1000.0
+}
+
+/// `(type, bubbles, cancelable, composed)` of built-in event types
+const TYPE_DEFAULTS: &[(&str, bool, bool, bool)] = &[
+    ("click", true, true, true),
+    ("dblclick", true, true, true),
+    ("auxclick", true, true, true),
+    ("contextmenu", true, true, true),
+    ("mousedown", true, true, true),
+    ("mouseup", true, true, true),
+    ("mousemove", true, true, true),
+    ("mouseover", true, true, true),
+    ("mouseout", true, true, true),
+    ("mouseenter", false, false, false),
+    ("mouseleave", false, false, false),
+    ("wheel", true, true, true),
+    ("keydown", true, true, true),
+    ("keyup", true, true, true),
+    ("beforeinput", true, true, true),
+    ("input", true, false, true),
+    ("focus", false, false, true),
+    ("blur", false, false, true),
+    ("focusin", true, false, true),
+    ("focusout", true, false, true),
+    ("compositionstart", true, true, true),
+    ("compositionupdate", true, false, true),
+    ("compositionend", true, false, true),
+    ("change", true, false, false),
+    ("submit", true, true, false),
+    ("reset", true, true, false),
+    ("load", false, false, false),
+    ("readystatechange", false, false, false),
+    ("DOMContentLoaded", true, false, false),
+];
+
+impl EventInit {
+    /// Default initialization for the built-in event type `event_type`
+    ///
+    /// `click` bubbles, is cancelable and composed, while `focus` only
+    /// crosses shadow boundaries. Unknown and custom types get all flags off.
+    pub fn for_type(event_type: &str) -> Self {
+        TYPE_DEFAULTS
+            .iter()
+            .find(|(name, ..)| *name == event_type)
+            .map(|&(_, bubbles, cancelable, composed)| Self {
+                bubbles,
+                cancelable,
+                composed,
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Boolean flag that listeners can set through a shared `&Event`
+///
+/// Listeners receive `&Event` while the dispatcher holds a read lock, so the
+/// flags they control (`preventDefault`, `stopPropagation`, ...) need interior
+/// mutability.
+#[derive(Debug, Default)]
+pub(crate) struct EventFlag(AtomicBool);
+
+impl EventFlag {
+    /// Get the flag value
+    pub(crate) fn get(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Set the flag value
+    pub(crate) fn set(&self, value: bool) {
+        self.0.store(value, Ordering::SeqCst);
+    }
+}
+
+impl Clone for EventFlag {
+    fn clone(&self) -> Self {
+        Self(AtomicBool::new(self.get()))
+    }
+}
+
+/// Interface-specific event that an [`Event`] is the base of
+///
+/// Listeners receive the base `&Event`; this keeps the `MouseEvent` or
+/// `KeyboardEvent` it came from readable through [`Event::interface`].
+#[derive(Clone)]
+pub(crate) struct EventInterface(Arc<dyn Any + Send + Sync>);
+
+impl fmt::Debug for EventInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventInterface(..)")
+    }
+}
+
 /// Event reference type
 pub type EventRef = Arc<RwLock<Event>>;
 
@@ -50,7 +162,7 @@ pub struct Event {
     /// Whether the event can be canceled
     cancelable: bool,
     /// Whether preventDefault() has been called
-    default_prevented: bool,
+    default_prevented: EventFlag,
     /// Whether the event is composed (crosses shadow boundaries)
     composed: bool,
     /// Whether the event was dispatched by browser (vs script)
@@ -60,20 +172,24 @@ pub struct Event {
     /// Internal flag: event dispatch is in progress
     pub(crate) dispatch_flag: bool,
     /// Internal flag: stop propagation was called
-    pub(crate) stop_propagation_flag: bool,
+    pub(crate) stop_propagation_flag: EventFlag,
     /// Internal flag: stop immediate propagation was called
-    pub(crate) stop_immediate_propagation_flag: bool,
+    pub(crate) stop_immediate_propagation_flag: EventFlag,
+    /// Internal flag: a passive listener is being invoked
+    pub(crate) in_passive_listener: bool,
+    /// Payload carried by a `CustomEvent`
+    pub(crate) detail: Option<JsValue>,
+    /// Interface-specific event this event is the base of
+    pub(crate) interface: Option<EventInterface>,
+    /// Propagation path computed at dispatch start (target first)
+    pub(crate) path: Vec<EventTargetRef>,
 }
 
 impl Event {
     /// Create a new event with the specified type and initialization options
+    ///
+    /// The event is untrusted, as if created by script.
     pub fn new(event_type: &str, init: EventInit) -> Self {
-        let time_stamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs_f64()
-            * 1000.0; // Convert to milliseconds
-
         Self {
             event_type: event_type.to_string(),
             target: None,
@@ -81,13 +197,40 @@ impl Event {
             event_phase: EventPhase::None,
             bubbles: init.bubbles,
             cancelable: init.cancelable,
-            default_prevented: false,
+            default_prevented: EventFlag::default(),
             composed: init.composed,
             is_trusted: false,
-            time_stamp,
+            time_stamp: event_clock(),
             dispatch_flag: false,
-            stop_propagation_flag: false,
-            stop_immediate_propagation_flag: false,
+            stop_propagation_flag: EventFlag::default(),
+            stop_immediate_propagation_flag: EventFlag::default(),
+            in_passive_listener: false,
+            detail: None,
+            interface: None,
+            path: Vec::new(),
+        }
+    }
+
+    /// Create an event on behalf of the user agent, with `is_trusted` set
+    ///
+    /// Reserved for turning real user input into events, see
+    /// [`UserInput`](crate::UserInput). Script-created events must use
+    /// [`Event::new`].
+    pub(crate) fn new_trusted(event_type: &str, init: EventInit) -> Self {
+        Self {
+            is_trusted: true,
+            ..Self::new(event_type, init)
+        }
+    }
+
+    /// `base`, a copy of `interface`'s base event, carrying `interface` along
+    ///
+    /// Dispatch works on base events; this lets listeners read the
+    /// interface-specific data back with [`Event::interface`].
+    pub(crate) fn with_interface<T: Any + Send + Sync>(base: Event, interface: T) -> Self {
+        Self {
+            interface: Some(EventInterface(Arc::new(interface))),
+            ..base
         }
     }
 
@@ -123,7 +266,7 @@ impl Event {
 
     /// Check if default action has been prevented
     pub fn default_prevented(&self) -> bool {
-        self.default_prevented
+        self.default_prevented.get()
     }
 
     /// Check if event is composed
@@ -132,31 +275,62 @@ impl Event {
     }
 
     /// Check if event is trusted
+    ///
+    /// Only events generated from user input are trusted; events created by
+    /// script never are.
     pub fn is_trusted(&self) -> bool {
         self.is_trusted
     }
 
-    /// Get the event timestamp
+    /// Get the interface-specific event this event is the base of
+    ///
+    /// Events from user input carry the [`MouseEvent`](crate::MouseEvent) or
+    /// [`KeyboardEvent`](crate::KeyboardEvent) they were built from, with its
+    /// coordinates or key. `None` if the event is not of interface `T`.
+    pub fn interface<T: Any>(&self) -> Option<&T> {
+        self.interface.as_ref()?.0.downcast_ref()
+    }
+
+    /// Get the targets this event traverses, from the target up to the root
+    ///
+    /// Computed when dispatch starts and empty outside of dispatch. Unless
+    /// the event is composed, the path ends at the target's shadow root.
+    pub fn composed_path(&self) -> Vec<EventTargetRef> {
+        self.path.clone()
+    }
+
+    /// Get the payload of a `CustomEvent` (`None` for other events)
+    pub fn detail(&self) -> Option<&JsValue> {
+        self.detail.as_ref()
+    }
+
+    /// Get the time the event was created, in milliseconds
+    ///
+    /// Stamps come from a monotonic clock that counts from the Unix time at
+    /// which the process created its first event, so they never decrease.
     pub fn time_stamp(&self) -> f64 {
         self.time_stamp
     }
 
     /// Prevent the default action associated with this event
-    pub fn prevent_default(&mut self) {
-        if self.cancelable {
-            self.default_prevented = true;
+    ///
+    /// Takes `&self` so listeners can call it on the event they receive.
+    /// Has no effect when called from a passive listener.
+    pub fn prevent_default(&self) {
+        if self.cancelable && !self.in_passive_listener {
+            self.default_prevented.set(true);
         }
     }
 
     /// Stop the event from propagating further
-    pub fn stop_propagation(&mut self) {
-        self.stop_propagation_flag = true;
+    pub fn stop_propagation(&self) {
+        self.stop_propagation_flag.set(true);
     }
 
     /// Stop the event from propagating and prevent other listeners on the same target
-    pub fn stop_immediate_propagation(&mut self) {
-        self.stop_propagation_flag = true;
-        self.stop_immediate_propagation_flag = true;
+    pub fn stop_immediate_propagation(&self) {
+        self.stop_propagation_flag.set(true);
+        self.stop_immediate_propagation_flag.set(true);
     }
 
     /// Set the target (internal use only)
@@ -214,7 +388,7 @@ mod tests {
 
     #[test]
     fn test_prevent_default() {
-        let mut event = Event::new(
+        let event = Event::new(
             "click",
             EventInit {
                 bubbles: true,
@@ -229,7 +403,7 @@ mod tests {
 
     #[test]
     fn test_prevent_default_non_cancelable() {
-        let mut event = Event::new("click", EventInit::default());
+        let event = Event::new("click", EventInit::default());
         assert!(!event.cancelable());
         event.prevent_default();
         assert!(!event.default_prevented()); // Should not be set
@@ -237,20 +411,20 @@ mod tests {
 
     #[test]
     fn test_stop_propagation() {
-        let mut event = Event::new("click", EventInit::default());
-        assert!(!event.stop_propagation_flag);
+        let event = Event::new("click", EventInit::default());
+        assert!(!event.stop_propagation_flag.get());
         event.stop_propagation();
-        assert!(event.stop_propagation_flag);
+        assert!(event.stop_propagation_flag.get());
     }
 
     #[test]
     fn test_stop_immediate_propagation() {
-        let mut event = Event::new("click", EventInit::default());
-        assert!(!event.stop_propagation_flag);
-        assert!(!event.stop_immediate_propagation_flag);
+        let event = Event::new("click", EventInit::default());
+        assert!(!event.stop_propagation_flag.get());
+        assert!(!event.stop_immediate_propagation_flag.get());
         event.stop_immediate_propagation();
-        assert!(event.stop_propagation_flag);
-        assert!(event.stop_immediate_propagation_flag);
+        assert!(event.stop_propagation_flag.get());
+        assert!(event.stop_immediate_propagation_flag.get());
     }
 
     #[test]
@@ -266,4 +440,22 @@ mod tests {
         let event = Event::new("click", EventInit::default());
         assert!(event.time_stamp() > 0.0);
     }
+
+    #[test]
+    fn test_timestamps_never_decrease() {
+        let stamps: Vec<f64> = (0..100)
+            .map(|_| Event::new("click", EventInit::default()).time_stamp())
+            .collect();
+        assert!(stamps.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_only_user_agent_events_are_trusted() {
+        let init = EventInit::for_type("click");
+        assert!(!Event::new("click", init.clone()).is_trusted());
+
+        let trusted = Event::new_trusted("click", init);
+        assert!(trusted.is_trusted());
+        assert!(trusted.bubbles() && trusted.cancelable());
+    }
 }
@@ -2,6 +2,8 @@
 
 use crate::event::{EventPhase, EventRef};
 use crate::event_target::EventTargetRef;
+use crate::pointer_capture::PointerCaptureRegistry;
+use crate::scheduler::Scheduler;
 use dom_types::DomException;
 
 /// EventDispatcher implements the DOM Level 4 event dispatch algorithm
@@ -14,7 +16,27 @@ impl EventDispatcher {
     /// - Phase 1: CAPTURE (root → target, excluding target)
     /// - Phase 2: TARGET
     /// - Phase 3: BUBBLE (target → root, excluding target)
+    ///
+    /// This is dispatch on behalf of script, so the event is untrusted
+    /// afterwards, even if it was copied from a trusted one.
     pub fn dispatch(event: EventRef, target: EventTargetRef) -> Result<bool, DomException> {
+        {
+            let mut event_write = event.write();
+            if event_write.dispatch_flag {
+                return Err(DomException::InvalidStateError);
+            }
+            event_write.set_is_trusted(false);
+        }
+        Self::dispatch_trusted(event, target)
+    }
+
+    /// Dispatch an event to a target, keeping its `is_trusted` flag
+    ///
+    /// Used by the user agent for the trusted events it creates.
+    pub(crate) fn dispatch_trusted(
+        event: EventRef,
+        target: EventTargetRef,
+    ) -> Result<bool, DomException> {
         // Step 1: Validate event state
         {
             let event_read = event.read();
@@ -32,7 +54,9 @@ impl EventDispatcher {
         }
 
         // Step 3: Determine propagation path (from target up to root)
-        let path = Self::calculate_event_path(&target);
+        let composed = event.read().composed();
+        let path = Self::calculate_event_path(&target, composed);
+        event.write().path = path.clone();
 
         // Step 4: CAPTURE PHASE - dispatch to ancestors in reverse order
         // (from root toward target, but NOT including target itself)
@@ -42,10 +66,10 @@ impl EventDispatcher {
         }
 
         // Iterate from root to target (excluding target)
-        // path[0] is target, path[last] is root, so reverse and skip first
-        for ancestor in path.iter().rev().skip(1) {
+        // path[0] is target, path[last] is root, so skip the target and reverse
+        for ancestor in path.iter().skip(1).rev() {
             // Check if propagation was stopped
-            if event.read().stop_propagation_flag {
+            if event.read().stop_propagation_flag.get() {
                 break;
             }
 
@@ -53,19 +77,19 @@ impl EventDispatcher {
         }
 
         // Step 5: TARGET PHASE - dispatch to target itself
-        if !event.read().stop_propagation_flag {
+        if !event.read().stop_propagation_flag.get() {
             let mut event_write = event.write();
             event_write.set_event_phase(EventPhase::AtTarget);
         }
 
-        if !event.read().stop_propagation_flag {
+        if !event.read().stop_propagation_flag.get() {
             Self::invoke_listeners(&target, &event, EventPhase::AtTarget);
         }
 
         // Step 6: BUBBLE PHASE - dispatch to ancestors in forward order
         // (from target toward root, but NOT including target itself)
         let bubbles = event.read().bubbles();
-        if bubbles && !event.read().stop_propagation_flag {
+        if bubbles && !event.read().stop_propagation_flag.get() {
             let mut event_write = event.write();
             event_write.set_event_phase(EventPhase::Bubbling);
         }
@@ -74,7 +98,7 @@ impl EventDispatcher {
             // Iterate from target's parent to root
             for ancestor in path.iter().skip(1) {
                 // Check if propagation was stopped
-                if event.read().stop_propagation_flag {
+                if event.read().stop_propagation_flag.get() {
                     break;
                 }
 
@@ -88,19 +112,50 @@ impl EventDispatcher {
             event_write.set_event_phase(EventPhase::None);
             event_write.set_current_target(None);
             event_write.dispatch_flag = false;
+            event_write.path.clear();
+            event_write.stop_propagation_flag.set(false);
+            event_write.stop_immediate_propagation_flag.set(false);
         }
 
         // Return whether default action should be performed
         Ok(!event.read().default_prevented())
     }
 
+    /// Dispatch a pointer event for `pointer_id`, honoring pointer capture
+    ///
+    /// `hit_target` is the target found by hit-testing. If `registry` records
+    /// a capture for `pointer_id`, the event is dispatched to the capturing
+    /// target instead.
+    pub fn dispatch_pointer_event(
+        event: EventRef,
+        pointer_id: i32,
+        hit_target: EventTargetRef,
+        registry: &PointerCaptureRegistry,
+    ) -> Result<bool, DomException> {
+        let target = registry.capture_target(pointer_id).unwrap_or(hit_target);
+        Self::dispatch(event, target)
+    }
+
+    /// Queue a task on `scheduler` that dispatches `event` to `target`
+    ///
+    /// Listeners run when the scheduler runs the task, after any microtasks
+    /// queued before it. Dispatch errors are dropped, as there is no caller
+    /// left to report them to.
+    pub fn queue_dispatch(scheduler: &dyn Scheduler, event: EventRef, target: EventTargetRef) {
+        scheduler.enqueue_task(Box::new(move || {
+            let _ = Self::dispatch(event, target);
+        }));
+    }
+
     /// Calculate the event propagation path from target to root
     ///
     /// Returns a vector where:
     /// - Index 0 is the target
     /// - Index 1 is target's parent
     /// - Index n is the root
-    fn calculate_event_path(target: &EventTargetRef) -> Vec<EventTargetRef> {
+    ///
+    /// A shadow root continues to its host only when `composed` is set.
+    fn calculate_event_path(target: &EventTargetRef, composed: bool) -> Vec<EventTargetRef> {
         let mut path = vec![target.clone()];
 
         // Walk up the tree collecting ancestors
@@ -111,7 +166,20 @@ impl EventDispatcher {
                 current_read.get_parent()
             };
 
-            if let Some(parent_node) = parent_opt {
+            let (parent_target, shadow_host) = {
+                let current_read = current.read();
+                (current_read.parent_target(), current_read.shadow_host())
+            };
+            if let Some(host) = shadow_host {
+                if !composed {
+                    break;
+                }
+                path.push(host.clone());
+                current = host;
+            } else if let Some(parent_target) = parent_target {
+                path.push(parent_target.clone());
+                current = parent_target;
+            } else if let Some(parent_node) = parent_opt {
                 // For this simplified implementation, we create a new EventTargetRef
                 // wrapping the parent node
                 let parent_target = crate::event_target::EventTargetData::with_node(parent_node);
@@ -133,7 +201,7 @@ impl EventDispatcher {
         let listeners = {
             let target_read = target.read();
             let event_read = event.read();
-            target_read.get_listeners(event_read.event_type(), phase)
+            target_read.get_registered_listeners(event_read.event_type(), phase)
         };
 
         // Set current target
@@ -143,15 +211,25 @@ impl EventDispatcher {
         }
 
         // Invoke each listener
+        let event_type = event.read().event_type().to_string();
         for listener in listeners {
-            // Check if immediate propagation was stopped
-            if event.read().stop_immediate_propagation_flag {
-                break;
+            if !target.write().claim_listener(&event_type, &listener) {
+                continue;
             }
 
-            // Invoke the listener
-            let event_read = event.read();
-            listener.invoke(&event_read);
+            // preventDefault() is a no-op while a passive listener runs
+            event.write().in_passive_listener = listener.passive;
+            {
+                let event_read = event.read();
+                listener.listener.invoke(&event_read);
+            }
+            event.write().in_passive_listener = false;
+
+            // Remaining listeners on this target are skipped once immediate
+            // propagation has been stopped
+            if event.read().stop_immediate_propagation_flag.get() {
+                break;
+            }
         }
     }
 }
@@ -230,7 +308,7 @@ mod tests {
 
         assert!(result.is_ok());
         // Verify the flag was used (it should be cleaned up after dispatch)
-        assert!(!event.read().stop_propagation_flag);
+        assert!(!event.read().stop_propagation_flag.get());
     }
 
     #[test]
@@ -315,4 +393,371 @@ mod tests {
         assert!(order_vec.contains(&"capture"));
         assert!(order_vec.contains(&"bubble"));
     }
+
+    #[test]
+    fn test_stop_immediate_propagation_skips_remaining_listeners() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut parent = EventTargetData::new();
+        let order_clone = order.clone();
+        parent.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                order_clone.lock().unwrap().push("parent");
+            }),
+            AddEventListenerOptions::default(),
+        );
+        let parent_ref = Arc::new(RwLock::new(parent));
+
+        let mut target = EventTargetData::new();
+        target.set_parent_target(&parent_ref);
+
+        let order_clone = order.clone();
+        target.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                order_clone.lock().unwrap().push("first");
+            }),
+            AddEventListenerOptions::default(),
+        );
+        let order_clone = order.clone();
+        target.add_event_listener(
+            "click",
+            EventListener::from_fn(move |event| {
+                order_clone.lock().unwrap().push("second");
+                event.stop_immediate_propagation();
+            }),
+            AddEventListenerOptions::default(),
+        );
+        let order_clone = order.clone();
+        target.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                order_clone.lock().unwrap().push("third");
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let event = Arc::new(RwLock::new(Event::new(
+            "click",
+            EventInit {
+                bubbles: true,
+                ..Default::default()
+            },
+        )));
+
+        let target_ref = Arc::new(RwLock::new(target));
+        EventDispatcher::dispatch(event, target_ref).unwrap();
+
+        assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_bubbles_to_parent_target() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut parent = EventTargetData::new();
+        let order_clone = order.clone();
+        parent.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                order_clone.lock().unwrap().push("parent");
+            }),
+            AddEventListenerOptions::default(),
+        );
+        let parent_ref = Arc::new(RwLock::new(parent));
+
+        let mut target = EventTargetData::new();
+        target.set_parent_target(&parent_ref);
+        let order_clone = order.clone();
+        target.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                order_clone.lock().unwrap().push("target");
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let event = Arc::new(RwLock::new(Event::new(
+            "click",
+            EventInit {
+                bubbles: true,
+                ..Default::default()
+            },
+        )));
+
+        EventDispatcher::dispatch(event, Arc::new(RwLock::new(target))).unwrap();
+
+        assert_eq!(*order.lock().unwrap(), vec!["target", "parent"]);
+    }
+
+    fn wheel_default_prevented(passive: bool) -> bool {
+        let mut target = EventTargetData::new();
+        target.add_event_listener(
+            "wheel",
+            EventListener::from_fn(|event| event.prevent_default()),
+            AddEventListenerOptions {
+                passive,
+                ..Default::default()
+            },
+        );
+
+        let event = Arc::new(RwLock::new(Event::new(
+            "wheel",
+            EventInit {
+                cancelable: true,
+                ..Default::default()
+            },
+        )));
+
+        EventDispatcher::dispatch(event.clone(), Arc::new(RwLock::new(target))).unwrap();
+        let default_prevented = event.read().default_prevented();
+        default_prevented
+    }
+
+    #[test]
+    fn test_passive_listener_cannot_prevent_default() {
+        assert!(!wheel_default_prevented(true));
+        assert!(wheel_default_prevented(false));
+    }
+
+    fn click() -> EventRef {
+        Arc::new(RwLock::new(Event::new("click", EventInit::default())))
+    }
+
+    #[test]
+    fn test_once_listener_runs_once() {
+        let count = Arc::new(Mutex::new(0));
+        let count_clone = count.clone();
+
+        let mut target = EventTargetData::new();
+        target.add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                *count_clone.lock().unwrap() += 1;
+            }),
+            AddEventListenerOptions {
+                once: true,
+                ..Default::default()
+            },
+        );
+        let target = Arc::new(RwLock::new(target));
+
+        EventDispatcher::dispatch(click(), target.clone()).unwrap();
+        EventDispatcher::dispatch(click(), target.clone()).unwrap();
+
+        assert_eq!(*count.lock().unwrap(), 1);
+        assert!(target
+            .read()
+            .get_listeners("click", EventPhase::AtTarget)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_once_listener_not_reinvoked_by_reentrant_dispatch() {
+        let count = Arc::new(Mutex::new(0));
+        let target = Arc::new(RwLock::new(EventTargetData::new()));
+
+        let count_clone = count.clone();
+        let weak_target = Arc::downgrade(&target);
+        target.write().add_event_listener(
+            "click",
+            EventListener::from_fn(move |_| {
+                *count_clone.lock().unwrap() += 1;
+                if let Some(target) = weak_target.upgrade() {
+                    EventDispatcher::dispatch(click(), target).unwrap();
+                }
+            }),
+            AddEventListenerOptions {
+                once: true,
+                ..Default::default()
+            },
+        );
+
+        EventDispatcher::dispatch(click(), target.clone()).unwrap();
+        EventDispatcher::dispatch(click(), target).unwrap();
+
+        assert_eq!(*count.lock().unwrap(), 1);
+    }
+
+    fn recording_listener(
+        order: &Arc<Mutex<Vec<&'static str>>>,
+        name: &'static str,
+    ) -> EventListener {
+        let order = order.clone();
+        EventListener::from_fn(move |_| order.lock().unwrap().push(name))
+    }
+
+    /// Builds document <- body <- div, returned in that order
+    fn keydown_tree(order: &Arc<Mutex<Vec<&'static str>>>) -> [EventTargetRef; 3] {
+        let capture = AddEventListenerOptions {
+            capture: true,
+            ..Default::default()
+        };
+
+        let document = Arc::new(RwLock::new(EventTargetData::new()));
+        document.write().add_event_listener(
+            "keydown",
+            recording_listener(order, "document author"),
+            capture.clone(),
+        );
+
+        let body = Arc::new(RwLock::new(EventTargetData::new()));
+        body.write().set_parent_target(&document);
+        body.write()
+            .add_event_listener("keydown", recording_listener(order, "body"), capture);
+
+        let div = Arc::new(RwLock::new(EventTargetData::new()));
+        div.write().set_parent_target(&body);
+        div.write().add_event_listener(
+            "keydown",
+            recording_listener(order, "div"),
+            AddEventListenerOptions::default(),
+        );
+
+        [document, body, div]
+    }
+
+    fn keydown() -> EventRef {
+        Arc::new(RwLock::new(Event::new(
+            "keydown",
+            EventInit {
+                bubbles: true,
+                ..Default::default()
+            },
+        )))
+    }
+
+    #[test]
+    fn test_capture_runs_from_root_down_to_target_once() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let capture = AddEventListenerOptions {
+            capture: true,
+            ..Default::default()
+        };
+
+        // root <- middle <- parent <- target, each capturing
+        let mut chain: Vec<EventTargetRef> = Vec::new();
+        for name in ["root", "middle", "parent", "target"] {
+            let target = Arc::new(RwLock::new(EventTargetData::new()));
+            if let Some(parent) = chain.last() {
+                target.write().set_parent_target(parent);
+            }
+            let listener = recording_listener(&order, name);
+            target
+                .write()
+                .add_event_listener("keydown", listener, capture.clone());
+            chain.push(target);
+        }
+
+        EventDispatcher::dispatch(keydown(), chain[3].clone()).unwrap();
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["root", "middle", "parent", "target"]
+        );
+    }
+
+    #[test]
+    fn test_global_capture_listener_runs_first() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let [document, _body, div] = keydown_tree(&order);
+        document
+            .write()
+            .add_capturing_listener("keydown", recording_listener(&order, "global"));
+
+        EventDispatcher::dispatch(keydown(), div).unwrap();
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["global", "document author", "body", "div"]
+        );
+    }
+
+    #[test]
+    fn test_global_capture_listener_can_stop_author_handlers() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let [document, _body, div] = keydown_tree(&order);
+        let order_clone = order.clone();
+        document.write().add_capturing_listener(
+            "keydown",
+            EventListener::from_fn(move |event| {
+                order_clone.lock().unwrap().push("global");
+                event.stop_immediate_propagation();
+            }),
+        );
+
+        EventDispatcher::dispatch(keydown(), div).unwrap();
+
+        assert_eq!(*order.lock().unwrap(), vec!["global"]);
+    }
+
+    #[test]
+    fn test_composed_path_matches_capture_order() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let [document, body, div] = keydown_tree(&order);
+
+        let path = Arc::new(Mutex::new(Vec::new()));
+        let path_clone = path.clone();
+        div.write().add_event_listener(
+            "keydown",
+            EventListener::from_fn(move |event| {
+                *path_clone.lock().unwrap() = event.composed_path();
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let event = keydown();
+        EventDispatcher::dispatch(event.clone(), div.clone()).unwrap();
+
+        let path = path.lock().unwrap();
+        assert_eq!(path.len(), 3);
+        let capture_order = [&document, &body, &div];
+        for (on_path, captured) in path.iter().rev().zip(capture_order) {
+            assert!(Arc::ptr_eq(on_path, captured));
+        }
+        assert!(event.read().composed_path().is_empty());
+    }
+
+    fn path_through_shadow_root(composed: bool) -> Vec<EventTargetRef> {
+        let host = Arc::new(RwLock::new(EventTargetData::new()));
+        let shadow_root = Arc::new(RwLock::new(EventTargetData::new()));
+        shadow_root.write().set_shadow_host(&host);
+        let inner = Arc::new(RwLock::new(EventTargetData::new()));
+        inner.write().set_parent_target(&shadow_root);
+
+        let path = Arc::new(Mutex::new(Vec::new()));
+        let path_clone = path.clone();
+        inner.write().add_event_listener(
+            "click",
+            EventListener::from_fn(move |event| {
+                *path_clone.lock().unwrap() = event.composed_path();
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let event = Arc::new(RwLock::new(Event::new(
+            "click",
+            EventInit {
+                bubbles: true,
+                composed,
+                ..Default::default()
+            },
+        )));
+        EventDispatcher::dispatch(event, inner.clone()).unwrap();
+
+        let path = path.lock().unwrap().clone();
+        assert!(Arc::ptr_eq(&path[0], &inner));
+        assert!(Arc::ptr_eq(&path[1], &shadow_root));
+        if composed {
+            assert!(Arc::ptr_eq(&path[2], &host));
+        }
+        path
+    }
+
+    #[test]
+    fn test_composed_path_crosses_shadow_boundary_only_when_composed() {
+        assert_eq!(path_through_shadow_root(true).len(), 3);
+        assert_eq!(path_through_shadow_root(false).len(), 2);
+    }
 }
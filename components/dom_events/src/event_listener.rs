@@ -38,6 +38,19 @@ impl EventListener {
         Self::Object(Arc::new(obj))
     }
 
+    /// Check whether both listeners wrap the same callback
+    pub fn same_callback(&self, other: &EventListener) -> bool {
+        match (self, other) {
+            (EventListener::Function(a), EventListener::Function(b)) => {
+                std::ptr::addr_eq(Arc::as_ptr(a), Arc::as_ptr(b))
+            }
+            (EventListener::Object(a), EventListener::Object(b)) => {
+                std::ptr::addr_eq(Arc::as_ptr(a), Arc::as_ptr(b))
+            }
+            _ => false,
+        }
+    }
+
     /// Invoke the event listener
     pub fn invoke(&self, event: &Event) {
         match self {
@@ -85,6 +98,8 @@ pub(crate) struct RegisteredEventListener {
     pub once: bool,
     /// Whether this listener is passive
     pub passive: bool,
+    /// Whether this is a global capture listener that runs ahead of the others
+    pub global_capture: bool,
     /// Whether this listener has been removed
     pub removed: bool,
 }
@@ -97,6 +112,7 @@ impl RegisteredEventListener {
             capture: options.capture,
             once: options.once,
             passive: options.passive,
+            global_capture: false,
             removed: false,
         }
     }
@@ -1,13 +1,15 @@
 //! EventTarget trait and implementation
 
-use crate::event::EventPhase;
+use crate::event::{EventPhase, EventRef};
+use crate::event_dispatcher::EventDispatcher;
 use crate::event_listener::{
     AddEventListenerOptions, EventListener, EventListenerOptions, RegisteredEventListener,
 };
 use dom_core::NodeRef;
+use dom_types::DomException;
 use parking_lot::RwLock;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// Reference to an EventTarget
 /// This is Arc<RwLock<EventTargetData>> which stores event listeners
@@ -22,6 +24,10 @@ pub struct EventTargetData {
     pub(crate) listeners: HashMap<String, Vec<RegisteredEventListener>>,
     /// Reference to the node (for tree traversal during event dispatch)
     pub(crate) node_ref: Option<NodeRef>,
+    /// Explicit parent target, taking precedence over the node's parent
+    pub(crate) parent_target: Option<Weak<RwLock<EventTargetData>>>,
+    /// Host of the shadow tree, if this target is a shadow root
+    pub(crate) shadow_host: Option<Weak<RwLock<EventTargetData>>>,
 }
 
 impl std::fmt::Debug for EventTargetData {
@@ -29,6 +35,8 @@ impl std::fmt::Debug for EventTargetData {
         f.debug_struct("EventTargetData")
             .field("listeners", &self.listeners)
             .field("node_ref", &self.node_ref.as_ref().map(|_| "<NodeRef>"))
+            .field("has_parent_target", &self.parent_target.is_some())
+            .field("is_shadow_root", &self.shadow_host.is_some())
             .finish()
     }
 }
@@ -39,6 +47,8 @@ impl EventTargetData {
         Self {
             listeners: HashMap::new(),
             node_ref: None,
+            parent_target: None,
+            shadow_host: None,
         }
     }
 
@@ -47,6 +57,8 @@ impl EventTargetData {
         Self {
             listeners: HashMap::new(),
             node_ref: Some(node_ref),
+            parent_target: None,
+            shadow_host: None,
         }
     }
 
@@ -64,25 +76,82 @@ impl EventTargetData {
             .push(registered);
     }
 
+    /// Add a global capture listener
+    ///
+    /// Meant for the document root's target: the listener runs in the capture
+    /// phase ahead of every author listener of the same type registered here,
+    /// so assistive tech and global shortcuts see events first and can stop
+    /// them. Global capture listeners run in the order they were added.
+    pub fn add_capturing_listener(&mut self, event_type: &str, listener: EventListener) {
+        let mut registered = RegisteredEventListener::new(
+            listener,
+            AddEventListenerOptions {
+                capture: true,
+                ..Default::default()
+            },
+        );
+        registered.global_capture = true;
+
+        let listeners = self.listeners.entry(event_type.to_string()).or_default();
+        let pos = listeners
+            .iter()
+            .position(|l| !l.global_capture)
+            .unwrap_or(listeners.len());
+        listeners.insert(pos, registered);
+    }
+
     /// Remove an event listener
     pub fn remove_event_listener(
         &mut self,
         event_type: &str,
-        _listener: EventListener,
+        listener: EventListener,
         options: EventListenerOptions,
     ) {
-        if let Some(listeners) = self.listeners.get_mut(event_type) {
-            // Mark matching listeners as removed
-            // Note: In a real implementation, we'd need to compare listener equality
-            // For now, we remove the first matching capture/bubble listener
-            if let Some(pos) = listeners.iter().position(|l| l.capture == options.capture) {
-                listeners[pos].removed = true;
-            }
+        remove_matching(&mut self.listeners, event_type, &listener, options.capture);
+    }
+
+    /// Claim a listener from a dispatch snapshot for invocation
+    ///
+    /// Returns `false` if the listener was removed since the snapshot was
+    /// taken. A `once` listener is removed here, before it runs, so a
+    /// re-entrant dispatch from its callback can't invoke it again.
+    pub(crate) fn claim_listener(
+        &mut self,
+        event_type: &str,
+        registered: &RegisteredEventListener,
+    ) -> bool {
+        let still_registered = self.listeners.get(event_type).is_some_and(|listeners| {
+            listeners.iter().any(|l| {
+                !l.removed
+                    && l.capture == registered.capture
+                    && l.listener.same_callback(&registered.listener)
+            })
+        });
+        if still_registered && registered.once {
+            remove_matching(
+                &mut self.listeners,
+                event_type,
+                &registered.listener,
+                registered.capture,
+            );
         }
+        still_registered
     }
 
     /// Get listeners for a specific event type and phase
     pub fn get_listeners(&self, event_type: &str, phase: EventPhase) -> Vec<EventListener> {
+        self.get_registered_listeners(event_type, phase)
+            .into_iter()
+            .map(|l| l.listener)
+            .collect()
+    }
+
+    /// Get registered listeners, with their options, for an event type and phase
+    pub(crate) fn get_registered_listeners(
+        &self,
+        event_type: &str,
+        phase: EventPhase,
+    ) -> Vec<RegisteredEventListener> {
         if let Some(listeners) = self.listeners.get(event_type) {
             listeners
                 .iter()
@@ -93,7 +162,7 @@ impl EventTargetData {
                     EventPhase::Bubbling => !l.capture,
                     EventPhase::None => false,
                 })
-                .map(|l| l.listener.clone())
+                .cloned()
                 .collect()
         } else {
             Vec::new()
@@ -111,6 +180,71 @@ impl EventTargetData {
     pub fn set_node_ref(&mut self, node_ref: NodeRef) {
         self.node_ref = Some(node_ref);
     }
+
+    /// Set the target that events propagate to after this one
+    ///
+    /// Lets listeners registered on an ancestor's target take part in capture
+    /// and bubbling, which node-based parents can't provide.
+    pub fn set_parent_target(&mut self, parent: &EventTargetRef) {
+        self.parent_target = Some(Arc::downgrade(parent));
+    }
+
+    /// Remove the explicit parent target, if any
+    pub fn clear_parent_target(&mut self) {
+        self.parent_target = None;
+    }
+
+    /// Get the explicit parent target, if set and still alive
+    pub fn parent_target(&self) -> Option<EventTargetRef> {
+        self.parent_target.as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Mark this target as the shadow root attached to `host`
+    ///
+    /// Composed events continue from here to the host; other events stop at
+    /// the shadow root.
+    pub fn set_shadow_host(&mut self, host: &EventTargetRef) {
+        self.shadow_host = Some(Arc::downgrade(host));
+    }
+
+    /// Get the shadow host, if this target is a shadow root
+    pub fn shadow_host(&self) -> Option<EventTargetRef> {
+        self.shadow_host.as_ref().and_then(Weak::upgrade)
+    }
+}
+
+/// `dispatchEvent` for shared event targets
+///
+/// `EventTargetRef` is a plain `Arc`, so the method lives on this trait;
+/// import it to call `target.dispatch_event(event)`.
+pub trait EventTargetExt {
+    /// Dispatch `event` through the capture, target and bubble phases
+    ///
+    /// Returns `false` if the event is cancelable and a listener called
+    /// `prevent_default`, and `true` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidStateError` if the event is already being dispatched.
+    fn dispatch_event(&self, event: EventRef) -> Result<bool, DomException>;
+}
+
+impl EventTargetExt for EventTargetRef {
+    fn dispatch_event(&self, event: EventRef) -> Result<bool, DomException> {
+        EventDispatcher::dispatch(event, self.clone())
+    }
+}
+
+/// Removes the listener registered with this callback and capture flag
+fn remove_matching(
+    listeners: &mut HashMap<String, Vec<RegisteredEventListener>>,
+    event_type: &str,
+    listener: &EventListener,
+    capture: bool,
+) {
+    if let Some(registered) = listeners.get_mut(event_type) {
+        registered.retain(|l| !(l.capture == capture && l.listener.same_callback(listener)));
+    }
 }
 
 /// Helper struct to store event listeners on a target
@@ -146,17 +280,10 @@ impl EventListenerRegistry {
     pub fn remove_listener(
         &mut self,
         event_type: &str,
-        _listener: EventListener,
+        listener: EventListener,
         options: EventListenerOptions,
     ) {
-        if let Some(listeners) = self.listeners.get_mut(event_type) {
-            // Mark matching listeners as removed
-            // Note: In a real implementation, we'd need to compare listener equality
-            // For now, we remove the first matching capture/bubble listener
-            if let Some(pos) = listeners.iter().position(|l| l.capture == options.capture) {
-                listeners[pos].removed = true;
-            }
-        }
+        remove_matching(&mut self.listeners, event_type, &listener, options.capture);
     }
 
     /// Get listeners for a specific event type and phase
@@ -219,6 +346,67 @@ mod tests {
         assert_eq!(listeners.len(), 1);
     }
 
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    /// Parent and child targets whose listeners log each phase
+    ///
+    /// The parent's capture listener cancels the event.
+    fn cancelling_tree(log: &Log) -> (EventTargetRef, EventTargetRef) {
+        let parent = Arc::new(RwLock::new(EventTargetData::new()));
+        let child = Arc::new(RwLock::new(EventTargetData::new()));
+        child.write().set_parent_target(&parent);
+
+        let logger = |entry: &'static str, cancel: bool| {
+            let log = log.clone();
+            EventListener::from_fn(move |event| {
+                log.lock().unwrap().push(entry);
+                if cancel {
+                    event.prevent_default();
+                }
+            })
+        };
+        let capture = AddEventListenerOptions {
+            capture: true,
+            ..Default::default()
+        };
+        let bubble = AddEventListenerOptions::default();
+        let mut parent_data = parent.write();
+        parent_data.add_event_listener("submit", logger("capture", true), capture);
+        parent_data.add_event_listener("submit", logger("bubble", false), bubble.clone());
+        drop(parent_data);
+        child
+            .write()
+            .add_event_listener("submit", logger("target", false), bubble);
+        (parent, child)
+    }
+
+    fn submit_event(cancelable: bool) -> EventRef {
+        let init = crate::EventInit {
+            bubbles: true,
+            cancelable,
+            ..Default::default()
+        };
+        Arc::new(RwLock::new(crate::Event::new("submit", init)))
+    }
+
+    #[test]
+    fn test_dispatch_event_reports_cancelled_default() {
+        let log = Log::default();
+        let (_parent, target) = cancelling_tree(&log);
+
+        assert_eq!(target.dispatch_event(submit_event(true)), Ok(false));
+        assert_eq!(*log.lock().unwrap(), ["capture", "target", "bubble"]);
+    }
+
+    #[test]
+    fn test_dispatch_event_ignores_cancelling_non_cancelable_events() {
+        let log = Log::default();
+        let (_parent, target) = cancelling_tree(&log);
+
+        assert_eq!(target.dispatch_event(submit_event(false)), Ok(true));
+        assert_eq!(*log.lock().unwrap(), ["capture", "target", "bubble"]);
+    }
+
     #[test]
     fn test_registry_add_listener() {
         let mut registry = EventListenerRegistry::new();
@@ -1,37 +1,39 @@
 //! CustomEvent implementation - for custom application-specific events
 
 use crate::event::{Event, EventInit};
+use dom_types::JsValue;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// CustomEvent initialization options
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CustomEventInit {
     /// Base Event initialization
     pub event_init: EventInit,
-    /// Custom detail data (would be Any in full implementation)
-    pub detail: Option<String>, // Simplified - would be generic data in full impl
+    /// Custom detail data
+    pub detail: Option<JsValue>,
 }
 
 /// CustomEvent reference type
 pub type CustomEventRef = Arc<RwLock<CustomEvent>>;
 
 /// CustomEvent - for custom application-specific events
+///
+/// The detail payload is stored on the base [`Event`], so listeners receiving
+/// the dispatched event can read it with [`Event::detail`].
 #[derive(Debug)]
 pub struct CustomEvent {
     /// Base Event data
     event: Event,
-    /// Custom detail data
-    detail: Option<String>,
 }
 
 impl CustomEvent {
     /// Create a new CustomEvent with the specified type and initialization options
     pub fn new(event_type: &str, init: CustomEventInit) -> Self {
-        Self {
-            event: Event::new(event_type, init.event_init),
-            detail: init.detail,
-        }
+        let mut event = Event::new(event_type, init.event_init);
+        event.detail = init.detail;
+        Self { event }
     }
 
     /// Get the base Event
@@ -45,14 +47,26 @@ impl CustomEvent {
     }
 
     /// Get the custom detail data
-    pub fn detail(&self) -> Option<&str> {
-        self.detail.as_deref()
+    pub fn detail(&self) -> Option<&JsValue> {
+        self.event.detail()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event_dispatcher::EventDispatcher;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn login_detail() -> JsValue {
+        JsValue::Object(HashMap::from([
+            ("user".to_string(), JsValue::string("john")),
+            ("attempts".to_string(), JsValue::number(2.0)),
+        ]))
+    }
 
     #[test]
     fn test_custom_event_creation() {
@@ -62,14 +76,17 @@ mod tests {
                 cancelable: false,
                 composed: true,
             },
-            detail: Some("Custom data payload".to_string()),
+            detail: Some(JsValue::string("Custom data payload")),
         };
 
         let custom_event = CustomEvent::new("my-custom-event", init);
 
         assert_eq!(custom_event.event().event_type(), "my-custom-event");
         assert_eq!(custom_event.event().bubbles(), true);
-        assert_eq!(custom_event.detail(), Some("Custom data payload"));
+        assert_eq!(
+            custom_event.detail(),
+            Some(&JsValue::string("Custom data payload"))
+        );
     }
 
     #[test]
@@ -90,15 +107,48 @@ mod tests {
     }
 
     #[test]
-    fn test_custom_event_json_detail() {
+    fn test_custom_event_object_detail_reaches_listener() {
+        let custom_event = CustomEvent::new(
+            "user:action",
+            CustomEventInit {
+                detail: Some(login_detail()),
+                ..Default::default()
+            },
+        );
+
+        let received = Arc::new(Mutex::new(None));
+        let received_clone = received.clone();
+        let mut target = EventTargetData::new();
+        target.add_event_listener(
+            "user:action",
+            EventListener::from_fn(move |event| {
+                *received_clone.lock().unwrap() = event.detail().cloned();
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let event = Arc::new(RwLock::new(custom_event.event().clone()));
+        EventDispatcher::dispatch(event, Arc::new(RwLock::new(target))).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), Some(login_detail()));
+    }
+
+    #[test]
+    fn test_custom_event_init_serde_round_trip() {
         let init = CustomEventInit {
-            detail: Some(r#"{"user": "john", "action": "login"}"#.to_string()),
-            ..Default::default()
+            event_init: EventInit {
+                bubbles: true,
+                ..Default::default()
+            },
+            detail: Some(login_detail()),
         };
 
-        let custom_event = CustomEvent::new("user:action", init);
+        let json = serde_json::to_string(&init).unwrap();
+        let restored: CustomEventInit = serde_json::from_str(&json).unwrap();
+        let custom_event = CustomEvent::new("user:action", restored);
 
-        assert_eq!(custom_event.detail(), Some(r#"{"user": "john", "action": "login"}"#));
+        assert!(custom_event.event().bubbles());
+        assert_eq!(custom_event.detail(), Some(&login_detail()));
     }
 
     #[test]
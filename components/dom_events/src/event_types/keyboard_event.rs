@@ -25,6 +25,12 @@ pub struct KeyboardEventInit {
     pub alt_key: bool,
     /// Whether Meta key was pressed
     pub meta_key: bool,
+    /// Whether Caps Lock was on
+    pub caps_lock: bool,
+    /// Whether Num Lock was on
+    pub num_lock: bool,
+    /// Whether Scroll Lock was on
+    pub scroll_lock: bool,
 }
 
 /// KeyboardEvent reference type
@@ -51,6 +57,12 @@ pub struct KeyboardEvent {
     alt_key: bool,
     /// Meta key modifier
     meta_key: bool,
+    /// Caps Lock state
+    caps_lock: bool,
+    /// Num Lock state
+    num_lock: bool,
+    /// Scroll Lock state
+    scroll_lock: bool,
 }
 
 impl KeyboardEvent {
@@ -66,6 +78,9 @@ impl KeyboardEvent {
             shift_key: init.shift_key,
             alt_key: init.alt_key,
             meta_key: init.meta_key,
+            caps_lock: init.caps_lock,
+            num_lock: init.num_lock,
+            scroll_lock: init.scroll_lock,
         }
     }
 
@@ -118,6 +133,23 @@ impl KeyboardEvent {
     pub fn meta_key(&self) -> bool {
         self.meta_key
     }
+
+    /// Get the state of a modifier key by its key value
+    ///
+    /// Recognizes `"Control"`, `"Shift"`, `"Alt"`, `"Meta"`, `"CapsLock"`,
+    /// `"NumLock"` and `"ScrollLock"`; any other key returns `false`.
+    pub fn get_modifier_state(&self, key: &str) -> bool {
+        match key {
+            "Control" => self.ctrl_key,
+            "Shift" => self.shift_key,
+            "Alt" => self.alt_key,
+            "Meta" => self.meta_key,
+            "CapsLock" => self.caps_lock,
+            "NumLock" => self.num_lock,
+            "ScrollLock" => self.scroll_lock,
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -145,6 +177,9 @@ mod tests {
             shift_key: false,
             alt_key: false,
             meta_key: false,
+            caps_lock: false,
+            num_lock: false,
+            scroll_lock: false,
         };
 
         let keyboard_event = KeyboardEvent::new("keydown", init);
@@ -195,4 +230,52 @@ mod tests {
         assert_eq!(keyboard_event.key(), "");
         assert_eq!(keyboard_event.repeat(), false);
     }
+
+    const MODIFIER_KEYS: [&str; 7] = [
+        "Control",
+        "Shift",
+        "Alt",
+        "Meta",
+        "CapsLock",
+        "NumLock",
+        "ScrollLock",
+    ];
+
+    fn init_with_modifier(key: &str) -> KeyboardEventInit {
+        let mut init = KeyboardEventInit::default();
+        match key {
+            "Control" => init.ctrl_key = true,
+            "Shift" => init.shift_key = true,
+            "Alt" => init.alt_key = true,
+            "Meta" => init.meta_key = true,
+            "CapsLock" => init.caps_lock = true,
+            "NumLock" => init.num_lock = true,
+            "ScrollLock" => init.scroll_lock = true,
+            _ => unreachable!(),
+        }
+        init
+    }
+
+    #[test]
+    fn test_get_modifier_state() {
+        for key in MODIFIER_KEYS {
+            let keyboard_event = KeyboardEvent::new("keydown", init_with_modifier(key));
+            for other in MODIFIER_KEYS {
+                assert_eq!(keyboard_event.get_modifier_state(other), other == key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_modifier_state_unknown_key() {
+        let init = KeyboardEventInit {
+            ctrl_key: true,
+            caps_lock: true,
+            ..Default::default()
+        };
+        let keyboard_event = KeyboardEvent::new("keydown", init);
+
+        assert!(!keyboard_event.get_modifier_state("Hyper"));
+        assert!(!keyboard_event.get_modifier_state("control"));
+    }
 }
@@ -1,6 +1,12 @@
 //! MouseEvent implementation - for mouse-related events
+//!
+//! The pointer position is given in three frames: `client` coordinates are
+//! relative to the viewport, `page` coordinates to the document, so that
+//! `page = client + scroll`, and `offset` coordinates to the target's
+//! bounding client rect, so that `offset = client - rect origin`.
 
 use super::ui_event::{UIEvent, UIEventInit};
+use dom_types::DOMRect;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -32,6 +38,12 @@ pub struct MouseEventInit {
     pub screen_x: i32,
     /// Y coordinate relative to screen
     pub screen_y: i32,
+    /// Horizontal scroll offset of the document when the event occurred
+    pub scroll_x: f64,
+    /// Vertical scroll offset of the document when the event occurred
+    pub scroll_y: f64,
+    /// Bounding client rect of the target, from `get_bounding_client_rect`
+    pub target_rect: Option<DOMRect>,
     /// Mouse button that triggered the event
     pub button: i16,
     /// Buttons being pressed when event was triggered
@@ -62,6 +74,12 @@ pub struct MouseEvent {
     screen_x: i32,
     /// Y coordinate relative to screen
     screen_y: i32,
+    /// Horizontal document scroll offset
+    scroll_x: f64,
+    /// Vertical document scroll offset
+    scroll_y: f64,
+    /// Bounding client rect of the target
+    target_rect: Option<DOMRect>,
     /// Mouse button
     button: i16,
     /// Buttons being pressed
@@ -85,6 +103,9 @@ impl MouseEvent {
             client_y: init.client_y,
             screen_x: init.screen_x,
             screen_y: init.screen_y,
+            scroll_x: init.scroll_x,
+            scroll_y: init.scroll_y,
+            target_rect: init.target_rect,
             button: init.button,
             buttons: init.buttons,
             ctrl_key: init.ctrl_key,
@@ -124,6 +145,41 @@ impl MouseEvent {
         self.screen_y
     }
 
+    /// Get the X coordinate relative to the document, `client_x + scroll_x`
+    pub fn page_x(&self) -> f64 {
+        f64::from(self.client_x) + self.scroll_x
+    }
+
+    /// Get the Y coordinate relative to the document, `client_y + scroll_y`
+    pub fn page_y(&self) -> f64 {
+        f64::from(self.client_y) + self.scroll_y
+    }
+
+    /// Get the X coordinate relative to the target's bounding client rect
+    ///
+    /// `None` until the target's rect is known.
+    pub fn offset_x(&self) -> Option<f64> {
+        let rect = self.target_rect?;
+        Some(f64::from(self.client_x) - rect.left())
+    }
+
+    /// Get the Y coordinate relative to the target's bounding client rect
+    ///
+    /// `None` until the target's rect is known.
+    pub fn offset_y(&self) -> Option<f64> {
+        let rect = self.target_rect?;
+        Some(f64::from(self.client_y) - rect.top())
+    }
+
+    /// Record the target's bounding client rect, for the `offset` coordinates
+    ///
+    /// The rect is in viewport coordinates, as returned by
+    /// `get_bounding_client_rect`, and is usually only known once the target
+    /// has been hit-tested.
+    pub fn set_target_rect(&mut self, rect: DOMRect) {
+        self.target_rect = Some(rect);
+    }
+
     /// Get mouse button
     pub fn button(&self) -> i16 {
         self.button
@@ -176,6 +232,9 @@ mod tests {
             client_y: 200,
             screen_x: 300,
             screen_y: 400,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            target_rect: None,
             button: 0, // Main button
             buttons: 1,
             ctrl_key: false,
@@ -226,6 +285,54 @@ mod tests {
         assert_eq!(mouse_event.buttons(), 2);
     }
 
+    #[test]
+    fn test_mouse_event_coordinate_frames() {
+        // An element laid out at (40, 30) in the viewport, 200 x 100
+        let element_rect = DOMRect::new(40.0, 30.0, 200.0, 100.0);
+        let init = MouseEventInit {
+            client_x: 90,
+            client_y: 55,
+            target_rect: Some(element_rect),
+            ..Default::default()
+        };
+        let event = MouseEvent::new("click", init.clone());
+
+        assert_eq!((event.page_x(), event.page_y()), (90.0, 55.0));
+        assert_eq!(event.offset_x(), Some(50.0));
+        assert_eq!(event.offset_y(), Some(25.0));
+
+        // Scrolling the document by (15, 300) moves the element up in the
+        // viewport, so the same client point is further into the page
+        let scrolled = MouseEvent::new(
+            "click",
+            MouseEventInit {
+                scroll_x: 15.0,
+                scroll_y: 300.0,
+                target_rect: Some(DOMRect::new(25.0, -270.0, 200.0, 100.0)),
+                ..init
+            },
+        );
+        assert_eq!((scrolled.client_x(), scrolled.client_y()), (90, 55));
+        assert_eq!((scrolled.page_x(), scrolled.page_y()), (105.0, 355.0));
+        assert_eq!(scrolled.offset_x(), Some(65.0));
+        assert_eq!(scrolled.offset_y(), Some(325.0));
+    }
+
+    #[test]
+    fn test_mouse_event_offset_needs_target_rect() {
+        let init = MouseEventInit {
+            client_x: 10,
+            client_y: 20,
+            ..Default::default()
+        };
+        let mut event = MouseEvent::new("mousemove", init);
+        assert_eq!(event.offset_x(), None);
+
+        event.set_target_rect(DOMRect::new(4.0, 5.0, 10.0, 10.0));
+        assert_eq!(event.offset_x(), Some(6.0));
+        assert_eq!(event.offset_y(), Some(15.0));
+    }
+
     #[test]
     fn test_mouse_event_default() {
         let init = MouseEventInit::default();
@@ -99,6 +99,26 @@ impl WheelEvent {
     pub fn delta_mode(&self) -> u32 {
         self.delta_mode
     }
+
+    /// Get `(delta_x, delta_y, delta_z)` converted to pixels
+    ///
+    /// Line deltas are scaled by `line_height` and page deltas by
+    /// `page_height`; pixel deltas, and deltas in an unknown mode, are
+    /// returned unchanged. A metric that is zero, negative or not finite
+    /// counts as 0, so a missing line height never yields NaN or flips the
+    /// scroll direction.
+    pub fn pixel_deltas(&self, line_height: f64, page_height: f64) -> (f64, f64, f64) {
+        let (x, y, z) = (self.delta_x, self.delta_y, self.delta_z);
+        let metric = match self.delta_mode {
+            Self::DOM_DELTA_LINE => line_height,
+            Self::DOM_DELTA_PAGE => page_height,
+            _ => return (x, y, z),
+        };
+        if !(metric.is_finite() && metric > 0.0) {
+            return (0.0, 0.0, 0.0);
+        }
+        (x * metric, y * metric, z * metric)
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +199,41 @@ mod tests {
         assert_eq!(wheel_event.delta_mode(), 0);
     }
 
+    fn wheel(delta_mode: u32, delta_x: f64, delta_y: f64) -> WheelEvent {
+        let init = WheelEventInit {
+            delta_x,
+            delta_y,
+            delta_z: 1.0,
+            delta_mode,
+            ..Default::default()
+        };
+        WheelEvent::new("wheel", init)
+    }
+
+    #[test]
+    fn test_pixel_deltas_per_mode() {
+        let pixels = wheel(WheelEvent::DOM_DELTA_PIXEL, -12.5, 40.0);
+        assert_eq!(pixels.pixel_deltas(20.0, 600.0), (-12.5, 40.0, 1.0));
+
+        let lines = wheel(WheelEvent::DOM_DELTA_LINE, -3.0, 2.0);
+        assert_eq!(lines.pixel_deltas(20.0, 600.0), (-60.0, 40.0, 20.0));
+
+        let pages = wheel(WheelEvent::DOM_DELTA_PAGE, 0.0, -1.5);
+        assert_eq!(pages.pixel_deltas(20.0, 600.0), (0.0, -900.0, 600.0));
+    }
+
+    #[test]
+    fn test_pixel_deltas_guard_degenerate_metrics() {
+        let lines = wheel(WheelEvent::DOM_DELTA_LINE, -3.0, 2.0);
+        assert_eq!(lines.pixel_deltas(0.0, 600.0), (0.0, 0.0, 0.0));
+        assert_eq!(lines.pixel_deltas(-20.0, 600.0), (0.0, 0.0, 0.0));
+        assert_eq!(lines.pixel_deltas(f64::NAN, 600.0), (0.0, 0.0, 0.0));
+
+        // Only the metric of the event's own mode matters
+        let pixels = wheel(WheelEvent::DOM_DELTA_PIXEL, 5.0, 6.0);
+        assert_eq!(pixels.pixel_deltas(0.0, f64::INFINITY), (5.0, 6.0, 1.0));
+    }
+
     #[test]
     fn test_wheel_event_constants() {
         assert_eq!(WheelEvent::DOM_DELTA_PIXEL, 0);
@@ -15,7 +15,9 @@
 //! # Quick Start
 //!
 //! ```rust
-//! use dom_events::{Event, EventInit, EventDispatcher};
+//! use dom_events::{Event, EventInit, EventTargetData, EventTargetExt};
+//! use parking_lot::RwLock;
+//! use std::sync::Arc;
 //!
 //! // Create an event
 //! let event = Event::new("click", EventInit {
@@ -25,7 +27,10 @@
 //! });
 //!
 //! // Events can be dispatched to targets
-//! // target.dispatch_event(event)?;
+//! let target = Arc::new(RwLock::new(EventTargetData::new()));
+//! let not_cancelled = target.dispatch_event(Arc::new(RwLock::new(event)))?;
+//! assert!(not_cancelled);
+//! # Ok::<(), dom_types::DomException>(())
 //! ```
 //!
 //! # Event Types
@@ -95,6 +100,10 @@ pub mod event_listener;
 pub mod event_target;
 pub mod event_types;
 pub mod document_ext;
+pub mod pointer_capture;
+pub mod scheduler;
+pub mod text_input;
+pub mod user_input;
 
 // Re-exports
 pub use event::{Event, EventInit, EventPhase, EventRef, EventTargetRef as EventTarget};
@@ -102,7 +111,11 @@ pub use event_dispatcher::EventDispatcher;
 pub use event_listener::{
     AddEventListenerOptions, EventListener, EventListenerObject, EventListenerOptions,
 };
-pub use event_target::{EventListenerRegistry, EventTargetData, EventTargetRef};
+pub use event_target::{EventListenerRegistry, EventTargetData, EventTargetExt, EventTargetRef};
+pub use pointer_capture::PointerCaptureRegistry;
+pub use scheduler::{ImmediateScheduler, ManualScheduler, Scheduler, SchedulerRef, Task};
+pub use text_input::TextInputExt;
+pub use user_input::UserInput;
 pub use event_types::{
     UIEvent, UIEventInit, UIEventRef,
     MouseEvent, MouseEventInit, MouseEventRef, MouseButton,
@@ -114,7 +127,7 @@ pub use event_types::{
     CompositionEvent, CompositionEventInit, CompositionEventRef,
 };
 pub use document_ext::{
-    DocumentEventExt, create_event_by_interface,
+    DocumentEventExt, ReadyStateEvents, create_event_by_interface,
     create_ui_event, create_mouse_event, create_keyboard_event,
     create_focus_event, create_input_event, create_wheel_event,
     create_custom_event, create_composition_event,
@@ -0,0 +1,191 @@
+//! Pointer capture (`setPointerCapture` / `releasePointerCapture`)
+//!
+//! While a pointer is captured, every pointer event for that pointer id is
+//! targeted at the capturing element instead of the hit-tested one. The
+//! [`PointerCaptureRegistry`] records which target holds each pointer and is
+//! consulted by [`EventDispatcher::dispatch_pointer_event`](crate::EventDispatcher::dispatch_pointer_event).
+
+use crate::event::{Event, EventInit};
+use crate::event_dispatcher::EventDispatcher;
+use crate::event_target::EventTargetRef;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Fired at a target when it gains capture of a pointer
+pub const GOT_POINTER_CAPTURE: &str = "gotpointercapture";
+
+/// Fired at a target when it loses capture of a pointer
+pub const LOST_POINTER_CAPTURE: &str = "lostpointercapture";
+
+/// Map from pointer id to the target capturing that pointer
+#[derive(Debug, Default)]
+pub struct PointerCaptureRegistry {
+    captures: HashMap<i32, EventTargetRef>,
+}
+
+impl PointerCaptureRegistry {
+    /// Create a registry with no captured pointers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture `pointer_id` to `target`
+    ///
+    /// A previous capturing target first receives `lostpointercapture`, then
+    /// `target` receives `gotpointercapture`. Capturing again to the same
+    /// target fires nothing.
+    pub fn set_pointer_capture(&mut self, target: &EventTargetRef, pointer_id: i32) {
+        if self.has_pointer_capture(target, pointer_id) {
+            return;
+        }
+        if let Some(previous) = self.captures.insert(pointer_id, target.clone()) {
+            fire_capture_event(LOST_POINTER_CAPTURE, &previous);
+        }
+        fire_capture_event(GOT_POINTER_CAPTURE, target);
+    }
+
+    /// Release capture of `pointer_id` held by `target`
+    ///
+    /// Fires `lostpointercapture` at `target`. Does nothing if `target` does
+    /// not hold the capture.
+    pub fn release_pointer_capture(&mut self, target: &EventTargetRef, pointer_id: i32) {
+        if !self.has_pointer_capture(target, pointer_id) {
+            return;
+        }
+        self.captures.remove(&pointer_id);
+        fire_capture_event(LOST_POINTER_CAPTURE, target);
+    }
+
+    /// Check whether `target` holds the capture of `pointer_id`
+    pub fn has_pointer_capture(&self, target: &EventTargetRef, pointer_id: i32) -> bool {
+        self.captures
+            .get(&pointer_id)
+            .is_some_and(|captured| Arc::ptr_eq(captured, target))
+    }
+
+    /// Get the target capturing `pointer_id`, if any
+    pub fn capture_target(&self, pointer_id: i32) -> Option<EventTargetRef> {
+        self.captures.get(&pointer_id).cloned()
+    }
+}
+
+fn fire_capture_event(event_type: &str, target: &EventTargetRef) {
+    let event = Arc::new(RwLock::new(Event::new(
+        event_type,
+        EventInit {
+            bubbles: true,
+            composed: true,
+            ..Default::default()
+        },
+    )));
+    let _ = EventDispatcher::dispatch(event, target.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn logging_target(name: &'static str, log: &Log) -> EventTargetRef {
+        let mut target = EventTargetData::new();
+        for event_type in ["pointermove", GOT_POINTER_CAPTURE, LOST_POINTER_CAPTURE] {
+            let log = log.clone();
+            target.add_event_listener(
+                event_type,
+                EventListener::from_fn(move |event| {
+                    log.lock()
+                        .unwrap()
+                        .push(format!("{}:{}", name, event.event_type()));
+                }),
+                AddEventListenerOptions::default(),
+            );
+        }
+        Arc::new(RwLock::new(target))
+    }
+
+    fn pointermove(registry: &PointerCaptureRegistry, pointer_id: i32, hit: &EventTargetRef) {
+        let event = Arc::new(RwLock::new(Event::new("pointermove", EventInit::default())));
+        EventDispatcher::dispatch_pointer_event(event, pointer_id, hit.clone(), registry).unwrap();
+    }
+
+    #[test]
+    fn test_captured_pointer_events_are_retargeted() {
+        let log = Log::default();
+        let slider = logging_target("slider", &log);
+        let page = logging_target("page", &log);
+        let mut registry = PointerCaptureRegistry::new();
+
+        registry.set_pointer_capture(&slider, 1);
+        assert!(registry.has_pointer_capture(&slider, 1));
+        assert!(!registry.has_pointer_capture(&slider, 2));
+        assert!(!registry.has_pointer_capture(&page, 1));
+
+        pointermove(&registry, 1, &page);
+        // Other pointers are not affected by the capture
+        pointermove(&registry, 2, &page);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "slider:gotpointercapture",
+                "slider:pointermove",
+                "page:pointermove"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_release_restores_normal_routing() {
+        let log = Log::default();
+        let slider = logging_target("slider", &log);
+        let page = logging_target("page", &log);
+        let mut registry = PointerCaptureRegistry::new();
+
+        registry.set_pointer_capture(&slider, 1);
+        // Releasing from a target without the capture is a no-op
+        registry.release_pointer_capture(&page, 1);
+        assert!(registry.has_pointer_capture(&slider, 1));
+
+        registry.release_pointer_capture(&slider, 1);
+        assert!(!registry.has_pointer_capture(&slider, 1));
+        assert!(registry.capture_target(1).is_none());
+
+        pointermove(&registry, 1, &page);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "slider:gotpointercapture",
+                "slider:lostpointercapture",
+                "page:pointermove"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_moves_between_targets() {
+        let log = Log::default();
+        let first = logging_target("first", &log);
+        let second = logging_target("second", &log);
+        let mut registry = PointerCaptureRegistry::new();
+
+        registry.set_pointer_capture(&first, 7);
+        registry.set_pointer_capture(&first, 7);
+        registry.set_pointer_capture(&second, 7);
+
+        assert!(registry.has_pointer_capture(&second, 7));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "first:gotpointercapture",
+                "first:lostpointercapture",
+                "second:gotpointercapture"
+            ]
+        );
+    }
+}
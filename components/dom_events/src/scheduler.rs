@@ -0,0 +1,189 @@
+//! Task and microtask scheduling
+//!
+//! Mutation observer callbacks run at the next microtask checkpoint and
+//! queued events run as tasks of their own, after the code that caused them.
+//! Such deferred work goes through a [`Scheduler`], so the embedder decides
+//! when it runs. [`ManualScheduler`] only runs work when pumped, which makes
+//! the ordering observable in tests.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+/// Deferred unit of work
+pub type Task = Box<dyn FnOnce() + Send>;
+
+/// Thread-safe reference to a scheduler
+pub type SchedulerRef = Arc<dyn Scheduler>;
+
+/// Queues tasks and microtasks for later execution
+pub trait Scheduler: Send + Sync {
+    /// Queue `task` to run at the next microtask checkpoint
+    fn enqueue_microtask(&self, task: Task);
+
+    /// Queue `task` to run as a task, after pending microtasks
+    fn enqueue_task(&self, task: Task);
+}
+
+/// Scheduler that runs work as soon as it is enqueued
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImmediateScheduler;
+
+impl Scheduler for ImmediateScheduler {
+    fn enqueue_microtask(&self, task: Task) {
+        task();
+    }
+
+    fn enqueue_task(&self, task: Task) {
+        task();
+    }
+}
+
+/// Scheduler whose queues only run on explicit calls
+///
+/// Work may enqueue more work; microtasks queued while draining run in the
+/// same drain, as at a real microtask checkpoint.
+#[derive(Default)]
+pub struct ManualScheduler {
+    microtasks: Mutex<VecDeque<Task>>,
+    tasks: Mutex<VecDeque<Task>>,
+}
+
+impl ManualScheduler {
+    /// Create a scheduler with empty queues
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a scheduler wrapped for sharing
+    pub fn new_ref() -> Arc<Self> {
+        Arc::new(Self::new())
+    }
+
+    /// Number of microtasks waiting to run
+    pub fn pending_microtasks(&self) -> usize {
+        self.microtasks.lock().len()
+    }
+
+    /// Number of tasks waiting to run
+    pub fn pending_tasks(&self) -> usize {
+        self.tasks.lock().len()
+    }
+
+    /// Run microtasks until the queue is empty, returning how many ran
+    pub fn run_microtasks(&self) -> usize {
+        let mut ran = 0;
+        while let Some(microtask) = pop(&self.microtasks) {
+            microtask();
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Run the tasks queued so far, returning how many ran
+    ///
+    /// Pending microtasks drain first, and again after each task. Tasks
+    /// queued by these tasks wait for the next call.
+    pub fn run_tasks(&self) -> usize {
+        self.run_microtasks();
+        let queued = self.pending_tasks();
+        for _ in 0..queued {
+            let Some(task) = pop(&self.tasks) else {
+                break;
+            };
+            task();
+            self.run_microtasks();
+        }
+        queued
+    }
+}
+
+/// Takes the next item off `queue`, releasing the lock before it runs
+///
+/// Popping inside a `while let` condition would hold the guard for the
+/// whole loop body, deadlocking work that enqueues more work.
+fn pop(queue: &Mutex<VecDeque<Task>>) -> Option<Task> {
+    queue.lock().pop_front()
+}
+
+impl Scheduler for ManualScheduler {
+    fn enqueue_microtask(&self, task: Task) {
+        self.microtasks.lock().push_back(task);
+    }
+
+    fn enqueue_task(&self, task: Task) {
+        self.tasks.lock().push_back(task);
+    }
+}
+
+impl fmt::Debug for ManualScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManualScheduler")
+            .field("microtasks", &self.pending_microtasks())
+            .field("tasks", &self.pending_tasks())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, EventInit};
+    use crate::event_dispatcher::EventDispatcher;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use parking_lot::RwLock;
+
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    fn logged(log: &Log, entry: &'static str) -> Task {
+        let log = log.clone();
+        Box::new(move || log.lock().push(entry))
+    }
+
+    #[test]
+    fn test_manual_scheduler_drains_microtasks_before_tasks() {
+        let scheduler = ManualScheduler::new_ref();
+        let log = Log::default();
+
+        scheduler.enqueue_task(logged(&log, "task"));
+        let nested = {
+            let (scheduler, log) = (scheduler.clone(), log.clone());
+            Box::new(move || {
+                log.lock().push("microtask");
+                scheduler.enqueue_microtask(logged(&log, "nested microtask"));
+            })
+        };
+        scheduler.enqueue_microtask(nested);
+        assert!(log.lock().is_empty());
+
+        assert_eq!(scheduler.run_tasks(), 1);
+        assert_eq!(*log.lock(), ["microtask", "nested microtask", "task"]);
+        assert_eq!(scheduler.pending_microtasks(), 0);
+    }
+
+    #[test]
+    fn test_queued_event_dispatches_when_tasks_run() {
+        let scheduler = ManualScheduler::new_ref();
+        let log = Log::default();
+        let mut target = EventTargetData::new();
+        let listener_log = log.clone();
+        target.add_event_listener(
+            "load",
+            EventListener::from_fn(move |_| listener_log.lock().push("load")),
+            AddEventListenerOptions::default(),
+        );
+        let target = Arc::new(RwLock::new(target));
+        let event = Arc::new(RwLock::new(Event::new("load", EventInit::default())));
+
+        EventDispatcher::queue_dispatch(&*scheduler, event, target);
+        scheduler.enqueue_microtask(logged(&log, "microtask"));
+        assert!(log.lock().is_empty());
+
+        scheduler.run_microtasks();
+        assert_eq!(*log.lock(), ["microtask"]);
+        assert_eq!(scheduler.run_tasks(), 1);
+        assert_eq!(*log.lock(), ["microtask", "load"]);
+    }
+}
@@ -0,0 +1,281 @@
+//! Synthetic keyboard text input
+//!
+//! Typing a character into an editable element fires `keydown`, then a
+//! cancelable `beforeinput`, then mutates the element's text, then fires
+//! `input`. [`TextInputExt::dispatch_text_input`] runs that sequence for each
+//! character, at a target whose node is a `<textarea>` or a `contenteditable`
+//! element. Listeners read the key and the inserted data from the
+//! [`KeyboardEvent`] and [`InputEvent`] with [`Event::interface`].
+
+use crate::event::{Event, EventInit};
+use crate::event_dispatcher::EventDispatcher;
+use crate::event_target::EventTargetRef;
+use crate::event_types::{
+    InputEvent, InputEventInit, KeyboardEvent, KeyboardEventInit, UIEventInit,
+};
+use dom_core::{new_node_ref, CharacterData, Element, NodeRef, Text};
+use dom_types::DomException;
+use parking_lot::RwLock;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Text entry for event targets backed by editable elements
+pub trait TextInputExt {
+    /// Type `text` into the target's element, one character at a time
+    ///
+    /// Cancelling `keydown` or `beforeinput` skips that character and
+    /// suppresses its `input`. Returns whether every character was inserted,
+    /// or `InvalidStateError` if the target's node is not an editable element.
+    fn dispatch_text_input(&self, text: &str) -> Result<bool, DomException>;
+}
+
+impl TextInputExt for EventTargetRef {
+    fn dispatch_text_input(&self, text: &str) -> Result<bool, DomException> {
+        let node = self
+            .read()
+            .node_ref
+            .clone()
+            .filter(is_editable)
+            .ok_or(DomException::InvalidStateError)?;
+
+        let mut inserted_all = true;
+        let mut buffer = [0; 4];
+        for character in text.chars() {
+            let character = &*character.encode_utf8(&mut buffer);
+            inserted_all &= type_character(self, &node, character)?;
+        }
+        Ok(inserted_all)
+    }
+}
+
+/// Type one `character` into `node`, returning whether it was inserted
+fn type_character(
+    target: &EventTargetRef,
+    node: &NodeRef,
+    character: &str,
+) -> Result<bool, DomException> {
+    let keydown = KeyboardEvent::new(
+        "keydown",
+        KeyboardEventInit {
+            ui_event_init: input_init(true),
+            key: character.to_string(),
+            ..Default::default()
+        },
+    );
+    let base = keydown.ui_event().event().clone();
+    if !fire(target, base, keydown)? {
+        return Ok(false);
+    }
+
+    let before_input = InputEvent::new("beforeinput", insert_text_init(character, true));
+    let base = before_input.ui_event().event().clone();
+    if !fire(target, base, before_input)? {
+        return Ok(false);
+    }
+
+    insert_text(node, character)?;
+
+    let input = InputEvent::new("input", insert_text_init(character, false));
+    let base = input.ui_event().event().clone();
+    fire(target, base, input)?;
+    Ok(true)
+}
+
+fn input_init(cancelable: bool) -> UIEventInit {
+    UIEventInit {
+        event_init: EventInit {
+            bubbles: true,
+            cancelable,
+            composed: true,
+        },
+        ..Default::default()
+    }
+}
+
+fn insert_text_init(text: &str, cancelable: bool) -> InputEventInit {
+    InputEventInit {
+        ui_event_init: input_init(cancelable),
+        data: Some(text.to_string()),
+        input_type: "insertText".to_string(),
+        is_composing: false,
+    }
+}
+
+/// Dispatch `event`, whose base event is `base`, at `target`
+///
+/// Returns whether the event wasn't cancelled.
+fn fire<T: Any + Send + Sync>(
+    target: &EventTargetRef,
+    base: Event,
+    event: T,
+) -> Result<bool, DomException> {
+    let event = Event::with_interface(base, event);
+    EventDispatcher::dispatch(Arc::new(RwLock::new(event)), target.clone())
+}
+
+/// Whether `node` is a writable `<textarea>` or a `contenteditable` element
+fn is_editable(node: &NodeRef) -> bool {
+    let node = node.read();
+    let Some(element) = node.as_any().downcast_ref::<Element>() else {
+        return false;
+    };
+    if element.tag_name() == "TEXTAREA" {
+        return !element.has_attribute("disabled") && !element.has_attribute("readonly");
+    }
+    element
+        .get_attribute("contenteditable")
+        .is_some_and(|value| !value.eq_ignore_ascii_case("false"))
+}
+
+/// Append `text` to the element, extending a trailing text node if present
+fn insert_text(node: &NodeRef, text: &str) -> Result<(), DomException> {
+    let last_child = node.read().node_data().children.last().cloned();
+    if let Some(last_child) = last_child {
+        let mut last_child = last_child.write();
+        if let Some(last_text) = last_child.as_any_mut().downcast_mut::<Text>() {
+            last_text.append_data(text);
+            return Ok(());
+        }
+    }
+
+    let text_node = new_node_ref(Text::new(text));
+    node.write().append_child(text_node)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn editable(tag: &str, attributes: &[(&str, &str)]) -> NodeRef {
+        let mut element = Element::new(tag);
+        for (name, value) in attributes {
+            element.set_attribute(*name, *value).unwrap();
+        }
+        new_node_ref(element)
+    }
+
+    fn logging_target(node: &NodeRef, log: &Log) -> EventTargetRef {
+        let mut target = EventTargetData::with_node(node.clone());
+        for event_type in ["keydown", "beforeinput", "input"] {
+            let log = log.clone();
+            target.add_event_listener(
+                event_type,
+                EventListener::from_fn(move |event| {
+                    log.lock().unwrap().push(event.event_type().to_string());
+                }),
+                AddEventListenerOptions::default(),
+            );
+        }
+        Arc::new(RwLock::new(target))
+    }
+
+    fn text_content(node: &NodeRef) -> String {
+        node.read().text_content().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_text_input_mutates_and_fires_input() {
+        let log = Log::default();
+        let node = editable("div", &[("contenteditable", "true")]);
+        let target = logging_target(&node, &log);
+
+        assert!(target.dispatch_text_input("Hel").unwrap());
+        assert!(target.dispatch_text_input("lo").unwrap());
+
+        assert_eq!(text_content(&node), "Hello");
+        assert_eq!(node.read().child_nodes().len(), 1);
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["keydown", "beforeinput", "input"].repeat(5)
+        );
+    }
+
+    #[test]
+    fn test_text_input_events_carry_each_character() {
+        let node = editable("textarea", &[]);
+        let target = logging_target(&node, &Log::default());
+        let typed = Log::default();
+        for event_type in ["keydown", "input"] {
+            let typed = typed.clone();
+            target.write().add_event_listener(
+                event_type,
+                EventListener::from_fn(move |event| {
+                    let typed_data = match event.interface::<KeyboardEvent>() {
+                        Some(keydown) => format!("key {}", keydown.key()),
+                        None => {
+                            let input = event.interface::<InputEvent>().unwrap();
+                            format!("{} {}", input.input_type(), input.data().unwrap())
+                        }
+                    };
+                    typed.lock().unwrap().push(typed_data);
+                }),
+                AddEventListenerOptions::default(),
+            );
+        }
+
+        assert!(target.dispatch_text_input("é!").unwrap());
+        assert_eq!(
+            *typed.lock().unwrap(),
+            ["key é", "insertText é", "key !", "insertText !"]
+        );
+    }
+
+    #[test]
+    fn test_cancelled_keydown_skips_only_that_character() {
+        let log = Log::default();
+        let node = editable("div", &[("contenteditable", "")]);
+        let target = logging_target(&node, &log);
+        target.write().add_event_listener(
+            "keydown",
+            EventListener::from_fn(|event| {
+                if event.interface::<KeyboardEvent>().unwrap().key() == "b" {
+                    event.prevent_default();
+                }
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        assert!(!target.dispatch_text_input("abc").unwrap());
+        assert_eq!(text_content(&node), "ac");
+        assert_eq!(log.lock().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_cancelled_beforeinput_prevents_mutation() {
+        let log = Log::default();
+        let node = editable("textarea", &[]);
+        let target = logging_target(&node, &log);
+        target.write().add_event_listener(
+            "beforeinput",
+            EventListener::from_fn(|event| event.prevent_default()),
+            AddEventListenerOptions::default(),
+        );
+
+        assert!(!target.dispatch_text_input("x").unwrap());
+        assert_eq!(text_content(&node), "");
+        assert_eq!(*log.lock().unwrap(), ["keydown", "beforeinput"]);
+    }
+
+    #[test]
+    fn test_text_input_requires_editable_element() {
+        let log = Log::default();
+        for node in [
+            editable("div", &[]),
+            editable("div", &[("contenteditable", "false")]),
+            editable("textarea", &[("readonly", "")]),
+        ] {
+            let target = logging_target(&node, &log);
+            assert_eq!(
+                target.dispatch_text_input("x").unwrap_err(),
+                DomException::InvalidStateError
+            );
+        }
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Trusted events from real user input
+//!
+//! Events built with the public constructors are untrusted, as if created by
+//! script, and [`EventDispatcher::dispatch`] untrusts whatever it dispatches.
+//! The embedder reports what the user did as a [`UserInput`] instead, whose
+//! [`queue_dispatch`](UserInput::queue_dispatch) is the only way to dispatch a
+//! trusted event.
+
+use crate::event::{Event, EventInit};
+use crate::event_dispatcher::EventDispatcher;
+use crate::event_target::EventTargetRef;
+use crate::event_types::{KeyboardEvent, KeyboardEventInit, MouseEvent, MouseEventInit};
+use crate::scheduler::Scheduler;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Input the user performed, as reported by the embedder
+#[derive(Debug, Clone)]
+pub enum UserInput {
+    /// Mouse input of the given type, such as `click`, with its position,
+    /// buttons and modifiers
+    Mouse(String, MouseEventInit),
+    /// Keyboard input of the given type, such as `keydown`, with its key and
+    /// modifiers
+    Keyboard(String, KeyboardEventInit),
+    /// Any other input of the given type
+    Other(String, EventInit),
+}
+
+impl UserInput {
+    /// Queue a task on `scheduler` that dispatches this input to `target`
+    ///
+    /// The dispatched event is trusted. Mouse and keyboard input carry their
+    /// [`MouseEvent`] or [`KeyboardEvent`], which listeners read with
+    /// [`Event::interface`].
+    pub fn queue_dispatch(self, scheduler: &dyn Scheduler, target: EventTargetRef) {
+        let event = Arc::new(RwLock::new(self.into_event()));
+        scheduler.enqueue_task(Box::new(move || {
+            let _ = EventDispatcher::dispatch_trusted(event, target);
+        }));
+    }
+
+    /// Build the trusted event for this input
+    fn into_event(self) -> Event {
+        let mut event = match self {
+            Self::Mouse(event_type, init) => {
+                let mouse = MouseEvent::new(&event_type, init);
+                Event::with_interface(mouse.ui_event().event().clone(), mouse)
+            }
+            Self::Keyboard(event_type, init) => {
+                let keyboard = KeyboardEvent::new(&event_type, init);
+                Event::with_interface(keyboard.ui_event().event().clone(), keyboard)
+            }
+            Self::Other(event_type, init) => Event::new_trusted(&event_type, init),
+        };
+        event.set_is_trusted(true);
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_listener::{AddEventListenerOptions, EventListener};
+    use crate::event_target::EventTargetData;
+    use crate::event_types::UIEventInit;
+    use crate::scheduler::ManualScheduler;
+    use std::sync::Mutex;
+
+    fn click_at(x: i32, y: i32) -> UserInput {
+        UserInput::Mouse(
+            "click".to_string(),
+            MouseEventInit {
+                ui_event_init: UIEventInit {
+                    event_init: EventInit::for_type("click"),
+                    ..Default::default()
+                },
+                client_x: x,
+                client_y: y,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_user_input_is_trusted_and_carries_its_interface() {
+        let target = Arc::new(RwLock::new(EventTargetData::new()));
+        let seen = Arc::new(Mutex::new(None));
+        let seen_clone = seen.clone();
+        target.write().add_event_listener(
+            "click",
+            EventListener::from_fn(move |event| {
+                let position = event
+                    .interface::<MouseEvent>()
+                    .map(|mouse| (mouse.client_x(), mouse.client_y()));
+                *seen_clone.lock().unwrap() = Some((event.is_trusted(), position));
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let scheduler = ManualScheduler::new_ref();
+        click_at(12, 34).queue_dispatch(&*scheduler, target);
+        assert!(seen.lock().unwrap().is_none());
+
+        scheduler.run_tasks();
+        assert_eq!(*seen.lock().unwrap(), Some((true, Some((12, 34)))));
+    }
+
+    #[test]
+    fn test_redispatching_a_trusted_copy_untrusts_it() {
+        let event = click_at(0, 0).into_event();
+        assert!(event.is_trusted());
+        assert!(event.interface::<KeyboardEvent>().is_none());
+
+        let copy = Arc::new(RwLock::new(event.clone()));
+        let target = Arc::new(RwLock::new(EventTargetData::new()));
+        EventDispatcher::dispatch(copy.clone(), target).unwrap();
+        assert!(!copy.read().is_trusted());
+    }
+}
@@ -26,9 +26,15 @@
 //!
 //! // Get string representation
 //! assert_eq!(atoms::DIV.as_str(), Some("div"));
+//!
+//! // Intern strings outside the predefined table
+//! let custom = Atom::intern("my-widget");
+//! assert_eq!(Atom::intern("my-widget"), custom);
+//! assert_eq!(custom.as_str(), Some("my-widget"));
 //! ```
 
 use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -45,7 +51,7 @@ use std::fmt;
 /// - **100-199**: HTML attributes
 /// - **200-299**: Event types
 /// - **300-399**: CSS property names (common ones)
-/// - **400+**: Reserved for dynamic atoms
+/// - **400+**: Dynamic atoms assigned by [`Atom::intern`]
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Atom(u32);
 
@@ -68,7 +74,7 @@ impl Atom {
     /// Get atom for a string, returning None if not a known atom
     ///
     /// This performs a hash lookup to find the corresponding atom.
-    /// For strings that aren't in the atom table, returns None.
+    /// For strings that are neither predefined nor interned yet, returns None.
     ///
     /// # Example
     ///
@@ -79,12 +85,38 @@ impl Atom {
     /// assert_eq!(Atom::from_str("unknown-element"), None);
     /// ```
     pub fn from_str(s: &str) -> Option<Atom> {
-        ATOM_MAP.get(s).copied()
+        ATOM_MAP
+            .get(s)
+            .copied()
+            .or_else(|| DynamicAtomTable::global().get(s))
+    }
+
+    /// Get the atom for a string, interning it if it is not known yet
+    ///
+    /// Predefined strings map to their static atoms. Any other string gets
+    /// a dynamic atom from the global [`DynamicAtomTable`], so interning
+    /// equal strings yields equal atoms on every thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use browser_dom_impl::atoms::{Atom, atoms};
+    ///
+    /// assert_eq!(Atom::intern("div"), atoms::DIV);
+    /// let widget = Atom::intern("x-widget");
+    /// assert!(widget.is_dynamic());
+    /// assert_eq!(Atom::from_str("x-widget"), Some(widget));
+    /// ```
+    pub fn intern(s: &str) -> Atom {
+        match ATOM_MAP.get(s) {
+            Some(&atom) => atom,
+            None => DynamicAtomTable::global().intern(s),
+        }
     }
 
     /// Get string representation of atom
     ///
-    /// Returns None if this is not a known static atom.
+    /// Returns None if this is neither a predefined nor an interned atom.
     ///
     /// # Example
     ///
@@ -94,6 +126,9 @@ impl Atom {
     /// assert_eq!(atoms::DIV.as_str(), Some("div"));
     /// ```
     pub fn as_str(&self) -> Option<&'static str> {
+        if self.is_dynamic() {
+            return DynamicAtomTable::global().resolve(*self);
+        }
         REVERSE_MAP.get(&self.0).copied()
     }
 
@@ -121,6 +156,12 @@ impl Atom {
         self.0 >= 300 && self.0 < 400
     }
 
+    /// Check if this atom is in the dynamic range
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        self.0 >= FIRST_DYNAMIC_ATOM
+    }
+
     /// Check if this is a void element (self-closing)
     pub fn is_void_element(&self) -> bool {
         matches!(
@@ -972,6 +1013,79 @@ static REVERSE_MAP: Lazy<HashMap<u32, &'static str>> = Lazy::new(|| {
         .collect()
 });
 
+/// First ID handed out to dynamically interned strings
+const FIRST_DYNAMIC_ATOM: u32 = 400;
+
+static DYNAMIC_ATOMS: Lazy<DynamicAtomTable> = Lazy::new(|| DynamicAtomTable {
+    inner: RwLock::new(DynamicAtoms::default()),
+});
+
+/// Thread-safe interner for strings outside the predefined table
+///
+/// Each previously unseen string gets the next ID from 400 up; repeats get
+/// the ID they were first given. Interned strings are never freed, so only
+/// names that recur, such as custom element or `data-*` attribute names,
+/// should be interned.
+///
+/// There is exactly one table, reached through [`DynamicAtomTable::global`],
+/// so a dynamic ID always resolves to the same string.
+#[derive(Debug)]
+pub struct DynamicAtomTable {
+    inner: RwLock<DynamicAtoms>,
+}
+
+#[derive(Debug, Default)]
+struct DynamicAtoms {
+    ids: HashMap<&'static str, Atom>,
+    strings: Vec<&'static str>,
+}
+
+impl DynamicAtomTable {
+    /// The table behind [`Atom::intern`]
+    pub fn global() -> &'static DynamicAtomTable {
+        &DYNAMIC_ATOMS
+    }
+
+    /// Get the atom for `s`, assigning the next dynamic ID if it is new
+    pub fn intern(&self, s: &str) -> Atom {
+        if let Some(atom) = self.get(s) {
+            return atom;
+        }
+
+        // Another thread may have interned `s` since the read lock was released
+        let mut inner = self.inner.write();
+        if let Some(&atom) = inner.ids.get(s) {
+            return atom;
+        }
+        let atom = Atom(FIRST_DYNAMIC_ATOM + inner.strings.len() as u32);
+        let s: &'static str = Box::leak(s.into());
+        inner.strings.push(s);
+        inner.ids.insert(s, atom);
+        atom
+    }
+
+    /// Get the atom for `s` if it was interned already
+    pub fn get(&self, s: &str) -> Option<Atom> {
+        self.inner.read().ids.get(s).copied()
+    }
+
+    /// Get the string a dynamic atom was interned from
+    pub fn resolve(&self, atom: Atom) -> Option<&'static str> {
+        let index = atom.0.checked_sub(FIRST_DYNAMIC_ATOM)?;
+        self.inner.read().strings.get(index as usize).copied()
+    }
+
+    /// Number of interned strings
+    pub fn len(&self) -> usize {
+        self.inner.read().strings.len()
+    }
+
+    /// Whether no string was interned yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 /// Get the total number of predefined atoms
 pub fn atom_count() -> usize {
     ATOM_MAP.len()
@@ -1150,6 +1264,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_intern_reuses_dynamic_atoms() {
+        let first = Atom::intern("x-custom-tag");
+        let second = Atom::intern("x-custom-tag");
+        assert_eq!(first, second);
+        assert!(first.is_dynamic());
+        assert!(first.raw() >= 400);
+        assert_eq!(first.as_str(), Some("x-custom-tag"));
+        assert_eq!(first.to_string(), "x-custom-tag");
+
+        assert_ne!(Atom::intern("data-user-id"), first);
+        // Predefined strings keep their static atoms
+        assert_eq!(Atom::intern("div"), atoms::DIV);
+    }
+
+    #[test]
+    fn test_intern_agrees_across_threads() {
+        let table = DynamicAtomTable::global();
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| ["x-alpha", "x-beta", "x-gamma"].map(Atom::intern)))
+            .collect();
+
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(results.iter().all(|atoms| *atoms == results[0]));
+        assert!(table.len() >= 3);
+        for atom in results[0] {
+            let name = table.resolve(atom).unwrap();
+            assert_eq!(table.get(name), Some(atom));
+        }
+    }
+
     #[test]
     fn test_atom_copy() {
         let atom1 = atoms::DIV;
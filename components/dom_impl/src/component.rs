@@ -1,9 +1,20 @@
 //! Main DOM component for message bus integration
 
 use crate::{
-    config::DomConfig, messages::*, Document, DocumentRef, Element, ElementRef, Node, NodeRef, Text,
+    build_dom_tree_with_config,
+    config::DomConfig,
+    downcast_element,
+    messages::*,
+    policy::{AllowAllPolicy, DomPolicy},
+    Document, DocumentRef, Element, ElementRef, Node, NodeRef, SelectorMatcher,
 };
-use dom_types::{DocumentId, NodeId};
+use dom_core::{ClassInvalidation, StyleInvalidationSink};
+use dom_events::{
+    EventInit, EventTargetData, EventTargetRef, ImmediateScheduler, KeyboardEventInit,
+    MouseEventInit, SchedulerRef, UIEventInit, UserInput,
+};
+use dom_types::{DocumentId, DomException, NodeId};
+use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 
@@ -11,7 +22,7 @@ use std::sync::{Arc, Weak};
 type RwLock<T> = parking_lot::RwLock<T>;
 
 /// Weak reference to a node for the registry
-pub type WeakNodeRef = Weak<RwLock<Box<dyn Node>>>;
+pub type WeakNodeRef = Weak<RwLock<dyn Node>>;
 
 /// Main DOM component that handles messages from other browser components
 pub struct DomComponent {
@@ -21,6 +32,12 @@ pub struct DomComponent {
     /// Global node registry (weak references to prevent memory leaks)
     node_registry: HashMap<NodeId, WeakNodeRef>,
 
+    /// Reverse of `node_registry`, keyed by node address
+    ///
+    /// The registry's weak references keep each allocation, and so its
+    /// address, from being reused while the entry exists.
+    node_ids: HashMap<usize, NodeId>,
+
     /// Configuration
     config: DomConfig,
 
@@ -29,20 +46,116 @@ pub struct DomComponent {
 
     /// Next document ID to assign
     next_document_id: DocumentId,
+
+    /// Policy consulted before applying mutating operations
+    policy: Box<dyn DomPolicy>,
+
+    /// Mutation records waiting to be delivered
+    pending_mutations: Vec<MutationRecord>,
+
+    /// Nodes affected by the pending mutations, without duplicates
+    pending_affected: Vec<NodeId>,
+
+    /// Runs deferred work such as mutation observer callbacks
+    scheduler: SchedulerRef,
+
+    /// Event targets of nodes, receiving the events of user interactions
+    event_targets: HashMap<NodeId, EventTargetRef>,
+
+    /// Style sink of every document, queueing class changes for the CSS engine
+    style_invalidations: Arc<StyleInvalidationQueue>,
+}
+
+/// Class changes reported by the elements of the component's documents
+#[derive(Debug, Default)]
+struct StyleInvalidationQueue {
+    /// Address of each changed element with its class delta, oldest first
+    changes: Mutex<Vec<(usize, ClassInvalidation)>>,
+}
+
+impl StyleInvalidationSink for StyleInvalidationQueue {
+    fn classes_changed(&self, root: &Element, change: &ClassInvalidation) {
+        if let Some(root) = root.self_ref() {
+            let root: NodeRef = root;
+            self.changes
+                .lock()
+                .push((node_address(&root), change.clone()));
+        }
+    }
 }
 
 impl DomComponent {
     /// Create a new DOM component with the given configuration
     pub fn new(config: DomConfig) -> Self {
+        Self::with_policy(config, AllowAllPolicy)
+    }
+
+    /// Create a new DOM component whose operations are gated by `policy`
+    pub fn with_policy(config: DomConfig, policy: impl DomPolicy + 'static) -> Self {
         Self {
             documents: HashMap::new(),
             node_registry: HashMap::new(),
+            node_ids: HashMap::new(),
             config,
             next_node_id: 1,
             next_document_id: 1,
+            policy: Box::new(policy),
+            pending_mutations: Vec::new(),
+            pending_affected: Vec::new(),
+            scheduler: Arc::new(ImmediateScheduler),
+            event_targets: HashMap::new(),
+            style_invalidations: Arc::default(),
         }
     }
 
+    /// Replace the operation policy
+    pub fn set_policy(&mut self, policy: impl DomPolicy + 'static) {
+        self.policy = Box::new(policy);
+    }
+
+    /// Replace the scheduler that runs deferred work
+    ///
+    /// By default work runs immediately.
+    pub fn set_scheduler(&mut self, scheduler: SchedulerRef) {
+        self.scheduler = scheduler;
+    }
+
+    /// The scheduler that runs deferred work
+    pub fn scheduler(&self) -> &SchedulerRef {
+        &self.scheduler
+    }
+
+    /// Create a mutation observer delivering through the component's scheduler
+    ///
+    /// Records are delivered in a microtask, batched with every other record
+    /// the observer queues before it runs.
+    #[cfg(feature = "dom-advanced")]
+    pub fn create_mutation_observer(
+        &self,
+        callback: impl Fn(&[dom_advanced::MutationRecord]) + Send + Sync + 'static,
+    ) -> dom_advanced::MutationObserver {
+        dom_advanced::MutationObserver::with_scheduler(callback, self.scheduler.clone())
+    }
+
+    /// Take the style invalidations for the CSS engine, oldest first
+    ///
+    /// Every `class` change of an element in the component's documents
+    /// yields a `StyleInvalidation` carrying the changed classes, with the
+    /// element as the root of the invalidated subtree.
+    pub fn take_style_invalidations(&mut self) -> Vec<DomComponentMessage> {
+        let changes = std::mem::take(&mut *self.style_invalidations.changes.lock());
+        changes
+            .into_iter()
+            .filter_map(|(address, change)| {
+                let node_id = *self.node_ids.get(&address)?;
+                Some(DomComponentMessage::StyleInvalidation {
+                    node_id,
+                    reason: change.into(),
+                })
+            })
+            .collect()
+    }
+
     /// Handle an incoming message from the browser message bus
     pub fn handle_message(&mut self, msg: DomComponentMessage) -> DomComponentResponse {
         match msg {
@@ -52,11 +165,13 @@ impl DomComponent {
                 doctype,
             } => self.handle_parsed_document(request_id, root, doctype),
 
-            DomComponentMessage::ScriptManipulation {
-                operation,
-                node_id,
-                params,
-            } => self.handle_script_manipulation(operation, node_id, params),
+            DomComponentMessage::ScriptManipulation { operation, .. } => {
+                self.handle_script_manipulations(vec![operation])
+            }
+
+            DomComponentMessage::ScriptManipulations { operations } => {
+                self.handle_script_manipulations(operations)
+            }
 
             DomComponentMessage::StyleInvalidation { node_id, reason } => {
                 self.handle_style_invalidation(node_id, reason)
@@ -74,6 +189,18 @@ impl DomComponent {
         }
     }
 
+    /// Get the event target of `node_id`, creating it on first use
+    ///
+    /// `UserInteraction` messages dispatch trusted events to the target of
+    /// their node, as tasks on the scheduler. The events capture and bubble
+    /// through the targets of the node's ancestors.
+    pub fn event_target(&mut self, node_id: NodeId) -> EventTargetRef {
+        self.event_targets
+            .entry(node_id)
+            .or_insert_with(|| Arc::new(RwLock::new(EventTargetData::new())))
+            .clone()
+    }
+
     /// Handle a parsed document from the HTML parser
     fn handle_parsed_document(
         &mut self,
@@ -82,16 +209,26 @@ impl DomComponent {
         _doctype: Option<DocumentType>,
     ) -> DomComponentResponse {
         // Create a new document
-        let document = Document::new();
-        let document_ref = Arc::new(RwLock::new(document));
+        let mut document = Document::new();
+        document.set_depth_limit(Some(self.config.max_tree_depth));
+        let document_ref = document.into_ref();
 
         // Build the DOM tree from the parsed nodes
-        if let Some(root_element) = self.build_dom_tree(&document_ref, root) {
-            // Set the document element
-            let mut doc = document_ref.write();
-            doc.set_document_element(root_element);
+        match self.build_dom_tree(&document_ref, &root) {
+            Ok(Some(root_element)) => {
+                // Set the document element
+                let mut doc = document_ref.write();
+                doc.set_document_element(root_element);
+            }
+            Ok(None) => {}
+            Err(error) => return error_response(error),
         }
 
+        // Report later class changes to the CSS engine; the classes the tree
+        // was built with are not changes
+        let sink = self.style_invalidations.clone();
+        document_ref.write().set_style_invalidation_sink(Some(sink));
+
         // Assign document ID and store
         let document_id = self.next_document_id;
         self.next_document_id += 1;
@@ -104,77 +241,100 @@ impl DomComponent {
     }
 
     /// Build DOM tree from parsed nodes
+    ///
+    /// Delegates to [`build_dom_tree_with_config`], so the elements are the
+    /// ones `document` created and indexed. Every node of the tree, root
+    /// included, is registered; a root that is not an element yields `None`.
     fn build_dom_tree(
         &mut self,
-        _document: &DocumentRef,
-        parsed: ParsedNode,
-    ) -> Option<ElementRef> {
-        match parsed.node_type {
-            ParsedNodeType::Element => {
-                let tag_name = parsed.tag_name.as_ref()?;
-
-                // Create element (delegate to dom-core)
-                let mut element = Element::new(tag_name.clone());
-
-                // Set attributes
-                for (name, value) in parsed.attributes {
-                    let _ = element.set_attribute(&name, &value);
-                }
+        document: &DocumentRef,
+        parsed: &ParsedNode,
+    ) -> Result<Option<ElementRef>, DomException> {
+        if parsed.node_type != ParsedNodeType::Element || parsed.tag_name.is_none() {
+            return Ok(None);
+        }
+        let root = build_dom_tree_with_config(parsed, &mut document.write(), &self.config)?;
 
-                let element_ref = Arc::new(RwLock::new(element));
-
-                // Register element with node ID (simplified - just track the ID)
-                let _node_id = self.next_node_id;
-                self.next_node_id += 1;
-
-                // Process children recursively
-                for child_parsed in parsed.children {
-                    match child_parsed.node_type {
-                        ParsedNodeType::Element => {
-                            if let Some(child_element) =
-                                self.build_dom_tree(_document, child_parsed)
-                            {
-                                // Convert ElementRef to NodeRef
-                                let child_node: NodeRef =
-                                    Arc::new(RwLock::new(Box::new(child_element.read().clone())));
-
-                                {
-                                    let mut elem = element_ref.write();
-                                    let _ = elem.append_child(child_node);
-                                }
-                            }
-                        }
-                        ParsedNodeType::Text => {
-                            if let Some(text_content) = child_parsed.text_content {
-                                let text = Text::new(text_content);
-                                let text_node: NodeRef =
-                                    Arc::new(RwLock::new(Box::new(text) as Box<dyn Node>));
-
-                                {
-                                    let mut elem = element_ref.write();
-                                    let _ = elem.append_child(text_node);
-                                }
-                            }
-                        }
-                        _ => {
-                            // Skip other node types for now
-                        }
-                    }
-                }
+        let mut stack = vec![root.clone()];
+        while let Some(node) = stack.pop() {
+            self.register_node(&node);
+            stack.extend(node.read().child_nodes().into_iter().rev());
+        }
+        Ok(downcast_element(&root))
+    }
 
-                Some(element_ref)
+    /// Create an element carrying the configured attribute, child and depth limits
+    pub fn create_element(&self, tag_name: &str) -> Element {
+        let mut element = Element::new(tag_name);
+        element.set_attribute_limit(Some(self.config.max_attributes_per_element));
+        element.set_attribute_value_limit(Some(self.config.max_attribute_value_len));
+        element.set_child_limit(Some(self.config.max_children));
+        element.set_depth_limit(Some(self.config.max_tree_depth));
+        element
+    }
+
+    /// Set an attribute on `element`
+    ///
+    /// The attribute count and value length are enforced by the element
+    /// itself, against the limits [`create_element`](Self::create_element)
+    /// gave it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QuotaExceededError` if `value` is longer than
+    /// `max_attribute_value_len` bytes, or if `name` is a new attribute and
+    /// the element is at its attribute limit.
+    pub fn set_attribute(
+        &self,
+        element: &mut Element,
+        name: &str,
+        value: &str,
+    ) -> Result<(), DomException> {
+        element.set_attribute(name, value)
+    }
+
+    /// Fails if `value` is longer than `max_attribute_value_len`
+    ///
+    /// Used for operations that name an element by id only; elements from
+    /// [`create_element`](Self::create_element) check their values themselves.
+    fn check_attribute_value(&self, value: &str) -> Result<(), DomException> {
+        if value.len() > self.config.max_attribute_value_len {
+            return Err(DomException::QuotaExceededError);
+        }
+        Ok(())
+    }
+
+    /// Handle the script manipulations of one task
+    ///
+    /// The mutation records of all operations are coalesced into a single
+    /// `DomMutated` response. An operation with a response of its own, such
+    /// as an error or the id of a created node, ends the batch with that
+    /// response; the records queued before it wait for
+    /// [`flush_mutations`](Self::flush_mutations).
+    fn handle_script_manipulations(
+        &mut self,
+        operations: Vec<DomOperation>,
+    ) -> DomComponentResponse {
+        for operation in operations {
+            if let Some(response) = self.apply_script_manipulation(operation) {
+                return response;
             }
-            _ => None,
         }
+        self.take_mutations()
     }
 
-    /// Handle script manipulation
-    fn handle_script_manipulation(
+    /// Apply a script manipulation, queueing its mutation records
+    ///
+    /// Returns `None` when the operation only produced mutation records, or
+    /// the response to send for errors and operations with a result.
+    fn apply_script_manipulation(
         &mut self,
         operation: DomOperation,
-        _node_id: NodeId,
-        _params: OperationParams,
-    ) -> DomComponentResponse {
+    ) -> Option<DomComponentResponse> {
+        if let Err(error) = self.policy.check_operation(&operation) {
+            return Some(error_response(error));
+        }
+
         match operation {
             DomOperation::AppendChild {
                 parent_id,
@@ -184,10 +344,10 @@ impl DomComponent {
                 // 1. Look up parent and child from node_registry
                 // 2. Perform appendChild operation
                 // 3. Generate mutation records
-                // For now, return a placeholder response
+                // For now, queue a placeholder record
 
-                DomComponentResponse::DomMutated {
-                    mutations: vec![MutationRecord {
+                self.queue_mutation(
+                    MutationRecord {
                         mutation_type: MutationType::ChildList,
                         target: parent_id,
                         added_nodes: vec![child_id],
@@ -196,9 +356,10 @@ impl DomComponent {
                         next_sibling: None,
                         attribute_name: None,
                         old_value: None,
-                    }],
-                    affected_nodes: vec![parent_id, child_id],
-                }
+                    },
+                    &[parent_id, child_id],
+                );
+                None
             }
 
             DomOperation::SetAttribute {
@@ -208,9 +369,12 @@ impl DomComponent {
             } => {
                 // Look up element and set attribute (delegating to dom-core)
                 // Generate mutation record
+                if let Err(error) = self.check_attribute_value(&value) {
+                    return Some(error_response(error));
+                }
 
-                DomComponentResponse::DomMutated {
-                    mutations: vec![MutationRecord {
+                self.queue_mutation(
+                    MutationRecord {
                         mutation_type: MutationType::Attributes,
                         target: element_id,
                         added_nodes: vec![],
@@ -219,31 +383,35 @@ impl DomComponent {
                         next_sibling: None,
                         attribute_name: Some(name),
                         old_value: Some(value),
-                    }],
-                    affected_nodes: vec![element_id],
-                }
+                    },
+                    &[element_id],
+                );
+                None
             }
 
             DomOperation::RemoveChild {
                 parent_id,
                 child_id,
-            } => DomComponentResponse::DomMutated {
-                mutations: vec![MutationRecord {
-                    mutation_type: MutationType::ChildList,
-                    target: parent_id,
-                    added_nodes: vec![],
-                    removed_nodes: vec![child_id],
-                    previous_sibling: None,
-                    next_sibling: None,
-                    attribute_name: None,
-                    old_value: None,
-                }],
-                affected_nodes: vec![parent_id, child_id],
-            },
+            } => {
+                self.queue_mutation(
+                    MutationRecord {
+                        mutation_type: MutationType::ChildList,
+                        target: parent_id,
+                        added_nodes: vec![],
+                        removed_nodes: vec![child_id],
+                        previous_sibling: None,
+                        next_sibling: None,
+                        attribute_name: None,
+                        old_value: None,
+                    },
+                    &[parent_id, child_id],
+                );
+                None
+            }
 
             DomOperation::RemoveAttribute { element_id, name } => {
-                DomComponentResponse::DomMutated {
-                    mutations: vec![MutationRecord {
+                self.queue_mutation(
+                    MutationRecord {
                         mutation_type: MutationType::Attributes,
                         target: element_id,
                         added_nodes: vec![],
@@ -252,50 +420,92 @@ impl DomComponent {
                         next_sibling: None,
                         attribute_name: Some(name),
                         old_value: None,
-                    }],
-                    affected_nodes: vec![element_id],
-                }
+                    },
+                    &[element_id],
+                );
+                None
             }
 
-            DomOperation::SetTextContent { node_id, text: _ } => DomComponentResponse::DomMutated {
-                mutations: vec![MutationRecord {
-                    mutation_type: MutationType::CharacterData,
-                    target: node_id,
-                    added_nodes: vec![],
-                    removed_nodes: vec![],
-                    previous_sibling: None,
-                    next_sibling: None,
-                    attribute_name: None,
-                    old_value: None,
-                }],
-                affected_nodes: vec![node_id],
-            },
+            DomOperation::SetTextContent { node_id, text: _ } => {
+                self.queue_mutation(
+                    MutationRecord {
+                        mutation_type: MutationType::CharacterData,
+                        target: node_id,
+                        added_nodes: vec![],
+                        removed_nodes: vec![],
+                        previous_sibling: None,
+                        next_sibling: None,
+                        attribute_name: None,
+                        old_value: None,
+                    },
+                    &[node_id],
+                );
+                None
+            }
 
             DomOperation::CreateElement { tag_name, .. } => {
                 // Create element and register
                 let _element = Element::new(tag_name);
-                let element_id = self.next_node_id;
-                self.next_node_id += 1;
+                let element_id = self.allocate_node_id();
 
                 // Return success (simplified)
-                DomComponentResponse::QueryResult {
+                Some(DomComponentResponse::QueryResult {
                     request_id: 0,
                     result: QueryResultType::NodeIds(vec![element_id]),
-                }
+                })
             }
 
             DomOperation::CreateTextNode { .. } => {
-                let node_id = self.next_node_id;
-                self.next_node_id += 1;
+                let node_id = self.allocate_node_id();
 
-                DomComponentResponse::QueryResult {
+                Some(DomComponentResponse::QueryResult {
                     request_id: 0,
                     result: QueryResultType::NodeIds(vec![node_id]),
-                }
+                })
             }
         }
     }
 
+    /// Deliver the queued mutation records as a single `DomMutated` response
+    ///
+    /// Returns `None` if no mutations are pending.
+    pub fn flush_mutations(&mut self) -> Option<DomComponentResponse> {
+        if self.pending_mutations.is_empty() {
+            return None;
+        }
+        Some(self.take_mutations())
+    }
+
+    fn queue_mutation(&mut self, record: MutationRecord, affected: &[NodeId]) {
+        self.pending_mutations.push(record);
+        for node_id in affected {
+            if !self.pending_affected.contains(node_id) {
+                self.pending_affected.push(*node_id);
+            }
+        }
+    }
+
+    /// Take the queued records for delivery, grouped per observed node
+    ///
+    /// An observer receives the records of the nodes it observes, so each
+    /// target's records stay together and in order. Targets come in the
+    /// order they first mutated.
+    fn take_mutations(&mut self) -> DomComponentResponse {
+        let mut groups: Vec<(NodeId, Vec<MutationRecord>)> = Vec::new();
+        for record in self.pending_mutations.drain(..) {
+            let target = record.target;
+            match groups.iter_mut().find(|(id, _)| *id == target) {
+                Some((_, records)) => records.push(record),
+                None => groups.push((target, vec![record])),
+            }
+        }
+        let mutations = groups.into_iter().flat_map(|(_, records)| records);
+        DomComponentResponse::DomMutated {
+            mutations: mutations.collect(),
+            affected_nodes: std::mem::take(&mut self.pending_affected),
+        }
+    }
+
     /// Handle style invalidation
     fn handle_style_invalidation(
         &mut self,
@@ -312,12 +522,15 @@ impl DomComponent {
     /// Handle user interaction
     fn handle_user_interaction(
         &mut self,
-        _event_type: String,
+        event_type: String,
         target_id: NodeId,
-        _event_data: EventData,
+        event_data: EventData,
     ) -> DomComponentResponse {
-        // In a real implementation, we would dispatch events through the event system
-        // For now, return a simple response indicating the tree might have changed
+        if let Some(target) = self.tree_event_target(target_id) {
+            user_input(event_type, &event_data).queue_dispatch(&*self.scheduler, target);
+        }
+
+        // Listeners may have changed the tree
 
         DomComponentResponse::DomTreeChanged {
             change_type: TreeChangeType::SubtreeModified,
@@ -339,17 +552,13 @@ impl DomComponent {
                 }
             }
 
-            QueryType::QuerySelector {
-                selector: _,
-                context: _,
-            } => {
-                // In a real implementation, use dom-selectors to query
-                // For now, return empty results
+            // The first match, or none, as a list like querySelectorAll
+            QueryType::QuerySelector { selector, context } => {
+                self.selector_query(request_id, &selector, context, true)
+            }
 
-                DomComponentResponse::QueryResult {
-                    request_id,
-                    result: QueryResultType::NodeIds(vec![]),
-                }
+            QueryType::QuerySelectorAll { selector, context } => {
+                self.selector_query(request_id, &selector, context, false)
             }
 
             QueryType::GetNodeProperties { node_id: _ } => {
@@ -364,6 +573,134 @@ impl DomComponent {
         }
     }
 
+    /// Respond to a selector query with the IDs of the matching elements
+    fn selector_query(
+        &self,
+        request_id: u64,
+        selector: &str,
+        context: Option<NodeId>,
+        first_only: bool,
+    ) -> DomComponentResponse {
+        match self.select(selector, context, first_only) {
+            Ok(found) => DomComponentResponse::QueryResult {
+                request_id,
+                result: QueryResultType::NodeIds(found),
+            },
+            Err(error) => error_response(error),
+        }
+    }
+
+    /// Find the IDs of the elements matching `selector`, in tree order
+    ///
+    /// With a `context` node only its descendants are searched; without one,
+    /// every document is, in the order they were created. Only registered
+    /// nodes have IDs, so elements added outside the component are skipped.
+    /// An invalid selector fails with `SyntaxError` and an unknown context
+    /// with `NotFoundError`.
+    fn select(
+        &self,
+        selector: &str,
+        context: Option<NodeId>,
+        first_only: bool,
+    ) -> Result<Vec<NodeId>, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        let mut found = Vec::new();
+
+        if let Some(context) = context {
+            let children = self
+                .node(context)
+                .ok_or(DomException::NotFoundError)?
+                .read()
+                .child_nodes();
+            self.collect_matches(children, &matcher, first_only, &mut found)?;
+            return Ok(found);
+        }
+
+        let mut document_ids: Vec<&DocumentId> = self.documents.keys().collect();
+        document_ids.sort();
+        for document_id in document_ids {
+            let Some(root) = self.documents[document_id].read().document_element() else {
+                continue;
+            };
+            self.collect_matches(vec![root as NodeRef], &matcher, first_only, &mut found)?;
+            if first_only && !found.is_empty() {
+                break;
+            }
+        }
+        Ok(found)
+    }
+
+    /// Push the IDs of the registered elements among `roots` and their
+    /// descendants that match `matcher`, in tree order
+    ///
+    /// Elements are matched in place, so combinators see their real
+    /// ancestors.
+    fn collect_matches(
+        &self,
+        roots: Vec<NodeRef>,
+        matcher: &SelectorMatcher,
+        first_only: bool,
+        found: &mut Vec<NodeId>,
+    ) -> Result<(), DomException> {
+        let mut stack: Vec<NodeRef> = roots.into_iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            let id = self.node_ids.get(&node_address(&node));
+            if let (Some(element), Some(id)) = (downcast_element(&node), id) {
+                if matcher.matches(&element)? {
+                    found.push(*id);
+                    if first_only {
+                        return Ok(());
+                    }
+                }
+            }
+            let children = node.read().child_nodes();
+            stack.extend(children.into_iter().rev());
+        }
+        Ok(())
+    }
+
+    /// Get the event target of `node_id`, linked to its ancestors' targets
+    ///
+    /// Each registered ancestor's target becomes the parent target of the
+    /// one below it, so events dispatched at the node capture and bubble
+    /// through them. `None` if the node is not alive.
+    fn tree_event_target(&mut self, node_id: NodeId) -> Option<EventTargetRef> {
+        let node = self.node(node_id)?;
+        let target = self.event_target(node_id);
+        let mut child = target.clone();
+        let mut parent = node.read().parent_node();
+        while let Some(ancestor) = parent {
+            parent = ancestor.read().parent_node();
+            if let Some(&ancestor_id) = self.node_ids.get(&node_address(&ancestor)) {
+                let ancestor_target = self.event_target(ancestor_id);
+                child.write().set_parent_target(&ancestor_target);
+                child = ancestor_target;
+            }
+        }
+        // The top target may have been linked when the tree looked different
+        child.write().clear_parent_target();
+        Some(target)
+    }
+
+    /// Look up a registered node that is still alive
+    fn node(&self, node_id: NodeId) -> Option<NodeRef> {
+        self.node_registry.get(&node_id)?.upgrade()
+    }
+
+    /// Give `node` an ID for messages to refer to it by
+    fn register_node(&mut self, node: &NodeRef) -> NodeId {
+        let node_id = self.allocate_node_id();
+        self.node_registry.insert(node_id, Arc::downgrade(node));
+        self.node_ids.insert(node_address(node), node_id);
+        node_id
+    }
+
+    fn allocate_node_id(&mut self) -> NodeId {
+        let node_id = self.next_node_id;
+        self.next_node_id += 1;
+        node_id
+    }
+
     /// Get a document by ID
     pub fn get_document(&self, document_id: DocumentId) -> Option<&DocumentRef> {
         self.documents.get(&document_id)
@@ -375,9 +712,103 @@ impl DomComponent {
     }
 }
 
+/// Address identifying `node`, without the trait object metadata
+fn node_address(node: &NodeRef) -> usize {
+    Arc::as_ptr(node) as *const () as usize
+}
+
+/// Mouse event types, whose input carries a position
+const MOUSE_EVENT_TYPES: &[&str] = &[
+    "click",
+    "dblclick",
+    "auxclick",
+    "contextmenu",
+    "mousedown",
+    "mouseup",
+    "mousemove",
+    "mouseover",
+    "mouseout",
+    "mouseenter",
+    "mouseleave",
+];
+
+/// Keyboard event types, whose input carries a key
+const KEYBOARD_EVENT_TYPES: &[&str] = &["keydown", "keyup", "keypress"];
+
+/// Describe a `UserInteraction` message as user input of `event_type`
+fn user_input(event_type: String, data: &EventData) -> UserInput {
+    let ui_event_init = UIEventInit {
+        event_init: EventInit::for_type(&event_type),
+        ..Default::default()
+    };
+    let modifiers = &data.modifiers;
+    if MOUSE_EVENT_TYPES.contains(&event_type.as_str()) {
+        let init = MouseEventInit {
+            ui_event_init,
+            client_x: data.mouse_x.unwrap_or_default(),
+            client_y: data.mouse_y.unwrap_or_default(),
+            ctrl_key: modifiers.ctrl,
+            shift_key: modifiers.shift,
+            alt_key: modifiers.alt,
+            meta_key: modifiers.meta,
+            ..Default::default()
+        };
+        UserInput::Mouse(event_type, init)
+    } else if KEYBOARD_EVENT_TYPES.contains(&event_type.as_str()) {
+        let init = KeyboardEventInit {
+            ui_event_init,
+            key: data.key.clone().unwrap_or_default(),
+            ctrl_key: modifiers.ctrl,
+            shift_key: modifiers.shift,
+            alt_key: modifiers.alt,
+            meta_key: modifiers.meta,
+            ..Default::default()
+        };
+        UserInput::Keyboard(event_type, init)
+    } else {
+        let init = EventInit::for_type(&event_type);
+        UserInput::Other(event_type, init)
+    }
+}
+
+/// Build an error response carrying the exception and its legacy DOM code
+fn error_response(error: DomException) -> DomComponentResponse {
+    DomComponentResponse::Error {
+        message: error.to_string(),
+        code: u32::from(error.code()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dom_core::new_node_ref;
+
+    struct NoScriptsPolicy;
+
+    impl DomPolicy for NoScriptsPolicy {
+        fn check_operation(&self, operation: &DomOperation) -> Result<(), DomException> {
+            match operation {
+                DomOperation::CreateElement { tag_name, .. }
+                    if tag_name.eq_ignore_ascii_case("script") =>
+                {
+                    Err(DomException::SecurityError)
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn create_element_message(tag_name: &str) -> DomComponentMessage {
+        DomComponentMessage::ScriptManipulation {
+            operation: DomOperation::CreateElement {
+                tag_name: tag_name.to_string(),
+                namespace: None,
+            },
+            node_id: 0,
+            params: OperationParams::default(),
+        }
+    }
 
     fn create_test_parsed_tree() -> ParsedNode {
         ParsedNode {
@@ -502,6 +933,284 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "dom-advanced")]
+    fn test_manual_scheduler_defers_mutation_callbacks_and_events() {
+        use dom_advanced::mutation::{append_child_observed, MutationObserverInit};
+        use dom_events::{
+            AddEventListenerOptions, Event, EventDispatcher, EventInit, EventListener,
+            EventTargetData, ManualScheduler,
+        };
+
+        let scheduler = ManualScheduler::new_ref();
+        let mut component = DomComponent::new(DomConfig::default());
+        component.set_scheduler(scheduler.clone());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mutation_log = log.clone();
+        let observer = component.create_mutation_observer(move |records| {
+            let entry = format!("mutations: {}", records.len());
+            mutation_log.lock().push(entry);
+        });
+        let mut document = Document::new();
+        let root: NodeRef = document.create_element("div").unwrap();
+        let options = MutationObserverInit {
+            child_list: true,
+            ..Default::default()
+        };
+        observer.observe(root.clone(), options).unwrap();
+
+        let mut target = EventTargetData::new();
+        let event_log = log.clone();
+        target.add_event_listener(
+            "change",
+            EventListener::from_fn(move |_| event_log.lock().push("change".to_string())),
+            AddEventListenerOptions::default(),
+        );
+        let event = Arc::new(RwLock::new(Event::new("change", EventInit::default())));
+        EventDispatcher::queue_dispatch(
+            &**component.scheduler(),
+            event,
+            Arc::new(RwLock::new(target)),
+        );
+        for tag in ["p", "span"] {
+            let child = document.create_element(tag).unwrap();
+            append_child_observed(&root, child).unwrap();
+        }
+
+        // Queued before the mutations, the event still waits for them
+        assert!(log.lock().is_empty());
+        assert_eq!(scheduler.pending_microtasks(), 1);
+        assert_eq!(scheduler.run_tasks(), 1);
+        assert_eq!(*log.lock(), ["mutations: 2", "change"]);
+    }
+
+    fn interaction(event_type: &str, target_id: NodeId, data: EventData) -> DomComponentMessage {
+        DomComponentMessage::UserInteraction {
+            event_type: event_type.to_string(),
+            target_id,
+            event_data: data,
+        }
+    }
+
+    #[test]
+    fn test_user_interaction_dispatches_trusted_event() {
+        use dom_events::{AddEventListenerOptions, EventListener, MouseEvent};
+
+        let mut component = DomComponent::new(DomConfig::default());
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![parsed_element("p", "text", vec![])]),
+            doctype: None,
+        });
+        let p = node_ids(query(&mut component, query_selector("p", None)))[0];
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let listener_seen = seen.clone();
+        component.event_target(p).write().add_event_listener(
+            "click",
+            EventListener::from_fn(move |event| {
+                let position = event
+                    .interface::<MouseEvent>()
+                    .map(|mouse| (mouse.client_x(), mouse.client_y(), mouse.shift_key()));
+                let stamp = (event.is_trusted(), event.time_stamp(), position);
+                listener_seen.lock().push(stamp);
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        for _ in 0..2 {
+            let data = EventData {
+                mouse_x: Some(10),
+                mouse_y: Some(20),
+                key: None,
+                modifiers: Modifiers {
+                    shift: true,
+                    ..Default::default()
+                },
+                extra: HashMap::new(),
+            };
+            component.handle_message(interaction("click", p, data));
+        }
+
+        let seen = seen.lock();
+        assert_eq!(seen.len(), 2);
+        assert!(seen.iter().all(|(trusted, ..)| *trusted));
+        assert!(seen
+            .iter()
+            .all(|(.., position)| *position == Some((10, 20, true))));
+        assert!(seen[0].1 <= seen[1].1);
+    }
+
+    #[test]
+    fn test_user_interaction_carries_key() {
+        use dom_events::{AddEventListenerOptions, EventListener, KeyboardEvent};
+
+        let mut component = DomComponent::new(DomConfig::default());
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![]),
+            doctype: None,
+        });
+        let html = node_ids(query(&mut component, query_selector("html", None)))[0];
+
+        let keys = Arc::new(Mutex::new(Vec::new()));
+        let listener_keys = keys.clone();
+        component.event_target(html).write().add_event_listener(
+            "keydown",
+            EventListener::from_fn(move |event| {
+                let key = event
+                    .interface::<KeyboardEvent>()
+                    .map(|k| k.key().to_string());
+                listener_keys.lock().push(key);
+            }),
+            AddEventListenerOptions::default(),
+        );
+
+        let data = EventData {
+            key: Some("Enter".to_string()),
+            ..Default::default()
+        };
+        component.handle_message(interaction("keydown", html, data));
+        assert_eq!(*keys.lock(), [Some("Enter".to_string())]);
+    }
+
+    #[test]
+    fn test_user_interaction_captures_and_bubbles_through_ancestors() {
+        use dom_events::{AddEventListenerOptions, EventListener};
+
+        let mut component = DomComponent::new(DomConfig::default());
+        let list = parsed_element("ul", "list", vec![parsed_element("li", "item", vec![])]);
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![list]),
+            doctype: None,
+        });
+        let html = node_ids(query(&mut component, query_selector("html", None)))[0];
+        let item = node_ids(query(&mut component, query_selector("li", None)))[0];
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let listen = |target: &EventTargetRef, label: &'static str, capture: bool| {
+            let log = log.clone();
+            target.write().add_event_listener(
+                "click",
+                EventListener::from_fn(move |_| log.lock().push(label)),
+                AddEventListenerOptions {
+                    capture,
+                    ..Default::default()
+                },
+            );
+        };
+        let html_target = component.event_target(html);
+        listen(&html_target, "capture", true);
+        listen(&html_target, "bubble", false);
+        listen(&component.event_target(item), "target", false);
+
+        component.handle_message(interaction("click", item, EventData::default()));
+        assert_eq!(*log.lock(), ["capture", "target", "bubble"]);
+    }
+
+    #[test]
+    fn test_batch_coalesces_mutations_per_target() {
+        let mut component = DomComponent::new(DomConfig::default());
+
+        let response = component.handle_message(DomComponentMessage::ScriptManipulations {
+            operations: vec![
+                DomOperation::SetAttribute {
+                    element_id: 1,
+                    name: "class".to_string(),
+                    value: "a".to_string(),
+                },
+                DomOperation::AppendChild {
+                    parent_id: 1,
+                    child_id: 2,
+                },
+                DomOperation::RemoveAttribute {
+                    element_id: 2,
+                    name: "hidden".to_string(),
+                },
+                DomOperation::RemoveChild {
+                    parent_id: 1,
+                    child_id: 3,
+                },
+            ],
+        });
+
+        match response {
+            DomComponentResponse::DomMutated {
+                mutations,
+                affected_nodes,
+            } => {
+                let summary: Vec<_> = mutations
+                    .iter()
+                    .map(|m| (m.mutation_type.clone(), m.target))
+                    .collect();
+                assert_eq!(
+                    summary,
+                    vec![
+                        (MutationType::Attributes, 1),
+                        (MutationType::ChildList, 1),
+                        (MutationType::ChildList, 1),
+                        (MutationType::Attributes, 2),
+                    ]
+                );
+                assert_eq!(mutations[1].added_nodes, vec![2]);
+                assert_eq!(mutations[2].removed_nodes, vec![3]);
+                assert_eq!(affected_nodes, vec![1, 2, 3]);
+            }
+            other => panic!("Expected DomMutated response, got {:?}", other),
+        }
+
+        // Everything was delivered with the batch
+        assert!(component.flush_mutations().is_none());
+    }
+
+    #[test]
+    fn test_batch_error_leaves_earlier_records_for_flush() {
+        let mut component = DomComponent::with_policy(DomConfig::default(), NoScriptsPolicy);
+
+        let response = component.handle_message(DomComponentMessage::ScriptManipulations {
+            operations: vec![
+                DomOperation::SetAttribute {
+                    element_id: 5,
+                    name: "id".to_string(),
+                    value: "x".to_string(),
+                },
+                DomOperation::CreateElement {
+                    tag_name: "script".to_string(),
+                    namespace: None,
+                },
+                DomOperation::SetTextContent {
+                    node_id: 6,
+                    text: "hi".to_string(),
+                },
+            ],
+        });
+        assert!(matches!(response, DomComponentResponse::Error { .. }));
+
+        // The operations after the error were not applied
+        match component.flush_mutations() {
+            Some(DomComponentResponse::DomMutated { mutations, .. }) => {
+                assert_eq!(mutations.len(), 1);
+                assert_eq!(mutations[0].target, 5);
+            }
+            other => panic!("Expected DomMutated response, got {:?}", other),
+        }
+        assert!(component.flush_mutations().is_none());
+    }
+
+    #[test]
+    fn test_empty_batch_sends_no_mutations() {
+        let mut component = DomComponent::new(DomConfig::default());
+        let response = component.handle_message(DomComponentMessage::ScriptManipulations {
+            operations: Vec::new(),
+        });
+        match response {
+            DomComponentResponse::DomMutated { mutations, .. } => assert!(mutations.is_empty()),
+            other => panic!("Expected DomMutated response, got {:?}", other),
+        }
+        assert!(component.flush_mutations().is_none());
+    }
+
     #[test]
     fn test_handle_style_invalidation() {
         let mut component = DomComponent::new(DomConfig::default());
@@ -525,6 +1234,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_class_change_emits_targeted_invalidation() {
+        let mut component = DomComponent::new(DomConfig::default());
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![parsed_element("nav", "menu", vec![])]),
+            doctype: None,
+        });
+        let nav = node_ids(query(&mut component, query_selector("nav", None)))[0];
+        assert!(component.take_style_invalidations().is_empty());
+
+        let element = downcast_element(&component.node(nav).unwrap()).unwrap();
+        element.write().set_attribute("class", "menu open").unwrap();
+
+        let mut messages = component.take_style_invalidations();
+        assert_eq!(messages.len(), 1);
+        let message = messages.remove(0);
+        match &message {
+            DomComponentMessage::StyleInvalidation {
+                node_id,
+                reason: InvalidationReason::ClassListChanged { added, removed },
+            } => {
+                assert_eq!(*node_id, nav);
+                assert_eq!(added, &["open"]);
+                assert!(removed.is_empty());
+            }
+            other => panic!("Expected ClassListChanged invalidation, got {:?}", other),
+        }
+        assert!(component.take_style_invalidations().is_empty());
+
+        match component.handle_message(message) {
+            DomComponentResponse::LayoutInvalidated {
+                root_id,
+                invalidation_type,
+            } => {
+                assert_eq!(root_id, nav);
+                assert!(matches!(
+                    invalidation_type,
+                    LayoutInvalidationType::Incremental
+                ));
+            }
+            other => panic!("Expected LayoutInvalidated response, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_handle_query() {
         let mut component = DomComponent::new(DomConfig::default());
@@ -547,6 +1301,218 @@ mod tests {
         }
     }
 
+    fn parsed_element(tag: &str, class: &str, children: Vec<ParsedNode>) -> ParsedNode {
+        ParsedNode {
+            node_type: ParsedNodeType::Element,
+            tag_name: Some(tag.to_string()),
+            attributes: HashMap::from([("class".to_string(), class.to_string())]),
+            text_content: None,
+            children,
+        }
+    }
+
+    fn query(component: &mut DomComponent, query: QueryType) -> QueryResultType {
+        match component.handle_message(DomComponentMessage::Query {
+            request_id: 9,
+            query,
+        }) {
+            DomComponentResponse::QueryResult { request_id, result } => {
+                assert_eq!(request_id, 9);
+                result
+            }
+            other => panic!("Expected QueryResult response, got {:?}", other),
+        }
+    }
+
+    fn query_selector(selector: &str, context: Option<NodeId>) -> QueryType {
+        let selector = selector.to_string();
+        QueryType::QuerySelector { selector, context }
+    }
+
+    fn query_selector_all(selector: &str, context: Option<NodeId>) -> QueryType {
+        let selector = selector.to_string();
+        QueryType::QuerySelectorAll { selector, context }
+    }
+
+    fn node_ids(result: QueryResultType) -> Vec<NodeId> {
+        match result {
+            QueryResultType::NodeIds(ids) => ids,
+            other => panic!("Expected node ids, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_query_selector_returns_node_ids() {
+        let mut component = DomComponent::new(DomConfig::default());
+        let list = parsed_element(
+            "ul",
+            "list",
+            vec![
+                parsed_element("li", "item first", vec![]),
+                parsed_element("li", "item", vec![]),
+            ],
+        );
+        let root = parsed_element("html", "root", vec![list]);
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root,
+            doctype: None,
+        });
+
+        let items = node_ids(query(&mut component, query_selector_all(".item", None)));
+        assert_eq!(items.len(), 2);
+        let names: Vec<String> = items
+            .iter()
+            .map(|id| {
+                let node = component.node(*id).unwrap();
+                let node = node.read();
+                let element = node.as_any().downcast_ref::<Element>().unwrap();
+                element.get_attribute("class").unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(names, ["item first", "item"]);
+
+        let first = node_ids(query(&mut component, query_selector("li", None)));
+        assert_eq!(first, [items[0]]);
+
+        let html = node_ids(query(&mut component, query_selector("html", None)));
+        let root = component.get_document(1).unwrap().read().document_element();
+        let root: NodeRef = root.unwrap();
+        assert!(Arc::ptr_eq(&component.node(html[0]).unwrap(), &root));
+
+        // A context searches its descendants only
+        let list_id = node_ids(query(&mut component, query_selector("ul", None)))[0];
+        let in_list = query(&mut component, query_selector_all("li", Some(list_id)));
+        assert_eq!(node_ids(in_list), items);
+        let in_root = query(&mut component, query_selector("html", Some(html[0])));
+        assert!(node_ids(in_root).is_empty());
+    }
+
+    #[test]
+    fn test_query_selector_combinators_see_real_ancestors() {
+        let mut component = DomComponent::new(DomConfig::default());
+        let nested = parsed_element("ol", "nested", vec![parsed_element("li", "deep", vec![])]);
+        let list = parsed_element(
+            "ul",
+            "list",
+            vec![
+                parsed_element("li", "direct", vec![nested]),
+                parsed_element("li", "direct", vec![]),
+            ],
+        );
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![list]),
+            doctype: None,
+        });
+
+        let class_of = |component: &DomComponent, id: &NodeId| {
+            let node = component.node(*id).unwrap();
+            let node = node.read();
+            let element = node.as_any().downcast_ref::<Element>().unwrap();
+            element.get_attribute("class").unwrap().to_string()
+        };
+
+        let descendants = node_ids(query(&mut component, query_selector_all("ul li", None)));
+        let classes: Vec<String> = descendants
+            .iter()
+            .map(|id| class_of(&component, id))
+            .collect();
+        assert_eq!(classes, ["direct", "deep", "direct"]);
+
+        let children = node_ids(query(&mut component, query_selector_all("ul > li", None)));
+        let classes: Vec<String> = children.iter().map(|id| class_of(&component, id)).collect();
+        assert_eq!(classes, ["direct", "direct"]);
+
+        let deep = node_ids(query(&mut component, query_selector("html ol > li", None)));
+        assert_eq!(class_of(&component, &deep[0]), "deep");
+    }
+
+    #[test]
+    fn test_parsed_document_indexes_element_ids() {
+        let mut component = DomComponent::new(DomConfig::default());
+        let mut list = parsed_element("ul", "list", vec![parsed_element("li", "item", vec![])]);
+        list.attributes.insert("id".to_string(), "menu".to_string());
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed_element("html", "root", vec![list]),
+            doctype: None,
+        });
+
+        let list_id = node_ids(query(&mut component, query_selector("ul", None)))[0];
+        let list = component.node(list_id).unwrap();
+        let by_id = component
+            .get_document(1)
+            .unwrap()
+            .read()
+            .get_element_by_id("menu")
+            .unwrap();
+        assert!(std::ptr::addr_eq(Arc::as_ptr(&by_id), Arc::as_ptr(&list)));
+    }
+
+    #[test]
+    fn test_query_selector_errors() {
+        let mut component = DomComponent::new(DomConfig::default());
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: create_test_parsed_tree(),
+            doctype: None,
+        });
+
+        let invalid = component.handle_message(DomComponentMessage::Query {
+            request_id: 2,
+            query: query_selector_all("", None),
+        });
+        match invalid {
+            DomComponentResponse::Error { code, .. } => assert_eq!(code, 12),
+            other => panic!("Expected SyntaxError response, got {:?}", other),
+        }
+
+        let unknown_context = component.handle_message(DomComponentMessage::Query {
+            request_id: 3,
+            query: query_selector("p", Some(999)),
+        });
+        match unknown_context {
+            DomComponentResponse::Error { code, .. } => assert_eq!(code, 8),
+            other => panic!("Expected NotFoundError response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parsed_template_children_go_into_content() {
+        let mut component = DomComponent::new(DomConfig::default());
+        let mut parsed = create_test_parsed_tree();
+        let template = ParsedNode {
+            node_type: ParsedNodeType::Element,
+            tag_name: Some("template".to_string()),
+            attributes: HashMap::new(),
+            text_content: None,
+            children: vec![create_test_parsed_tree().children.remove(0)],
+        };
+        parsed.children.push(template);
+
+        component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 1,
+            root: parsed,
+            doctype: None,
+        });
+
+        let document = component.get_document(1).unwrap().read();
+        let html = document.document_element().unwrap();
+        let template = html.read().child_nodes()[1].clone();
+        let template = template.read();
+        let template = template.as_any().downcast_ref::<Element>().unwrap();
+
+        assert!(template.child_nodes().is_empty());
+        let content = template.content().unwrap().read().child_nodes();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].read().node_name(), "BODY");
+        assert_eq!(
+            content[0].read().text_content(),
+            Some("Hello World".to_string())
+        );
+    }
+
     #[test]
     fn test_multiple_documents() {
         let mut component = DomComponent::new(DomConfig::default());
@@ -572,4 +1538,263 @@ mod tests {
         assert!(component.get_document(1).is_some());
         assert!(component.get_document(2).is_some());
     }
+
+    fn limited_component() -> DomComponent {
+        DomComponent::new(DomConfig {
+            max_attributes_per_element: 2,
+            max_children: 2,
+            max_attribute_value_len: 8,
+            ..DomConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_attribute_count_limit() {
+        let component = limited_component();
+        let mut element = component.create_element("div");
+
+        component.set_attribute(&mut element, "id", "a").unwrap();
+        component.set_attribute(&mut element, "class", "b").unwrap();
+        // Replacing an existing attribute does not count against the limit
+        component.set_attribute(&mut element, "id", "c").unwrap();
+
+        assert_eq!(
+            component.set_attribute(&mut element, "title", "d"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(element.attributes().len(), 2);
+    }
+
+    #[test]
+    fn test_attribute_value_length_limit() {
+        let mut component = limited_component();
+        let mut element = component.create_element("div");
+
+        component
+            .set_attribute(&mut element, "id", "12345678")
+            .unwrap();
+        assert_eq!(
+            component.set_attribute(&mut element, "title", "123456789"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert!(!element.has_attribute("title"));
+
+        let response = component.handle_message(DomComponentMessage::ScriptManipulation {
+            operation: DomOperation::SetAttribute {
+                element_id: 1,
+                name: "title".to_string(),
+                value: "123456789".to_string(),
+            },
+            node_id: 1,
+            params: OperationParams::default(),
+        });
+        assert!(matches!(
+            response,
+            DomComponentResponse::Error { code: 22, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parsed_document_respects_attribute_limits() {
+        let mut component = limited_component();
+        let mut parsed = create_test_parsed_tree();
+        let body = &mut parsed.children[0];
+        body.attributes.insert("id".to_string(), "x".to_string());
+        body.attributes.insert("title".to_string(), "y".to_string());
+
+        let response = component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 7,
+            root: parsed,
+            doctype: None,
+        });
+        match response {
+            DomComponentResponse::Error { message, code } => {
+                assert_eq!(message, DomException::QuotaExceededError.to_string());
+                assert_eq!(code, 22);
+            }
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+
+        let mut parsed = create_test_parsed_tree();
+        parsed.attributes.insert("lang".to_string(), "x".repeat(9));
+        assert!(matches!(
+            component.handle_message(DomComponentMessage::ParsedDocument {
+                request_id: 8,
+                root: parsed,
+                doctype: None,
+            }),
+            DomComponentResponse::Error { code: 22, .. }
+        ));
+
+        // Within the limits the document is built as usual
+        assert!(matches!(
+            component.handle_message(DomComponentMessage::ParsedDocument {
+                request_id: 9,
+                root: create_test_parsed_tree(),
+                doctype: None,
+            }),
+            DomComponentResponse::DomTreeReady { .. }
+        ));
+    }
+
+    #[test]
+    fn test_created_elements_carry_limits() {
+        let component = limited_component();
+        let mut element = component.create_element("div");
+
+        element.set_attribute("id", "a").unwrap();
+        element.set_attribute("class", "b").unwrap();
+        assert_eq!(
+            element.set_attribute("title", "c"),
+            Err(DomException::QuotaExceededError)
+        );
+        assert_eq!(
+            element.set_attribute_ns(None, "lang", "en"),
+            Err(DomException::QuotaExceededError)
+        );
+
+        let parent: NodeRef = Arc::new(RwLock::new(component.create_element("ul")));
+        for _ in 0..2 {
+            parent
+                .write()
+                .append_child(Arc::new(RwLock::new(Element::new("li"))))
+                .unwrap();
+        }
+        assert_eq!(
+            parent
+                .write()
+                .append_child(Arc::new(RwLock::new(Element::new("li"))))
+                .unwrap_err(),
+            DomException::QuotaExceededError
+        );
+    }
+
+    #[test]
+    fn test_parsed_document_respects_child_limit() {
+        let mut component = limited_component();
+        let text = |content: &str| ParsedNode {
+            node_type: ParsedNodeType::Text,
+            tag_name: None,
+            attributes: HashMap::new(),
+            text_content: Some(content.to_string()),
+            children: vec![],
+        };
+        let mut parsed = create_test_parsed_tree();
+        parsed.children[0].children.push(text("a"));
+
+        assert!(matches!(
+            component.handle_message(DomComponentMessage::ParsedDocument {
+                request_id: 1,
+                root: parsed.clone(),
+                doctype: None,
+            }),
+            DomComponentResponse::DomTreeReady { .. }
+        ));
+
+        parsed.children[0].children.push(text("b"));
+        match component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 2,
+            root: parsed,
+            doctype: None,
+        }) {
+            DomComponentResponse::Error { message, code } => {
+                assert_eq!(message, DomException::QuotaExceededError.to_string());
+                assert_eq!(code, 22);
+            }
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parsed_document_respects_depth_limit() {
+        let mut component = DomComponent::new(DomConfig {
+            max_tree_depth: 3,
+            ..DomConfig::default()
+        });
+        let element = |tag: &str, children: Vec<ParsedNode>| ParsedNode {
+            node_type: ParsedNodeType::Element,
+            tag_name: Some(tag.to_string()),
+            attributes: HashMap::new(),
+            text_content: None,
+            children,
+        };
+
+        // html > body > div is exactly at the limit, however wide
+        let wide = element(
+            "html",
+            vec![element(
+                "body",
+                (0..10).map(|_| element("div", vec![])).collect(),
+            )],
+        );
+        assert!(matches!(
+            component.handle_message(DomComponentMessage::ParsedDocument {
+                request_id: 1,
+                root: wide,
+                doctype: None,
+            }),
+            DomComponentResponse::DomTreeReady { .. }
+        ));
+
+        let deep = element(
+            "html",
+            vec![element(
+                "body",
+                vec![element("div", vec![element("span", vec![])])],
+            )],
+        );
+        match component.handle_message(DomComponentMessage::ParsedDocument {
+            request_id: 2,
+            root: deep,
+            doctype: None,
+        }) {
+            DomComponentResponse::Error { message, code } => {
+                assert_eq!(message, DomException::HierarchyRequestError.to_string());
+                assert_eq!(code, 3);
+            }
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+
+        // Created elements refuse to grow the tree past the limit as well
+        let chain: Vec<NodeRef> = (0..3)
+            .map(|_| new_node_ref(component.create_element("div")))
+            .collect();
+        chain[0].write().append_child(chain[1].clone()).unwrap();
+        chain[1].write().append_child(chain[2].clone()).unwrap();
+        let extra: NodeRef = Arc::new(RwLock::new(Element::new("p")));
+        assert_eq!(
+            chain[2].write().append_child(extra).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+    }
+
+    #[test]
+    fn test_policy_vetoes_script_creation() {
+        let mut component = DomComponent::with_policy(DomConfig::default(), NoScriptsPolicy);
+
+        match component.handle_message(create_element_message("SCRIPT")) {
+            DomComponentResponse::Error { message, code } => {
+                assert_eq!(message, DomException::SecurityError.to_string());
+                assert_eq!(code, 18);
+            }
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+
+        // Other elements are still allowed
+        assert!(matches!(
+            component.handle_message(create_element_message("div")),
+            DomComponentResponse::QueryResult { .. }
+        ));
+    }
+
+    #[test]
+    fn test_permissive_policy_allows_script_creation() {
+        let mut component = DomComponent::with_policy(DomConfig::default(), NoScriptsPolicy);
+        component.set_policy(AllowAllPolicy);
+
+        assert!(matches!(
+            component.handle_message(create_element_message("script")),
+            DomComponentResponse::QueryResult { .. }
+        ));
+    }
 }
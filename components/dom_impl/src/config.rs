@@ -22,6 +22,17 @@ pub struct DomConfig {
 
     /// Initial arena capacity for node storage
     pub arena_capacity: usize,
+
+    /// Maximum number of attributes a single element may carry
+    ///
+    /// This is also the limit known as `max_attribute_count`, which
+    /// serialized configs may use as the field name; see
+    /// [`max_attribute_count`](Self::max_attribute_count).
+    #[serde(alias = "max_attribute_count")]
+    pub max_attributes_per_element: usize,
+
+    /// Maximum length of an attribute value, in bytes
+    pub max_attribute_value_len: usize,
 }
 
 impl Default for DomConfig {
@@ -33,10 +44,21 @@ impl Default for DomConfig {
             enable_shadow_dom: true,
             gc_threshold: 100000,
             arena_capacity: 50000,
+            max_attributes_per_element: 1024,
+            max_attribute_value_len: 1 << 20,
         }
     }
 }
 
+impl DomConfig {
+    /// Maximum number of attributes a single element may carry
+    ///
+    /// Alias of [`max_attributes_per_element`](Self::max_attributes_per_element).
+    pub fn max_attribute_count(&self) -> usize {
+        self.max_attributes_per_element
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +72,8 @@ mod tests {
         assert!(config.enable_shadow_dom);
         assert_eq!(config.gc_threshold, 100000);
         assert_eq!(config.arena_capacity, 50000);
+        assert_eq!(config.max_attributes_per_element, 1024);
+        assert_eq!(config.max_attribute_value_len, 1 << 20);
     }
 
     #[test]
@@ -62,6 +86,18 @@ mod tests {
         assert_eq!(config.max_children, deserialized.max_children);
     }
 
+    #[test]
+    fn test_max_attribute_count_alias() {
+        let mut json = serde_json::to_value(DomConfig::default()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.remove("max_attributes_per_element");
+        fields.insert("max_attribute_count".to_string(), 16.into());
+
+        let config: DomConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.max_attributes_per_element, 16);
+        assert_eq!(config.max_attribute_count(), 16);
+    }
+
     #[test]
     fn test_custom_config() {
         let config = DomConfig {
@@ -71,6 +107,8 @@ mod tests {
             enable_shadow_dom: false,
             gc_threshold: 50000,
             arena_capacity: 25000,
+            max_attributes_per_element: 64,
+            max_attribute_value_len: 4096,
         };
 
         assert_eq!(config.max_tree_depth, 256);
@@ -57,6 +57,7 @@
 //! ```
 
 use crate::messages::{ParsedNode, ParsedNodeType};
+use dom_core::{Document, DocumentRef};
 use dom_types::{DomException, NodeId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -112,114 +113,7 @@ pub trait HtmlParserIntegration {
 
 // ========== JavaScript Runtime Integration ==========
 
-/// JavaScript value representation for DOM-JS interop
-///
-/// This enum represents JavaScript values that can be passed to and from
-/// DOM methods when called from JavaScript.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum JsValue {
-    /// JavaScript `undefined`
-    Undefined,
-    /// JavaScript `null`
-    Null,
-    /// JavaScript boolean
-    Boolean(bool),
-    /// JavaScript number (all JS numbers are f64)
-    Number(f64),
-    /// JavaScript string
-    String(String),
-    /// Reference to a DOM node
-    NodeRef(NodeId),
-    /// JavaScript array
-    Array(Vec<JsValue>),
-    /// JavaScript object (simple key-value representation)
-    Object(HashMap<String, JsValue>),
-}
-
-impl Default for JsValue {
-    fn default() -> Self {
-        JsValue::Undefined
-    }
-}
-
-impl JsValue {
-    /// Creates a new undefined value
-    pub fn undefined() -> Self {
-        JsValue::Undefined
-    }
-
-    /// Creates a new null value
-    pub fn null() -> Self {
-        JsValue::Null
-    }
-
-    /// Creates a new boolean value
-    pub fn boolean(value: bool) -> Self {
-        JsValue::Boolean(value)
-    }
-
-    /// Creates a new number value
-    pub fn number(value: f64) -> Self {
-        JsValue::Number(value)
-    }
-
-    /// Creates a new string value
-    pub fn string(value: impl Into<String>) -> Self {
-        JsValue::String(value.into())
-    }
-
-    /// Creates a new node reference
-    pub fn node_ref(id: NodeId) -> Self {
-        JsValue::NodeRef(id)
-    }
-
-    /// Returns true if this value is undefined
-    pub fn is_undefined(&self) -> bool {
-        matches!(self, JsValue::Undefined)
-    }
-
-    /// Returns true if this value is null
-    pub fn is_null(&self) -> bool {
-        matches!(self, JsValue::Null)
-    }
-
-    /// Returns true if this value is null or undefined
-    pub fn is_nullish(&self) -> bool {
-        matches!(self, JsValue::Undefined | JsValue::Null)
-    }
-
-    /// Attempts to extract a boolean value
-    pub fn as_bool(&self) -> Option<bool> {
-        match self {
-            JsValue::Boolean(b) => Some(*b),
-            _ => None,
-        }
-    }
-
-    /// Attempts to extract a number value
-    pub fn as_number(&self) -> Option<f64> {
-        match self {
-            JsValue::Number(n) => Some(*n),
-            _ => None,
-        }
-    }
-
-    /// Attempts to extract a string value
-    pub fn as_str(&self) -> Option<&str> {
-        match self {
-            JsValue::String(s) => Some(s),
-            _ => None,
-        }
-    }
-
-    /// Attempts to extract a node reference
-    pub fn as_node_ref(&self) -> Option<NodeId> {
-        match self {
-            JsValue::NodeRef(id) => Some(*id),
-            _ => None,
-        }
-    }
-}
+pub use dom_types::JsValue;
 
 /// Binding information for a JavaScript-accessible DOM method
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -746,14 +640,24 @@ pub trait TestHarness {
     }
 }
 
+/// Body of a test registered with [`DomTestHarness::register_test`]
+///
+/// It gets the harness with a fresh [`document`](DomTestHarness::document)
+/// and returns the assertions it made.
+pub type HarnessTest = Box<dyn Fn(&mut DomTestHarness) -> Vec<TestAssertion>>;
+
 /// DOM-specific test harness implementation
 ///
-/// A concrete implementation of `TestHarness` for DOM testing.
-/// This provides a basic framework for running DOM-related tests.
-#[derive(Debug)]
+/// A concrete implementation of `TestHarness` for DOM testing. Tests are
+/// closures registered by name; running one gives it a fresh document to
+/// build on and turns its assertions into a result.
 pub struct DomTestHarness {
     /// The document created for testing (None before setup)
     test_document: Option<NodeId>,
+    /// Document built by `setup` for the current test
+    document: Option<DocumentRef>,
+    /// Registered tests by name
+    tests: HashMap<String, HarnessTest>,
     /// Test assertions collected during the current test
     assertions: Vec<TestAssertion>,
     /// Counter for generating unique node IDs in tests
@@ -765,11 +669,23 @@ impl DomTestHarness {
     pub fn new() -> Self {
         Self {
             test_document: None,
+            document: None,
+            tests: HashMap::new(),
             assertions: Vec::new(),
             next_node_id: 1,
         }
     }
 
+    /// Registers `test` to run under `name`, replacing any test of that name
+    pub fn register_test(&mut self, name: impl Into<String>, test: HarnessTest) {
+        self.tests.insert(name.into(), test);
+    }
+
+    /// Gets the document of the current test (None before setup)
+    pub fn document(&self) -> Option<&DocumentRef> {
+        self.document.as_ref()
+    }
+
     /// Generates a new unique node ID for testing
     pub fn generate_node_id(&mut self) -> NodeId {
         let id = self.next_node_id;
@@ -804,6 +720,20 @@ impl Default for DomTestHarness {
     }
 }
 
+impl std::fmt::Debug for DomTestHarness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut tests: Vec<&String> = self.tests.keys().collect();
+        tests.sort();
+        f.debug_struct("DomTestHarness")
+            .field("test_document", &self.test_document)
+            .field("document", &self.document)
+            .field("tests", &tests)
+            .field("assertions", &self.assertions)
+            .field("next_node_id", &self.next_node_id)
+            .finish()
+    }
+}
+
 impl TestHarness for DomTestHarness {
     fn run_test(&mut self, test_name: &str) -> TestResult {
         // Clear previous assertions
@@ -814,21 +744,38 @@ impl TestHarness for DomTestHarness {
             return TestResult::error("Empty test name");
         }
 
-        // Default implementation - subclasses should override with actual test logic
-        // For now, return a placeholder indicating the test needs implementation
-        TestResult::skip(format!("Test '{}' not implemented", test_name))
+        // Taken out while it runs, since it borrows the harness mutably
+        let Some(test) = self.tests.remove(test_name) else {
+            return TestResult::skip(format!("Test '{}' not implemented", test_name));
+        };
+        if self.document.is_none() {
+            self.setup();
+        }
+        let assertions = test(self);
+        self.tests.insert(test_name.to_string(), test);
+        self.assertions.extend(assertions);
+
+        match self.assertions.iter().find(|a| !a.passed) {
+            None => TestResult::Pass,
+            Some(failed) => TestResult::fail(format!(
+                "{}: expected {}, got {}",
+                failed.description, failed.expected, failed.actual
+            )),
+        }
     }
 
     fn setup(&mut self) {
         // Create a fresh test document
         let doc_id = self.generate_node_id();
         self.test_document = Some(doc_id);
+        self.document = Some(Document::new().into_ref());
         self.assertions.clear();
     }
 
     fn teardown(&mut self) {
         // Clean up test document
         self.test_document = None;
+        self.document = None;
         // Don't clear assertions - they're needed for result reporting
     }
 
@@ -1100,12 +1047,14 @@ mod tests {
         // After setup, has document
         harness.setup();
         assert!(harness.test_document.is_some());
+        assert!(harness.document().is_some());
         let doc_id = harness.get_test_document();
         assert!(doc_id > 0);
 
         // After teardown, no document
         harness.teardown();
         assert!(harness.test_document.is_none());
+        assert!(harness.document().is_none());
     }
 
     #[test]
@@ -1188,4 +1137,59 @@ mod tests {
             assert!(matches!(result, TestResult::Skip(_)));
         }
     }
+
+    fn register_root_tests(harness: &mut DomTestHarness) {
+        harness.register_test(
+            "builds_root",
+            Box::new(|harness| {
+                let mut document = harness.document().unwrap().write();
+                let html = document.create_element("html").unwrap();
+                document.set_document_element(html);
+                let root = document.document_element().unwrap();
+                let tag = root.read().tag_name().to_string();
+                vec![harness.assert_equals(tag.as_str(), "HTML", "root is html")]
+            }),
+        );
+        harness.register_test(
+            "expects_root",
+            Box::new(|harness| {
+                let document = harness.document().unwrap().read();
+                let has_root = document.document_element().is_some();
+                vec![
+                    harness.assert_true(harness.document().is_some(), "document exists"),
+                    harness.assert_true(has_root, "document has a root"),
+                ]
+            }),
+        );
+    }
+
+    #[test]
+    fn test_dom_test_harness_runs_registered_tests() {
+        let mut harness = DomTestHarness::new();
+        register_root_tests(&mut harness);
+
+        harness.setup();
+        assert_eq!(harness.run_test("builds_root"), TestResult::Pass);
+        assert_eq!(harness.get_assertions().len(), 1);
+        assert!(harness.all_assertions_passed());
+
+        harness.setup();
+        let result = harness.run_test("expects_root");
+        let expected = "document has a root: expected true, got false";
+        assert_eq!(result, TestResult::fail(expected));
+        assert_eq!(harness.get_assertions().len(), 2);
+        assert!(harness.get_assertions()[0].passed);
+        assert!(!harness.get_assertions()[1].passed);
+    }
+
+    #[test]
+    fn test_dom_test_harness_gives_each_test_a_fresh_document() {
+        let mut harness = DomTestHarness::new();
+        register_root_tests(&mut harness);
+
+        let results = harness.run_tests(&["builds_root", "expects_root"]);
+        assert_eq!(results[0].1, TestResult::Pass);
+        // The root built by the first test is gone in the second
+        assert!(results[1].1.is_fail());
+    }
 }
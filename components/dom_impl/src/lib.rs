@@ -108,6 +108,8 @@
 //!     enable_shadow_dom: true,
 //!     gc_threshold: 100000,          // GC trigger threshold
 //!     arena_capacity: 50000,         // Initial arena capacity
+//!     max_attributes_per_element: 1024,
+//!     max_attribute_value_len: 1 << 20, // Bytes per attribute value
 //! };
 //! ```
 //!
@@ -186,6 +188,7 @@ pub mod config;
 pub mod integration;
 pub mod message_handler;
 pub mod messages;
+pub mod policy;
 pub mod sanitization;
 pub mod validation;
 
@@ -198,25 +201,32 @@ pub use messages::{
     ParsedNodeType, QueryResultType, QueryType, TreeChangeType,
 };
 
+// Operation policies for sandboxed contexts
+pub use policy::{AllowAllPolicy, DomPolicy};
+
 // Integration traits and types
 pub use integration::{
-    ComputedStyleMap, CssEngineIntegration, DomTestHarness, HtmlParserIntegration,
+    ComputedStyleMap, CssEngineIntegration, DomTestHarness, HarnessTest, HtmlParserIntegration,
     JsBindingRegistry, JsBindings, JsMethodBinding, JsPropertyBinding, JsValue, StyleNode,
     TestAssertion, TestHarness, TestResult,
 };
 
 // Atom string interning
-pub use atoms::{all_atoms, atom_count, Atom};
+pub use atoms::{all_atoms, atom_count, Atom, DynamicAtomTable};
 /// Re-export predefined atoms module
 pub mod predefined_atoms {
     pub use super::atoms::atoms::*;
 }
 
 // Message handler for direct DOM operations
-pub use message_handler::{DirectDomMessage, DirectDomResponse, DomErrorCode, DomMessageHandler};
+pub use message_handler::{
+    build_dom_tree, build_dom_tree_with_config, DirectDomMessage, DirectDomResponse, DomErrorCode,
+    DomMessageHandler,
+};
 
 // Sanitization for XSS prevention
 pub use sanitization::{
     dangerous_tags, escape_html, event_handlers, is_dangerous_url, might_contain_html,
-    sanitize_html, sanitize_html_with_stats, SanitizationPolicy, SanitizationResult,
+    sanitize_html, sanitize_html_with_stats, sanitize_tree, InnerHtmlExt, SanitizationPolicy,
+    SanitizationResult,
 };
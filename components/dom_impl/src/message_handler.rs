@@ -4,8 +4,16 @@
 //! DOM operations. It complements the more comprehensive `messages` module
 //! by providing a streamlined API for common operations.
 
-use dom_types::NodeId;
+use crate::component::WeakNodeRef;
+use crate::config::DomConfig;
+use crate::messages::{ParsedNode, ParsedNodeType};
+use dom_core::serialize::{serialize_node, serialize_node_to_writer};
+use dom_core::{Document, Element, NodeRef, SerializeOptions};
+use dom_types::{DomException, NodeId};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
 
 /// Simplified messages for direct DOM component communication
 ///
@@ -159,6 +167,14 @@ pub enum DirectDomMessage {
         /// Whether to deep clone (include descendants)
         deep: bool,
     },
+
+    /// Compare the document position of two nodes (`a.compareDocumentPosition(b)`)
+    CompareDocumentPosition {
+        /// Reference node ID
+        a: NodeId,
+        /// Node ID compared against the reference node
+        b: NodeId,
+    },
 }
 
 /// Responses from direct DOM message handling
@@ -194,6 +210,12 @@ pub enum DirectDomResponse {
         result: bool,
     },
 
+    /// Document position bitmask (for CompareDocumentPosition)
+    DocumentPosition {
+        /// Bitmask of `DocumentPosition` flags
+        position: u16,
+    },
+
     /// Error response
     Error {
         /// Error message describing what went wrong
@@ -220,12 +242,94 @@ pub enum DomErrorCode {
     InvalidSelector = 6,
     /// Invalid character in attribute name or value
     InvalidCharacter = 7,
+    /// Index or size out of range
+    IndexSizeError = 8,
+    /// Object cannot be modified
+    NoModificationAllowedError = 9,
+    /// Operation not supported
+    NotSupportedError = 10,
+    /// Object in an invalid state
+    InvalidStateError = 11,
+    /// Object cannot be modified in this way
+    InvalidModificationError = 12,
+    /// Operation not allowed by namespaces in XML
+    NamespaceError = 13,
+    /// Operation is insecure
+    SecurityError = 14,
+    /// Quota has been exceeded
+    QuotaExceededError = 15,
+    /// Operation timed out
+    TimeoutError = 16,
+    /// Object cannot be cloned
+    DataCloneError = 17,
     /// Operation not implemented
     NotImplemented = 99,
     /// Unknown error
     Unknown = 100,
 }
 
+impl DomErrorCode {
+    /// Gets the exception this code stands for
+    ///
+    /// Codes without an exception of their own map to the closest one, so
+    /// converting back does not always give the same code:
+    ///
+    /// * `InvalidNodeId` becomes `NotFoundError`
+    /// * `InvalidNodeType` and `NotImplemented` become `NotSupportedError`
+    /// * `InvalidSelector` becomes a `SyntaxError` with a generic message
+    ///
+    /// `Unknown` has no exception and gives `None`.
+    pub fn to_exception(self) -> Option<DomException> {
+        let exception = match self {
+            DomErrorCode::NodeNotFound | DomErrorCode::InvalidNodeId => DomException::NotFoundError,
+            DomErrorCode::InvalidNodeType
+            | DomErrorCode::NotSupportedError
+            | DomErrorCode::NotImplemented => DomException::NotSupportedError,
+            DomErrorCode::HierarchyRequestError => DomException::HierarchyRequestError,
+            DomErrorCode::WrongDocumentError => DomException::WrongDocumentError,
+            DomErrorCode::InvalidSelector => {
+                DomException::SyntaxError("Invalid selector".to_string())
+            }
+            DomErrorCode::InvalidCharacter => DomException::InvalidCharacterError,
+            DomErrorCode::IndexSizeError => DomException::IndexSizeError,
+            DomErrorCode::NoModificationAllowedError => DomException::NoModificationAllowedError,
+            DomErrorCode::InvalidStateError => DomException::InvalidStateError,
+            DomErrorCode::InvalidModificationError => DomException::InvalidModificationError,
+            DomErrorCode::NamespaceError => DomException::NamespaceError,
+            DomErrorCode::SecurityError => DomException::SecurityError,
+            DomErrorCode::QuotaExceededError => DomException::QuotaExceededError,
+            DomErrorCode::TimeoutError => DomException::TimeoutError,
+            DomErrorCode::DataCloneError => DomException::DataCloneError,
+            DomErrorCode::Unknown => return None,
+        };
+        Some(exception)
+    }
+}
+
+/// Every exception has a code; a `SyntaxError` becomes `InvalidSelector`,
+/// dropping its message.
+impl From<DomException> for DomErrorCode {
+    fn from(error: DomException) -> Self {
+        match error {
+            DomException::IndexSizeError => DomErrorCode::IndexSizeError,
+            DomException::HierarchyRequestError => DomErrorCode::HierarchyRequestError,
+            DomException::WrongDocumentError => DomErrorCode::WrongDocumentError,
+            DomException::InvalidCharacterError => DomErrorCode::InvalidCharacter,
+            DomException::NoModificationAllowedError => DomErrorCode::NoModificationAllowedError,
+            DomException::NotFoundError => DomErrorCode::NodeNotFound,
+            DomException::NotSupportedError => DomErrorCode::NotSupportedError,
+            DomException::InvalidStateError => DomErrorCode::InvalidStateError,
+            DomException::SyntaxError(_) => DomErrorCode::InvalidSelector,
+            DomException::InvalidModificationError => DomErrorCode::InvalidModificationError,
+            DomException::NamespaceError => DomErrorCode::NamespaceError,
+            DomException::SecurityError => DomErrorCode::SecurityError,
+            DomException::QuotaExceededError => DomErrorCode::QuotaExceededError,
+            DomException::TimeoutError => DomErrorCode::TimeoutError,
+            DomException::DataCloneError => DomErrorCode::DataCloneError,
+        }
+    }
+}
+
 /// Handler for processing direct DOM messages
 ///
 /// This handler provides a simplified interface for DOM operations.
@@ -234,6 +338,9 @@ pub enum DomErrorCode {
 pub struct DomMessageHandler {
     /// Next node ID to assign for new nodes
     next_node_id: NodeId,
+
+    /// Nodes registered with the handler (weak references to prevent memory leaks)
+    nodes: HashMap<NodeId, WeakNodeRef>,
 }
 
 impl Default for DomMessageHandler {
@@ -245,7 +352,48 @@ impl Default for DomMessageHandler {
 impl DomMessageHandler {
     /// Create a new message handler
     pub fn new() -> Self {
-        Self { next_node_id: 1 }
+        Self {
+            next_node_id: 1,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Register an existing node and return the ID messages can refer to it by
+    pub fn register_node(&mut self, node: &NodeRef) -> NodeId {
+        let node_id = self.allocate_node_id();
+        self.nodes.insert(node_id, Arc::downgrade(node));
+        node_id
+    }
+
+    /// Look up a registered node that is still alive
+    fn node(&self, node_id: NodeId) -> Option<NodeRef> {
+        self.nodes.get(&node_id).and_then(|node| node.upgrade())
+    }
+
+    /// Serialize the registered node `root` and its descendants to HTML
+    ///
+    /// Returns `NotFoundError` if `root` is not a live registered node.
+    pub fn serialize_to_html(&self, root: NodeId) -> Result<String, DomException> {
+        let node = self.node(root).ok_or(DomException::NotFoundError)?;
+        let html = serialize_node(&*node.read(), &SerializeOptions::default());
+        Ok(html)
+    }
+
+    /// Stream the HTML of the registered node `root` into `writer`
+    ///
+    /// Writes the same bytes as [`serialize_to_html`](Self::serialize_to_html)
+    /// without building the whole document as a string. An unknown `root`
+    /// fails with `ErrorKind::NotFound` wrapping `NotFoundError`.
+    pub fn serialize_to_writer<W: io::Write>(
+        &self,
+        root: NodeId,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let node = self
+            .node(root)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, DomException::NotFoundError))?;
+        let node = node.read();
+        serialize_node_to_writer(&*node, &SerializeOptions::default(), writer)
     }
 
     /// Handle a direct DOM message and return a response
@@ -297,14 +445,27 @@ impl DomMessageHandler {
                 DirectDomResponse::Success { node_id: None }
             }
 
-            DirectDomMessage::SetAttribute { .. } => {
-                // Stub: Would set attribute on element
-                DirectDomResponse::Success { node_id: None }
+            DirectDomMessage::SetAttribute {
+                element_id,
+                name,
+                value,
+            } => {
+                let result =
+                    self.with_element(element_id, |element| element.set_attribute(name, value));
+                match result {
+                    Ok(()) => DirectDomResponse::Success { node_id: None },
+                    Err(response) => response,
+                }
             }
 
-            DirectDomMessage::GetAttribute { .. } => {
-                // Stub: Would get attribute from element
-                DirectDomResponse::AttributeValue { value: None }
+            DirectDomMessage::GetAttribute { element_id, name } => {
+                let value = self.with_element(element_id, |element| {
+                    Ok(element.get_attribute(&name).map(str::to_string))
+                });
+                match value {
+                    Ok(value) => DirectDomResponse::AttributeValue { value },
+                    Err(response) => response,
+                }
             }
 
             DirectDomMessage::RemoveAttribute { .. } => {
@@ -354,6 +515,38 @@ impl DomMessageHandler {
                     node_id: Some(node_id),
                 }
             }
+
+            DirectDomMessage::CompareDocumentPosition { a, b } => {
+                match (self.node(a), self.node(b)) {
+                    (Some(a), Some(b)) => DirectDomResponse::DocumentPosition {
+                        position: dom_core::compare_document_position(&a, &b),
+                    },
+                    _ => exception_response(DomException::NotFoundError),
+                }
+            }
+        }
+    }
+
+    /// Run `f` on the registered element `element_id`
+    ///
+    /// Fails with a `NodeNotFound` response for unknown nodes, an
+    /// `InvalidNodeType` one for nodes other than elements, and the response
+    /// for the exception `f` returns otherwise.
+    fn with_element<T>(
+        &self,
+        element_id: NodeId,
+        f: impl FnOnce(&mut Element) -> Result<T, DomException>,
+    ) -> Result<T, DirectDomResponse> {
+        let node = self
+            .node(element_id)
+            .ok_or_else(|| exception_response(DomException::NotFoundError))?;
+        let mut node = node.write();
+        match node.as_any_mut().downcast_mut::<Element>() {
+            Some(element) => f(element).map_err(exception_response),
+            None => Err(DirectDomResponse::Error {
+                message: format!("Node {} is not an element", element_id),
+                code: DomErrorCode::InvalidNodeType,
+            }),
         }
     }
 
@@ -370,9 +563,98 @@ impl DomMessageHandler {
     }
 }
 
+/// Build the error response for `error`
+fn exception_response(error: DomException) -> DirectDomResponse {
+    DirectDomResponse::Error {
+        message: error.to_string(),
+        code: error.into(),
+    }
+}
+
+/// Build a DOM tree owned by `doc` from a parser's output, with default limits
+///
+/// See [`build_dom_tree_with_config`].
+pub fn build_dom_tree(parsed: &ParsedNode, doc: &mut Document) -> Result<NodeRef, DomException> {
+    build_dom_tree_with_config(parsed, doc, &DomConfig::default())
+}
+
+/// Build a DOM tree owned by `doc` from a parser's output
+///
+/// Elements, text and comments become the matching `dom_core` nodes; a
+/// parsed document becomes a fragment holding its children. Elements carry
+/// the attribute and child limits of `config`, and `doc` takes its depth
+/// limit. The tree is built from the root down, so each insertion only
+/// checks the depth of the node it adds.
+///
+/// # Errors
+///
+/// * `InvalidCharacterError` - an invalid tag or attribute name
+/// * `QuotaExceededError` - too many attributes or children, or an attribute
+///   value longer than `max_attribute_value_len`
+/// * `HierarchyRequestError` - nesting deeper than `max_tree_depth`
+pub fn build_dom_tree_with_config(
+    parsed: &ParsedNode,
+    doc: &mut Document,
+    config: &DomConfig,
+) -> Result<NodeRef, DomException> {
+    doc.set_depth_limit(Some(config.max_tree_depth));
+    let root = create_parsed_node(parsed, doc, config)?;
+
+    let mut stack = vec![(parsed, root.clone())];
+    while let Some((parsed, node)) = stack.pop() {
+        // Template children go into the inert content fragment
+        let template_content = node
+            .read()
+            .as_any()
+            .downcast_ref::<Element>()
+            .and_then(Element::content);
+        let container = template_content.unwrap_or(node);
+        for child in &parsed.children {
+            let child_node = create_parsed_node(child, doc, config)?;
+            container.write().append_child(child_node.clone())?;
+            stack.push((child, child_node));
+        }
+    }
+    Ok(root)
+}
+
+/// Create the node for `parsed`, without its children
+fn create_parsed_node(
+    parsed: &ParsedNode,
+    doc: &mut Document,
+    config: &DomConfig,
+) -> Result<NodeRef, DomException> {
+    let text = || parsed.text_content.clone().unwrap_or_default();
+    match parsed.node_type {
+        ParsedNodeType::Text => Ok(doc.create_text_node(text())),
+        ParsedNodeType::Comment => Ok(doc.create_comment(text())),
+        ParsedNodeType::Document => Ok(doc.create_document_fragment()),
+        ParsedNodeType::Element => {
+            let tag_name = parsed.tag_name.as_deref().unwrap_or_default();
+            let element = doc.create_element(tag_name)?;
+            let mut guard = element.write();
+            guard.set_attribute_limit(Some(config.max_attributes_per_element));
+            guard.set_attribute_value_limit(Some(config.max_attribute_value_len));
+            guard.set_child_limit(Some(config.max_children));
+
+            // Sorted, since the parser hands attributes over unordered
+            let mut attributes: Vec<(String, String)> = parsed
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            attributes.sort();
+            guard.set_attributes(attributes)?;
+            drop(guard);
+            Ok(element as NodeRef)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dom_core::new_node_ref;
 
     #[test]
     fn test_create_document() {
@@ -449,34 +731,106 @@ mod tests {
         assert!(matches!(response, DirectDomResponse::Success { .. }));
     }
 
+    fn set_attribute(element_id: NodeId, name: &str, value: &str) -> DirectDomMessage {
+        DirectDomMessage::SetAttribute {
+            element_id,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn get_attribute(element_id: NodeId, name: &str) -> DirectDomMessage {
+        DirectDomMessage::GetAttribute {
+            element_id,
+            name: name.to_string(),
+        }
+    }
+
+    fn error_code(response: DirectDomResponse) -> DomErrorCode {
+        match response {
+            DirectDomResponse::Error { code, .. } => code,
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+    }
+
     #[test]
-    fn test_set_attribute() {
+    fn test_set_then_get_attribute() {
         let mut handler = DomMessageHandler::new();
+        let div = node("div");
+        let div_id = handler.register_node(&div);
 
-        let response = handler.handle(DirectDomMessage::SetAttribute {
-            element_id: 1,
-            name: "class".to_string(),
-            value: "active".to_string(),
-        });
+        let response = handler.handle(set_attribute(div_id, "class", "active"));
+        assert_eq!(response, DirectDomResponse::Success { node_id: None });
 
-        assert!(matches!(response, DirectDomResponse::Success { .. }));
+        let value = Some("active".to_string());
+        let response = handler.handle(get_attribute(div_id, "class"));
+        assert_eq!(response, DirectDomResponse::AttributeValue { value });
+        let response = handler.handle(get_attribute(div_id, "title"));
+        assert_eq!(response, DirectDomResponse::AttributeValue { value: None });
+
+        let response = handler.handle(set_attribute(div_id, "bad name", "x"));
+        assert_eq!(error_code(response), DomErrorCode::InvalidCharacter);
+    }
+
+    #[test]
+    fn test_error_codes_round_trip_through_exceptions() {
+        use DomErrorCode::*;
+
+        let one_to_one = [
+            NodeNotFound,
+            HierarchyRequestError,
+            WrongDocumentError,
+            InvalidSelector,
+            InvalidCharacter,
+            IndexSizeError,
+            NoModificationAllowedError,
+            NotSupportedError,
+            InvalidStateError,
+            InvalidModificationError,
+            NamespaceError,
+            SecurityError,
+            QuotaExceededError,
+            TimeoutError,
+            DataCloneError,
+        ];
+        for code in one_to_one {
+            let exception = code.to_exception().unwrap();
+            assert_eq!(DomErrorCode::from(exception), code);
+        }
+
+        // Codes sharing an exception come back as the exception's own code
+        let lossy = [
+            (InvalidNodeId, NodeNotFound),
+            (InvalidNodeType, NotSupportedError),
+            (NotImplemented, NotSupportedError),
+        ];
+        for (code, back) in lossy {
+            assert_eq!(DomErrorCode::from(code.to_exception().unwrap()), back);
+        }
+        assert_eq!(Unknown.to_exception(), None);
+
+        // Any syntax error reads as a selector error, losing its message
+        let syntax = DomException::SyntaxError("bad token".to_string());
+        assert_eq!(DomErrorCode::from(syntax), InvalidSelector);
+        assert_eq!(
+            DomErrorCode::from(DomException::NamespaceError),
+            NamespaceError
+        );
     }
 
     #[test]
-    fn test_get_attribute() {
+    fn test_attribute_of_unknown_or_non_element_node() {
         let mut handler = DomMessageHandler::new();
 
-        let response = handler.handle(DirectDomMessage::GetAttribute {
-            element_id: 1,
-            name: "class".to_string(),
-        });
+        let response = handler.handle(set_attribute(1, "class", "active"));
+        assert_eq!(error_code(response), DomErrorCode::NodeNotFound);
+        let response = handler.handle(get_attribute(1, "class"));
+        assert_eq!(error_code(response), DomErrorCode::NodeNotFound);
 
-        match response {
-            DirectDomResponse::AttributeValue { value } => {
-                assert!(value.is_none());
-            }
-            _ => panic!("Expected AttributeValue response"),
-        }
+        let text = boxed(dom_core::Text::new("hi"));
+        let text_id = handler.register_node(&text);
+        let response = handler.handle(get_attribute(text_id, "class"));
+        assert_eq!(error_code(response), DomErrorCode::InvalidNodeType);
     }
 
     #[test]
@@ -646,6 +1000,247 @@ mod tests {
         assert_eq!(handler.next_node_id, 1);
     }
 
+    fn node(tag: &str) -> NodeRef {
+        new_node_ref(dom_core::Element::new(tag))
+    }
+
+    fn boxed(node: impl dom_core::Node + 'static) -> NodeRef {
+        Arc::new(parking_lot::RwLock::new(node))
+    }
+
+    fn parsed(node_type: ParsedNodeType, tag: &str, children: Vec<ParsedNode>) -> ParsedNode {
+        ParsedNode {
+            node_type,
+            tag_name: Some(tag.to_string()).filter(|tag| !tag.is_empty()),
+            attributes: HashMap::new(),
+            text_content: None,
+            children,
+        }
+    }
+
+    fn parsed_text(node_type: ParsedNodeType, text: &str) -> ParsedNode {
+        ParsedNode {
+            text_content: Some(text.to_string()),
+            ..parsed(node_type, "", Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_build_dom_tree_from_parsed_nodes() {
+        use dom_core::Node;
+
+        let mut paragraph = parsed(
+            ParsedNodeType::Element,
+            "p",
+            vec![parsed_text(ParsedNodeType::Text, "Hello")],
+        );
+        paragraph
+            .attributes
+            .insert("class".to_string(), "intro".to_string());
+        paragraph
+            .attributes
+            .insert("id".to_string(), "greeting".to_string());
+        let body = parsed(
+            ParsedNodeType::Element,
+            "body",
+            vec![paragraph, parsed_text(ParsedNodeType::Comment, "end")],
+        );
+        let html = parsed(ParsedNodeType::Element, "html", vec![body]);
+
+        let mut doc = Document::new();
+        let root = build_dom_tree(&html, &mut doc).unwrap();
+
+        assert_eq!(root.read().node_name(), "HTML");
+        let body = root.read().first_child().unwrap();
+        assert_eq!(body.read().node_name(), "BODY");
+        assert!(Arc::ptr_eq(&body.read().parent_node().unwrap(), &root));
+        let children = body.read().child_nodes();
+        assert_eq!(children[1].read().node_type(), dom_types::NodeType::Comment);
+        assert_eq!(children[1].read().node_value(), Some("end"));
+
+        let paragraph = children[0].read();
+        let element = paragraph.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(element.get_attribute("class"), Some("intro"));
+        assert_eq!(element.class_list(), ["intro"]);
+        assert_eq!(element.id(), Some("greeting"));
+        assert_eq!(
+            paragraph.first_child().unwrap().read().node_type(),
+            dom_types::NodeType::Text
+        );
+        assert_eq!(root.read().text_content().as_deref(), Some("Hello"));
+        drop(paragraph);
+
+        // The tree holds the elements the document created, indexed once
+        // the tree is connected
+        assert!(doc.get_element_by_id("greeting").is_none());
+        doc.append_child(root.clone()).unwrap();
+        let by_id = doc.get_element_by_id("greeting").unwrap();
+        assert!(std::ptr::addr_eq(
+            Arc::as_ptr(&by_id),
+            Arc::as_ptr(&children[0])
+        ));
+        by_id.write().set_attribute("id", "welcome").unwrap();
+        assert!(doc.get_element_by_id("greeting").is_none());
+        assert!(doc.get_element_by_id("welcome").is_some());
+    }
+
+    #[test]
+    fn test_build_dom_tree_respects_config_limits() {
+        let nested = parsed(
+            ParsedNodeType::Element,
+            "div",
+            vec![parsed(
+                ParsedNodeType::Element,
+                "div",
+                vec![parsed(ParsedNodeType::Element, "div", Vec::new())],
+            )],
+        );
+        let shallow = DomConfig {
+            max_tree_depth: 2,
+            ..DomConfig::default()
+        };
+        let mut doc = Document::new();
+        assert_eq!(
+            build_dom_tree_with_config(&nested, &mut doc, &shallow).unwrap_err(),
+            DomException::HierarchyRequestError
+        );
+
+        let mut input = parsed(ParsedNodeType::Element, "input", Vec::new());
+        input.attributes.insert("value".to_string(), "x".repeat(9));
+        let short_values = DomConfig {
+            max_attribute_value_len: 8,
+            ..DomConfig::default()
+        };
+        assert_eq!(
+            build_dom_tree_with_config(&input, &mut doc, &short_values).unwrap_err(),
+            DomException::QuotaExceededError
+        );
+    }
+
+    #[test]
+    fn test_set_attribute_message_respects_value_limit() {
+        let config = DomConfig {
+            max_attribute_value_len: 8,
+            ..DomConfig::default()
+        };
+        let input = parsed(ParsedNodeType::Element, "input", Vec::new());
+        let root = build_dom_tree_with_config(&input, &mut Document::new(), &config).unwrap();
+        let mut handler = DomMessageHandler::new();
+        let input_id = handler.register_node(&root);
+
+        let response = handler.handle(set_attribute(input_id, "value", &"x".repeat(9)));
+        assert_eq!(error_code(response), DomErrorCode::QuotaExceededError);
+        let response = handler.handle(get_attribute(input_id, "value"));
+        assert_eq!(response, DirectDomResponse::AttributeValue { value: None });
+    }
+
+    #[test]
+    fn test_serialize_to_writer_matches_html() {
+        use dom_core::{Element, Node, Text};
+
+        let root = node("main");
+        for section_index in 0..50 {
+            let section = node("section");
+            for item_index in 0..20 {
+                let label = format!("item {} & {} <\u{a0}>", section_index, item_index);
+                let mut item = Element::new("p");
+                item.set_attribute("data-i", &item_index.to_string())
+                    .unwrap();
+                item.append_child(boxed(Text::new(label))).unwrap();
+                section.write().append_child(boxed(item)).unwrap();
+            }
+            root.write().append_child(section).unwrap();
+        }
+
+        let mut handler = DomMessageHandler::new();
+        let root_id = handler.register_node(&root);
+        let html = handler.serialize_to_html(root_id).unwrap();
+        assert!(html.len() > 30_000);
+
+        let mut bytes = Vec::new();
+        handler.serialize_to_writer(root_id, &mut bytes).unwrap();
+        assert_eq!(bytes, html.into_bytes());
+    }
+
+    #[test]
+    fn test_serialize_unknown_node() {
+        let handler = DomMessageHandler::new();
+        assert_eq!(
+            handler.serialize_to_html(7).unwrap_err(),
+            DomException::NotFoundError
+        );
+        let error = handler.serialize_to_writer(7, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    fn position(handler: &mut DomMessageHandler, a: NodeId, b: NodeId) -> u16 {
+        match handler.handle(DirectDomMessage::CompareDocumentPosition { a, b }) {
+            DirectDomResponse::DocumentPosition { position } => position,
+            other => panic!("Expected DocumentPosition response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compare_document_position() {
+        let root = node("div");
+        let first = node("p");
+        let second = node("p");
+        let inner = node("span");
+        root.write().append_child(first.clone()).unwrap();
+        root.write().append_child(second.clone()).unwrap();
+        first.write().append_child(inner.clone()).unwrap();
+        let detached = node("aside");
+
+        let mut handler = DomMessageHandler::new();
+        let root_id = handler.register_node(&root);
+        let first_id = handler.register_node(&first);
+        let second_id = handler.register_node(&second);
+        let inner_id = handler.register_node(&inner);
+        let detached_id = handler.register_node(&detached);
+
+        let cases = [
+            (root_id, inner_id, &root, &inner),
+            (inner_id, root_id, &inner, &root),
+            (first_id, second_id, &first, &second),
+            (second_id, first_id, &second, &first),
+            (inner_id, detached_id, &inner, &detached),
+        ];
+        for (a_id, b_id, a, b) in cases {
+            assert_eq!(
+                position(&mut handler, a_id, b_id),
+                dom_core::compare_document_position(a, b)
+            );
+        }
+        assert_eq!(
+            position(&mut handler, first_id, second_id),
+            dom_core::DocumentPosition::Following as u16
+        );
+        assert_ne!(
+            position(&mut handler, inner_id, detached_id)
+                & dom_core::DocumentPosition::Disconnected as u16,
+            0
+        );
+    }
+
+    #[test]
+    fn test_compare_document_position_unknown_node() {
+        let mut handler = DomMessageHandler::new();
+        let known = node("div");
+        let known_id = handler.register_node(&known);
+
+        let response = handler.handle(DirectDomMessage::CompareDocumentPosition {
+            a: known_id,
+            b: 999,
+        });
+        match response {
+            DirectDomResponse::Error { message, code } => {
+                assert_eq!(code, DomErrorCode::NodeNotFound);
+                assert_eq!(message, DomException::NotFoundError.to_string());
+            }
+            other => panic!("Expected Error response, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_insert_before() {
         let mut handler = DomMessageHandler::new();
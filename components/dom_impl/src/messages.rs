@@ -1,5 +1,6 @@
 //! Message types for communication with other browser components
 
+use dom_core::ClassInvalidation;
 use dom_types::{DocumentId, NodeId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -27,6 +28,15 @@ pub enum DomComponentMessage {
         params: OperationParams,
     },
 
+    /// JavaScript runtime ran several DOM manipulations in one task
+    ///
+    /// Their mutation records are delivered together, in a single
+    /// `DomMutated` response.
+    ScriptManipulations {
+        /// The DOM operations to perform, in order
+        operations: Vec<DomOperation>,
+    },
+
     /// CSS engine has detected a style change requiring invalidation
     StyleInvalidation {
         /// Node that needs style recalculation
@@ -67,7 +77,8 @@ pub enum DomComponentResponse {
 
     /// DOM has been mutated (sent to JavaScript for MutationObserver)
     DomMutated {
-        /// List of mutation records
+        /// List of mutation records, grouped by target in the order each
+        /// target first mutated
         mutations: Vec<MutationRecord>,
         /// IDs of all affected nodes
         affected_nodes: Vec<NodeId>,
@@ -219,6 +230,13 @@ pub struct OperationParams {
 pub enum InvalidationReason {
     /// Class attribute changed
     ClassChanged,
+    /// Specific classes were added to or removed from the subtree root
+    ClassListChanged {
+        /// Classes added by the change
+        added: Vec<String>,
+        /// Classes removed by the change
+        removed: Vec<String>,
+    },
     /// ID attribute changed
     IdChanged,
     /// Style attribute changed
@@ -229,8 +247,17 @@ pub enum InvalidationReason {
     PseudoClassChanged,
 }
 
+impl From<ClassInvalidation> for InvalidationReason {
+    fn from(change: ClassInvalidation) -> Self {
+        InvalidationReason::ClassListChanged {
+            added: change.added,
+            removed: change.removed,
+        }
+    }
+}
+
 /// Event data from user interaction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EventData {
     /// Mouse coordinates (if applicable)
     pub mouse_x: Option<i32>,
@@ -268,6 +295,14 @@ pub enum QueryType {
         context: Option<NodeId>,
     },
 
+    /// Query selector returning every match
+    QuerySelectorAll {
+        /// CSS selector
+        selector: String,
+        /// Context node (None = document)
+        context: Option<NodeId>,
+    },
+
     /// Get element by ID
     GetElementById {
         /// Element ID attribute
@@ -0,0 +1,65 @@
+//! Operation policies for sandboxed DOM instances
+//!
+//! A [`DomPolicy`] is consulted by [`DomComponent`](crate::DomComponent) before
+//! it applies a mutating [`DomOperation`]. Unlike sanitization, which cleans
+//! markup strings, a policy gates structured operations and can veto them
+//! with a specific [`DomException`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use browser_dom_impl::{DomException, DomOperation, DomPolicy};
+//!
+//! struct NoScripts;
+//!
+//! impl DomPolicy for NoScripts {
+//!     fn check_operation(&self, operation: &DomOperation) -> Result<(), DomException> {
+//!         match operation {
+//!             DomOperation::CreateElement { tag_name, .. }
+//!                 if tag_name.eq_ignore_ascii_case("script") =>
+//!             {
+//!                 Err(DomException::SecurityError)
+//!             }
+//!             _ => Ok(()),
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::messages::DomOperation;
+use dom_types::DomException;
+
+/// Gate consulted before a mutating operation is applied
+pub trait DomPolicy: Send + Sync {
+    /// Checks whether `operation` may be applied
+    ///
+    /// # Errors
+    ///
+    /// Returns the `DomException` that should be reported instead of
+    /// applying the operation.
+    fn check_operation(&self, operation: &DomOperation) -> Result<(), DomException> {
+        let _ = operation;
+        Ok(())
+    }
+}
+
+/// Policy that allows every operation (the component default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAllPolicy;
+
+impl DomPolicy for AllowAllPolicy {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_all_policy() {
+        let policy = AllowAllPolicy;
+        let operation = DomOperation::CreateElement {
+            tag_name: "script".to_string(),
+            namespace: None,
+        };
+        assert!(policy.check_operation(&operation).is_ok());
+    }
+}
@@ -22,7 +22,7 @@
 //!
 //! // Check if content should be filtered
 //! assert!(policy.should_remove_tag("script"));
-//! assert!(policy.should_strip_attribute("onclick", "alert(1)"));
+//! assert!(policy.should_strip_attribute("div", "onclick", "alert(1)"));
 //!
 //! // Sanitize HTML string
 //! let dirty_html = "<div onclick=\"alert('xss')\">Hello</div><script>evil()</script>";
@@ -42,10 +42,16 @@
 //! - Always sanitize user input before rendering
 //! - Use `strict()` policy for untrusted content
 //! - The `sanitize_html` function is a basic implementation; for production
-//!   use, consider a proper HTML parser-based sanitizer
+//!   use, parse the markup and sanitize the resulting nodes with `sanitize_tree`
 
+use dom_core::fragment_parser::parse_fragment;
+use dom_core::serialize::{serialize_node, SerializeOptions};
+use dom_core::{DocumentFragment, Element, Node, NodeRef};
+use dom_types::DomException;
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// Dangerous HTML tags that can execute code or load external resources.
 ///
@@ -217,6 +223,10 @@ pub struct SanitizationPolicy {
     /// Custom allowed attributes (whitelist mode if Some)
     /// When set, only attributes in this set are allowed
     pub allowed_attributes: Option<HashSet<String>>,
+    /// Attributes allowed on specific tags, keyed by lowercase tag name
+    /// When a tag has an entry, only those attributes and the globally
+    /// allowed ones survive on it
+    pub allowed_attributes_per_tag: HashMap<String, HashSet<String>>,
     /// Allow data: URLs for images (default: false for security)
     pub allow_data_urls_for_images: bool,
 }
@@ -235,6 +245,7 @@ impl Default for SanitizationPolicy {
             allowed_tags: None,
             blocked_tags: HashSet::new(),
             allowed_attributes: None,
+            allowed_attributes_per_tag: HashMap::new(),
             allow_data_urls_for_images: false,
         }
     }
@@ -360,6 +371,7 @@ impl SanitizationPolicy {
             allowed_tags: None,
             blocked_tags: HashSet::new(),
             allowed_attributes: None,
+            allowed_attributes_per_tag: HashMap::new(),
             allow_data_urls_for_images: false,
         }
     }
@@ -375,6 +387,7 @@ impl SanitizationPolicy {
             allowed_tags: Some(HashSet::new()), // Empty whitelist = no tags allowed
             blocked_tags: HashSet::new(),
             allowed_attributes: Some(HashSet::new()), // No attributes allowed
+            allowed_attributes_per_tag: HashMap::new(),
             allow_data_urls_for_images: false,
         }
     }
@@ -424,6 +437,7 @@ impl SanitizationPolicy {
     ///
     /// # Arguments
     ///
+    /// * `tag_name` - The tag of the element carrying the attribute (case-insensitive)
     /// * `attr_name` - The attribute name (case-insensitive)
     /// * `attr_value` - The attribute value (for URL checking)
     ///
@@ -436,12 +450,21 @@ impl SanitizationPolicy {
     /// ```rust
     /// use browser_dom_impl::sanitization::SanitizationPolicy;
     ///
-    /// let policy = SanitizationPolicy::default();
-    /// assert!(policy.should_strip_attribute("onclick", "alert(1)"));
-    /// assert!(policy.should_strip_attribute("href", "javascript:void(0)"));
-    /// assert!(!policy.should_strip_attribute("class", "my-class"));
+    /// let mut policy = SanitizationPolicy::default();
+    /// assert!(policy.should_strip_attribute("div", "onclick", "alert(1)"));
+    /// assert!(policy.should_strip_attribute("a", "href", "javascript:void(0)"));
+    /// assert!(!policy.should_strip_attribute("div", "class", "my-class"));
+    ///
+    /// policy.allow_attributes_on("img", ["src", "alt"]);
+    /// assert!(!policy.should_strip_attribute("img", "alt", "Logo"));
+    /// assert!(policy.should_strip_attribute("img", "class", "logo"));
     /// ```
-    pub fn should_strip_attribute(&self, attr_name: &str, attr_value: &str) -> bool {
+    pub fn should_strip_attribute(
+        &self,
+        tag_name: &str,
+        attr_name: &str,
+        attr_value: &str,
+    ) -> bool {
         let attr_lower = attr_name.to_lowercase();
 
         // Check event handlers
@@ -454,8 +477,9 @@ impl SanitizationPolicy {
             return true;
         }
 
-        // Check javascript URLs in URL attributes
-        if self.block_javascript_urls && URL_ATTRIBUTES.contains(&attr_lower.as_str()) {
+        // Check javascript URLs in URL attributes, whatever their prefix
+        let local_name = attr_lower.rsplit(':').next().unwrap_or_default();
+        if self.block_javascript_urls && URL_ATTRIBUTES.contains(&local_name) {
             if is_dangerous_url(attr_value) {
                 // Special case: allow data: URLs for images if configured
                 if self.allow_data_urls_for_images
@@ -468,12 +492,17 @@ impl SanitizationPolicy {
             }
         }
 
-        // Check attribute whitelist
+        // Check the tag's own whitelist, then the global one
+        let tag_lower = tag_name.to_lowercase();
+        let tag_allowed = self.allowed_attributes_per_tag.get(&tag_lower);
+        if tag_allowed.is_some_and(|allowed| allowed.contains(&attr_lower)) {
+            return false;
+        }
         if let Some(ref allowed) = self.allowed_attributes {
             return !allowed.contains(&attr_lower);
         }
 
-        false
+        tag_allowed.is_some()
     }
 
     /// Check if a tag is explicitly allowed in whitelist mode.
@@ -500,6 +529,21 @@ impl SanitizationPolicy {
             self.blocked_tags.insert(tag.into().to_lowercase());
         }
     }
+
+    /// Allow attributes on one tag only, on top of the global whitelist.
+    pub fn allow_attributes_on<I, S>(&mut self, tag: impl Into<String>, attributes: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let allowed = self
+            .allowed_attributes_per_tag
+            .entry(tag.into().to_lowercase())
+            .or_default();
+        for attr in attributes {
+            allowed.insert(attr.into().to_lowercase());
+        }
+    }
 }
 
 /// Check if a URL uses a dangerous scheme.
@@ -689,6 +733,192 @@ pub fn sanitize_html_with_stats(html: &str, policy: &SanitizationPolicy) -> Sani
     }
 }
 
+/// Sanitize a parsed node tree in place.
+///
+/// Walks the descendants of `root`, removing elements for which
+/// [`SanitizationPolicy::should_remove_tag`] holds and stripping attributes
+/// for which [`SanitizationPolicy::should_strip_attribute`] holds. Dangerous
+/// and explicitly blocked tags are deleted along with their contents; tags
+/// that are merely missing from the `allowed_tags` whitelist are unwrapped,
+/// so their sanitized children take their place. `root` itself is never
+/// removed, only its attributes are stripped. The content fragments of
+/// templates are sanitized like any other children, since they serialize
+/// with the template.
+///
+/// Unlike [`sanitize_html_with_stats`], the counts are exact: every deleted
+/// or unwrapped element counts once, and `html` is the serialized `root`.
+///
+/// # Errors
+///
+/// Returns the error of a node that could not be removed or moved out of an
+/// unwrapped element. The tree is then only partly sanitized.
+pub fn sanitize_tree(
+    root: &NodeRef,
+    policy: &SanitizationPolicy,
+) -> Result<SanitizationResult, DomException> {
+    let mut result = SanitizationResult {
+        html: String::new(),
+        tags_removed: 0,
+        attributes_stripped: 0,
+        removed_tags: Vec::new(),
+    };
+    strip_attributes(root, policy, &mut result);
+    sanitize_subtree(root, policy, &mut result)?;
+    result.html = serialize_node(&*root.read(), &SerializeOptions::default());
+    Ok(result)
+}
+
+/// Sanitized `innerHTML` assignment for elements
+///
+/// `dom_core` parses markup but has no notion of sanitization policies.
+/// Importing this trait gives [`Element`] a setter that runs the parsed
+/// nodes through [`sanitize_tree`] with the default [`SanitizationPolicy`]
+/// before they become children.
+pub trait InnerHtmlExt {
+    /// Replaces the children with the nodes parsed from `html`
+    ///
+    /// Parsing is best effort, as described for [`parse_fragment`], so any
+    /// input is accepted. For a `<template>` the children of its content
+    /// fragment are replaced instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a tree operation that fails while sanitizing the
+    /// parsed nodes or swapping them in, such as a violated quota.
+    fn set_inner_html(&mut self, html: &str) -> Result<(), DomException>;
+}
+
+impl InnerHtmlExt for Element {
+    fn set_inner_html(&mut self, html: &str) -> Result<(), DomException> {
+        let fragment: NodeRef = Arc::new(RwLock::new(DocumentFragment::new()));
+        for node in parse_fragment(html) {
+            fragment.write().append_child(node)?;
+        }
+        sanitize_tree(&fragment, &SanitizationPolicy::default())?;
+        let nodes = fragment.read().child_nodes();
+
+        match self.content() {
+            Some(content) => replace_children(&mut *content.write(), nodes),
+            None => replace_children(self, nodes),
+        }
+    }
+}
+
+fn replace_children(parent: &mut dyn Node, nodes: Vec<NodeRef>) -> Result<(), DomException> {
+    for child in parent.child_nodes() {
+        parent.remove_child(child)?;
+    }
+    for node in nodes {
+        parent.append_child(node)?;
+    }
+    Ok(())
+}
+
+/// How a disallowed element leaves the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagRemoval {
+    /// Remove the element and its descendants
+    Delete,
+    /// Replace the element with its children
+    Unwrap,
+}
+
+/// Decide whether and how an element with `tag_name` is removed
+fn tag_removal(policy: &SanitizationPolicy, tag_name: &str) -> Option<TagRemoval> {
+    if !policy.should_remove_tag(tag_name) {
+        return None;
+    }
+    let tag_lower = tag_name.to_lowercase();
+    let dangerous = policy.remove_dangerous_tags && DANGEROUS_TAGS.contains(tag_lower.as_str());
+    if dangerous || policy.blocked_tags.contains(&tag_lower) {
+        Some(TagRemoval::Delete)
+    } else {
+        Some(TagRemoval::Unwrap)
+    }
+}
+
+/// Sanitize the children of `node` and, for a template, of its content
+fn sanitize_subtree(
+    node: &NodeRef,
+    policy: &SanitizationPolicy,
+    result: &mut SanitizationResult,
+) -> Result<(), DomException> {
+    let content = node
+        .read()
+        .as_any()
+        .downcast_ref::<Element>()
+        .and_then(Element::content);
+    if let Some(content) = content {
+        sanitize_children(&content, policy, result)?;
+    }
+    sanitize_children(node, policy, result)
+}
+
+/// Sanitize the children of `parent`, depth first
+fn sanitize_children(
+    parent: &NodeRef,
+    policy: &SanitizationPolicy,
+    result: &mut SanitizationResult,
+) -> Result<(), DomException> {
+    let children = parent.read().child_nodes();
+    for child in children {
+        let tag_name = {
+            let node = child.read();
+            match node.as_any().downcast_ref::<Element>() {
+                Some(element) => element.tag_name().to_lowercase(),
+                None => continue,
+            }
+        };
+
+        match tag_removal(policy, &tag_name) {
+            Some(TagRemoval::Delete) => {
+                parent.write().remove_child(child)?;
+            }
+            Some(TagRemoval::Unwrap) => {
+                sanitize_children(&child, policy, result)?;
+                let grandchildren = child.read().child_nodes();
+                for grandchild in grandchildren {
+                    parent
+                        .write()
+                        .insert_before(grandchild, Some(child.clone()))?;
+                }
+                parent.write().remove_child(child)?;
+            }
+            None => {
+                strip_attributes(&child, policy, result);
+                sanitize_subtree(&child, policy, result)?;
+                continue;
+            }
+        }
+
+        result.tags_removed += 1;
+        if !result.removed_tags.contains(&tag_name) {
+            result.removed_tags.push(tag_name);
+        }
+    }
+    Ok(())
+}
+
+/// Strip the attributes the policy disallows from an element node
+fn strip_attributes(node: &NodeRef, policy: &SanitizationPolicy, result: &mut SanitizationResult) {
+    let mut node = node.write();
+    let Some(element) = node.as_any_mut().downcast_mut::<Element>() else {
+        return;
+    };
+    let tag_name = element.tag_name().to_string();
+    let stripped: Vec<String> = element
+        .attributes()
+        .iter()
+        .filter(|(name, value)| policy.should_strip_attribute(&tag_name, name, value))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in stripped {
+        if element.remove_attribute(&name).is_ok() {
+            result.attributes_stripped += 1;
+        }
+    }
+}
+
 /// Remove a specific tag (opening and closing) from HTML.
 fn remove_tag(html: &str, tag: &str) -> String {
     let mut result = html.to_string();
@@ -875,7 +1105,7 @@ fn clean_tag_attributes(tag: &str, policy: &SanitizationPolicy) -> String {
 
     // Parse and filter attributes
     if !attrs_str.is_empty() {
-        let clean_attrs = filter_attributes(attrs_str, policy);
+        let clean_attrs = filter_attributes(tag_name, attrs_str, policy);
         if !clean_attrs.is_empty() {
             result.push(' ');
             result.push_str(&clean_attrs);
@@ -891,14 +1121,14 @@ fn clean_tag_attributes(tag: &str, policy: &SanitizationPolicy) -> String {
 }
 
 /// Filter attributes according to policy.
-fn filter_attributes(attrs_str: &str, policy: &SanitizationPolicy) -> String {
+fn filter_attributes(tag_name: &str, attrs_str: &str, policy: &SanitizationPolicy) -> String {
     let mut result = Vec::new();
     let mut remaining = attrs_str.trim();
 
     while !remaining.is_empty() {
         // Try to parse an attribute
         if let Some((attr_name, attr_value, rest)) = parse_next_attribute(remaining) {
-            if !policy.should_strip_attribute(&attr_name, &attr_value) {
+            if !policy.should_strip_attribute(tag_name, &attr_name, &attr_value) {
                 // Keep this attribute
                 if attr_value.is_empty() {
                     result.push(attr_name);
@@ -1014,6 +1244,136 @@ pub fn escape_html(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dom_core::{new_node_ref, Text};
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    fn element(tag: &str, attributes: &[(&str, &str)], children: Vec<NodeRef>) -> NodeRef {
+        let mut element = Element::new(tag);
+        for (name, value) in attributes {
+            element.set_attribute(*name, *value).unwrap();
+        }
+        let element = new_node_ref(element);
+        for child in children {
+            element.write().append_child(child).unwrap();
+        }
+        element
+    }
+
+    fn text(data: &str) -> NodeRef {
+        new_node_ref(Text::new(data))
+    }
+
+    /// `<div><script>…</script><p onclick><a href="javascript:…">…</a></p></div>`
+    fn dirty_tree() -> NodeRef {
+        let script = element("script", &[], vec![text("evil()")]);
+        let link = element(
+            "a",
+            &[("href", "javascript:alert(1)"), ("title", "home")],
+            vec![text("Home")],
+        );
+        let paragraph = element("p", &[("onclick", "steal()")], vec![link]);
+        element("div", &[("class", "post")], vec![script, paragraph])
+    }
+
+    #[test]
+    fn test_sanitize_tree_counts_exact_removals() {
+        let root = dirty_tree();
+        let result = sanitize_tree(&root, &SanitizationPolicy::default()).unwrap();
+
+        assert_eq!(result.tags_removed, 1);
+        assert_eq!(result.removed_tags, ["script"]);
+        assert_eq!(result.attributes_stripped, 2);
+        assert_eq!(
+            result.html,
+            "<div class=\"post\"><p><a title=\"home\">Home</a></p></div>"
+        );
+        assert_eq!(root.read().child_nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_sanitize_tree_unwraps_tags_outside_whitelist() {
+        let root = dirty_tree();
+        let form = element("form", &[], vec![text("Keep "), element("b", &[], vec![])]);
+        root.write().append_child(form).unwrap();
+
+        let result = sanitize_tree(&root, &SanitizationPolicy::strict()).unwrap();
+
+        // The script's text goes with it, the form's children stay in place
+        assert_eq!(result.tags_removed, 2);
+        assert_eq!(result.removed_tags, ["script", "form"]);
+        assert_eq!(
+            result.html,
+            "<div class=\"post\"><p><a title=\"home\">Home</a></p>Keep <b></b></div>"
+        );
+    }
+
+    #[test]
+    fn test_set_inner_html_builds_children() {
+        let list = element("ul", &[], vec![text("old")]);
+        let mut guard = list.write();
+        let list = guard.as_any_mut().downcast_mut::<Element>().unwrap();
+        list.set_inner_html("<li class=\"first\">One <em>1</em></li><li>Two</li>")
+            .unwrap();
+
+        let items = list.child_nodes();
+        assert_eq!(items.len(), 2);
+        let first = items[0].read();
+        let first_children = first.child_nodes();
+        assert_eq!(first.node_name(), "LI");
+        assert_eq!(first_children[0].read().node_value(), Some("One "));
+        let emphasis = first_children[1].read().text_content();
+        assert_eq!(emphasis.as_deref(), Some("1"));
+        assert_eq!(items[1].read().text_content().as_deref(), Some("Two"));
+        assert_eq!(
+            list.inner_html(),
+            "<li class=\"first\">One <em>1</em></li><li>Two</li>"
+        );
+    }
+
+    #[test]
+    fn test_set_inner_html_applies_default_policy() {
+        let mut div = Element::new("div");
+        let html = "<p onclick=\"steal()\">Hi<script>evil()</script></p><script>x</script>";
+        div.set_inner_html(html).unwrap();
+        assert_eq!(div.inner_html(), "<p>Hi</p>");
+
+        div.set_inner_html("<b>unclosed <i>tags").unwrap();
+        assert_eq!(div.inner_html(), "<b>unclosed <i>tags</i></b>");
+    }
+
+    #[test]
+    fn test_set_inner_html_sanitizes_template_content() {
+        let mut div = Element::new("div");
+        let html = "<template><script>evil()</script>\
+                    <img src=\"x\" onerror=\"steal()\"></template>";
+        div.set_inner_html(html).unwrap();
+
+        assert_eq!(div.inner_html(), "<template><img src=\"x\"></template>");
+        let children = div.child_nodes();
+        let template = children[0].read();
+        let template = template.as_any().downcast_ref::<Element>().unwrap();
+        let content = template.content().unwrap();
+        assert_eq!(content.read().child_nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_sanitize_tree_strips_namespaced_url_attributes() {
+        let xlink = "http://www.w3.org/1999/xlink";
+        let mut link = Element::new("a");
+        link.set_attribute_ns(Some(xlink), "xlink:href", "javascript:alert(1)")
+            .unwrap();
+        let root = element("div", &[], vec![new_node_ref(link)]);
+
+        let result = sanitize_tree(&root, &SanitizationPolicy::default()).unwrap();
+
+        assert_eq!(result.attributes_stripped, 1);
+        assert_eq!(result.html, "<div><a></a></div>");
+        let link = root.read().child_nodes()[0].clone();
+        let link = link.read();
+        let link = link.as_any().downcast_ref::<Element>().unwrap();
+        assert_eq!(link.get_attribute_ns(Some(xlink), "href"), None);
+    }
 
     #[test]
     fn test_dangerous_tag_detection() {
@@ -1037,24 +1397,24 @@ mod tests {
     #[test]
     fn test_event_handler_stripping() {
         let policy = SanitizationPolicy::default();
-        assert!(policy.should_strip_attribute("onclick", "alert(1)"));
-        assert!(policy.should_strip_attribute("ONCLICK", "alert(1)"));
-        assert!(policy.should_strip_attribute("onClick", "alert(1)"));
-        assert!(policy.should_strip_attribute("onload", ""));
-        assert!(policy.should_strip_attribute("onerror", "malicious()"));
-        assert!(policy.should_strip_attribute("onmouseover", "doSomething()"));
-        assert!(policy.should_strip_attribute("onfocus", "focus()"));
-        assert!(!policy.should_strip_attribute("class", "foo"));
-        assert!(!policy.should_strip_attribute("id", "bar"));
-        assert!(!policy.should_strip_attribute("style", "color: red"));
+        assert!(policy.should_strip_attribute("div", "onclick", "alert(1)"));
+        assert!(policy.should_strip_attribute("div", "ONCLICK", "alert(1)"));
+        assert!(policy.should_strip_attribute("div", "onClick", "alert(1)"));
+        assert!(policy.should_strip_attribute("div", "onload", ""));
+        assert!(policy.should_strip_attribute("div", "onerror", "malicious()"));
+        assert!(policy.should_strip_attribute("div", "onmouseover", "doSomething()"));
+        assert!(policy.should_strip_attribute("div", "onfocus", "focus()"));
+        assert!(!policy.should_strip_attribute("div", "class", "foo"));
+        assert!(!policy.should_strip_attribute("div", "id", "bar"));
+        assert!(!policy.should_strip_attribute("div", "style", "color: red"));
     }
 
     #[test]
     fn test_custom_event_handlers_starting_with_on() {
         let policy = SanitizationPolicy::default();
         // Even unknown on* handlers should be stripped
-        assert!(policy.should_strip_attribute("oncustomevent", "handler()"));
-        assert!(policy.should_strip_attribute("onwhatever", "handler()"));
+        assert!(policy.should_strip_attribute("div", "oncustomevent", "handler()"));
+        assert!(policy.should_strip_attribute("div", "onwhatever", "handler()"));
     }
 
     #[test]
@@ -1102,7 +1462,7 @@ mod tests {
         let policy = SanitizationPolicy::permissive();
         // Still blocks dangerous things
         assert!(policy.should_remove_tag("script"));
-        assert!(policy.should_strip_attribute("onclick", "alert(1)"));
+        assert!(policy.should_strip_attribute("div", "onclick", "alert(1)"));
         // Allows most other tags
         assert!(!policy.should_remove_tag("form"));
         assert!(!policy.should_remove_tag("input"));
@@ -1114,7 +1474,7 @@ mod tests {
         assert!(policy.should_remove_tag("div"));
         assert!(policy.should_remove_tag("span"));
         assert!(policy.should_remove_tag("p"));
-        assert!(policy.should_strip_attribute("class", "foo"));
+        assert!(policy.should_strip_attribute("div", "class", "foo"));
     }
 
     #[test]
@@ -1127,22 +1487,56 @@ mod tests {
         assert!(!policy.should_remove_tag("div"));
     }
 
+    #[test]
+    fn test_per_tag_attribute_whitelist() {
+        let mut policy = SanitizationPolicy::default();
+        policy.allowed_attributes = Some(["class", "title"].map(String::from).into());
+        policy.allow_attributes_on("a", ["href"]);
+        policy.allow_attributes_on("IMG", ["src", "alt"]);
+
+        assert!(!policy.should_strip_attribute("a", "href", "/home"));
+        assert!(policy.should_strip_attribute("span", "href", "/home"));
+        assert!(!policy.should_strip_attribute("img", "src", "/logo.png"));
+        assert!(policy.should_strip_attribute("a", "src", "/logo.png"));
+        assert!(policy.should_strip_attribute("div", "alt", "Logo"));
+        // Globally allowed attributes survive on tags with their own entry
+        assert!(!policy.should_strip_attribute("a", "title", "Home"));
+        // Dangerous values are stripped even when the tag allows the attribute
+        assert!(policy.should_strip_attribute("a", "href", "javascript:alert(1)"));
+    }
+
+    #[test]
+    fn test_per_tag_whitelist_restricts_tag_without_global_whitelist() {
+        let mut policy = SanitizationPolicy::default();
+        policy.allow_attributes_on("img", ["src", "alt"]);
+
+        assert!(!policy.should_strip_attribute("img", "alt", "Logo"));
+        assert!(policy.should_strip_attribute("img", "width", "10"));
+        assert!(!policy.should_strip_attribute("span", "width", "10"));
+
+        let html = "<img src=\"/logo.png\" width=\"10\"><span width=\"10\">x</span>";
+        assert_eq!(
+            sanitize_html(html, &policy),
+            "<img src=\"/logo.png\"><span width=\"10\">x</span>"
+        );
+    }
+
     #[test]
     fn test_url_attribute_checking() {
         let policy = SanitizationPolicy::default();
 
         // href with javascript
-        assert!(policy.should_strip_attribute("href", "javascript:alert(1)"));
+        assert!(policy.should_strip_attribute("a", "href", "javascript:alert(1)"));
 
         // src with javascript
-        assert!(policy.should_strip_attribute("src", "javascript:alert(1)"));
+        assert!(policy.should_strip_attribute("img", "src", "javascript:alert(1)"));
 
         // action with javascript
-        assert!(policy.should_strip_attribute("action", "javascript:alert(1)"));
+        assert!(policy.should_strip_attribute("form", "action", "javascript:alert(1)"));
 
         // Safe URLs
-        assert!(!policy.should_strip_attribute("href", "https://example.com"));
-        assert!(!policy.should_strip_attribute("src", "/images/logo.png"));
+        assert!(!policy.should_strip_attribute("a", "href", "https://example.com"));
+        assert!(!policy.should_strip_attribute("img", "src", "/images/logo.png"));
     }
 
     #[test]
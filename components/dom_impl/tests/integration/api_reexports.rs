@@ -126,6 +126,8 @@ fn test_config_and_component_integration() {
         enable_shadow_dom: false,
         gc_threshold: 10000,
         arena_capacity: 5000,
+        max_attributes_per_element: 32,
+        max_attribute_value_len: 1024,
     };
 
     // Create component
@@ -29,14 +29,14 @@ fn test_document_element_creation() {
     doc.set_document_element(html.clone());
 
     // Append body to html
-    html.write().append_child(
-        Arc::new(RwLock::new(Box::new(body.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    html.write()
+        .append_child(Arc::new(RwLock::new(body.read().clone())))
+        .unwrap();
 
     // Append div to body
-    body.write().append_child(
-        Arc::new(RwLock::new(Box::new(div.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    body.write()
+        .append_child(Arc::new(RwLock::new(div.read().clone())))
+        .unwrap();
 
     // Verify document element is set
     assert!(doc.document_element().is_some());
@@ -161,11 +161,11 @@ fn test_insert_before() {
     let child2 = doc.create_element("p").unwrap();
 
     // Append child1
-    let child1_node: NodeRef = Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>));
+    let child1_node: NodeRef = Arc::new(RwLock::new(child1.read().clone()));
     parent.write().append_child(child1_node.clone()).unwrap();
 
     // Insert child2 before child1
-    let child2_node: NodeRef = Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>));
+    let child2_node: NodeRef = Arc::new(RwLock::new(child2.read().clone()));
     parent.write().insert_before(child2_node.clone(), Some(child1_node)).unwrap();
 
     // Verify order: child2 should be first
@@ -183,7 +183,7 @@ fn test_remove_child() {
     let parent = doc.create_element("div").unwrap();
     let child = doc.create_element("span").unwrap();
 
-    let child_node: NodeRef = Arc::new(RwLock::new(Box::new(child.read().clone()) as Box<dyn Node>));
+    let child_node: NodeRef = Arc::new(RwLock::new(child.read().clone()));
     parent.write().append_child(child_node.clone()).unwrap();
 
     // Verify child is present
@@ -205,11 +205,11 @@ fn test_clone_node() {
 
     // Create a child
     let child = Element::new("span");
-    let child_node: NodeRef = Arc::new(RwLock::new(Box::new(child) as Box<dyn Node>));
+    let child_node: NodeRef = Arc::new(RwLock::new(child));
     element.append_child(child_node).unwrap();
 
     // Shallow clone
-    let element_node: NodeRef = Arc::new(RwLock::new(Box::new(element.clone()) as Box<dyn Node>));
+    let element_node: NodeRef = Arc::new(RwLock::new(element.clone()));
     let shallow_clone = element_node.read().clone_node(false);
     assert_eq!(shallow_clone.read().node_name(), "DIV");
     assert_eq!(shallow_clone.read().child_nodes().len(), 0); // No children in shallow clone
@@ -255,7 +255,7 @@ fn test_event_init_event() {
 /// Test prevent_default
 #[test]
 fn test_event_prevent_default() {
-    let mut event = DomEvent::new("click", DomEventInit {
+    let event = DomEvent::new("click", DomEventInit {
         bubbles: true,
         cancelable: true,
         composed: false,
@@ -298,15 +298,15 @@ fn test_html_collection_by_tag_name() {
     let p = Arc::new(RwLock::new(Element::new("p")));
 
     // Add children as NodeRefs
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(span1.read().clone()) as Box<dyn Node>))
-    ).unwrap();
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(span2.read().clone()) as Box<dyn Node>))
-    ).unwrap();
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(p.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(span1.read().clone())))
+        .unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(span2.read().clone())))
+        .unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(p.read().clone())))
+        .unwrap();
 
     // Create collection for spans
     let collection = HTMLCollection::by_tag_name(root.clone(), "span".to_string());
@@ -325,9 +325,9 @@ fn test_html_collection_by_class_name() {
     let mut span = Element::new("span");
     span.set_attribute("class", "highlight active").unwrap();
 
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(span) as Box<dyn Node>))
-    ).unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(span)))
+        .unwrap();
 
     // Create collection for class
     let collection = HTMLCollection::by_class_name(root.clone(), "highlight".to_string());
@@ -356,7 +356,7 @@ fn test_range_creation() {
 #[test]
 fn test_range_boundary_points() {
     let doc = Document::new();
-    let text_node: NodeRef = Arc::new(RwLock::new(Box::new(Text::new("Hello World")) as Box<dyn Node>));
+    let text_node: NodeRef = Arc::new(RwLock::new(Text::new("Hello World")));
 
     let mut range = Range::new(&doc);
 
@@ -375,7 +375,7 @@ fn test_range_boundary_points() {
 #[test]
 fn test_range_collapse() {
     let doc = Document::new();
-    let text_node: NodeRef = Arc::new(RwLock::new(Box::new(Text::new("Hello World")) as Box<dyn Node>));
+    let text_node: NodeRef = Arc::new(RwLock::new(Text::new("Hello World")));
 
     let mut range = Range::new(&doc);
     range.set_start(text_node.clone(), 0).unwrap();
@@ -410,8 +410,8 @@ fn test_document_create_range() {
 /// Test static NodeList creation
 #[test]
 fn test_static_node_list() {
-    let text1: NodeRef = Arc::new(RwLock::new(Box::new(Text::new("Hello")) as Box<dyn Node>));
-    let text2: NodeRef = Arc::new(RwLock::new(Box::new(Text::new("World")) as Box<dyn Node>));
+    let text1: NodeRef = Arc::new(RwLock::new(Text::new("Hello")));
+    let text2: NodeRef = Arc::new(RwLock::new(Text::new("World")));
 
     let node_list = NodeList::new_static(vec![text1, text2]);
 
@@ -518,7 +518,7 @@ fn test_document_import_node() {
     elem.write().set_attribute("id", "imported").unwrap();
 
     // Convert to NodeRef
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
 
     // Import into doc2 (deep clone)
     let imported = doc2.import_node(elem_node, true).unwrap();
@@ -535,7 +535,7 @@ fn test_document_adopt_node() {
 
     // Create element in doc1
     let elem = doc1.create_element("div").unwrap();
-    let elem_node: NodeRef = Arc::new(RwLock::new(Box::new(elem.read().clone()) as Box<dyn Node>));
+    let elem_node: NodeRef = Arc::new(RwLock::new(elem.read().clone()));
 
     // Adopt into doc2
     let adopted = doc2.adopt_node(elem_node).unwrap();
@@ -557,14 +557,14 @@ fn test_tree_walker_traversal() {
     let child2 = doc.create_element("p").unwrap();
 
     // Build tree
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(child1.read().clone()) as Box<dyn Node>))
-    ).unwrap();
-    root.write().append_child(
-        Arc::new(RwLock::new(Box::new(child2.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(child1.read().clone())))
+        .unwrap();
+    root.write()
+        .append_child(Arc::new(RwLock::new(child2.read().clone())))
+        .unwrap();
 
-    let root_node: NodeRef = Arc::new(RwLock::new(Box::new(root.read().clone()) as Box<dyn Node>));
+    let root_node: NodeRef = Arc::new(RwLock::new(root.read().clone()));
 
     // Create TreeWalker that shows all elements
     let tree_walker = TreeWalker::new(root_node.clone(), SHOW_ELEMENT, None);
@@ -580,7 +580,7 @@ fn test_tree_walker_traversal() {
 /// Test NodeIterator traversal
 #[test]
 fn test_node_iterator() {
-    let root: NodeRef = Arc::new(RwLock::new(Box::new(Element::new("div")) as Box<dyn Node>));
+    let root: NodeRef = Arc::new(RwLock::new(Element::new("div")));
 
     let iterator = NodeIterator::new(root.clone(), SHOW_ALL, None);
 
@@ -616,25 +616,25 @@ fn test_complete_document_workflow() {
     doc.set_document_element(html.clone());
 
     // Add head and body to html
-    html.write().append_child(
-        Arc::new(RwLock::new(Box::new(head.read().clone()) as Box<dyn Node>))
-    ).unwrap();
-    html.write().append_child(
-        Arc::new(RwLock::new(Box::new(body.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    html.write()
+        .append_child(Arc::new(RwLock::new(head.read().clone())))
+        .unwrap();
+    html.write()
+        .append_child(Arc::new(RwLock::new(body.read().clone())))
+        .unwrap();
 
     // Add title to head
-    head.write().append_child(
-        Arc::new(RwLock::new(Box::new(title.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    head.write()
+        .append_child(Arc::new(RwLock::new(title.read().clone())))
+        .unwrap();
 
     // Add text to title
     title.write().append_child(title_text).unwrap();
 
     // Add div to body
-    body.write().append_child(
-        Arc::new(RwLock::new(Box::new(div.read().clone()) as Box<dyn Node>))
-    ).unwrap();
+    body.write()
+        .append_child(Arc::new(RwLock::new(div.read().clone())))
+        .unwrap();
 
     // Add text to div
     div.write().append_child(content_text).unwrap();
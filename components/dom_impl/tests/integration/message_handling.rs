@@ -427,6 +427,7 @@ fn test_config_is_accessible() {
         enable_shadow_dom: true,
         gc_threshold: 50000,
         arena_capacity: 25000,
+        ..DomConfig::default()
     };
 
     let component = DomComponent::new(config.clone());
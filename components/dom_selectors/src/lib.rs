@@ -55,6 +55,7 @@
 //! | Contains | `[class*="btn"]` | Substring match |
 //! | Starts | `[href^="https"]` | Prefix match |
 //! | Ends | `[src$=".png"]` | Suffix match |
+//! | Namespaced | `[xlink\|href]`, `[*\|href]` | Attribute in a namespace |
 //!
 //! ## Combinators
 //!
@@ -92,4 +93,5 @@ pub mod matcher;
 pub mod query;
 
 // Re-exports
+pub use matcher::{Combinator, MatchExplanation, MatchStep, SelectorMatcher};
 pub use query::{Selectable, SelectorQuery};
@@ -1,7 +1,16 @@
 //! CSS selector matching logic
 
-use dom_core::{ElementRef, Node, NodeRef};
+use dom_core::{ElementRef, Node, NodeRef, XLINK_NAMESPACE, XMLNS_NAMESPACE, XML_NAMESPACE};
 use dom_types::{DomException, NodeType};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Namespace prefixes usable in attribute selectors without declaring them
+const DEFAULT_NAMESPACES: &[(&str, &str)] = &[
+    ("xlink", XLINK_NAMESPACE),
+    ("xml", XML_NAMESPACE),
+    ("xmlns", XMLNS_NAMESPACE),
+];
 
 /// Parsed selector matcher
 pub struct SelectorMatcher {
@@ -20,7 +29,7 @@ struct SelectorSegment {
 
 /// CSS combinators
 #[derive(Debug, Clone, PartialEq)]
-enum Combinator {
+pub enum Combinator {
     /// Descendant combinator (" ")
     Descendant,
     /// Child combinator (">")
@@ -39,15 +48,161 @@ enum SelectorComponent {
     /// Universal selector ("*")
     Universal,
     /// Attribute exists (e.g., "[disabled]")
-    AttributeExists(String),
+    AttributeExists(AttributeName),
     /// Attribute equals (e.g., "[type='text']")
-    AttributeEquals(String, String),
+    AttributeEquals(AttributeName, String),
+}
+
+/// Attribute name of an attribute selector, e.g. `xlink|href`
+#[derive(Debug, Clone, PartialEq)]
+struct AttributeName {
+    namespace: AttributeNamespace,
+    local_name: String,
+}
+
+/// Namespace part of an attribute selector
+#[derive(Debug, Clone, PartialEq)]
+enum AttributeNamespace {
+    /// No prefix or `|name`: only attributes without a namespace
+    Null,
+    /// `*|name`: attributes in any namespace or none
+    Any,
+    /// `prefix|name`: attributes in the namespace bound to `prefix`
+    Prefixed { prefix: String, uri: String },
+}
+
+impl AttributeName {
+    /// Parse `name`, `|name`, `*|name` or `prefix|name`
+    fn parse(name: &str, namespaces: &HashMap<String, String>) -> Result<Self, DomException> {
+        let (namespace, local_name) = match name.split_once('|') {
+            None => (AttributeNamespace::Null, name),
+            Some(("", local_name)) => (AttributeNamespace::Null, local_name),
+            Some(("*", local_name)) => (AttributeNamespace::Any, local_name),
+            Some((prefix, local_name)) => {
+                let uri = namespaces.get(prefix).ok_or_else(|| {
+                    DomException::syntax_error(format!("Undeclared namespace prefix '{}'", prefix))
+                })?;
+                let namespace = AttributeNamespace::Prefixed {
+                    prefix: prefix.to_string(),
+                    uri: uri.clone(),
+                };
+                (namespace, local_name)
+            }
+        };
+        if local_name.is_empty() {
+            return Err(DomException::syntax_error("Empty attribute name"));
+        }
+        Ok(Self {
+            namespace,
+            local_name: local_name.to_string(),
+        })
+    }
+
+    /// Value of the first attribute of `element` with this name
+    fn value<'a>(&'a self, element: &'a dom_core::Element) -> Option<&'a str> {
+        element
+            .attributes_by_local_name(&self.local_name)
+            .find(|(namespace, _)| match &self.namespace {
+                AttributeNamespace::Null => namespace.is_none(),
+                AttributeNamespace::Any => true,
+                AttributeNamespace::Prefixed { uri, .. } => *namespace == Some(uri.as_str()),
+            })
+            .map(|(_, value)| value)
+    }
+}
+
+impl fmt::Display for AttributeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.namespace {
+            AttributeNamespace::Null => {}
+            AttributeNamespace::Any => write!(f, "*|")?,
+            AttributeNamespace::Prefixed { prefix, .. } => write!(f, "{}|", prefix)?,
+        }
+        write!(f, "{}", self.local_name)
+    }
+}
+
+impl fmt::Display for SelectorComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorComponent::Tag(tag) => write!(f, "{}", tag),
+            SelectorComponent::Class(class) => write!(f, ".{}", class),
+            SelectorComponent::Id(id) => write!(f, "#{}", id),
+            SelectorComponent::Universal => write!(f, "*"),
+            SelectorComponent::AttributeExists(name) => write!(f, "[{}]", name),
+            SelectorComponent::AttributeEquals(name, value) => {
+                write!(f, "[{}=\"{}\"]", name, value)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SelectorSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for component in &self.components {
+            write!(f, "{}", component)?;
+        }
+        Ok(())
+    }
+}
+
+/// Step-by-step account of matching a selector against an element
+///
+/// Steps run right to left, from the subject compound towards the leftmost
+/// one, and stop at the first compound that failed.
+#[derive(Debug, Clone)]
+pub struct MatchExplanation {
+    /// Whether the whole selector matched
+    pub matched: bool,
+    /// One step per compound selector that was tested
+    pub steps: Vec<MatchStep>,
+}
+
+impl MatchExplanation {
+    /// The step that made the match fail, if any
+    pub fn failed_step(&self) -> Option<&MatchStep> {
+        self.steps.iter().find(|step| !step.matched)
+    }
+}
+
+/// Outcome of testing one compound selector
+#[derive(Debug, Clone)]
+pub struct MatchStep {
+    /// Source text of the compound selector, e.g. `div.a`
+    pub compound: String,
+    /// Combinator linking this compound to the previous step (`None` for the subject)
+    pub combinator: Option<Combinator>,
+    /// Element the compound was tested against, as `tag#id.class`
+    ///
+    /// `None` when the combinator had no candidate, e.g. the element had no
+    /// parent or no ancestor matched.
+    pub element: Option<String>,
+    /// Whether the compound matched
+    pub matched: bool,
 }
 
 impl SelectorMatcher {
     /// Create a new selector matcher by parsing the selector string
+    ///
+    /// Attribute selectors may use the `xlink`, `xml` and `xmlns` namespace
+    /// prefixes, e.g. `[xlink|href]`.
     pub fn new(selector: &str) -> Result<Self, DomException> {
-        let segments = Self::parse_selector(selector)?;
+        let namespaces = DEFAULT_NAMESPACES
+            .iter()
+            .map(|(prefix, uri)| (prefix.to_string(), uri.to_string()))
+            .collect();
+        Self::with_namespaces(selector, &namespaces)
+    }
+
+    /// Create a selector matcher resolving namespace prefixes through `namespaces`
+    ///
+    /// `namespaces` maps each prefix usable in attribute selectors to its
+    /// namespace URI. An undeclared prefix is a `SyntaxError`.
+    pub fn with_namespaces(
+        selector: &str,
+        namespaces: &HashMap<String, String>,
+    ) -> Result<Self, DomException> {
+        let segments = Self::parse_selector(selector, namespaces)?;
 
         Ok(Self { segments })
     }
@@ -64,6 +219,98 @@ impl SelectorMatcher {
         self.matches_with_segments(element, &self.segments)
     }
 
+    /// Explain why `element` does or doesn't match this selector
+    ///
+    /// Descendant combinators report the nearest ancestor through which the
+    /// rest of the selector matches, falling back to the nearest ancestor that
+    /// matches the compound alone.
+    pub fn explain(&self, element: &ElementRef) -> MatchExplanation {
+        let last = self.segments.len() - 1;
+        let (subject, matched, parent) = {
+            let elem = element.read();
+            (
+                element_label(&elem),
+                Self::matches_segment_raw(&elem, &self.segments[last]),
+                elem.parent_node(),
+            )
+        };
+
+        let mut steps = vec![MatchStep {
+            compound: self.segments[last].to_string(),
+            combinator: None,
+            element: Some(subject),
+            matched,
+        }];
+        if matched {
+            self.explain_ancestors(parent, last, &mut steps);
+        }
+
+        MatchExplanation {
+            matched: steps.iter().all(|step| step.matched),
+            steps,
+        }
+    }
+
+    /// Explain the compounds left of `matched_idx`, starting from `parent`
+    fn explain_ancestors(
+        &self,
+        mut parent: Option<NodeRef>,
+        matched_idx: usize,
+        steps: &mut Vec<MatchStep>,
+    ) {
+        for idx in (0..matched_idx).rev() {
+            let segment = &self.segments[idx];
+            let remaining = &self.segments[..=idx];
+            let combinator = segment.combinator.clone().unwrap_or(Combinator::Descendant);
+
+            let candidate = match combinator {
+                Combinator::Child => parent.clone(),
+                Combinator::Descendant => {
+                    let ancestors: Vec<NodeRef> =
+                        std::iter::successors(parent.clone(), |node| node.read().parent_node())
+                            .collect();
+                    ancestors
+                        .iter()
+                        .find(|ancestor| {
+                            Self::node_matches_segments(ancestor, remaining, self).unwrap_or(false)
+                        })
+                        .or_else(|| {
+                            ancestors.iter().find(|ancestor| {
+                                Self::node_matches_segments(
+                                    ancestor,
+                                    std::slice::from_ref(segment),
+                                    self,
+                                )
+                                .unwrap_or(false)
+                            })
+                        })
+                        .cloned()
+                }
+            };
+
+            let tested = candidate.as_ref().and_then(|node| {
+                let guard = node.read();
+                let elem = guard.as_any().downcast_ref::<dom_core::Element>()?;
+                Some((
+                    element_label(elem),
+                    Self::matches_segment_raw(elem, segment),
+                ))
+            });
+
+            let matched = tested.as_ref().is_some_and(|(_, matched)| *matched);
+            steps.push(MatchStep {
+                compound: segment.to_string(),
+                combinator: Some(combinator),
+                element: tested.map(|(label, _)| label),
+                matched,
+            });
+            if !matched {
+                return;
+            }
+            parent = candidate.and_then(|node| node.read().parent_node());
+        }
+    }
+
     /// Match an element against segments (handles combinators)
     fn matches_with_segments(
         &self,
@@ -181,12 +428,12 @@ impl SelectorMatcher {
                     // Universal selector matches everything
                 }
                 SelectorComponent::AttributeExists(name) => {
-                    if element.get_attribute(name).is_none() {
+                    if name.value(element).is_none() {
                         return false;
                     }
                 }
                 SelectorComponent::AttributeEquals(name, value) => {
-                    if element.get_attribute(name).as_deref() != Some(value) {
+                    if name.value(element) != Some(value.as_str()) {
                         return false;
                     }
                 }
@@ -243,12 +490,12 @@ impl SelectorMatcher {
                     // Universal selector matches everything
                 }
                 SelectorComponent::AttributeExists(name) => {
-                    if elem.get_attribute(name).is_none() {
+                    if name.value(&elem).is_none() {
                         return false;
                     }
                 }
                 SelectorComponent::AttributeEquals(name, value) => {
-                    if elem.get_attribute(name).as_deref() != Some(value) {
+                    if name.value(&elem) != Some(value.as_str()) {
                         return false;
                     }
                 }
@@ -271,7 +518,10 @@ impl SelectorMatcher {
     }
 
     /// Parse a selector string into segments
-    fn parse_selector(selector: &str) -> Result<Vec<SelectorSegment>, DomException> {
+    fn parse_selector(
+        selector: &str,
+        namespaces: &HashMap<String, String>,
+    ) -> Result<Vec<SelectorSegment>, DomException> {
         let selector = selector.trim();
 
         if selector.is_empty() {
@@ -362,7 +612,7 @@ impl SelectorMatcher {
                         attr_selector.push(ch);
                     }
 
-                    Self::parse_attribute(&attr_selector, &mut current_components)?;
+                    Self::parse_attribute(&attr_selector, namespaces, &mut current_components)?;
                 }
 
                 // Combinator: child (>)
@@ -470,6 +720,7 @@ impl SelectorMatcher {
     /// Parse an attribute selector
     fn parse_attribute(
         attr: &str,
+        namespaces: &HashMap<String, String>,
         components: &mut Vec<SelectorComponent>,
     ) -> Result<(), DomException> {
         let attr = attr.trim();
@@ -491,22 +742,37 @@ impl SelectorMatcher {
             }
 
             components.push(SelectorComponent::AttributeEquals(
-                name.to_string(),
+                AttributeName::parse(name, namespaces)?,
                 value.to_string(),
             ));
         } else {
             // Just attribute existence
-            components.push(SelectorComponent::AttributeExists(attr.to_string()));
+            let name = AttributeName::parse(attr, namespaces)?;
+            components.push(SelectorComponent::AttributeExists(name));
         }
 
         Ok(())
     }
 }
 
+/// Short label for an element, e.g. `div#main.container`
+fn element_label(element: &dom_core::Element) -> String {
+    let mut label = element.tag_name().to_ascii_lowercase();
+    if let Some(id) = element.id() {
+        label.push('#');
+        label.push_str(id);
+    }
+    for class in element.class_list() {
+        label.push('.');
+        label.push_str(class);
+    }
+    label
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::Element;
+    use dom_core::{new_node_ref, Element};
     use parking_lot::RwLock;
     use std::sync::Arc;
 
@@ -601,4 +867,112 @@ mod tests {
         assert_eq!(matcher.segments.len(), 2);
         assert_eq!(matcher.segments[0].combinator, Some(Combinator::Child));
     }
+
+    fn matches(selector: &str, element: &Element) -> bool {
+        let element = Arc::new(RwLock::new(element.clone()));
+        SelectorMatcher::new(selector)
+            .unwrap()
+            .matches(&element)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_match_prefixed_attribute() {
+        let mut link = Element::new("a");
+        link.set_attribute_ns(Some(XLINK_NAMESPACE), "xlink:href", "#top")
+            .unwrap();
+
+        assert!(matches("[xlink|href]", &link));
+        assert!(matches("a[xlink|href='#top']", &link));
+        assert!(!matches("[xlink|href='#end']", &link));
+        assert!(!matches("[xml|href]", &link));
+        assert!(SelectorMatcher::new("[svg|href]").is_err());
+
+        let namespaces = HashMap::from([("l".to_string(), XLINK_NAMESPACE.to_string())]);
+        let matcher = SelectorMatcher::with_namespaces("[l|href]", &namespaces).unwrap();
+        assert!(matcher.matches(&Arc::new(RwLock::new(link))).unwrap());
+    }
+
+    #[test]
+    fn test_match_attribute_in_any_namespace() {
+        let mut linked = Element::new("use");
+        linked
+            .set_attribute_ns(Some(XLINK_NAMESPACE), "xlink:href", "#icon")
+            .unwrap();
+        let mut plain = Element::new("a");
+        plain.set_attribute("href", "/home").unwrap();
+
+        assert!(matches("[*|href]", &linked));
+        assert!(matches("[*|href='#icon']", &linked));
+        assert!(matches("[*|href]", &plain));
+        assert!(!matches("[*|src]", &plain));
+    }
+
+    #[test]
+    fn test_unprefixed_attribute_matches_null_namespace_only() {
+        let mut linked = Element::new("use");
+        linked
+            .set_attribute_ns(Some(XLINK_NAMESPACE), "xlink:href", "#icon")
+            .unwrap();
+        let mut plain = Element::new("a");
+        plain.set_attribute("href", "/home").unwrap();
+
+        assert!(!matches("[href]", &linked));
+        assert!(!matches("[|href]", &linked));
+        assert!(matches("[href]", &plain));
+        assert!(matches("[|href='/home']", &plain));
+    }
+
+    fn element_node(tag: &str) -> NodeRef {
+        new_node_ref(Element::new(tag))
+    }
+
+    /// Builds `section > {parent_tag} > span.a` and returns the span
+    fn span_in(parent_tag: &str) -> (NodeRef, ElementRef) {
+        let section = element_node("section");
+        let parent = element_node(parent_tag);
+        section.write().append_child(parent.clone()).unwrap();
+
+        let mut span = Element::new("span");
+        span.set_attribute("class", "a").unwrap();
+        span.node_data_mut()
+            .set_parent(Some(Arc::downgrade(&parent)));
+        (section, Arc::new(RwLock::new(span)))
+    }
+
+    #[test]
+    fn test_explain_matching_child_selector() {
+        let (_section, span) = span_in("div");
+        let explanation = SelectorMatcher::new("div > .a").unwrap().explain(&span);
+
+        assert!(explanation.matched);
+        assert!(explanation.failed_step().is_none());
+        assert_eq!(explanation.steps.len(), 2);
+        assert_eq!(explanation.steps[0].compound, ".a");
+        assert_eq!(explanation.steps[0].element.as_deref(), Some("span.a"));
+        assert_eq!(explanation.steps[1].compound, "div");
+        assert_eq!(explanation.steps[1].combinator, Some(Combinator::Child));
+        assert_eq!(explanation.steps[1].element.as_deref(), Some("div"));
+    }
+
+    #[test]
+    fn test_explain_reports_failed_combinator() {
+        let (_section, span) = span_in("p");
+        let explanation = SelectorMatcher::new("div > .a").unwrap().explain(&span);
+
+        assert!(!explanation.matched);
+        let failed = explanation.failed_step().unwrap();
+        assert_eq!(failed.compound, "div");
+        assert_eq!(failed.combinator, Some(Combinator::Child));
+        assert_eq!(failed.element.as_deref(), Some("p"));
+
+        let explanation = SelectorMatcher::new("article .a").unwrap().explain(&span);
+        let failed = explanation.failed_step().unwrap();
+        assert_eq!(failed.combinator, Some(Combinator::Descendant));
+        assert_eq!(failed.element, None);
+
+        let explanation = SelectorMatcher::new("section .a").unwrap().explain(&span);
+        assert!(explanation.matched);
+        assert_eq!(explanation.steps[1].element.as_deref(), Some("section"));
+    }
 }
@@ -1,8 +1,12 @@
 //! Selectable trait and query methods
 
 use crate::matcher::SelectorMatcher;
+#[cfg(feature = "shadow-dom")]
+use dom_advanced::shadow::assigned_slot;
+#[cfg(feature = "shadow-dom")]
+use dom_advanced::ShadowRoot;
 use dom_collections::NodeList;
-use dom_core::{Element, ElementRef, Node, NodeRef};
+use dom_core::{downcast_element, DocumentFragment, Element, ElementRef, Node, NodeRef};
 use dom_types::{DomException, NodeType};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -20,6 +24,15 @@ pub trait Selectable {
 
     /// Find the closest ancestor (including self) matching the selector
     fn closest(&self, selector: &str) -> Result<Option<ElementRef>, DomException>;
+
+    /// Like [`closest`](Selectable::closest), but walks the flattened tree
+    ///
+    /// Slotted nodes continue through their assigned slot and, on reaching
+    /// the top of the shadow tree, through the shadow host. Without the
+    /// `shadow-dom` feature there are no shadow trees, so this is `closest`.
+    fn closest_composed(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        self.closest(selector)
+    }
 }
 
 /// Helper struct for executing selector queries
@@ -52,18 +65,14 @@ impl SelectorQuery {
         node: &NodeRef,
         matcher: &SelectorMatcher,
     ) -> Result<Option<ElementRef>, DomException> {
-        let node_guard = node.read();
-        let node_type = node_guard.node_type();
-
         // Check if current node is element and matches (using full element matching)
-        if node_type == NodeType::Element && Self::matches_node(node, matcher) {
-            // Downcast to Element using as_any() for full selector support
-            drop(node_guard);
+        if Self::matches_node(node, matcher) {
             return Ok(Self::try_as_element(node));
         }
 
         // Search children
-        for child in node_guard.child_nodes() {
+        let children = node.read().child_nodes();
+        for child in children {
             if let Ok(Some(found)) = Self::find_first_recursive(&child, matcher) {
                 return Ok(Some(found));
             }
@@ -78,60 +87,63 @@ impl SelectorQuery {
         matcher: &SelectorMatcher,
         results: &mut Vec<ElementRef>,
     ) {
-        let node_guard = node.read();
-        let node_type = node_guard.node_type();
-
         // Check if current node is element and matches (using full element matching)
-        if node_type == NodeType::Element && Self::matches_node(node, matcher) {
-            // Downcast to Element using as_any() for full selector support
-            if let Some(element_ref) = Self::try_as_element(node) {
-                results.push(element_ref);
-            }
+        if Self::matches_node(node, matcher) {
+            results.extend(Self::try_as_element(node));
         }
 
         // Search all children
-        for child in node_guard.child_nodes() {
+        let children = node.read().child_nodes();
+        for child in children {
             Self::find_all_recursive(&child, matcher, results);
         }
     }
 
-    /// Try to convert NodeRef to ElementRef by downcasting using as_any()
-    fn try_as_element(node: &NodeRef) -> Option<ElementRef> {
-        let node_guard = node.read();
+    /// Find the first match among `roots` and their descendants
+    fn find_first_in(
+        roots: &[NodeRef],
+        matcher: &SelectorMatcher,
+    ) -> Result<Option<ElementRef>, DomException> {
+        for root in roots {
+            if let Ok(Some(found)) = Self::find_first_recursive(root, matcher) {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
 
-        // Use as_any() to downcast to Element
-        if let Some(element) = node_guard.as_any().downcast_ref::<Element>() {
-            // Clone the element to create an ElementRef
-            let element_clone = element.clone();
-            drop(node_guard);
-            return Some(Arc::new(RwLock::new(element_clone)));
+    /// Collect every match among `roots` and their descendants
+    fn find_all_in(roots: &[NodeRef], matcher: &SelectorMatcher) -> NodeList {
+        let mut results = Vec::new();
+        for root in roots {
+            Self::find_all_recursive(root, matcher, &mut results);
         }
 
-        None
+        // Convert ElementRef vec to NodeRef vec
+        let node_refs: Vec<NodeRef> = results
+            .into_iter()
+            .map(|elem_ref| elem_ref as NodeRef)
+            .collect();
+
+        NodeList::new_static(node_refs)
+    }
+
+    /// Get the tree's own ElementRef for `node`, if it is an element
+    fn try_as_element(node: &NodeRef) -> Option<ElementRef> {
+        downcast_element(node)
     }
 
     /// Match a node using full element matching (classes, IDs, attributes)
     fn matches_node(node: &NodeRef, matcher: &SelectorMatcher) -> bool {
-        let node_guard = node.read();
-        if node_guard.node_type() != NodeType::Element {
-            return false;
+        // Use full matcher (supports classes, IDs, attributes)
+        if let Some(element_ref) = Self::try_as_element(node) {
+            return matcher.matches(&element_ref).unwrap_or(false);
         }
 
-        // Use as_any() to downcast to Element for full matching
-        if let Some(element) = node_guard.as_any().downcast_ref::<Element>() {
-            let element_clone = element.clone();
-            drop(node_guard);
-
-            // Create an ElementRef for matching
-            let element_ref = Arc::new(RwLock::new(element_clone));
-
-            // Use full matcher (supports classes, IDs, attributes)
-            matcher.matches(&element_ref).unwrap_or(false)
-        } else {
-            // Fallback to simple tag matching if downcast fails
-            let tag = node_guard.node_name();
-            matcher.matches_tag_only(tag)
-        }
+        // Fallback to simple tag matching if downcast fails
+        let node_guard = node.read();
+        node_guard.node_type() == NodeType::Element
+            && matcher.matches_tag_only(node_guard.node_name())
     }
 }
 
@@ -139,37 +151,12 @@ impl SelectorQuery {
 impl Selectable for Element {
     fn query_selector(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
         let matcher = SelectorMatcher::new(selector)?;
-
-        // Search descendants
-        for child in self.child_nodes() {
-            if let Ok(Some(found)) = SelectorQuery::find_first_recursive(&child, &matcher) {
-                return Ok(Some(found));
-            }
-        }
-
-        Ok(None)
+        SelectorQuery::find_first_in(&self.child_nodes(), &matcher)
     }
 
     fn query_selector_all(&self, selector: &str) -> Result<NodeList, DomException> {
         let matcher = SelectorMatcher::new(selector)?;
-        let mut results = Vec::new();
-
-        // Search all descendants
-        for child in self.child_nodes() {
-            SelectorQuery::find_all_recursive(&child, &matcher, &mut results);
-        }
-
-        // Convert ElementRef vec to NodeRef vec
-        let node_refs: Vec<NodeRef> = results
-            .into_iter()
-            .map(|elem_ref| {
-                Arc::new(RwLock::new(
-                    Box::new(elem_ref.read().clone()) as Box<dyn Node>
-                ))
-            })
-            .collect();
-
-        Ok(NodeList::new_static(node_refs))
+        Ok(SelectorQuery::find_all_in(&self.child_nodes(), &matcher))
     }
 
     fn matches(&self, selector: &str) -> Result<bool, DomException> {
@@ -203,12 +190,106 @@ impl Selectable for Element {
 
         Ok(None)
     }
+
+    #[cfg(feature = "shadow-dom")]
+    fn closest_composed(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        let elem_ref = Arc::new(RwLock::new(self.clone()));
+
+        if matcher.matches(&elem_ref)? {
+            return Ok(Some(elem_ref));
+        }
+
+        // `node` is used to look up slot assignments, `parent` continues the
+        // current tree, and `hosts` holds the hosts of the shadow trees entered
+        let mut node = self.node_data().get_self_node_ref();
+        let mut parent = self.parent_node();
+        let mut hosts: Vec<ElementRef> = Vec::new();
+
+        loop {
+            let candidate = if let Some(slot) = node.as_ref().and_then(assigned_slot) {
+                hosts.extend(slot.host());
+                let slot_element = slot.element();
+                node = slot_element.read().node_data().get_self_node_ref();
+                parent = slot_element.read().parent_node();
+                Some(slot_element)
+            } else if let Some(current) = parent.take() {
+                parent = current.read().parent_node();
+                let candidate = SelectorQuery::try_as_element(&current);
+                node = Some(current);
+                candidate
+            } else if let Some(host) = hosts.pop() {
+                node = host.read().node_data().get_self_node_ref();
+                parent = host.read().parent_node();
+                Some(host)
+            } else {
+                return Ok(None);
+            };
+
+            if let Some(element) = candidate {
+                if matcher.matches(&element)? {
+                    return Ok(Some(element));
+                }
+            }
+        }
+    }
+}
+
+// Queries on a shadow root only see the shadow tree, not the host's light DOM
+#[cfg(feature = "shadow-dom")]
+impl Selectable for ShadowRoot {
+    fn query_selector(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        SelectorQuery::find_first_in(&self.children(), &matcher)
+    }
+
+    fn query_selector_all(&self, selector: &str) -> Result<NodeList, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        Ok(SelectorQuery::find_all_in(&self.children(), &matcher))
+    }
+
+    /// A shadow root is not an element, so it never matches
+    fn matches(&self, selector: &str) -> Result<bool, DomException> {
+        SelectorMatcher::new(selector)?;
+        Ok(false)
+    }
+
+    /// A shadow root has no ancestors within its own tree
+    fn closest(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        SelectorMatcher::new(selector)?;
+        Ok(None)
+    }
+}
+
+// Detached subtrees can be queried before they are inserted anywhere
+impl Selectable for DocumentFragment {
+    fn query_selector(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        SelectorQuery::find_first_in(self.children(), &matcher)
+    }
+
+    fn query_selector_all(&self, selector: &str) -> Result<NodeList, DomException> {
+        let matcher = SelectorMatcher::new(selector)?;
+        Ok(SelectorQuery::find_all_in(self.children(), &matcher))
+    }
+
+    /// A fragment is not an element, so it never matches
+    fn matches(&self, selector: &str) -> Result<bool, DomException> {
+        SelectorMatcher::new(selector)?;
+        Ok(false)
+    }
+
+    /// A fragment has no parent, so there is nothing to walk up to
+    fn closest(&self, selector: &str) -> Result<Option<ElementRef>, DomException> {
+        SelectorMatcher::new(selector)?;
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dom_core::Document;
+    use dom_core::{new_node_ref, Document};
 
     #[test]
     fn test_selector_query_basic() {
@@ -226,4 +307,125 @@ mod tests {
         let result = elem.matches("button");
         assert!(result.is_ok());
     }
+
+    #[cfg(feature = "shadow-dom")]
+    #[test]
+    fn test_shadow_root_queries_are_scoped() {
+        use dom_advanced::shadow::SlotAssignmentMode;
+        use dom_advanced::ShadowRootMode;
+
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        doc.set_document_element(host.clone());
+        let shadow = ShadowRoot::new(
+            host.clone(),
+            ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        );
+
+        // Light child of the host, outside the shadow tree
+        let light = doc.create_element("span").unwrap();
+        light.write().set_attribute("id", "light").unwrap();
+        host.write().append_child(light.clone()).unwrap();
+
+        let panel = doc.create_element("section").unwrap();
+        panel.write().set_attribute("id", "panel").unwrap();
+        let mut item = Element::new("span");
+        item.set_attribute("class", "item").unwrap();
+        panel.write().append_child(new_node_ref(item)).unwrap();
+        shadow.append_child(panel.clone()).unwrap();
+
+        let found = shadow.query_selector("#panel").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&found, &panel));
+        assert!(shadow.query_selector("#light").unwrap().is_none());
+        assert_eq!(shadow.query_selector_all("span").unwrap().length(), 1);
+        assert_eq!(shadow.query_selector_all(".item").unwrap().length(), 1);
+        assert!(shadow.query_selector("div").unwrap().is_none());
+        assert!(!shadow.matches("div").unwrap());
+        assert!(shadow.query_selector("[").is_err());
+
+        // Ids are scoped to their tree: each side only finds its own
+        let found = shadow.get_element_by_id("panel").unwrap();
+        assert!(Arc::ptr_eq(&found, &panel));
+        assert!(shadow.get_element_by_id("light").is_none());
+        let found = doc.get_element_by_id("light").unwrap();
+        assert!(Arc::ptr_eq(&found, &light));
+        assert!(doc.get_element_by_id("panel").is_none());
+        assert!(host.read().query_selector("#panel").unwrap().is_none());
+    }
+
+    #[cfg(feature = "shadow-dom")]
+    #[test]
+    fn test_closest_composed_reaches_shadow_host() {
+        use dom_advanced::shadow::{SlotAssignmentMode, SlotElement};
+        use dom_advanced::{ShadowRoot, ShadowRootMode};
+
+        let mut doc = Document::new();
+        let host = doc.create_element("div").unwrap();
+        host.write().set_attribute("class", "card").unwrap();
+
+        let shadow = ShadowRoot::new(
+            host.clone(),
+            ShadowRootMode::Open,
+            false,
+            SlotAssignmentMode::Named,
+        );
+        let slot = SlotElement::new(doc.create_element("slot").unwrap());
+        shadow.add_slot(&slot);
+
+        // Light child of the host, slotted into the shadow tree
+        let child = new_node_ref(Element::new("span"));
+        host.write().append_child(child.clone()).unwrap();
+        slot.distribute(&host.read().child_nodes());
+
+        let guard = child.read();
+        let span = guard.as_any().downcast_ref::<Element>().unwrap();
+
+        // The light tree goes straight to the host, past the slot
+        let found = span.closest(".card").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&found, &host));
+        assert!(span.closest("slot").unwrap().is_none());
+        let found = span.closest_composed(".card").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&found, &host));
+
+        // The slot itself is part of the flattened ancestor chain
+        let found = span.closest_composed("slot").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&found, &slot.element()));
+
+        assert!(span.closest_composed(".missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fragment_queries_cloned_subtree() {
+        fn element(tag: &str, class: &str) -> NodeRef {
+            let mut element = Element::new(tag);
+            element.set_attribute("class", class).unwrap();
+            new_node_ref(element)
+        }
+
+        // <section class=outer><div class=a><div class=b/></div></section><div class=c/>
+        let section = element("section", "outer");
+        let outer_div = element("div", "a");
+        outer_div.write().append_child(element("div", "b")).unwrap();
+        section.write().append_child(outer_div).unwrap();
+        let mut fragment = DocumentFragment::new();
+        fragment.append_child(section).unwrap();
+        fragment.append_child(element("div", "c")).unwrap();
+
+        let clone = fragment.clone_node(true);
+        let guard = clone.read();
+        let clone = guard.as_any().downcast_ref::<DocumentFragment>().unwrap();
+
+        let first = clone.query_selector("div").unwrap().unwrap();
+        assert_eq!(first.read().get_attribute("class"), Some("a"));
+        assert_eq!(clone.query_selector_all("div").unwrap().length(), 3);
+        assert_eq!(clone.query_selector_all(".outer div").unwrap().length(), 2);
+        assert!(clone.query_selector("p").unwrap().is_none());
+
+        // The fragment itself is not an element
+        assert!(!clone.matches("*").unwrap());
+        assert!(clone.closest("*").unwrap().is_none());
+        assert!(clone.query_selector("[").is_err());
+    }
 }
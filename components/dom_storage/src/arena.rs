@@ -96,6 +96,40 @@ impl NodeArena {
         let mut nodes = self.nodes.write();
         let mut free_list = self.free_list.lock();
 
+        self.allocate_slot(&mut nodes, &mut free_list, Arc::new(node))
+    }
+
+    /// Allocates several nodes while taking the arena's locks only once.
+    ///
+    /// Slots are reused and generations assigned exactly as by calling
+    /// [`allocate`](Self::allocate) on each value in turn, and no other
+    /// allocation can interleave with the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The node data to store
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the new nodes, in the order of `values`.
+    pub fn allocate_batch<T: Any + Send + Sync>(&self, values: Vec<T>) -> Vec<NodeId> {
+        let mut nodes = self.nodes.write();
+        let mut free_list = self.free_list.lock();
+
+        nodes.reserve(values.len().saturating_sub(free_list.len()));
+        values
+            .into_iter()
+            .map(|node| self.allocate_slot(&mut nodes, &mut free_list, Arc::new(node)))
+            .collect()
+    }
+
+    /// Stores `node` in a free or new slot, with the locks already held.
+    fn allocate_slot(
+        &self,
+        nodes: &mut Vec<Option<NodeEntry>>,
+        free_list: &mut Vec<usize>,
+        node: Arc<dyn Any + Send + Sync>,
+    ) -> NodeId {
         // Try to reuse a freed slot
         let index = if let Some(index) = free_list.pop() {
             index
@@ -109,7 +143,7 @@ impl NodeArena {
         let generation = self.generation.fetch_add(1, Ordering::SeqCst);
 
         nodes[index] = Some(NodeEntry {
-            node: Arc::new(node),
+            node,
             generation,
             strong_refs: AtomicUsize::new(1),
             weak_refs: AtomicUsize::new(0),
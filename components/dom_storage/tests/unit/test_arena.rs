@@ -253,6 +253,77 @@ fn test_concurrent_get() {
     }
 }
 
+#[test]
+fn test_allocate_batch() {
+    let arena = NodeArena::new();
+    let values: Vec<String> = (0..1000).map(|i| format!("node_{}", i)).collect();
+
+    let ids = arena.allocate_batch(values);
+
+    assert_eq!(ids.len(), 1000);
+    assert_eq!(arena.len(), 1000);
+    for (i, id) in ids.iter().enumerate() {
+        let node = arena.get(*id).unwrap();
+        let expected = format!("node_{}", i);
+        assert_eq!(node.downcast_ref::<String>().unwrap(), &expected);
+    }
+}
+
+#[test]
+fn test_allocate_batch_matches_individual_allocation() {
+    let single = NodeArena::new();
+    let batched = NodeArena::new();
+    for arena in [&single, &batched] {
+        let ids: Vec<NodeId> = (0..4).map(|i| arena.allocate(i)).collect();
+        arena.deallocate(ids[1]);
+        arena.deallocate(ids[3]);
+    }
+
+    let expected: Vec<NodeId> = (0..3).map(|i| single.allocate(i)).collect();
+    let ids = batched.allocate_batch(vec![0, 1, 2]);
+
+    // Freed slots are reused first, under new generations
+    assert_eq!(ids, expected);
+    assert_eq!(batched.get(ids[0]).unwrap().downcast_ref::<i32>(), Some(&0));
+    assert!(batched.allocate_batch(Vec::<i32>::new()).is_empty());
+}
+
+#[test]
+fn test_allocate_batch_concurrent_with_single_allocations() {
+    let arena = Arc::new(NodeArena::new());
+
+    let batch_arena = arena.clone();
+    let batcher = thread::spawn(move || {
+        let mut ids = Vec::new();
+        for round in 0..10 {
+            let values: Vec<String> = (0..100).map(|i| format!("batch_{}_{}", round, i)).collect();
+            ids.extend(batch_arena.allocate_batch(values));
+        }
+        ids
+    });
+    let single_arena = arena.clone();
+    let single = thread::spawn(move || {
+        (0..1000)
+            .map(|i| single_arena.allocate(format!("single_{}", i)))
+            .collect::<Vec<_>>()
+    });
+
+    let batch_ids = batcher.join().unwrap();
+    let single_ids = single.join().unwrap();
+
+    assert_eq!(arena.len(), 2000);
+    for (i, id) in batch_ids.iter().enumerate() {
+        let node = arena.get(*id).unwrap();
+        let expected = format!("batch_{}_{}", i / 100, i % 100);
+        assert_eq!(node.downcast_ref::<String>().unwrap(), &expected);
+    }
+    for (i, id) in single_ids.iter().enumerate() {
+        let node = arena.get(*id).unwrap();
+        let expected = format!("single_{}", i);
+        assert_eq!(node.downcast_ref::<String>().unwrap(), &expected);
+    }
+}
+
 #[test]
 fn test_node_id_equality() {
     let id1 = NodeId {
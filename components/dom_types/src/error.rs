@@ -3,6 +3,7 @@
 //! This module defines the [`DomException`] enum which represents all the error
 //! types that can occur during DOM operations, as per the DOM Level 4 specification.
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// DOM exception types as defined in the DOM Level 4 specification.
@@ -18,8 +19,13 @@ use thiserror::Error;
 /// let error = DomException::NotFoundError;
 /// println!("Error: {}", error);
 /// ```
-#[derive(Debug, Error, Clone, PartialEq)]
+#[derive(Debug, Error, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DomException {
+    /// The index or size is negative or greater than the allowed amount
+    /// (e.g., splitting a text node past its length).
+    #[error("Index size error")]
+    IndexSizeError,
+
     /// The operation would create an invalid hierarchy
     /// (e.g., inserting a node in an inappropriate location).
     #[error("Hierarchy request error")]
@@ -84,6 +90,21 @@ pub enum DomException {
     /// (e.g., cross-origin access violation).
     #[error("Security error")]
     SecurityError,
+
+    /// The operation would exceed a configured resource limit
+    /// (e.g., setting more attributes than an element may carry).
+    #[error("Quota exceeded error")]
+    QuotaExceededError,
+
+    /// The operation did not finish in the time allowed
+    /// (e.g., an incremental collection running past its deadline).
+    #[error("Timeout error")]
+    TimeoutError,
+
+    /// The object cannot be cloned
+    /// (e.g., structured cloning a node or a function).
+    #[error("Data clone error")]
+    DataCloneError,
 }
 
 impl DomException {
@@ -100,6 +121,56 @@ impl DomException {
     pub fn syntax_error(message: impl Into<String>) -> Self {
         DomException::SyntaxError(message.into())
     }
+
+    /// Gets the legacy numeric code exposed as `DOMException.code`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::DomException;
+    ///
+    /// assert_eq!(DomException::NotFoundError.code(), 8);
+    /// ```
+    pub fn code(&self) -> u16 {
+        match self {
+            DomException::IndexSizeError => 1,
+            DomException::HierarchyRequestError => 3,
+            DomException::WrongDocumentError => 4,
+            DomException::InvalidCharacterError => 5,
+            DomException::NoModificationAllowedError => 7,
+            DomException::NotFoundError => 8,
+            DomException::NotSupportedError => 9,
+            DomException::InvalidStateError => 11,
+            DomException::SyntaxError(_) => 12,
+            DomException::InvalidModificationError => 13,
+            DomException::NamespaceError => 14,
+            DomException::SecurityError => 18,
+            DomException::QuotaExceededError => 22,
+            DomException::TimeoutError => 23,
+            DomException::DataCloneError => 25,
+        }
+    }
+
+    /// Gets the spec name exposed as `DOMException.name`, e.g. `"NotFoundError"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomException::IndexSizeError => "IndexSizeError",
+            DomException::HierarchyRequestError => "HierarchyRequestError",
+            DomException::WrongDocumentError => "WrongDocumentError",
+            DomException::InvalidCharacterError => "InvalidCharacterError",
+            DomException::NoModificationAllowedError => "NoModificationAllowedError",
+            DomException::NotFoundError => "NotFoundError",
+            DomException::NotSupportedError => "NotSupportedError",
+            DomException::InvalidStateError => "InvalidStateError",
+            DomException::SyntaxError(_) => "SyntaxError",
+            DomException::InvalidModificationError => "InvalidModificationError",
+            DomException::NamespaceError => "NamespaceError",
+            DomException::SecurityError => "SecurityError",
+            DomException::QuotaExceededError => "QuotaExceededError",
+            DomException::TimeoutError => "TimeoutError",
+            DomException::DataCloneError => "DataCloneError",
+        }
+    }
 }
 
 #[cfg(test)]
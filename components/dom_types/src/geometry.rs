@@ -1,7 +1,8 @@
 //! Geometry types for DOM bounding rectangles.
 //!
 //! This module defines the [`DOMRect`] and [`DOMRectList`] types used for
-//! representing element bounding boxes and geometry information.
+//! representing element bounding boxes and geometry information, and the
+//! [`LayoutMetrics`] and [`ScrollState`] that layout maintains per element.
 
 /// A rectangle representing element geometry with x, y, width, and height.
 ///
@@ -173,6 +174,100 @@ impl DOMRect {
     pub fn contains_point(&self, x: f64, y: f64) -> bool {
         x >= self.left() && x <= self.right() && y >= self.top() && y <= self.bottom()
     }
+
+    /// Checks if this rectangle overlaps `other`.
+    ///
+    /// Edges are inclusive, so rectangles that only touch along an edge or
+    /// corner intersect, as does a zero-size rectangle lying on an edge.
+    /// Negative widths and heights are normalized first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::DOMRect;
+    ///
+    /// let a = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+    /// assert!(a.intersects(&DOMRect::new(10.0, 0.0, 10.0, 10.0)));
+    /// assert!(!a.intersects(&DOMRect::new(11.0, 0.0, 10.0, 10.0)));
+    /// ```
+    pub fn intersects(&self, other: &DOMRect) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.left() <= b.right()
+            && b.left() <= a.right()
+            && a.top() <= b.bottom()
+            && b.top() <= a.bottom()
+    }
+
+    /// Returns the overlapping region of this rectangle and `other`.
+    ///
+    /// Returns `None` when the rectangles are disjoint; rectangles that only
+    /// touch yield a zero-width or zero-height rectangle along the shared edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::DOMRect;
+    ///
+    /// let a = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+    /// let b = DOMRect::new(5.0, 5.0, 10.0, 10.0);
+    /// assert_eq!(a.intersection(&b), Some(DOMRect::new(5.0, 5.0, 5.0, 5.0)));
+    /// ```
+    pub fn intersection(&self, other: &DOMRect) -> Option<DOMRect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let (a, b) = (self.normalized(), other.normalized());
+        Some(Self::from_edges(
+            a.left().max(b.left()),
+            a.top().max(b.top()),
+            a.right().min(b.right()),
+            a.bottom().min(b.bottom()),
+        ))
+    }
+
+    /// Returns the smallest rectangle containing both this rectangle and `other`.
+    ///
+    /// A rectangle with zero area does not extend the result, so the union
+    /// with an empty rectangle is the other rectangle (normalized).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::DOMRect;
+    ///
+    /// let a = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+    /// let b = DOMRect::new(20.0, 5.0, 10.0, 10.0);
+    /// assert_eq!(a.union(&b), DOMRect::new(0.0, 0.0, 30.0, 15.0));
+    /// ```
+    pub fn union(&self, other: &DOMRect) -> DOMRect {
+        let (a, b) = (self.normalized(), other.normalized());
+        if a.is_empty() {
+            return b;
+        }
+        if b.is_empty() {
+            return a;
+        }
+        Self::from_edges(
+            a.left().min(b.left()),
+            a.top().min(b.top()),
+            a.right().max(b.right()),
+            a.bottom().max(b.bottom()),
+        )
+    }
+
+    /// Returns the same rectangle with a non-negative width and height.
+    fn normalized(&self) -> DOMRect {
+        Self::from_edges(
+            self.x.min(self.x + self.width),
+            self.y.min(self.y + self.height),
+            self.x.max(self.x + self.width),
+            self.y.max(self.y + self.height),
+        )
+    }
+
+    fn from_edges(left: f64, top: f64, right: f64, bottom: f64) -> DOMRect {
+        Self::new(left, top, right - left, bottom - top)
+    }
 }
 
 impl Default for DOMRect {
@@ -260,17 +355,20 @@ impl DOMRectList {
 
     /// Returns the rectangle at the given index, or `None` if out of bounds.
     ///
+    /// Unlike indexing with `list[index]`, this never panics, matching
+    /// `DOMRectList.item()` in the web API.
+    ///
     /// # Examples
     ///
     /// ```
     /// use dom_types::{DOMRect, DOMRectList};
     ///
     /// let list = DOMRectList::new(vec![DOMRect::new(10.0, 20.0, 30.0, 40.0)]);
-    /// assert!(list.item(0).is_some());
+    /// assert_eq!(list.item(0), Some(DOMRect::new(10.0, 20.0, 30.0, 40.0)));
     /// assert!(list.item(1).is_none());
     /// ```
-    pub fn item(&self, index: usize) -> Option<&DOMRect> {
-        self.0.get(index)
+    pub fn item(&self, index: usize) -> Option<DOMRect> {
+        self.0.get(index).copied()
     }
 
     /// Returns an iterator over the rectangles.
@@ -279,6 +377,14 @@ impl DOMRectList {
     }
 }
 
+impl std::ops::Index<usize> for DOMRectList {
+    type Output = DOMRect;
+
+    fn index(&self, index: usize) -> &DOMRect {
+        &self.0[index]
+    }
+}
+
 impl From<Vec<DOMRect>> for DOMRectList {
     fn from(rects: Vec<DOMRect>) -> Self {
         Self::new(rects)
@@ -303,6 +409,55 @@ impl<'a> IntoIterator for &'a DOMRectList {
     }
 }
 
+/// Box dimensions of an element as computed by layout.
+///
+/// All values are zero until the layout engine populates them.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct LayoutMetrics {
+    /// Viewport x coordinate of the left edge of the border box.
+    pub x: f64,
+    /// Viewport y coordinate of the top edge of the border box.
+    pub y: f64,
+    /// Width of the element's content including overflow (`scrollWidth`).
+    pub scroll_width: f64,
+    /// Height of the element's content including overflow (`scrollHeight`).
+    pub scroll_height: f64,
+    /// Inner width excluding borders and scrollbars (`clientWidth`).
+    pub client_width: f64,
+    /// Inner height excluding borders and scrollbars (`clientHeight`).
+    pub client_height: f64,
+    /// Border-box width (`offsetWidth`).
+    pub offset_width: f64,
+    /// Border-box height (`offsetHeight`).
+    pub offset_height: f64,
+}
+
+impl LayoutMetrics {
+    /// Returns the largest horizontal scroll offset, `scrollWidth - clientWidth`.
+    pub fn max_scroll_left(&self) -> f64 {
+        (self.scroll_width - self.client_width).max(0.0)
+    }
+
+    /// Returns the largest vertical scroll offset, `scrollHeight - clientHeight`.
+    pub fn max_scroll_top(&self) -> f64 {
+        (self.scroll_height - self.client_height).max(0.0)
+    }
+}
+
+/// Scroll position of a scroll container.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct ScrollState {
+    /// Horizontal scroll offset (`scrollLeft`).
+    pub left: f64,
+    /// Vertical scroll offset (`scrollTop`).
+    pub top: f64,
+    /// Destination `(left, top)` of a pending smooth scroll.
+    ///
+    /// The offsets above stay put until an external animator moves them
+    /// towards this target and clears it.
+    pub smooth_target: Option<(f64, f64)>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -366,6 +521,64 @@ mod tests {
         assert_eq!(rect, cloned);
     }
 
+    #[test]
+    fn test_dom_rect_overlapping() {
+        let a = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+        let b = DOMRect::new(5.0, -5.0, 10.0, 10.0);
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(DOMRect::new(5.0, 0.0, 5.0, 5.0)));
+        assert_eq!(a.union(&b), DOMRect::new(0.0, -5.0, 15.0, 15.0));
+    }
+
+    #[test]
+    fn test_dom_rect_touching_edge() {
+        let a = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+        let b = DOMRect::new(10.0, 2.0, 10.0, 4.0);
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(DOMRect::new(10.0, 2.0, 0.0, 4.0)));
+
+        let point = DOMRect::new(10.0, 10.0, 0.0, 0.0);
+        assert!(a.intersects(&point));
+    }
+
+    #[test]
+    fn test_dom_rect_disjoint() {
+        let a = DOMRect::new(-20.0, -20.0, 10.0, 10.0);
+        let b = DOMRect::new(0.0, 0.0, 10.0, 10.0);
+        assert!(!a.intersects(&b));
+        assert!(a.intersection(&b).is_none());
+        assert_eq!(a.union(&b), DOMRect::new(-20.0, -20.0, 30.0, 30.0));
+    }
+
+    #[test]
+    fn test_dom_rect_contained() {
+        let outer = DOMRect::new(0.0, 0.0, 100.0, 100.0);
+        let inner = DOMRect::new(20.0, 30.0, 10.0, 10.0);
+        assert!(outer.intersects(&inner));
+        assert_eq!(outer.intersection(&inner), Some(inner));
+        assert_eq!(outer.union(&inner), outer);
+    }
+
+    #[test]
+    fn test_dom_rect_negative_size_normalized() {
+        let flipped = DOMRect::new(10.0, 10.0, -10.0, -10.0);
+        let b = DOMRect::new(5.0, 5.0, 10.0, 10.0);
+        assert!(flipped.intersects(&b));
+        assert_eq!(
+            flipped.intersection(&b),
+            Some(DOMRect::new(5.0, 5.0, 5.0, 5.0))
+        );
+    }
+
+    #[test]
+    fn test_dom_rect_union_with_zero_area() {
+        let rect = DOMRect::new(5.0, 5.0, 10.0, 10.0);
+        let line = DOMRect::new(100.0, 100.0, 0.0, 50.0);
+        assert_eq!(rect.union(&line), rect);
+        assert_eq!(line.union(&rect), rect);
+        assert_eq!(DOMRect::empty().union(&DOMRect::empty()), DOMRect::empty());
+    }
+
     #[test]
     fn test_dom_rect_list_new() {
         let rects = vec![
@@ -402,6 +615,46 @@ mod tests {
         assert_eq!(collected.len(), 2);
     }
 
+    fn three_line_rects() -> DOMRectList {
+        DOMRectList::new(vec![
+            DOMRect::new(0.0, 0.0, 120.0, 16.0),
+            DOMRect::new(0.0, 16.0, 200.0, 16.0),
+            DOMRect::new(0.0, 32.0, 40.0, 16.0),
+        ])
+    }
+
+    #[test]
+    fn test_dom_rect_list_index() {
+        let list = three_line_rects();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].width, 120.0);
+        assert_eq!(list[1].y, 16.0);
+        assert_eq!(list[2], DOMRect::new(0.0, 32.0, 40.0, 16.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dom_rect_list_index_out_of_bounds() {
+        let _ = three_line_rects()[3];
+    }
+
+    #[test]
+    fn test_dom_rect_list_item_bounds_checked() {
+        let list = three_line_rects();
+        assert_eq!(list.item(2), Some(list[2]));
+        assert_eq!(list.item(3), None);
+    }
+
+    #[test]
+    fn test_dom_rect_list_iteration_order() {
+        let list = three_line_rects();
+        let mut tops = Vec::new();
+        for rect in &list {
+            tops.push(rect.top());
+        }
+        assert_eq!(tops, vec![0.0, 16.0, 32.0]);
+    }
+
     #[test]
     fn test_dom_rect_list_from_vec() {
         let rects = vec![DOMRect::empty()];
@@ -0,0 +1,114 @@
+//! JavaScript value representation.
+//!
+//! [`JsValue`] is the payload type for data crossing between the DOM and the
+//! JavaScript runtime, such as method arguments and `CustomEvent.detail`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::NodeId;
+
+/// JavaScript value representation for DOM-JS interop
+///
+/// This enum represents JavaScript values that can be passed to and from
+/// DOM methods when called from JavaScript.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum JsValue {
+    /// JavaScript `undefined`
+    #[default]
+    Undefined,
+    /// JavaScript `null`
+    Null,
+    /// JavaScript boolean
+    Boolean(bool),
+    /// JavaScript number (all JS numbers are f64)
+    Number(f64),
+    /// JavaScript string
+    String(String),
+    /// Reference to a DOM node
+    NodeRef(NodeId),
+    /// JavaScript array
+    Array(Vec<JsValue>),
+    /// JavaScript object (simple key-value representation)
+    Object(HashMap<String, JsValue>),
+}
+
+impl JsValue {
+    /// Creates a new undefined value
+    pub fn undefined() -> Self {
+        JsValue::Undefined
+    }
+
+    /// Creates a new null value
+    pub fn null() -> Self {
+        JsValue::Null
+    }
+
+    /// Creates a new boolean value
+    pub fn boolean(value: bool) -> Self {
+        JsValue::Boolean(value)
+    }
+
+    /// Creates a new number value
+    pub fn number(value: f64) -> Self {
+        JsValue::Number(value)
+    }
+
+    /// Creates a new string value
+    pub fn string(value: impl Into<String>) -> Self {
+        JsValue::String(value.into())
+    }
+
+    /// Creates a new node reference
+    pub fn node_ref(id: NodeId) -> Self {
+        JsValue::NodeRef(id)
+    }
+
+    /// Returns true if this value is undefined
+    pub fn is_undefined(&self) -> bool {
+        matches!(self, JsValue::Undefined)
+    }
+
+    /// Returns true if this value is null
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsValue::Null)
+    }
+
+    /// Returns true if this value is null or undefined
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, JsValue::Undefined | JsValue::Null)
+    }
+
+    /// Attempts to extract a boolean value
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Attempts to extract a number value
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            JsValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Attempts to extract a string value
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Attempts to extract a node reference
+    pub fn as_node_ref(&self) -> Option<NodeId> {
+        match self {
+            JsValue::NodeRef(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
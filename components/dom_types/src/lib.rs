@@ -57,6 +57,7 @@
 //! | [`error`] | DOM exception types (`DomException`) |
 //! | [`geometry`] | `DOMRect` and `DOMRectList` for bounding boxes |
 //! | [`ids`] | `NodeId` and `DocumentId` type aliases |
+//! | [`js_value`] | `JsValue` for DOM-JavaScript interop |
 //! | [`mutation`] | `MutationType` enum for MutationObserver |
 //! | [`node_type`] | `NodeType` enum for DOM node types |
 //! | [`shadow_root`] | `ShadowRootMode` enum for shadow DOM |
@@ -73,6 +74,7 @@
 pub mod error;
 pub mod geometry;
 pub mod ids;
+pub mod js_value;
 pub mod mutation;
 pub mod node_type;
 pub mod shadow_root;
@@ -80,8 +82,9 @@ pub mod string_map;
 
 // Re-exports
 pub use error::DomException;
-pub use geometry::{DOMRect, DOMRectList};
+pub use geometry::{DOMRect, DOMRectList, LayoutMetrics, ScrollState};
 pub use ids::{DocumentId, NodeId};
+pub use js_value::JsValue;
 pub use mutation::MutationType;
 pub use node_type::NodeType;
 pub use shadow_root::ShadowRootMode;
@@ -3,6 +3,8 @@
 //! This module defines the [`NodeType`] enum which represents the different
 //! types of nodes that can exist in a DOM tree, as per the DOM Level 4 specification.
 
+use std::fmt;
+
 /// Node type enumeration per DOM Level 4 specification.
 ///
 /// Each variant has a numeric value matching the DOM standard:
@@ -69,6 +71,58 @@ impl NodeType {
     pub fn as_u16(self) -> u16 {
         self as u16
     }
+
+    /// Returns true for element nodes
+    pub fn is_element(self) -> bool {
+        self == NodeType::Element
+    }
+
+    /// Returns true for nodes implementing `CharacterData`: text, CDATA
+    /// sections, processing instructions and comments
+    pub fn is_character_data(self) -> bool {
+        matches!(
+            self,
+            NodeType::Text
+                | NodeType::CDataSection
+                | NodeType::ProcessingInstruction
+                | NodeType::Comment
+        )
+    }
+
+    /// Returns true for document nodes
+    pub fn is_document(self) -> bool {
+        self == NodeType::Document
+    }
+
+    /// Returns the name of the matching `Node` constant
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::NodeType;
+    ///
+    /// assert_eq!(NodeType::Element.node_type_name(), "ELEMENT_NODE");
+    /// assert_eq!(NodeType::Text.to_string(), "TEXT_NODE");
+    /// ```
+    pub fn node_type_name(self) -> &'static str {
+        match self {
+            NodeType::Element => "ELEMENT_NODE",
+            NodeType::Attribute => "ATTRIBUTE_NODE",
+            NodeType::Text => "TEXT_NODE",
+            NodeType::CDataSection => "CDATA_SECTION_NODE",
+            NodeType::ProcessingInstruction => "PROCESSING_INSTRUCTION_NODE",
+            NodeType::Comment => "COMMENT_NODE",
+            NodeType::Document => "DOCUMENT_NODE",
+            NodeType::DocumentType => "DOCUMENT_TYPE_NODE",
+            NodeType::DocumentFragment => "DOCUMENT_FRAGMENT_NODE",
+        }
+    }
+}
+
+impl fmt::Display for NodeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.node_type_name())
+    }
 }
 
 #[cfg(test)]
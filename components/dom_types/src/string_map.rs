@@ -5,7 +5,7 @@
 
 use std::collections::HashMap;
 
-use crate::NodeId;
+use crate::{DomException, NodeId};
 
 /// Provides access to custom data attributes (data-*) on an element.
 ///
@@ -281,6 +281,38 @@ impl DOMStringMap {
         result
     }
 
+    /// Converts a camelCase name to a `data-*` attribute name, rejecting names
+    /// that no attribute maps back to.
+    ///
+    /// Per the HTML spec, a name with a `-` followed by a lowercase ASCII
+    /// letter is a `SyntaxError`: `data-foo-bar` reads back as `fooBar`, so
+    /// `foo-bar` can never be a dataset key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dom_types::{DOMStringMap, DomException};
+    ///
+    /// assert_eq!(DOMStringMap::try_to_attribute_name("fooBar").unwrap(), "data-foo-bar");
+    /// assert_eq!(DOMStringMap::try_to_attribute_name("foo-1").unwrap(), "data-foo-1");
+    /// assert!(matches!(
+    ///     DOMStringMap::try_to_attribute_name("foo-bar"),
+    ///     Err(DomException::SyntaxError(_))
+    /// ));
+    /// ```
+    pub fn try_to_attribute_name(name: &str) -> Result<String, DomException> {
+        let bytes = name.as_bytes();
+        let hyphen_before_lowercase = bytes
+            .windows(2)
+            .any(|pair| pair[0] == b'-' && pair[1].is_ascii_lowercase());
+        if hyphen_before_lowercase {
+            return Err(DomException::syntax_error(format!(
+                "'{name}' has a '-' before a lowercase letter"
+            )));
+        }
+        Ok(Self::to_attribute_name(name))
+    }
+
     /// Converts a data-kebab-case attribute name to a camelCase property name.
     ///
     /// This is the conversion used when reading HTML data attributes as
@@ -293,7 +325,9 @@ impl DOMStringMap {
     /// # Returns
     ///
     /// The corresponding camelCase property name, or `None` if the attribute
-    /// name doesn't start with "data-".
+    /// name doesn't start with "data-" or contains uppercase ASCII letters.
+    /// Only a `-` followed by a lowercase ASCII letter is folded; any other
+    /// `-`, such as one before a digit, stays in the name.
     ///
     /// # Examples
     ///
@@ -303,25 +337,25 @@ impl DOMStringMap {
     /// assert_eq!(DOMStringMap::from_attribute_name("data-user-name"), Some("userName".to_string()));
     /// assert_eq!(DOMStringMap::from_attribute_name("data-user-id"), Some("userId".to_string()));
     /// assert_eq!(DOMStringMap::from_attribute_name("data-simple"), Some("simple".to_string()));
+    /// assert_eq!(DOMStringMap::from_attribute_name("data-foo-1"), Some("foo-1".to_string()));
+    /// assert_eq!(DOMStringMap::from_attribute_name("data-Foo"), None);
     /// assert_eq!(DOMStringMap::from_attribute_name("class"), None);
     /// ```
     pub fn from_attribute_name(attr_name: &str) -> Option<String> {
-        if !attr_name.starts_with("data-") {
+        let name_part = attr_name.strip_prefix("data-")?;
+        if name_part.bytes().any(|byte| byte.is_ascii_uppercase()) {
             return None;
         }
 
-        let name_part = &attr_name[5..]; // Skip "data-"
         let mut result = String::new();
-        let mut capitalize_next = false;
-
-        for ch in name_part.chars() {
-            if ch == '-' {
-                capitalize_next = true;
-            } else if capitalize_next {
-                result.push(ch.to_ascii_uppercase());
-                capitalize_next = false;
-            } else {
-                result.push(ch);
+        let mut chars = name_part.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match chars.peek() {
+                Some(next) if ch == '-' && next.is_ascii_lowercase() => {
+                    result.push(next.to_ascii_uppercase());
+                    chars.next();
+                }
+                _ => result.push(ch),
             }
         }
 
@@ -427,6 +461,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_conversion_edge_cases() {
+        // A hyphen only folds into the next letter when that letter is lowercase
+        assert_eq!(
+            DOMStringMap::from_attribute_name("data-foo-1"),
+            Some("foo-1".to_string())
+        );
+        assert_eq!(
+            DOMStringMap::from_attribute_name("data--x"),
+            Some("X".to_string())
+        );
+        assert_eq!(
+            DOMStringMap::from_attribute_name("data-foo-"),
+            Some("foo-".to_string())
+        );
+        // Attributes with uppercase letters have no dataset key
+        assert_eq!(DOMStringMap::from_attribute_name("data-fooBar"), None);
+
+        assert_eq!(
+            DOMStringMap::try_to_attribute_name("foo-1"),
+            Ok("data-foo-1".to_string())
+        );
+        assert_eq!(
+            DOMStringMap::try_to_attribute_name("-Foo"),
+            Ok("data---foo".to_string())
+        );
+        assert!(matches!(
+            DOMStringMap::try_to_attribute_name("foo-bar"),
+            Err(DomException::SyntaxError(_))
+        ));
+    }
+
     #[test]
     fn test_roundtrip_conversion() {
         let names = ["userName", "userId", "myCustomData", "x", "aBC"];
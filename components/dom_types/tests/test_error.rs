@@ -68,6 +68,18 @@ fn test_security_error_display() {
     assert_eq!(err.to_string(), "Security error");
 }
 
+#[test]
+fn test_quota_exceeded_error_display() {
+    let err = DomException::QuotaExceededError;
+    assert_eq!(err.to_string(), "Quota exceeded error");
+}
+
+#[test]
+fn test_timeout_and_data_clone_error_display() {
+    assert_eq!(DomException::TimeoutError.to_string(), "Timeout error");
+    assert_eq!(DomException::DataCloneError.to_string(), "Data clone error");
+}
+
 #[test]
 fn test_dom_exception_is_error() {
     let err = DomException::NotFoundError;
@@ -117,6 +129,7 @@ fn test_syntax_error_with_long_string() {
 #[test]
 fn test_all_exception_types_are_unique() {
     let errors = vec![
+        DomException::IndexSizeError,
         DomException::HierarchyRequestError,
         DomException::WrongDocumentError,
         DomException::InvalidCharacterError,
@@ -127,6 +140,9 @@ fn test_all_exception_types_are_unique() {
         DomException::InvalidModificationError,
         DomException::NamespaceError,
         DomException::SecurityError,
+        DomException::QuotaExceededError,
+        DomException::TimeoutError,
+        DomException::DataCloneError,
     ];
 
     // Each error should have a unique display string
@@ -149,3 +165,58 @@ fn test_dom_exception_source() {
     let err = DomException::NotFoundError;
     assert!(err.source().is_none()); // DomException doesn't have a source
 }
+
+#[test]
+fn test_legacy_codes_and_names() {
+    use DomException::*;
+    let cases = [
+        (IndexSizeError, 1, "IndexSizeError"),
+        (HierarchyRequestError, 3, "HierarchyRequestError"),
+        (InvalidCharacterError, 5, "InvalidCharacterError"),
+        (NotFoundError, 8, "NotFoundError"),
+        (SyntaxError("bad".into()), 12, "SyntaxError"),
+        (QuotaExceededError, 22, "QuotaExceededError"),
+        (TimeoutError, 23, "TimeoutError"),
+        (DataCloneError, 25, "DataCloneError"),
+    ];
+    for (err, code, name) in cases {
+        assert_eq!(err.code(), code, "code of {}", name);
+        assert_eq!(err.name(), name);
+    }
+}
+
+#[test]
+fn test_propagates_through_boxed_error() {
+    fn remove_missing() -> Result<(), DomException> {
+        Err(DomException::NotFoundError)
+    }
+
+    fn caller() -> Result<(), Box<dyn Error>> {
+        remove_missing()?;
+        Ok(())
+    }
+
+    let err = caller().unwrap_err();
+    assert_eq!(err.to_string(), "Not found error");
+    let dom = err.downcast_ref::<DomException>().unwrap();
+    assert_eq!(dom.name(), "NotFoundError");
+}
+
+#[test]
+fn test_serde_round_trip() {
+    use DomException::*;
+    let errors = [
+        QuotaExceededError,
+        TimeoutError,
+        DataCloneError,
+        SyntaxError("bad selector".into()),
+    ];
+    for err in errors {
+        let json = serde_json::to_string(&err).unwrap();
+        let back: DomException = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, err);
+        assert_eq!(back.name(), err.name());
+    }
+    let json = serde_json::to_string(&TimeoutError).unwrap();
+    assert_eq!(json, "\"TimeoutError\"");
+}
@@ -122,3 +122,30 @@ fn test_all_node_types_roundtrip() {
         assert_eq!(node_type, deserialized);
     }
 }
+
+#[test]
+fn test_predicates_and_constant_names() {
+    use NodeType::*;
+
+    let names = [
+        (Element, "ELEMENT_NODE"),
+        (Attribute, "ATTRIBUTE_NODE"),
+        (Text, "TEXT_NODE"),
+        (CDataSection, "CDATA_SECTION_NODE"),
+        (ProcessingInstruction, "PROCESSING_INSTRUCTION_NODE"),
+        (Comment, "COMMENT_NODE"),
+        (Document, "DOCUMENT_NODE"),
+        (DocumentType, "DOCUMENT_TYPE_NODE"),
+        (DocumentFragment, "DOCUMENT_FRAGMENT_NODE"),
+    ];
+    let character_data = [Text, CDataSection, ProcessingInstruction, Comment];
+
+    for (node_type, name) in names {
+        assert_eq!(node_type.is_element(), node_type == Element);
+        let is_character_data = character_data.contains(&node_type);
+        assert_eq!(node_type.is_character_data(), is_character_data);
+        assert_eq!(node_type.is_document(), node_type == Document);
+        assert_eq!(node_type.node_type_name(), name);
+        assert_eq!(node_type.to_string(), name);
+    }
+}